@@ -0,0 +1,36 @@
+CREATE TABLE IF NOT EXISTS market_alerts (
+  id TEXT PRIMARY KEY,
+  market_kind TEXT NOT NULL CHECK (market_kind IN ('spot', 'futures_usdm')),
+  symbol TEXT NOT NULL,
+  condition_type TEXT NOT NULL CHECK (condition_type IN ('priceCross', 'percentMove', 'deltaSpike', 'volumeSpike', 'drawingCross')),
+  condition_json TEXT NOT NULL,
+  label TEXT,
+  enabled INTEGER NOT NULL CHECK (enabled IN (0, 1)),
+  one_shot INTEGER NOT NULL CHECK (one_shot IN (0, 1)),
+  cooldown_ms INTEGER NOT NULL CHECK (cooldown_ms >= 0),
+  notify_os INTEGER NOT NULL CHECK (notify_os IN (0, 1)),
+  last_triggered_at_ms INTEGER,
+  created_at_ms INTEGER NOT NULL,
+  updated_at_ms INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_market_alerts_scope
+  ON market_alerts (market_kind, symbol, enabled);
+
+CREATE TABLE IF NOT EXISTS market_alert_triggers (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  alert_id TEXT NOT NULL REFERENCES market_alerts (id) ON DELETE CASCADE,
+  market_kind TEXT NOT NULL CHECK (market_kind IN ('spot', 'futures_usdm')),
+  symbol TEXT NOT NULL,
+  condition_type TEXT NOT NULL,
+  label TEXT,
+  price REAL NOT NULL,
+  message TEXT NOT NULL,
+  triggered_at_ms INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_market_alert_triggers_alert
+  ON market_alert_triggers (alert_id, triggered_at_ms);
+
+CREATE INDEX IF NOT EXISTS idx_market_alert_triggers_time
+  ON market_alert_triggers (triggered_at_ms);
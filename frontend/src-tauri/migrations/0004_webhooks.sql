@@ -0,0 +1,26 @@
+CREATE TABLE IF NOT EXISTS webhook_targets (
+  id TEXT PRIMARY KEY,
+  label TEXT,
+  url TEXT NOT NULL,
+  headers_json TEXT NOT NULL,
+  body_template TEXT,
+  event_types_json TEXT NOT NULL,
+  enabled INTEGER NOT NULL CHECK (enabled IN (0, 1)),
+  created_at_ms INTEGER NOT NULL,
+  updated_at_ms INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS webhook_outbox (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  target_id TEXT NOT NULL REFERENCES webhook_targets (id) ON DELETE CASCADE,
+  event_type TEXT NOT NULL CHECK (event_type IN ('alert_triggered', 'connection_state')),
+  body TEXT NOT NULL,
+  status TEXT NOT NULL CHECK (status IN ('pending', 'failed')),
+  attempts INTEGER NOT NULL DEFAULT 0,
+  next_attempt_at_ms INTEGER NOT NULL,
+  last_error TEXT,
+  created_at_ms INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhook_outbox_due
+  ON webhook_outbox (status, next_attempt_at_ms);
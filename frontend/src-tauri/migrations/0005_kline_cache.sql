@@ -0,0 +1,26 @@
+CREATE TABLE IF NOT EXISTS kline_cache (
+  market_kind TEXT NOT NULL CHECK (market_kind IN ('spot', 'futures_usdm')),
+  symbol TEXT NOT NULL,
+  timeframe TEXT NOT NULL,
+  open_time INTEGER NOT NULL,
+  open REAL NOT NULL,
+  high REAL NOT NULL,
+  low REAL NOT NULL,
+  close REAL NOT NULL,
+  volume REAL NOT NULL CHECK (volume >= 0),
+  delta_high REAL NOT NULL,
+  delta_low REAL NOT NULL,
+  delta_close REAL NOT NULL,
+  is_closed INTEGER NOT NULL CHECK (is_closed IN (0, 1)),
+  updated_at_ms INTEGER NOT NULL,
+  PRIMARY KEY (market_kind, symbol, timeframe, open_time)
+) WITHOUT ROWID;
+
+CREATE TABLE IF NOT EXISTS kline_cache_coverage (
+  market_kind TEXT NOT NULL CHECK (market_kind IN ('spot', 'futures_usdm')),
+  symbol TEXT NOT NULL,
+  timeframe TEXT NOT NULL,
+  from_time INTEGER NOT NULL,
+  to_time INTEGER NOT NULL CHECK (to_time >= from_time),
+  PRIMARY KEY (market_kind, symbol, timeframe, from_time)
+) WITHOUT ROWID;
@@ -0,0 +1,439 @@
+//! Headless companion to the desktop app: records, backfills and exports market data into
+//! the same app data directory the desktop app reads.
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use desktop_template_lib::db::{db_path_in, initialize_pool_from_path};
+use desktop_template_lib::error::AppError;
+use desktop_template_lib::market::binance::BinanceClient;
+use desktop_template_lib::market::export::export_market_data;
+use desktop_template_lib::market::kline_store::{sync_kline_range, KlineSeries};
+use desktop_template_lib::market::pipeline::{now_unix_ms, run_market_stream};
+use desktop_template_lib::market::recorder::{
+    parse_utc_day_label, recordings_root_in, TradeRecorder,
+};
+use desktop_template_lib::market::sink::{MarketEvent, MarketEventSink, SequencedEvent};
+use desktop_template_lib::market::tape::TradeTape;
+use desktop_template_lib::market::types::{
+    MarketConnectionState, MarketExportArgs, MarketExportDataset, MarketExportFormat, MarketKind,
+    MarketPerfSnapshot, MarketStreamStatusSnapshot, MarketTimeframe, StartMarketStreamArgs,
+};
+use desktop_template_lib::market::webhooks::WebhookDispatcher;
+use parking_lot::Mutex;
+use sqlx::SqlitePool;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tokio_util::sync::CancellationToken;
+
+const MS_PER_DAY: i64 = 86_400_000;
+
+#[derive(Debug, Parser)]
+#[command(name = "market-cli", version, about)]
+struct Cli {
+    /// App data directory holding the SQLite cache and trade recordings. Point it at the
+    /// desktop app's data directory, or copy the directory there afterwards.
+    #[arg(long, env = "MARKET_CLI_DATA_DIR")]
+    data_dir: PathBuf,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Stream a symbol and record every trade until interrupted.
+    Record(StreamArgs),
+    /// Stream a symbol and print live status and perf without recording.
+    Watch(StreamArgs),
+    /// Fill the kline cache for a range from REST.
+    Backfill(BackfillArgs),
+    /// Export candles, delta candles or recorded trades for a range.
+    Export(ExportArgs),
+}
+
+#[derive(Debug, Args)]
+struct SeriesArgs {
+    /// `spot` or `futures_usdm`.
+    #[arg(long, default_value = "spot", value_parser = parse_market_kind)]
+    market: MarketKind,
+    #[arg(long, default_value = "BTCUSDT")]
+    symbol: String,
+    /// Candle timeframe: 1m, 5m, 1h, 4h, 1d, 1w or 1M.
+    #[arg(long, default_value = "1m", value_parser = parse_timeframe)]
+    timeframe: MarketTimeframe,
+}
+
+#[derive(Debug, Args)]
+struct StreamArgs {
+    #[command(flatten)]
+    series: SeriesArgs,
+    /// Stop after this many seconds instead of waiting for Ctrl-C.
+    #[arg(long)]
+    duration_secs: Option<u64>,
+    /// Print pipeline perf snapshots alongside status.
+    #[arg(long)]
+    perf: bool,
+    /// Seconds between repeated status lines; state changes always print immediately.
+    #[arg(long, default_value_t = 10)]
+    status_interval_secs: u64,
+}
+
+#[derive(Debug, Args)]
+struct RangeArgs {
+    /// Range start as unix milliseconds or a UTC `YYYY-MM-DD` date.
+    #[arg(long, value_parser = parse_time)]
+    from: i64,
+    /// Range end (exclusive) as unix milliseconds or a UTC `YYYY-MM-DD` date.
+    #[arg(long, value_parser = parse_time)]
+    to: i64,
+}
+
+impl RangeArgs {
+    /// Last millisecond inside the range; the kline sync and export bounds are inclusive.
+    fn last_ms(&self) -> Result<i64, AppError> {
+        if self.from >= self.to {
+            return Err(AppError::InvalidArgument(
+                "--from must be before --to".to_string(),
+            ));
+        }
+        Ok(self.to - 1)
+    }
+}
+
+#[derive(Debug, Args)]
+struct BackfillArgs {
+    #[command(flatten)]
+    series: SeriesArgs,
+    #[command(flatten)]
+    range: RangeArgs,
+}
+
+#[derive(Debug, Args)]
+struct ExportArgs {
+    #[command(flatten)]
+    series: SeriesArgs,
+    #[command(flatten)]
+    range: RangeArgs,
+    #[arg(long, value_enum, default_value_t = Dataset::Candles)]
+    dataset: Dataset,
+    #[arg(long, value_enum, default_value_t = Format::Csv)]
+    format: Format,
+    /// Output file path.
+    #[arg(long)]
+    output: PathBuf,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Dataset {
+    Candles,
+    DeltaCandles,
+    Trades,
+}
+
+impl From<Dataset> for MarketExportDataset {
+    fn from(value: Dataset) -> Self {
+        match value {
+            Dataset::Candles => Self::Candles,
+            Dataset::DeltaCandles => Self::DeltaCandles,
+            Dataset::Trades => Self::Trades,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Format {
+    Csv,
+    Parquet,
+}
+
+impl From<Format> for MarketExportFormat {
+    fn from(value: Format) -> Self {
+        match value {
+            Format::Csv => Self::Csv,
+            Format::Parquet => Self::Parquet,
+        }
+    }
+}
+
+fn parse_market_kind(value: &str) -> Result<MarketKind, String> {
+    MarketKind::parse_str(value).map_err(|error| error.to_string())
+}
+
+fn parse_timeframe(value: &str) -> Result<MarketTimeframe, String> {
+    MarketTimeframe::parse_str(value).map_err(|error| error.to_string())
+}
+
+fn parse_time(value: &str) -> Result<i64, String> {
+    if let Ok(unix_ms) = value.trim().parse::<i64>() {
+        return Ok(unix_ms);
+    }
+    parse_utc_day_label(value)
+        .map(|day| day * MS_PER_DAY)
+        .ok_or_else(|| format!("'{value}' is neither unix milliseconds nor a YYYY-MM-DD date"))
+}
+
+/// Prints status, perf and progress events to stdout. Repeated status lines are throttled so
+/// a long-running recorder keeps a readable log.
+struct ConsoleEventSink {
+    status_interval: Duration,
+    last_status: Mutex<Option<(MarketConnectionState, Option<String>, Instant)>>,
+    last_perf_at: Mutex<Option<Instant>>,
+}
+
+impl ConsoleEventSink {
+    fn new(status_interval: Duration) -> Self {
+        Self {
+            status_interval,
+            last_status: Mutex::new(None),
+            last_perf_at: Mutex::new(None),
+        }
+    }
+
+    fn print_status(&self, status: &MarketStreamStatusSnapshot) {
+        let now = Instant::now();
+        {
+            let mut last_status = self.last_status.lock();
+            let repeated = last_status
+                .as_ref()
+                .is_some_and(|(state, reason, printed_at)| {
+                    *state == status.state
+                        && *reason == status.reason
+                        && now.duration_since(*printed_at) < self.status_interval
+                });
+            if repeated {
+                return;
+            }
+            *last_status = Some((status.state, status.reason.clone(), now));
+        }
+
+        println!(
+            "status {:?} {}:{} lastAggId={} latency={}ms{}",
+            status.state,
+            status.market_kind.as_str(),
+            status.symbol,
+            display_or_dash(status.last_agg_id),
+            display_or_dash(status.latency_ms),
+            status
+                .reason
+                .as_deref()
+                .map(|reason| format!(" ({reason})"))
+                .unwrap_or_default()
+        );
+    }
+
+    fn print_perf(&self, perf: &MarketPerfSnapshot) {
+        let now = Instant::now();
+        {
+            let mut last_perf_at = self.last_perf_at.lock();
+            if last_perf_at
+                .is_some_and(|printed_at| now.duration_since(printed_at) < self.status_interval)
+            {
+                return;
+            }
+            *last_perf_at = Some(now);
+        }
+
+        println!(
+            "perf ingested={} emitted={} parse p50/p95/p99={}/{}/{}us apply={}/{}/{}us \
+             pipeline={}/{}/{}ms reconcile={}/{}",
+            perf.ingest_count,
+            perf.emit_count,
+            display_or_dash(perf.parse_p50_us),
+            display_or_dash(perf.parse_p95_us),
+            display_or_dash(perf.parse_p99_us),
+            display_or_dash(perf.apply_p50_us),
+            display_or_dash(perf.apply_p95_us),
+            display_or_dash(perf.apply_p99_us),
+            display_or_dash(perf.local_pipeline_p50_ms),
+            display_or_dash(perf.local_pipeline_p95_ms),
+            display_or_dash(perf.local_pipeline_p99_ms),
+            perf.reconcile_divergences,
+            perf.reconcile_checks,
+        );
+    }
+}
+
+impl MarketEventSink for ConsoleEventSink {
+    fn publish(&self, event: SequencedEvent<'_>) -> Result<(), AppError> {
+        match event.event {
+            MarketEvent::Status(status) => self.print_status(status),
+            MarketEvent::Perf(perf) => self.print_perf(perf),
+            MarketEvent::HistoryLoadProgress(progress) if progress.done => {
+                println!("history loaded: {} candles", progress.candles_fetched);
+            }
+            MarketEvent::ExportProgress(progress) => println!(
+                "export {}: {} rows ({}%)",
+                progress.path,
+                progress.rows_written,
+                display_or_dash(progress.progress_pct.map(|pct| pct.round()))
+            ),
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+fn display_or_dash<T: Display>(value: Option<T>) -> String {
+    value
+        .map(|value| value.to_string())
+        .unwrap_or_else(|| "-".to_string())
+}
+
+async fn stream(
+    data_dir: &Path,
+    db_pool: SqlitePool,
+    binance: BinanceClient,
+    args: StreamArgs,
+    record: bool,
+) -> Result<(), AppError> {
+    let config = StartMarketStreamArgs {
+        market_kind: Some(args.series.market),
+        symbol: Some(args.series.symbol),
+        timeframe: Some(args.series.timeframe),
+        perf_telemetry: Some(args.perf),
+        record_trades: Some(record),
+        ..Default::default()
+    }
+    .normalize()?;
+    let trade_recorder = if record {
+        Some(TradeRecorder::start(
+            &recordings_root_in(data_dir),
+            config.market_kind,
+            &config.symbol,
+        )?)
+    } else {
+        None
+    };
+
+    let events = Arc::new(ConsoleEventSink::new(Duration::from_secs(
+        args.status_interval_secs.max(1),
+    )));
+    let status_store = Arc::new(RwLock::new(MarketStreamStatusSnapshot::stopped(
+        config.symbol.clone(),
+        None,
+    )));
+    let webhooks = WebhookDispatcher::start(db_pool.clone())?;
+    let cancel_token = CancellationToken::new();
+    let stream = tokio::spawn(run_market_stream(
+        events,
+        config,
+        db_pool,
+        binance,
+        status_store,
+        Arc::default(),
+        Arc::default(),
+        Arc::default(),
+        None,
+        webhooks,
+        Arc::new(Mutex::new(TradeTape::default())),
+        trade_recorder,
+        None,
+        None,
+        cancel_token.clone(),
+    ));
+
+    let deadline = async {
+        match args.duration_secs {
+            Some(seconds) => tokio::time::sleep(Duration::from_secs(seconds)).await,
+            None => std::future::pending().await,
+        }
+    };
+    tokio::select! {
+        interrupted = tokio::signal::ctrl_c() => interrupted?,
+        _ = deadline => {}
+    }
+
+    cancel_token.cancel();
+    stream
+        .await
+        .map_err(|error| AppError::Internal(format!("stream task failed: {error}")))
+}
+
+async fn backfill(
+    db_pool: &SqlitePool,
+    binance: &BinanceClient,
+    args: BackfillArgs,
+) -> Result<(), AppError> {
+    let last_ms = args.range.last_ms()?;
+    let symbol = args.series.symbol.trim().to_ascii_uppercase();
+    let series = KlineSeries::new(args.series.market, &symbol, args.series.timeframe);
+    sync_kline_range(
+        db_pool,
+        binance,
+        series,
+        args.range.from,
+        last_ms,
+        now_unix_ms(),
+    )
+    .await?;
+    println!(
+        "backfilled {}:{} {} klines for [{}, {})",
+        args.series.market.as_str(),
+        symbol,
+        args.series.timeframe.as_str(),
+        args.range.from,
+        args.range.to
+    );
+    Ok(())
+}
+
+async fn export(
+    data_dir: &Path,
+    db_pool: &SqlitePool,
+    binance: &BinanceClient,
+    args: ExportArgs,
+) -> Result<(), AppError> {
+    let to_t = args.range.last_ms()?;
+    let request = MarketExportArgs {
+        market_kind: args.series.market,
+        symbol: args.series.symbol,
+        timeframe: Some(args.series.timeframe),
+        dataset: args.dataset.into(),
+        format: args.format.into(),
+        from_t: args.range.from,
+        to_t,
+        path: args.output.display().to_string(),
+    }
+    .normalize()?;
+    let events = Arc::new(ConsoleEventSink::new(Duration::ZERO));
+    let result = export_market_data(
+        events,
+        db_pool,
+        binance,
+        &recordings_root_in(data_dir),
+        request,
+    )
+    .await?;
+    println!("exported {} rows to {}", result.rows_written, result.path);
+    Ok(())
+}
+
+async fn run(cli: Cli) -> Result<(), AppError> {
+    std::fs::create_dir_all(&cli.data_dir)?;
+    let db_pool = initialize_pool_from_path(&db_path_in(&cli.data_dir)).await?;
+    let binance = BinanceClient::from_env();
+
+    match cli.command {
+        Command::Record(args) => stream(&cli.data_dir, db_pool, binance, args, true).await,
+        Command::Watch(args) => stream(&cli.data_dir, db_pool, binance, args, false).await,
+        Command::Backfill(args) => backfill(&db_pool, &binance, args).await,
+        Command::Export(args) => export(&cli.data_dir, &db_pool, &binance, args).await,
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    // The pipeline spawns through Tauri's runtime handle; share this one instead of letting
+    // Tauri start a second runtime.
+    tauri::async_runtime::set(tokio::runtime::Handle::current());
+
+    match run(Cli::parse()).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
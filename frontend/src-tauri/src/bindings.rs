@@ -0,0 +1,717 @@
+//! Generates `src/lib/ipc/bindings.ts`: zod schemas and TypeScript types for every command
+//! argument, command result and event payload, rendered from the JSON schemas of the Rust
+//! types. Arguments are described as they deserialize and results and events as they
+//! serialize, so an `Option` field is optional in arguments and always present, possibly
+//! null, in results.
+
+use crate::commands::{app_info::AppInfoResponse, health::HealthResponse};
+use crate::error::AppError;
+use crate::market::types::{
+    MarketAlertDeleteArgs, MarketAlertDeleteResult, MarketAlertDto, MarketAlertTriggerDto,
+    MarketAlertTriggersArgs, MarketAlertUpsertArgs, MarketAlertsListArgs, MarketApiStartArgs,
+    MarketDrawingDeleteArgs, MarketDrawingDeleteResult, MarketDrawingDto, MarketDrawingUpsertArgs,
+    MarketDrawingsScopeArgs, MarketExportArgs, MarketFrameAckArgs, MarketFrameAckResult,
+    MarketHistoryAckArgs, MarketHistoryAckResult, MarketHistoryPageArgs, MarketImportArchivesArgs,
+    MarketPerfSnapshot, MarketPreferencesSnapshot, MarketReplayControlArgs, MarketStreamSelectArgs,
+    MarketStreamSession, MarketStreamSnapshotArgs, MarketStreamStatusSnapshot,
+    MarketStreamStopResult, MarketSymbolsArgs, MarketTradesRecentArgs, MarketWindowOpenArgs,
+    MarketWindowSubscribeArgs, SaveMarketPreferencesArgs, StartMarketStreamArgs,
+    UiArchiveImportResult, UiCandle, UiCandlesBootstrap, UiCandlesRepair, UiDeltaCandle,
+    UiDeltaCandlesBootstrap, UiExportProgress, UiExportResult, UiHistoryLoadProgress,
+    UiHistoryPage, UiMarketApiStatus, UiMarketFrameUpdate, UiMarketStreamSnapshot, UiMarketWindow,
+    UiReplayStatus, UiTick, UiTradeTapeAppend, UiTradesSnapshot, WebhookOutboxRetryResult,
+    WebhookOutboxSummary, WebhookTargetDeleteArgs, WebhookTargetDeleteResult, WebhookTargetDto,
+    WebhookTargetUpsertArgs,
+};
+use crate::market::{
+    ALERT_TRIGGERED_EVENT, ARCHIVE_IMPORT_PROGRESS_EVENT, CANDLES_BOOTSTRAP_EVENT,
+    CANDLES_REPAIR_EVENT, CANDLE_UPDATE_EVENT, DELTA_CANDLES_BOOTSTRAP_EVENT,
+    DELTA_CANDLE_UPDATE_EVENT, EXPORT_PROGRESS_EVENT, HISTORY_LOAD_PROGRESS_EVENT,
+    MARKET_FRAME_UPDATE_EVENT, MARKET_PERF_EVENT, MARKET_STATUS_EVENT, PRICE_UPDATE_EVENT,
+    REPLAY_STATUS_EVENT, TRADE_TAPE_EVENT,
+};
+use schemars::generate::SchemaSettings;
+use schemars::{JsonSchema, Schema, SchemaGenerator};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The committed bindings, relative to this crate's manifest.
+pub const BINDINGS_PATH: &str = "../src/lib/ipc/bindings.ts";
+
+const DEFS_PREFIX: &str = "#/$defs/";
+
+type SchemaFn = fn(&mut SchemaGenerator) -> Schema;
+
+struct IpcCommand {
+    name: &'static str,
+    args: Option<SchemaFn>,
+    result: SchemaFn,
+}
+
+impl IpcCommand {
+    fn new<R: JsonSchema>(name: &'static str) -> Self {
+        Self {
+            name,
+            args: None,
+            result: SchemaGenerator::subschema_for::<R>,
+        }
+    }
+
+    fn with_args<A: JsonSchema, R: JsonSchema>(name: &'static str) -> Self {
+        Self {
+            name,
+            args: Some(SchemaGenerator::subschema_for::<A>),
+            result: SchemaGenerator::subschema_for::<R>,
+        }
+    }
+}
+
+/// Every command registered in `lib.rs`, in registration order. Commands taking an
+/// `Option<_>` argument may be invoked without one.
+fn ipc_commands() -> Vec<IpcCommand> {
+    vec![
+        IpcCommand::new::<HealthResponse>("health"),
+        IpcCommand::new::<AppInfoResponse>("app_info"),
+        IpcCommand::with_args::<Option<StartMarketStreamArgs>, MarketStreamSession>(
+            "start_market_stream",
+        ),
+        IpcCommand::with_args::<Option<MarketStreamSelectArgs>, MarketStreamStopResult>(
+            "stop_market_stream",
+        ),
+        IpcCommand::with_args::<Option<MarketStreamSelectArgs>, MarketStreamStatusSnapshot>(
+            "market_stream_status",
+        ),
+        IpcCommand::with_args::<Option<MarketStreamSnapshotArgs>, UiMarketStreamSnapshot>(
+            "market_stream_snapshot",
+        ),
+        IpcCommand::new::<Vec<MarketStreamSession>>("market_streams_list"),
+        IpcCommand::with_args::<MarketSymbolsArgs, Vec<String>>("market_symbols"),
+        IpcCommand::new::<Vec<String>>("market_spot_symbols"),
+        IpcCommand::with_args::<Option<MarketTradesRecentArgs>, UiTradesSnapshot>(
+            "market_trades_recent",
+        ),
+        IpcCommand::with_args::<MarketHistoryAckArgs, MarketHistoryAckResult>("market_history_ack"),
+        IpcCommand::with_args::<MarketFrameAckArgs, MarketFrameAckResult>("market_frame_ack"),
+        IpcCommand::with_args::<MarketHistoryPageArgs, UiHistoryPage>("market_history_page"),
+        IpcCommand::with_args::<MarketReplayControlArgs, UiReplayStatus>("market_replay_control"),
+        IpcCommand::with_args::<MarketImportArchivesArgs, UiArchiveImportResult>(
+            "market_import_archives",
+        ),
+        IpcCommand::with_args::<MarketExportArgs, UiExportResult>("market_export"),
+        IpcCommand::with_args::<Option<MarketWindowOpenArgs>, UiMarketWindow>("market_window_open"),
+        IpcCommand::with_args::<MarketWindowSubscribeArgs, UiMarketWindow>(
+            "market_window_subscribe",
+        ),
+        IpcCommand::new::<Vec<UiMarketWindow>>("market_windows_list"),
+        IpcCommand::with_args::<Option<MarketApiStartArgs>, UiMarketApiStatus>("market_api_start"),
+        IpcCommand::new::<UiMarketApiStatus>("market_api_stop"),
+        IpcCommand::new::<UiMarketApiStatus>("market_api_status"),
+        IpcCommand::new::<MarketPreferencesSnapshot>("market_preferences_get"),
+        IpcCommand::with_args::<SaveMarketPreferencesArgs, MarketPreferencesSnapshot>(
+            "market_preferences_save",
+        ),
+        IpcCommand::with_args::<MarketDrawingsScopeArgs, Vec<MarketDrawingDto>>(
+            "market_drawings_list",
+        ),
+        IpcCommand::with_args::<MarketDrawingUpsertArgs, MarketDrawingDto>("market_drawing_upsert"),
+        IpcCommand::with_args::<MarketDrawingDeleteArgs, MarketDrawingDeleteResult>(
+            "market_drawing_delete",
+        ),
+        IpcCommand::with_args::<Option<MarketAlertsListArgs>, Vec<MarketAlertDto>>(
+            "market_alerts_list",
+        ),
+        IpcCommand::with_args::<MarketAlertUpsertArgs, MarketAlertDto>("market_alert_upsert"),
+        IpcCommand::with_args::<MarketAlertDeleteArgs, MarketAlertDeleteResult>(
+            "market_alert_delete",
+        ),
+        IpcCommand::with_args::<Option<MarketAlertTriggersArgs>, Vec<MarketAlertTriggerDto>>(
+            "market_alert_triggers_list",
+        ),
+        IpcCommand::new::<Vec<WebhookTargetDto>>("webhook_targets_list"),
+        IpcCommand::with_args::<WebhookTargetUpsertArgs, WebhookTargetDto>("webhook_target_upsert"),
+        IpcCommand::with_args::<WebhookTargetDeleteArgs, WebhookTargetDeleteResult>(
+            "webhook_target_delete",
+        ),
+        IpcCommand::new::<WebhookOutboxSummary>("webhook_outbox_summary"),
+        IpcCommand::new::<WebhookOutboxRetryResult>("webhook_outbox_retry_failed"),
+    ]
+}
+
+/// Every event the core emits to the webview, with its payload. Stream events also carry
+/// the `generation` and `seq` of their stream, which the payload schemas let through.
+fn ipc_events() -> Vec<(&'static str, SchemaFn)> {
+    fn event<T: JsonSchema>(name: &'static str) -> (&'static str, SchemaFn) {
+        (name, SchemaGenerator::subschema_for::<T>)
+    }
+
+    vec![
+        event::<UiTick>(PRICE_UPDATE_EVENT),
+        event::<MarketStreamStatusSnapshot>(MARKET_STATUS_EVENT),
+        event::<UiMarketFrameUpdate>(MARKET_FRAME_UPDATE_EVENT),
+        event::<MarketPerfSnapshot>(MARKET_PERF_EVENT),
+        event::<UiCandle>(CANDLE_UPDATE_EVENT),
+        event::<UiCandlesBootstrap>(CANDLES_BOOTSTRAP_EVENT),
+        event::<UiCandlesRepair>(CANDLES_REPAIR_EVENT),
+        event::<UiDeltaCandle>(DELTA_CANDLE_UPDATE_EVENT),
+        event::<UiDeltaCandlesBootstrap>(DELTA_CANDLES_BOOTSTRAP_EVENT),
+        event::<UiHistoryLoadProgress>(HISTORY_LOAD_PROGRESS_EVENT),
+        event::<UiHistoryLoadProgress>(ARCHIVE_IMPORT_PROGRESS_EVENT),
+        event::<UiExportProgress>(EXPORT_PROGRESS_EVENT),
+        event::<MarketAlertTriggerDto>(ALERT_TRIGGERED_EVENT),
+        event::<UiTradeTapeAppend>(TRADE_TAPE_EVENT),
+        event::<UiReplayStatus>(REPLAY_STATUS_EVENT),
+    ]
+}
+
+/// Renders the contents of [`BINDINGS_PATH`].
+pub fn render_bindings() -> String {
+    let mut inputs = SchemaSettings::draft2020_12()
+        .for_deserialize()
+        .into_generator();
+    let mut outputs = SchemaSettings::draft2020_12()
+        .for_serialize()
+        .into_generator();
+
+    let commands = ipc_commands()
+        .into_iter()
+        .map(|command| {
+            let args = command.args.map(|args| args(&mut inputs).to_value());
+            (
+                command.name,
+                args,
+                (command.result)(&mut outputs).to_value(),
+            )
+        })
+        .collect::<Vec<_>>();
+    let events = ipc_events()
+        .into_iter()
+        .map(|(name, payload)| (name, payload(&mut outputs).to_value()))
+        .collect::<Vec<_>>();
+    outputs.subschema_for::<AppError>();
+
+    // A type read by one command and returned by another can differ between the two, for
+    // instance in which fields are required; its argument form then gets an `Input` suffix.
+    let output_defs = outputs.definitions().clone();
+    let input_defs = inputs.definitions().clone();
+    let input_names = input_defs
+        .iter()
+        .map(|(name, schema)| {
+            let renamed = match output_defs.get(name) {
+                Some(output) if output != schema => format!("{name}Input"),
+                _ => name.clone(),
+            };
+            (name.clone(), renamed)
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    let mut defs = output_defs.into_iter().collect::<BTreeMap<_, _>>();
+    for (name, mut schema) in input_defs {
+        rename_refs(&mut schema, &input_names);
+        defs.entry(input_names[&name].clone()).or_insert(schema);
+    }
+
+    let mut out = String::from(
+        "// Generated from the Rust IPC types by src-tauri/src/bindings.rs. Do not edit by hand;\n\
+         // regenerate with `UPDATE_IPC_BINDINGS=1 cargo test bindings` in src-tauri.\n\
+         import { z } from \"zod\";\n",
+    );
+
+    for name in emit_order(&defs) {
+        let schema = &defs[name];
+        out.push('\n');
+        if let Some(description) = schema.get("description").and_then(Value::as_str) {
+            out.push_str(&doc_comment(description, 0));
+        }
+        out.push_str(&format!(
+            "export const {} = {};\nexport type {name} = z.infer<typeof {}>;\n",
+            schema_ident(name),
+            zod(schema, 0),
+            schema_ident(name),
+        ));
+    }
+
+    out.push_str("\nexport const ipcCommands = {\n");
+    for (name, args, result) in commands {
+        let args = match args {
+            Some(mut args) => {
+                rename_refs(&mut args, &input_names);
+                let optional = is_nullable(&args);
+                let mut rendered = zod(&args, 2);
+                if optional {
+                    rendered.push_str(".optional()");
+                }
+                rendered
+            }
+            None => "z.undefined()".to_string(),
+        };
+        out.push_str(&format!(
+            "  {name}: {{ args: {args}, result: {} }},\n",
+            zod(&result, 2)
+        ));
+    }
+    out.push_str("} as const;\n\nexport const ipcEvents = {\n");
+    for (name, payload) in events {
+        out.push_str(&format!("  {name}: {},\n", zod(&payload, 2)));
+    }
+    out.push_str(
+        "} as const;\n\
+         \n\
+         export type IpcCommandName = keyof typeof ipcCommands;\n\
+         export type IpcArgsMap = {\n  [K in IpcCommandName]: z.infer<(typeof ipcCommands)[K][\"args\"]>;\n};\n\
+         export type IpcResponseMap = {\n  [K in IpcCommandName]: z.infer<(typeof ipcCommands)[K][\"result\"]>;\n};\n\
+         export type IpcEventName = keyof typeof ipcEvents;\n\
+         export type IpcEventPayloadMap = {\n  [K in IpcEventName]: z.infer<(typeof ipcEvents)[K]>;\n};\n",
+    );
+    out
+}
+
+fn schema_ident(name: &str) -> String {
+    let mut chars = name.chars();
+    let first = chars.next().map(|ch| ch.to_ascii_lowercase());
+    first.into_iter().chain(chars).collect::<String>() + "Schema"
+}
+
+fn rename_refs(schema: &mut Value, names: &BTreeMap<String, String>) {
+    match schema {
+        Value::Object(object) => {
+            if let Some(Value::String(reference)) = object.get_mut("$ref") {
+                if let Some(renamed) = reference
+                    .strip_prefix(DEFS_PREFIX)
+                    .and_then(|name| names.get(name))
+                {
+                    *reference = format!("{DEFS_PREFIX}{renamed}");
+                }
+            }
+            object
+                .values_mut()
+                .for_each(|value| rename_refs(value, names));
+        }
+        Value::Array(values) => values
+            .iter_mut()
+            .for_each(|value| rename_refs(value, names)),
+        _ => {}
+    }
+}
+
+fn collect_refs<'a>(schema: &'a Value, refs: &mut Vec<&'a str>) {
+    match schema {
+        Value::Object(object) => {
+            if let Some(name) = object
+                .get("$ref")
+                .and_then(Value::as_str)
+                .and_then(|reference| reference.strip_prefix(DEFS_PREFIX))
+            {
+                refs.push(name);
+            }
+            object.values().for_each(|value| collect_refs(value, refs));
+        }
+        Value::Array(values) => values.iter().for_each(|value| collect_refs(value, refs)),
+        _ => {}
+    }
+}
+
+/// Definition names ordered so that every schema is declared after the ones it references.
+fn emit_order(defs: &BTreeMap<String, Value>) -> Vec<&str> {
+    fn visit<'a>(
+        name: &'a str,
+        defs: &'a BTreeMap<String, Value>,
+        visited: &mut BTreeSet<&'a str>,
+        order: &mut Vec<&'a str>,
+    ) {
+        if !visited.insert(name) {
+            return;
+        }
+        let mut refs = Vec::new();
+        collect_refs(&defs[name], &mut refs);
+        for dependency in refs {
+            visit(dependency, defs, visited, order);
+        }
+        order.push(name);
+    }
+
+    let mut visited = BTreeSet::new();
+    let mut order = Vec::with_capacity(defs.len());
+    for name in defs.keys() {
+        visit(name, defs, &mut visited, &mut order);
+    }
+    order
+}
+
+fn doc_comment(description: &str, indent: usize) -> String {
+    let pad = " ".repeat(indent);
+    if !description.contains('\n') {
+        return format!("{pad}/** {description} */\n");
+    }
+    let mut out = format!("{pad}/**\n");
+    for line in description.lines() {
+        if line.is_empty() {
+            out.push_str(&format!("{pad} *\n"));
+        } else {
+            out.push_str(&format!("{pad} * {line}\n"));
+        }
+    }
+    out.push_str(&format!("{pad} */\n"));
+    out
+}
+
+fn is_null_schema(schema: &Value) -> bool {
+    schema.get("type").and_then(Value::as_str) == Some("null")
+}
+
+fn is_nullable(schema: &Value) -> bool {
+    match schema.get("type") {
+        Some(Value::Array(types)) => types.iter().any(|ty| ty == "null"),
+        Some(ty) => ty == "null",
+        None => ["anyOf", "oneOf"].iter().any(|key| {
+            schema
+                .get(key)
+                .and_then(Value::as_array)
+                .is_some_and(|variants| variants.iter().any(is_null_schema))
+        }),
+    }
+}
+
+/// Renders a zod expression for `schema`, whose first line continues a line indented by
+/// `indent` spaces.
+fn zod(schema: &Value, indent: usize) -> String {
+    let Some(object) = schema.as_object() else {
+        return "z.unknown()".to_string();
+    };
+    if let Some(name) = object
+        .get("$ref")
+        .and_then(Value::as_str)
+        .and_then(|reference| reference.strip_prefix(DEFS_PREFIX))
+    {
+        return schema_ident(name);
+    }
+
+    let types = match object.get("type") {
+        Some(Value::String(ty)) => vec![ty.as_str()],
+        Some(Value::Array(types)) => types.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    };
+    let mut nullable = types.contains(&"null");
+    let types = types
+        .into_iter()
+        .filter(|ty| *ty != "null")
+        .collect::<Vec<_>>();
+
+    let rendered = if let Some(value) = object.get("const") {
+        format!("z.literal({value})")
+    } else if let Some(values) = object.get("enum").and_then(Value::as_array) {
+        zod_enum(values)
+    } else if let Some(variants) = object
+        .get("anyOf")
+        .or_else(|| object.get("oneOf"))
+        .and_then(Value::as_array)
+    {
+        let variants = variants
+            .iter()
+            .filter(|variant| {
+                let null = is_null_schema(variant);
+                nullable |= null;
+                !null
+            })
+            .collect::<Vec<_>>();
+        zod_union(&variants, indent)
+    } else {
+        match types.as_slice() {
+            ["object"] => zod_object(object, indent),
+            ["array"] => zod_array(object, indent),
+            ["string"] => zod_string(object),
+            ["integer"] => zod_number(object, "z.number().int()"),
+            ["number"] => zod_number(object, "z.number().finite()"),
+            ["boolean"] => "z.boolean()".to_string(),
+            [] if nullable => {
+                nullable = false;
+                "z.null()".to_string()
+            }
+            [] => "z.unknown()".to_string(),
+            _ => panic!("unsupported IPC schema: {schema}"),
+        }
+    };
+
+    if nullable {
+        rendered + ".nullable()"
+    } else {
+        rendered
+    }
+}
+
+fn zod_enum(values: &[Value]) -> String {
+    if values.iter().all(Value::is_string) {
+        let values = values.iter().map(Value::to_string).collect::<Vec<_>>();
+        return format!("z.enum([{}])", values.join(", "));
+    }
+    let literals = values
+        .iter()
+        .map(|value| format!("z.literal({})", number_literal(value)))
+        .collect::<Vec<_>>();
+    format!("z.union([{}])", literals.join(", "))
+}
+
+fn zod_union(variants: &[&Value], indent: usize) -> String {
+    if let [variant] = variants {
+        return zod(variant, indent);
+    }
+
+    let string_values = variants
+        .iter()
+        .map(|variant| {
+            variant
+                .get("const")
+                .cloned()
+                .map(|value| vec![value])
+                .or_else(|| variant.get("enum").and_then(Value::as_array).cloned())
+        })
+        .collect::<Option<Vec<_>>>()
+        .map(|values| values.concat())
+        .filter(|values| values.iter().all(Value::is_string));
+    if let Some(values) = string_values {
+        return zod_enum(&values);
+    }
+
+    let pad = " ".repeat(indent + 2);
+    let members = variants
+        .iter()
+        .map(|variant| format!("{pad}{},\n", zod(variant, indent + 2)))
+        .collect::<String>();
+    let close = " ".repeat(indent);
+    match discriminator(variants) {
+        Some(tag) => format!("z.discriminatedUnion(\"{tag}\", [\n{members}{close}])"),
+        None => format!("z.union([\n{members}{close}])"),
+    }
+}
+
+/// The property every variant pins to a constant, as serde writes internally tagged enums.
+fn discriminator<'a>(variants: &[&'a Value]) -> Option<&'a str> {
+    let first = variants.first()?.get("properties")?.as_object()?;
+    first.iter().find_map(|(key, value)| {
+        value.get("const")?;
+        variants
+            .iter()
+            .all(|variant| {
+                variant
+                    .get("properties")
+                    .and_then(|properties| properties.get(key))
+                    .is_some_and(|property| property.get("const").is_some())
+            })
+            .then_some(key.as_str())
+    })
+}
+
+fn zod_object(object: &Map<String, Value>, indent: usize) -> String {
+    let properties = object.get("properties").and_then(Value::as_object);
+    let additional = object.get("additionalProperties");
+    if properties.is_none() {
+        if let Some(values) = additional.filter(|value| value.is_object()) {
+            return format!("z.record(z.string(), {})", zod(values, indent));
+        }
+    }
+
+    let required = object
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|names| names.iter().filter_map(Value::as_str).collect::<Vec<_>>())
+        .unwrap_or_default();
+    let mut out = String::from("z.object({");
+    if let Some(properties) = properties.filter(|properties| !properties.is_empty()) {
+        let pad = " ".repeat(indent + 2);
+        out.push('\n');
+        for (key, property) in properties {
+            if let Some(description) = property.get("description").and_then(Value::as_str) {
+                out.push_str(&doc_comment(description, indent + 2));
+            }
+            let optional = if required.contains(&key.as_str()) {
+                ""
+            } else {
+                ".optional()"
+            };
+            out.push_str(&format!(
+                "{pad}{}: {}{optional},\n",
+                property_key(key),
+                zod(property, indent + 2)
+            ));
+        }
+        out.push_str(&" ".repeat(indent));
+    }
+    out.push_str("})");
+    if additional == Some(&Value::Bool(false)) {
+        out.push_str(".strict()");
+    }
+    out
+}
+
+fn property_key(key: &str) -> String {
+    let is_identifier = key
+        .chars()
+        .next()
+        .is_some_and(|ch| ch.is_ascii_alphabetic() || ch == '_' || ch == '$')
+        && key
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '$');
+    if is_identifier {
+        key.to_string()
+    } else {
+        Value::from(key).to_string()
+    }
+}
+
+fn zod_array(object: &Map<String, Value>, indent: usize) -> String {
+    let mut out = match object.get("prefixItems").and_then(Value::as_array) {
+        Some(items) => {
+            let items = items
+                .iter()
+                .map(|item| zod(item, indent))
+                .collect::<Vec<_>>();
+            format!("z.tuple([{}])", items.join(", "))
+        }
+        None => format!(
+            "z.array({})",
+            object
+                .get("items")
+                .map_or_else(|| "z.unknown()".to_string(), |items| zod(items, indent))
+        ),
+    };
+    if let Some(min) = object.get("minItems") {
+        out.push_str(&format!(".min({})", number_literal(min)));
+    }
+    if let Some(max) = object.get("maxItems") {
+        out.push_str(&format!(".max({})", number_literal(max)));
+    }
+    out
+}
+
+fn zod_string(object: &Map<String, Value>) -> String {
+    let mut out = String::from("z.string()");
+    if let Some(min) = object.get("minLength") {
+        out.push_str(&format!(".min({})", number_literal(min)));
+    }
+    if let Some(max) = object.get("maxLength") {
+        out.push_str(&format!(".max({})", number_literal(max)));
+    }
+    if let Some(pattern) = object.get("pattern").and_then(Value::as_str) {
+        out.push_str(&format!(".regex(/{}/)", pattern.replace('/', "\\/")));
+    }
+    out
+}
+
+fn zod_number(object: &Map<String, Value>, base: &str) -> String {
+    let mut out = base.to_string();
+    let is_zero = |value: &Value| value.as_f64() == Some(0.0);
+    if let Some(min) = object.get("exclusiveMinimum") {
+        if is_zero(min) {
+            out.push_str(".positive()");
+        } else {
+            out.push_str(&format!(".gt({})", number_literal(min)));
+        }
+    } else if let Some(min) = object.get("minimum") {
+        if is_zero(min) {
+            out.push_str(".nonnegative()");
+        } else {
+            out.push_str(&format!(".min({})", number_literal(min)));
+        }
+    }
+    if let Some(max) = object.get("exclusiveMaximum") {
+        out.push_str(&format!(".lt({})", number_literal(max)));
+    } else if let Some(max) = object.get("maximum") {
+        out.push_str(&format!(".max({})", number_literal(max)));
+    }
+    out
+}
+
+/// Writes whole numbers with `_` separators, as the hand-written frontend code does.
+fn number_literal(value: &Value) -> String {
+    let whole = value
+        .as_i64()
+        .map(i128::from)
+        .or_else(|| value.as_u64().map(i128::from))
+        .or_else(|| {
+            value
+                .as_f64()
+                .filter(|number| number.fract() == 0.0 && number.abs() < 1e15)
+                .map(|number| number as i128)
+        });
+    let Some(whole) = whole else {
+        return value.to_string();
+    };
+    let digits = whole.unsigned_abs().to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index) % 3 == 0 {
+            grouped.push('_');
+        }
+        grouped.push(digit);
+    }
+    if whole < 0 {
+        format!("-{grouped}")
+    } else {
+        grouped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn committed_bindings_are_up_to_date() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(BINDINGS_PATH);
+        let rendered = render_bindings();
+        if std::env::var_os("UPDATE_IPC_BINDINGS").is_some() {
+            std::fs::write(&path, &rendered).expect("write bindings");
+            return;
+        }
+
+        let committed = std::fs::read_to_string(&path)
+            .unwrap_or_default()
+            .replace("\r\n", "\n");
+        assert!(
+            committed == rendered,
+            "src/lib/ipc/bindings.ts is out of date with the Rust IPC types; regenerate it with \
+             `UPDATE_IPC_BINDINGS=1 cargo test bindings`"
+        );
+    }
+
+    #[test]
+    fn binds_every_registered_command() {
+        let lib = include_str!("lib.rs");
+        let handlers = lib
+            .split("generate_handler![")
+            .nth(1)
+            .and_then(|rest| rest.split(']').next())
+            .expect("invoke handler list");
+        let registered = handlers
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .collect::<Vec<_>>();
+        let bound = ipc_commands()
+            .iter()
+            .map(|command| command.name)
+            .collect::<Vec<_>>();
+        assert_eq!(registered, bound);
+    }
+
+    #[test]
+    fn renders_schema_keywords_as_zod() {
+        let schema = serde_json::json!({
+            "type": "object",
+            "properties": {
+                "limit": { "type": ["integer", "null"], "minimum": 1, "maximum": 50000 },
+                "side": { "type": "string", "enum": ["buy", "sell"] },
+                "id": { "type": "string", "pattern": "^a/b$" }
+            },
+            "required": ["side", "id"]
+        });
+        assert_eq!(
+            zod(&schema, 0),
+            "z.object({\n  id: z.string().regex(/^a\\/b$/),\n  \
+             limit: z.number().int().min(1).max(50_000).nullable().optional(),\n  \
+             side: z.enum([\"buy\", \"sell\"]),\n})"
+        );
+        assert_eq!(number_literal(&serde_json::json!(-3_600_000)), "-3_600_000");
+        assert_eq!(number_literal(&serde_json::json!(0.5)), "0.5");
+    }
+}
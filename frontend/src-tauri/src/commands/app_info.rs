@@ -1,13 +1,19 @@
+use schemars::JsonSchema;
 use serde::Serialize;
 use tauri::AppHandle;
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct AppInfoResponse {
+    #[schemars(length(min = 1))]
     pub product_name: String,
+    #[schemars(length(min = 1))]
     pub version: String,
+    #[schemars(length(min = 1))]
     pub identifier: String,
+    #[schemars(length(min = 1))]
     pub platform: String,
+    #[schemars(length(min = 1))]
     pub arch: String,
 }
 
@@ -1,14 +1,17 @@
 use crate::{error::AppError, state::AppState};
+use schemars::JsonSchema;
 use serde::Serialize;
 use sqlx::SqlitePool;
 use std::time::Instant;
 use tauri::State;
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct HealthResponse {
+    #[schemars(extend("const" = "ok"))]
     pub status: &'static str,
     pub uptime_ms: u128,
+    #[schemars(extend("enum" = ["ok", "error"]))]
     pub db: &'static str,
 }
 
@@ -0,0 +1,46 @@
+use crate::error::AppError;
+use crate::market::persistence::{
+    delete_market_alert, list_market_alert_triggers, list_market_alerts, upsert_market_alert,
+};
+use crate::market::types::{
+    MarketAlertDeleteArgs, MarketAlertDeleteResult, MarketAlertDto, MarketAlertTriggerDto,
+    MarketAlertTriggersArgs, MarketAlertUpsertArgs, MarketAlertsListArgs,
+};
+use crate::state::AppState;
+use tauri::State;
+
+#[tauri::command]
+pub async fn market_alerts_list(
+    state: State<'_, AppState>,
+    args: Option<MarketAlertsListArgs>,
+) -> Result<Vec<MarketAlertDto>, AppError> {
+    list_market_alerts(&state.db_pool, args.unwrap_or_default()).await
+}
+
+#[tauri::command]
+pub async fn market_alert_upsert(
+    state: State<'_, AppState>,
+    args: MarketAlertUpsertArgs,
+) -> Result<MarketAlertDto, AppError> {
+    let alert = upsert_market_alert(&state.db_pool, args).await?;
+    state.alert_engine.reload().await?;
+    Ok(alert)
+}
+
+#[tauri::command]
+pub async fn market_alert_delete(
+    state: State<'_, AppState>,
+    args: MarketAlertDeleteArgs,
+) -> Result<MarketAlertDeleteResult, AppError> {
+    let result = delete_market_alert(&state.db_pool, args).await?;
+    state.alert_engine.reload().await?;
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn market_alert_triggers_list(
+    state: State<'_, AppState>,
+    args: Option<MarketAlertTriggersArgs>,
+) -> Result<Vec<MarketAlertTriggerDto>, AppError> {
+    list_market_alert_triggers(&state.db_pool, args.unwrap_or_default()).await
+}
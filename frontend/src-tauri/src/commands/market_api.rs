@@ -0,0 +1,30 @@
+use crate::error::AppError;
+use crate::market::local_api::MarketApiContext;
+use crate::market::types::{MarketApiStartArgs, UiMarketApiStatus};
+use crate::state::AppState;
+use std::sync::Arc;
+use tauri::State;
+
+#[tauri::command]
+pub async fn market_api_start(
+    state: State<'_, AppState>,
+    args: Option<MarketApiStartArgs>,
+) -> Result<UiMarketApiStatus, AppError> {
+    let port = args.unwrap_or_default().normalize()?;
+    let context = MarketApiContext {
+        db_pool: state.db_pool.clone(),
+        status_store: Arc::clone(&state.market_status),
+        trade_tape: Arc::clone(&state.trade_tape),
+    };
+    state.market_api.start(port, context).await
+}
+
+#[tauri::command]
+pub async fn market_api_stop(state: State<'_, AppState>) -> Result<UiMarketApiStatus, AppError> {
+    Ok(state.market_api.stop().await)
+}
+
+#[tauri::command]
+pub async fn market_api_status(state: State<'_, AppState>) -> Result<UiMarketApiStatus, AppError> {
+    Ok(state.market_api.status().await)
+}
@@ -0,0 +1,102 @@
+use crate::commands::market_stream::recording_stream_id;
+use crate::error::AppError;
+use crate::market::archive_import::{import_market_archives, MarketArchive, MarketArchiveKind};
+use crate::market::export::export_market_data;
+use crate::market::recorder::recordings_root;
+use crate::market::types::{
+    MarketExportArgs, MarketImportArchivesArgs, MarketKind, UiArchiveImportResult, UiExportResult,
+};
+use crate::state::AppState;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tauri::{AppHandle, State};
+
+#[tauri::command]
+pub async fn market_import_archives(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    args: MarketImportArchivesArgs,
+) -> Result<UiArchiveImportResult, AppError> {
+    let args = args.normalize()?;
+    let archives = args
+        .paths
+        .into_iter()
+        .map(MarketArchive::from_path)
+        .collect::<Result<Vec<_>, _>>()?;
+    let trade_series = archives
+        .iter()
+        .filter(|archive| archive.kind == MarketArchiveKind::AggTrades)
+        .map(|archive| (args.market_kind, archive.symbol.clone()))
+        .collect();
+    let _imports = TradeImportGuard::acquire(&state, trade_series).await?;
+
+    import_market_archives(
+        &app,
+        &state.db_pool,
+        &recordings_root(&app)?,
+        args.market_kind,
+        archives,
+    )
+    .await
+}
+
+/// Holds the series an import writes trade recordings for, so no recorder starts on them
+/// until the import finishes.
+struct TradeImportGuard<'a> {
+    imports: &'a parking_lot::Mutex<HashSet<(MarketKind, String)>>,
+    series: HashSet<(MarketKind, String)>,
+}
+
+impl<'a> TradeImportGuard<'a> {
+    async fn acquire(
+        state: &'a AppState,
+        series: HashSet<(MarketKind, String)>,
+    ) -> Result<Self, AppError> {
+        let streams = state.market_streams.lock().await;
+        let mut imports = state.trade_imports.lock();
+        for (market_kind, symbol) in &series {
+            if let Some(stream_id) = recording_stream_id(&streams, *market_kind, symbol) {
+                return Err(AppError::Conflict(format!(
+                    "stream '{stream_id}' is recording {symbol}; stop it before importing its trades"
+                )));
+            }
+            if imports.contains(&(*market_kind, symbol.clone())) {
+                return Err(AppError::Conflict(format!(
+                    "{symbol} trades are already being imported"
+                )));
+            }
+        }
+        imports.extend(series.iter().cloned());
+        Ok(Self {
+            imports: &state.trade_imports,
+            series,
+        })
+    }
+}
+
+impl Drop for TradeImportGuard<'_> {
+    fn drop(&mut self) {
+        let mut imports = self.imports.lock();
+        for series in &self.series {
+            imports.remove(series);
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn market_export(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    args: MarketExportArgs,
+) -> Result<UiExportResult, AppError> {
+    let request = args.normalize()?;
+    let recordings_root = recordings_root(&app)?;
+    export_market_data(
+        Arc::new(app),
+        &state.db_pool,
+        &state.binance,
+        &recordings_root,
+        request,
+    )
+    .await
+}
@@ -38,7 +38,9 @@ pub async fn market_drawing_upsert(
     state: State<'_, AppState>,
     args: MarketDrawingUpsertArgs,
 ) -> Result<MarketDrawingDto, AppError> {
-    upsert_market_drawing(&state.db_pool, args).await
+    let drawing = upsert_market_drawing(&state.db_pool, args).await?;
+    state.alert_engine.reload().await?;
+    Ok(drawing)
 }
 
 #[tauri::command]
@@ -46,5 +48,7 @@ pub async fn market_drawing_delete(
     state: State<'_, AppState>,
     args: MarketDrawingDeleteArgs,
 ) -> Result<MarketDrawingDeleteResult, AppError> {
-    delete_market_drawing(&state.db_pool, args).await
+    let result = delete_market_drawing(&state.db_pool, args).await?;
+    state.alert_engine.reload().await?;
+    Ok(result)
 }
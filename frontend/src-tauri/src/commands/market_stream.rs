@@ -1,61 +1,258 @@
 use crate::error::AppError;
 use crate::market::binance::{fetch_market_symbols, fetch_spot_symbols};
-use crate::market::pipeline::run_market_stream;
+use crate::market::kline_store::load_history_page;
+use crate::market::pipeline::{now_unix_ms, run_market_stream, snapshot_chart_state};
+use crate::market::recorder::{recordings_root, RecordedTradeCursor, TradeRecorder};
+use crate::market::replay::ReplaySource;
+use crate::market::sink::{FanoutEventSink, MarketEventSink};
+use crate::market::tape::TradeTape;
 use crate::market::types::{
-    MarketConnectionState, MarketStreamSession, MarketStreamStatusSnapshot, MarketStreamStopResult,
-    MarketSymbolsArgs, StartMarketStreamArgs,
+    normalize_stream_id, MarketConnectionState, MarketEventSeq, MarketFrameAckArgs,
+    MarketFrameAckResult, MarketHistoryAckArgs, MarketHistoryAckResult, MarketHistoryPageArgs,
+    MarketKind, MarketReplayControlArgs, MarketStreamConfig, MarketStreamSelectArgs,
+    MarketStreamSession, MarketStreamSnapshotArgs, MarketStreamStatusSnapshot,
+    MarketStreamStopResult, MarketSymbolsArgs, MarketTradesRecentArgs, StartMarketStreamArgs,
+    UiHistoryPage, UiMarketStreamSnapshot, UiReplayStatus, UiTradesSnapshot,
+    DEFAULT_MARKET_STREAM_ID, DEFAULT_SYMBOL,
 };
-use crate::state::{AppState, MarketStreamHandle};
-use reqwest::Client;
+use crate::market::windows::WindowRouterSink;
+use crate::state::{AppState, MarketStreamEntry, MarketStreamHandle};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use tauri::{AppHandle, State};
+use tauri::ipc::{InvokeResponseBody, JavaScriptChannelId};
+use tauri::{AppHandle, State, Webview};
 use tokio_util::sync::CancellationToken;
 
 #[tauri::command]
 pub async fn start_market_stream(
     app: AppHandle,
     state: State<'_, AppState>,
+    webview: Webview,
     args: Option<StartMarketStreamArgs>,
+    history_channel: Option<JavaScriptChannelId>,
 ) -> Result<MarketStreamSession, AppError> {
+    // `Channel` is only an argument when required; an optional one arrives as its id.
+    let history_channel = history_channel.map(|id| id.channel_on::<_, InvokeResponseBody>(webview));
     let config = args.unwrap_or_default().normalize()?;
+    if config.history_all && history_channel.is_none() {
+        return Err(AppError::InvalidArgument(
+            "historyAll requires a history channel".to_string(),
+        ));
+    }
+    let mut sinks: Vec<Arc<dyn MarketEventSink>> = vec![Arc::new(WindowRouterSink::new(
+        app.clone(),
+        Arc::clone(&state.window_subscriptions),
+        &config.stream_id,
+        config.timeframe,
+    ))];
+    if config.stream_id == DEFAULT_MARKET_STREAM_ID {
+        sinks.push(state.market_api.clone());
+    }
+    let events: Arc<dyn MarketEventSink> = Arc::new(FanoutEventSink::new(sinks));
 
-    let existing_handle = {
-        let mut stream_slot = state.market_stream.lock().await;
-        stream_slot.take()
+    let session = MarketStreamSession::from_config(&config);
+    let (
+        existing_handle,
+        status_store,
+        chart_state,
+        event_sequence,
+        frame_acks,
+        history_acks,
+        trade_tape,
+    ) = {
+        let mut streams = state.market_streams.lock().await;
+        check_stream_conflicts(&streams, &state.trade_imports.lock(), &config)?;
+        let entry = streams
+            .entry(config.stream_id.clone())
+            .or_insert_with(MarketStreamEntry::idle);
+        // Reserved under the same lock as the checks so concurrent starts and imports see
+        // this session while the previous task stops and the recorder opens.
+        entry.starting = Some(session.clone());
+        (
+            entry.handle.take(),
+            Arc::clone(&entry.status),
+            Arc::clone(&entry.chart_state),
+            Arc::clone(&entry.event_sequence),
+            Arc::clone(&entry.frame_acks),
+            Arc::clone(&entry.history_acks),
+            Arc::clone(&entry.trade_tape),
+        )
     };
     if let Some(handle) = existing_handle {
         handle.cancellation_token.cancel();
         let _ = handle.join_handle.await;
     }
 
+    let (trade_recorder, replay_source) = match open_trade_sources(&app, &state, &config) {
+        Ok(sources) => sources,
+        Err(error) => {
+            if let Some(entry) = state.market_streams.lock().await.get_mut(&config.stream_id) {
+                entry.starting = None;
+            }
+            return Err(error);
+        }
+    };
     let cancellation_token = CancellationToken::new();
     let task_token = cancellation_token.clone();
-    let status_store = Arc::clone(&state.market_status);
     let runtime_config = config.clone();
-    let app_handle = app.clone();
+    let alert_engine = Some(Arc::clone(&state.alert_engine));
+    let webhooks = Arc::clone(&state.webhooks);
+    let db_pool = state.db_pool.clone();
+    let binance = state.binance.clone();
+    let history_sink = history_channel.map(|channel| history_acks.begin(channel));
 
     let join_handle = tauri::async_runtime::spawn(async move {
-        run_market_stream(app_handle, runtime_config, status_store, task_token).await;
+        run_market_stream(
+            events,
+            runtime_config,
+            db_pool,
+            binance,
+            status_store,
+            chart_state,
+            event_sequence,
+            frame_acks,
+            alert_engine,
+            webhooks,
+            trade_tape,
+            trade_recorder,
+            history_sink,
+            replay_source,
+            task_token,
+        )
+        .await;
     });
 
     {
-        let mut stream_slot = state.market_stream.lock().await;
-        *stream_slot = Some(MarketStreamHandle {
+        let mut streams = state.market_streams.lock().await;
+        let entry = streams
+            .entry(config.stream_id.clone())
+            .or_insert_with(MarketStreamEntry::idle);
+        entry.starting = None;
+        entry.handle = Some(MarketStreamHandle {
             cancellation_token,
             join_handle,
+            session: session.clone(),
         });
     }
 
-    Ok(MarketStreamSession::from_config(&config))
+    Ok(session)
+}
+
+/// Opens the trade recorder or the replay cursor the stream needs, if any.
+fn open_trade_sources(
+    app: &AppHandle,
+    state: &AppState,
+    config: &MarketStreamConfig,
+) -> Result<(Option<TradeRecorder>, Option<ReplaySource>), AppError> {
+    let trade_recorder = if config.record_trades && !config.mock_mode && config.replay.is_none() {
+        Some(TradeRecorder::start(
+            &recordings_root(app)?,
+            config.market_kind,
+            &config.symbol,
+        )?)
+    } else {
+        None
+    };
+    let replay_source = match &config.replay {
+        Some(replay) => {
+            let cursor = RecordedTradeCursor::open(
+                &recordings_root(app)?,
+                config.market_kind,
+                &config.symbol,
+            )?;
+            state.replay_control.begin(replay);
+            Some(ReplaySource {
+                cursor,
+                control: Arc::clone(&state.replay_control),
+            })
+        }
+        None => None,
+    };
+    Ok((trade_recorder, replay_source))
+}
+
+/// Trade recordings and the replay controller are shared, so only one running stream may
+/// record a given series, none while an archive import rewrites its recordings, and only one
+/// may replay at a time. Starting streams count as running, and a stream id starts once at a
+/// time.
+fn check_stream_conflicts(
+    streams: &HashMap<String, MarketStreamEntry>,
+    trade_imports: &HashSet<(MarketKind, String)>,
+    config: &MarketStreamConfig,
+) -> Result<(), AppError> {
+    let records = config.record_trades && !config.mock_mode && config.replay.is_none();
+    if records && trade_imports.contains(&(config.market_kind, config.symbol.clone())) {
+        return Err(AppError::Conflict(format!(
+            "an archive import is writing {} recordings",
+            config.symbol
+        )));
+    }
+    for (stream_id, entry) in streams {
+        if *stream_id == config.stream_id {
+            if entry.starting.is_some() {
+                return Err(AppError::Conflict(format!(
+                    "stream '{stream_id}' is already starting"
+                )));
+            }
+            continue;
+        }
+        let Some(running) = entry.session() else {
+            continue;
+        };
+        if records && is_recording(running, config.market_kind, &config.symbol) {
+            return Err(AppError::Conflict(format!(
+                "stream '{stream_id}' is already recording {}",
+                config.symbol
+            )));
+        }
+        if config.replay.is_some() && running.replay.is_some() {
+            return Err(AppError::Conflict(format!(
+                "stream '{stream_id}' is already replaying"
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn is_recording(session: &MarketStreamSession, market_kind: MarketKind, symbol: &str) -> bool {
+    session.record_trades
+        && !session.mock_mode
+        && session.replay.is_none()
+        && session.market_kind == market_kind
+        && session.symbol == symbol
+}
+
+/// Id of the running or starting stream recording the series, if any.
+pub(crate) fn recording_stream_id<'a>(
+    streams: &'a HashMap<String, MarketStreamEntry>,
+    market_kind: MarketKind,
+    symbol: &str,
+) -> Option<&'a str> {
+    streams.iter().find_map(|(stream_id, entry)| {
+        let session = entry.session()?;
+        is_recording(session, market_kind, symbol).then_some(stream_id.as_str())
+    })
 }
 
 #[tauri::command]
 pub async fn stop_market_stream(
     state: State<'_, AppState>,
+    args: Option<MarketStreamSelectArgs>,
 ) -> Result<MarketStreamStopResult, AppError> {
-    let existing_handle = {
-        let mut stream_slot = state.market_stream.lock().await;
-        stream_slot.take()
+    let stream_id = args.unwrap_or_default().normalize()?;
+    let stopped = stop_stream(&state, &stream_id).await;
+    Ok(MarketStreamStopResult { stopped })
+}
+
+/// Stops `stream_id` if it is running and marks its status as stopped. Returns whether a
+/// running stream was stopped.
+pub(crate) async fn stop_stream(state: &AppState, stream_id: &str) -> bool {
+    let (existing_handle, status_store) = {
+        let mut streams = state.market_streams.lock().await;
+        match streams.get_mut(stream_id) {
+            Some(entry) => (entry.handle.take(), Some(Arc::clone(&entry.status))),
+            None => (None, None),
+        }
     };
 
     let stopped = if let Some(handle) = existing_handle {
@@ -66,16 +263,16 @@ pub async fn stop_market_stream(
         false
     };
 
-    {
+    if let Some(status_store) = status_store {
         let (current_market_kind, current_symbol, current_timeframe) = {
-            let readable = state.market_status.read().await;
+            let readable = status_store.read().await;
             (
                 readable.market_kind,
                 readable.symbol.clone(),
                 readable.timeframe,
             )
         };
-        let mut writable = state.market_status.write().await;
+        let mut writable = status_store.write().await;
         *writable = MarketStreamStatusSnapshot {
             state: MarketConnectionState::Stopped,
             market_kind: current_market_kind,
@@ -91,25 +288,221 @@ pub async fn stop_market_stream(
         };
     }
 
-    Ok(MarketStreamStopResult { stopped })
+    stopped
 }
 
 #[tauri::command]
 pub async fn market_stream_status(
     state: State<'_, AppState>,
+    args: Option<MarketStreamSelectArgs>,
 ) -> Result<MarketStreamStatusSnapshot, AppError> {
-    let snapshot = state.market_status.read().await.clone();
+    let stream_id = args.unwrap_or_default().normalize()?;
+    let status_store = state
+        .market_streams
+        .lock()
+        .await
+        .get(&stream_id)
+        .map(|entry| Arc::clone(&entry.status));
+    let snapshot = match status_store {
+        Some(status_store) => status_store.read().await.clone(),
+        None => MarketStreamStatusSnapshot::stopped(
+            DEFAULT_SYMBOL.to_string(),
+            Some("stream idle".to_string()),
+        ),
+    };
     Ok(snapshot)
 }
 
+/// Sessions of every running stream, ordered by stream id.
+#[tauri::command]
+pub async fn market_streams_list(
+    state: State<'_, AppState>,
+) -> Result<Vec<MarketStreamSession>, AppError> {
+    let mut sessions: Vec<MarketStreamSession> = state
+        .market_streams
+        .lock()
+        .await
+        .values()
+        .filter_map(|entry| entry.handle.as_ref().map(|handle| handle.session.clone()))
+        .collect();
+    sessions.sort_by(|left, right| left.stream_id.cmp(&right.stream_id));
+    Ok(sessions)
+}
+
+/// Chart state of a stream for a reloaded webview. A stopped stream returns whatever it had
+/// built when it stopped.
 #[tauri::command]
-pub async fn market_symbols(args: MarketSymbolsArgs) -> Result<Vec<String>, AppError> {
-    let client = Client::new();
-    fetch_market_symbols(&client, args.market_kind).await
+pub async fn market_stream_snapshot(
+    state: State<'_, AppState>,
+    args: Option<MarketStreamSnapshotArgs>,
+) -> Result<UiMarketStreamSnapshot, AppError> {
+    let query = args.unwrap_or_default().normalize()?;
+    let (session, status_store, chart_state, last_seq) = {
+        let streams = state.market_streams.lock().await;
+        match streams.get(&query.stream_id) {
+            // The sequence is read before the chart, so no event after it can be missing from
+            // the chart state.
+            Some(entry) => (
+                entry.handle.as_ref().map(|handle| handle.session.clone()),
+                Some(Arc::clone(&entry.status)),
+                Some(Arc::clone(&entry.chart_state)),
+                entry.event_sequence.last(),
+            ),
+            None => (None, None, None, None),
+        }
+    };
+    let status = match status_store {
+        Some(status_store) => status_store.read().await.clone(),
+        None => MarketStreamStatusSnapshot::stopped(
+            DEFAULT_SYMBOL.to_string(),
+            Some("stream idle".to_string()),
+        ),
+    };
+    let chart = chart_state
+        .map(|chart_state| snapshot_chart_state(&chart_state.lock(), query.history_limit))
+        .unwrap_or_default();
+
+    Ok(UiMarketStreamSnapshot {
+        session,
+        status,
+        last_seq,
+        last_agg_id: chart.last_agg_id,
+        last_price: chart.last_price,
+        last_candle: chart.last_candle,
+        last_delta_candle: chart.last_delta_candle,
+        candles: chart.candles,
+        delta_candles: chart.delta_candles,
+    })
 }
 
+/// Records that the calling window applied history chunks up to `seq` of its stream's
+/// current bootstrap.
 #[tauri::command]
-pub async fn market_spot_symbols() -> Result<Vec<String>, AppError> {
-    let client = Client::new();
-    fetch_spot_symbols(&client).await
+pub async fn market_history_ack(
+    state: State<'_, AppState>,
+    args: MarketHistoryAckArgs,
+) -> Result<MarketHistoryAckResult, AppError> {
+    let stream_id = normalize_stream_id(args.stream_id)?;
+    let history_acks = state
+        .market_streams
+        .lock()
+        .await
+        .get(&stream_id)
+        .map(|entry| Arc::clone(&entry.history_acks));
+    let accepted =
+        history_acks.is_some_and(|history_acks| history_acks.ack(args.bootstrap_id, args.seq));
+    Ok(MarketHistoryAckResult { accepted })
+}
+
+/// Records that the calling window applied frames up to `seq`. Acks for a stream that is not
+/// running, or older than one already recorded, are not accepted.
+#[tauri::command]
+pub async fn market_frame_ack(
+    state: State<'_, AppState>,
+    args: MarketFrameAckArgs,
+) -> Result<MarketFrameAckResult, AppError> {
+    let stream_id = normalize_stream_id(args.stream_id)?;
+    let frame_acks = state
+        .market_streams
+        .lock()
+        .await
+        .get(&stream_id)
+        .filter(|entry| entry.handle.is_some())
+        .map(|entry| Arc::clone(&entry.frame_acks));
+    let accepted = frame_acks.is_some_and(|frame_acks| {
+        frame_acks.ack(MarketEventSeq {
+            generation: args.generation,
+            seq: args.seq,
+        })
+    });
+    Ok(MarketFrameAckResult { accepted })
+}
+
+#[tauri::command]
+pub async fn market_replay_control(
+    state: State<'_, AppState>,
+    args: MarketReplayControlArgs,
+) -> Result<UiReplayStatus, AppError> {
+    state.replay_control.apply(args.normalize()?)
+}
+
+#[tauri::command]
+pub async fn market_history_page(
+    state: State<'_, AppState>,
+    args: MarketHistoryPageArgs,
+) -> Result<UiHistoryPage, AppError> {
+    let query = args.normalize()?;
+    load_history_page(&state.db_pool, &state.binance, &query, now_unix_ms()).await
+}
+
+#[tauri::command]
+pub async fn market_trades_recent(
+    state: State<'_, AppState>,
+    args: Option<MarketTradesRecentArgs>,
+) -> Result<UiTradesSnapshot, AppError> {
+    let args = args.unwrap_or_default();
+    let stream_id = normalize_stream_id(args.stream_id.clone())?;
+    let query = args.normalize()?;
+    let trade_tape = state
+        .market_streams
+        .lock()
+        .await
+        .get(&stream_id)
+        .map(|entry| Arc::clone(&entry.trade_tape));
+    let snapshot = match trade_tape {
+        Some(trade_tape) => trade_tape.lock().recent(&query),
+        None => TradeTape::default().recent(&query),
+    };
+    Ok(snapshot)
+}
+
+#[tauri::command]
+pub async fn market_symbols(
+    state: State<'_, AppState>,
+    args: MarketSymbolsArgs,
+) -> Result<Vec<String>, AppError> {
+    fetch_market_symbols(&state.binance, args.market_kind).await
+}
+
+#[tauri::command]
+pub async fn market_spot_symbols(state: State<'_, AppState>) -> Result<Vec<String>, AppError> {
+    fetch_spot_symbols(&state.binance).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recording_config(stream_id: &str) -> MarketStreamConfig {
+        StartMarketStreamArgs {
+            stream_id: Some(stream_id.to_string()),
+            symbol: Some("BTCUSDT".to_string()),
+            record_trades: Some(true),
+            ..Default::default()
+        }
+        .normalize()
+        .expect("recording config")
+    }
+
+    #[test]
+    fn a_starting_stream_holds_its_series_and_stream_id() {
+        let starting = recording_config("main");
+        let mut entry = MarketStreamEntry::idle();
+        entry.starting = Some(MarketStreamSession::from_config(&starting));
+        let streams = HashMap::from([("main".to_string(), entry)]);
+        let imports = HashSet::new();
+
+        assert!(matches!(
+            check_stream_conflicts(&streams, &imports, &recording_config("second")),
+            Err(AppError::Conflict(_))
+        ));
+        assert!(matches!(
+            check_stream_conflicts(&streams, &imports, &starting),
+            Err(AppError::Conflict(_))
+        ));
+        assert_eq!(
+            recording_stream_id(&streams, starting.market_kind, &starting.symbol),
+            Some("main")
+        );
+    }
 }
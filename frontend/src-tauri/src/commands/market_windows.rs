@@ -0,0 +1,98 @@
+use crate::commands::market_stream::stop_stream;
+use crate::error::AppError;
+use crate::market::types::{
+    MarketWindowOpenArgs, MarketWindowSubscribeArgs, MarketWindowSubscription, UiMarketWindow,
+    DEFAULT_MARKET_STREAM_ID,
+};
+use crate::state::AppState;
+use tauri::{AppHandle, State, WebviewUrl, WebviewWindow, WebviewWindowBuilder};
+
+const CHART_WINDOW_WIDTH: f64 = 1280.0;
+const CHART_WINDOW_HEIGHT: f64 = 800.0;
+
+/// Opens a detached chart window. The window is told which stream to show through its URL
+/// (`?streamId=...&timeframe=...`), and it only receives events from its subscriptions.
+#[tauri::command]
+pub async fn market_window_open(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    args: Option<MarketWindowOpenArgs>,
+) -> Result<UiMarketWindow, AppError> {
+    let request = args.unwrap_or_default().normalize()?;
+    let label = state.window_subscriptions.next_label();
+    let subscriptions = if request.subscriptions.is_empty() {
+        vec![MarketWindowSubscription {
+            stream_id: label.clone(),
+            timeframe: None,
+        }]
+    } else {
+        request.subscriptions
+    };
+
+    let primary = &subscriptions[0];
+    let mut url = format!("index.html?streamId={}", primary.stream_id);
+    if let Some(timeframe) = primary.timeframe {
+        url.push_str("&timeframe=");
+        url.push_str(timeframe.as_str());
+    }
+    let title = request
+        .title
+        .unwrap_or_else(|| format!("Trading · {}", primary.stream_id));
+
+    // Subscribe before the window exists so it cannot miss the first frames.
+    let window = state.window_subscriptions.set(&label, subscriptions);
+    let built = WebviewWindowBuilder::new(&app, &label, WebviewUrl::App(url.into()))
+        .title(title)
+        .inner_size(CHART_WINDOW_WIDTH, CHART_WINDOW_HEIGHT)
+        .build();
+    if let Err(error) = built {
+        state.window_subscriptions.remove(&label);
+        return Err(error.into());
+    }
+
+    Ok(window)
+}
+
+/// Replaces the subscriptions of the calling window.
+#[tauri::command]
+pub async fn market_window_subscribe(
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    args: MarketWindowSubscribeArgs,
+) -> Result<UiMarketWindow, AppError> {
+    let subscriptions = args.normalize()?;
+    Ok(state
+        .window_subscriptions
+        .set(window.label(), subscriptions))
+}
+
+#[tauri::command]
+pub async fn market_windows_list(
+    state: State<'_, AppState>,
+) -> Result<Vec<UiMarketWindow>, AppError> {
+    Ok(state.window_subscriptions.list())
+}
+
+/// Drops a closed window's subscriptions and stops the detached streams nobody watches any
+/// more. The main stream is left to `stop_market_stream`.
+pub(crate) async fn release_window(state: &AppState, label: &str) {
+    if !state.window_subscriptions.remove(label) {
+        return;
+    }
+
+    let orphaned: Vec<String> = state
+        .market_streams
+        .lock()
+        .await
+        .iter()
+        .filter(|(stream_id, entry)| {
+            entry.handle.is_some()
+                && stream_id.as_str() != DEFAULT_MARKET_STREAM_ID
+                && !state.window_subscriptions.is_watched(stream_id)
+        })
+        .map(|(stream_id, _)| stream_id.clone())
+        .collect();
+    for stream_id in orphaned {
+        stop_stream(state, &stream_id).await;
+    }
+}
@@ -1,4 +1,9 @@
 pub mod app_info;
 pub mod health;
+pub mod market_alerts;
+pub mod market_api;
+pub mod market_data;
 pub mod market_preferences;
 pub mod market_stream;
+pub mod market_windows;
+pub mod webhooks;
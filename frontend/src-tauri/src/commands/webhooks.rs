@@ -0,0 +1,55 @@
+use crate::error::AppError;
+use crate::market::persistence::{
+    delete_webhook_target, list_webhook_targets, upsert_webhook_target,
+};
+use crate::market::pipeline::now_unix_ms;
+use crate::market::types::{
+    WebhookOutboxRetryResult, WebhookOutboxSummary, WebhookTargetDeleteArgs,
+    WebhookTargetDeleteResult, WebhookTargetDto, WebhookTargetUpsertArgs,
+};
+use crate::market::webhooks::{requeue_failed_webhooks, webhook_outbox_summary as outbox_summary};
+use crate::state::AppState;
+use tauri::State;
+
+#[tauri::command]
+pub async fn webhook_targets_list(
+    state: State<'_, AppState>,
+) -> Result<Vec<WebhookTargetDto>, AppError> {
+    list_webhook_targets(&state.db_pool).await
+}
+
+#[tauri::command]
+pub async fn webhook_target_upsert(
+    state: State<'_, AppState>,
+    args: WebhookTargetUpsertArgs,
+) -> Result<WebhookTargetDto, AppError> {
+    let target = upsert_webhook_target(&state.db_pool, args).await?;
+    state.webhooks.wake();
+    Ok(target)
+}
+
+#[tauri::command]
+pub async fn webhook_target_delete(
+    state: State<'_, AppState>,
+    args: WebhookTargetDeleteArgs,
+) -> Result<WebhookTargetDeleteResult, AppError> {
+    delete_webhook_target(&state.db_pool, args).await
+}
+
+#[tauri::command]
+pub async fn webhook_outbox_summary(
+    state: State<'_, AppState>,
+) -> Result<WebhookOutboxSummary, AppError> {
+    outbox_summary(&state.db_pool).await
+}
+
+#[tauri::command]
+pub async fn webhook_outbox_retry_failed(
+    state: State<'_, AppState>,
+) -> Result<WebhookOutboxRetryResult, AppError> {
+    let requeued = requeue_failed_webhooks(&state.db_pool, now_unix_ms()).await?;
+    if requeued > 0 {
+        state.webhooks.wake();
+    }
+    Ok(WebhookOutboxRetryResult { requeued })
+}
@@ -14,10 +14,15 @@ fn resolve_db_filename() -> String {
 }
 
 fn resolve_db_path(app_handle: &AppHandle) -> Result<PathBuf, AppError> {
-    let mut base_dir = app_handle.path().app_data_dir()?;
+    let base_dir = app_handle.path().app_data_dir()?;
     std::fs::create_dir_all(&base_dir)?;
-    base_dir.push(resolve_db_filename());
-    Ok(base_dir)
+    Ok(db_path_in(&base_dir))
+}
+
+/// Database file inside an app data directory, so headless tools open the same cache as the
+/// desktop app.
+pub fn db_path_in(data_dir: &Path) -> PathBuf {
+    data_dir.join(resolve_db_filename())
 }
 
 pub async fn run_migrations(pool: &SqlitePool) -> Result<(), AppError> {
@@ -1,10 +1,47 @@
-use serde::ser::Serializer;
+use schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
+use serde::ser::{SerializeStruct, Serializer};
+use serde::Serialize;
+use std::borrow::Cow;
 use thiserror::Error;
 
+/// Every value [`AppError::code`] returns.
+pub const APP_ERROR_CODES: [&str; 21] = [
+    "invalid_argument",
+    "invalid_symbol",
+    "rate_limited",
+    "http_status",
+    "database",
+    "migration",
+    "io",
+    "network",
+    "websocket",
+    "json_decode",
+    "parse",
+    "archive",
+    "parquet",
+    "window_not_found",
+    "conflict",
+    "unavailable",
+    "timeout",
+    "cancelled",
+    "corrupt_data",
+    "internal",
+    "runtime",
+];
+
 #[derive(Debug, Error)]
 pub enum AppError {
     #[error("invalid argument: {0}")]
     InvalidArgument(String),
+    #[error("invalid symbol: {0}")]
+    InvalidSymbol(String),
+    #[error("rate limited (HTTP {status})")]
+    RateLimited {
+        status: u16,
+        retry_after_ms: Option<u64>,
+    },
+    #[error("http status {status}: {message}")]
+    HttpStatus { status: u16, message: String },
     #[error("database error: {0}")]
     Sqlx(#[from] sqlx::Error),
     #[error("migration error: {0}")]
@@ -19,8 +56,29 @@ pub enum AppError {
     SimdJson(#[from] simd_json::Error),
     #[error("float parse error: {0}")]
     ParseFloat(#[from] std::num::ParseFloatError),
+    #[error("archive error: {0}")]
+    Archive(#[from] zip::result::ZipError),
+    #[error("parquet error: {0}")]
+    Parquet(#[from] parquet::errors::ParquetError),
     #[error("window not found: {0}")]
     WindowNotFound(String),
+    /// The request clashes with what is already running, such as a second recorder.
+    #[error("conflict: {0}")]
+    Conflict(String),
+    /// Something the request depends on is not ready yet.
+    #[error("unavailable: {0}")]
+    Unavailable(String),
+    #[error("timed out: {0}")]
+    Timeout(String),
+    /// The work was stopped or replaced before it finished.
+    #[error("cancelled: {0}")]
+    Cancelled(String),
+    /// Stored data failed its integrity checks.
+    #[error("corrupt data: {0}")]
+    Corrupt(String),
+    /// A failure inside the app, such as a background task that panicked.
+    #[error("internal error: {0}")]
+    Internal(String),
     #[error("runtime error: {0}")]
     Tauri(#[from] tauri::Error),
 }
@@ -31,11 +89,239 @@ impl From<tokio_tungstenite::tungstenite::Error> for AppError {
     }
 }
 
+/// Variant-specific context serialized next to the error code. Absent fields are omitted.
+#[derive(Debug, Clone, Default, Serialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AppErrorDetails {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_after_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+impl AppError {
+    /// Maps a non-success HTTP status. 429 and 418 (Binance's ban after ignoring 429s) are rate
+    /// limits; `retry_after` is the raw `Retry-After` header, in seconds.
+    pub fn from_http_status(status: u16, retry_after: Option<&str>, message: String) -> Self {
+        if status == 429 || status == 418 {
+            let retry_after_ms = retry_after
+                .and_then(|value| value.trim().parse::<u64>().ok())
+                .map(|seconds| seconds.saturating_mul(1_000));
+            return Self::RateLimited {
+                status,
+                retry_after_ms,
+            };
+        }
+        Self::HttpStatus { status, message }
+    }
+
+    /// Stable identifier of the variant, which the frontend matches on instead of the message.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::InvalidArgument(_) => "invalid_argument",
+            Self::InvalidSymbol(_) => "invalid_symbol",
+            Self::RateLimited { .. } => "rate_limited",
+            Self::HttpStatus { .. } => "http_status",
+            Self::Sqlx(_) => "database",
+            Self::Migration(_) => "migration",
+            Self::Io(_) => "io",
+            Self::Reqwest(_) => "network",
+            Self::WebSocket(_) => "websocket",
+            Self::SimdJson(_) => "json_decode",
+            Self::ParseFloat(_) => "parse",
+            Self::Archive(_) => "archive",
+            Self::Parquet(_) => "parquet",
+            Self::WindowNotFound(_) => "window_not_found",
+            Self::Conflict(_) => "conflict",
+            Self::Unavailable(_) => "unavailable",
+            Self::Timeout(_) => "timeout",
+            Self::Cancelled(_) => "cancelled",
+            Self::Corrupt(_) => "corrupt_data",
+            Self::Internal(_) => "internal",
+            Self::Tauri(_) => "runtime",
+        }
+    }
+
+    /// Whether the same request may succeed if repeated later without changes.
+    pub fn retryable(&self) -> bool {
+        match self {
+            Self::RateLimited { .. }
+            | Self::WebSocket(_)
+            | Self::Unavailable(_)
+            | Self::Timeout(_) => true,
+            Self::HttpStatus { status, .. } => *status >= 500,
+            Self::Reqwest(error) => {
+                error.is_timeout()
+                    || error.is_connect()
+                    || error
+                        .status()
+                        .is_some_and(|status| status.is_server_error() || status.as_u16() == 429)
+            }
+            Self::Sqlx(error) => matches!(error, sqlx::Error::PoolTimedOut),
+            Self::Io(error) => matches!(
+                error.kind(),
+                std::io::ErrorKind::TimedOut
+                    | std::io::ErrorKind::Interrupted
+                    | std::io::ErrorKind::WouldBlock
+            ),
+            _ => false,
+        }
+    }
+
+    pub fn details(&self) -> Option<AppErrorDetails> {
+        match self {
+            Self::InvalidSymbol(symbol) => Some(AppErrorDetails {
+                symbol: Some(symbol.clone()),
+                ..AppErrorDetails::default()
+            }),
+            Self::RateLimited {
+                status,
+                retry_after_ms,
+            } => Some(AppErrorDetails {
+                status: Some(*status),
+                retry_after_ms: *retry_after_ms,
+                ..AppErrorDetails::default()
+            }),
+            Self::HttpStatus { status, .. } => Some(AppErrorDetails {
+                status: Some(*status),
+                ..AppErrorDetails::default()
+            }),
+            Self::Reqwest(error) => error.status().map(|status| AppErrorDetails {
+                status: Some(status.as_u16()),
+                ..AppErrorDetails::default()
+            }),
+            Self::WindowNotFound(label) => Some(AppErrorDetails {
+                label: Some(label.clone()),
+                ..AppErrorDetails::default()
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Serializes as `{ code, message, retryable, details }`.
 impl serde::Serialize for AppError {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        serializer.serialize_str(&self.to_string())
+        let mut state = serializer.serialize_struct("AppError", 4)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.serialize_field("retryable", &self.retryable())?;
+        state.serialize_field("details", &self.details())?;
+        state.end()
+    }
+}
+
+impl JsonSchema for AppError {
+    fn schema_name() -> Cow<'static, str> {
+        "AppError".into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "type": "object",
+            "properties": {
+                "code": { "type": "string", "enum": APP_ERROR_CODES },
+                "message": { "type": "string" },
+                "retryable": { "type": "boolean" },
+                "details": generator.subschema_for::<Option<AppErrorDetails>>(),
+            },
+            "required": ["code", "message", "retryable", "details"],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use simd_json::prelude::*;
+
+    #[test]
+    fn serializes_code_message_retryable_and_details() {
+        let error = AppError::from_http_status(429, Some("12"), "too many requests".to_string());
+        let value = simd_json::serde::to_owned_value(&error).expect("serialize");
+        assert_eq!(
+            value.get("code").and_then(|v| v.as_str()),
+            Some("rate_limited")
+        );
+        assert_eq!(value.get("retryable").and_then(|v| v.as_bool()), Some(true));
+        let details = value.get("details").expect("details");
+        assert_eq!(details.get("status").and_then(|v| v.as_u64()), Some(429));
+        assert_eq!(
+            details.get("retryAfterMs").and_then(|v| v.as_u64()),
+            Some(12_000)
+        );
+        assert!(details.get("symbol").is_none());
+
+        let value =
+            simd_json::serde::to_owned_value(AppError::InvalidArgument("bad limit".to_string()))
+                .expect("serialize");
+        assert_eq!(
+            value.get("code").and_then(|v| v.as_str()),
+            Some("invalid_argument")
+        );
+        assert_eq!(
+            value.get("message").and_then(|v| v.as_str()),
+            Some("invalid argument: bad limit")
+        );
+        assert_eq!(
+            value.get("retryable").and_then(|v| v.as_bool()),
+            Some(false)
+        );
+        assert!(value.get("details").is_some_and(|v| v.is_null()));
+    }
+
+    #[test]
+    fn codes_are_listed_in_the_schema() {
+        let errors = [
+            AppError::InvalidArgument(String::new()),
+            AppError::InvalidSymbol(String::new()),
+            AppError::from_http_status(429, None, String::new()),
+            AppError::from_http_status(500, None, String::new()),
+            AppError::Io(std::io::Error::other("disk")),
+            AppError::WindowNotFound(String::new()),
+            AppError::Conflict(String::new()),
+            AppError::Unavailable(String::new()),
+            AppError::Timeout(String::new()),
+            AppError::Cancelled(String::new()),
+            AppError::Corrupt(String::new()),
+            AppError::Internal(String::new()),
+        ];
+        for error in errors {
+            assert!(APP_ERROR_CODES.contains(&error.code()), "{}", error.code());
+        }
+    }
+
+    #[test]
+    fn classifies_http_statuses() {
+        assert!(matches!(
+            AppError::from_http_status(418, None, String::new()),
+            AppError::RateLimited {
+                status: 418,
+                retry_after_ms: None
+            }
+        ));
+        let server = AppError::from_http_status(503, Some("5"), "unavailable".to_string());
+        assert_eq!(server.code(), "http_status");
+        assert!(server.retryable());
+        let client = AppError::from_http_status(404, None, "not found".to_string());
+        assert!(!client.retryable());
+    }
+
+    #[test]
+    fn internal_failures_are_not_invalid_arguments() {
+        let timeout = AppError::Timeout("history chunk 3 was not acknowledged".to_string());
+        assert_eq!(timeout.code(), "timeout");
+        assert!(timeout.retryable());
+        let internal = AppError::Internal("export task failed".to_string());
+        assert_eq!(internal.code(), "internal");
+        assert!(!internal.retryable());
+        assert_eq!(AppError::Cancelled(String::new()).code(), "cancelled");
     }
 }
@@ -1,48 +1,109 @@
+pub mod bindings;
 mod commands;
-mod db;
-mod error;
-mod market;
+pub mod db;
+pub mod error;
+pub mod market;
 mod state;
 
 use commands::{
     app_info::app_info,
     health::health,
+    market_alerts::{
+        market_alert_delete, market_alert_triggers_list, market_alert_upsert, market_alerts_list,
+    },
+    market_api::{market_api_start, market_api_status, market_api_stop},
+    market_data::{market_export, market_import_archives},
     market_preferences::{
         market_drawing_delete, market_drawing_upsert, market_drawings_list, market_preferences_get,
         market_preferences_save,
     },
     market_stream::{
-        market_spot_symbols, market_stream_status, market_symbols, start_market_stream,
-        stop_market_stream,
+        market_frame_ack, market_history_ack, market_history_page, market_replay_control,
+        market_spot_symbols, market_stream_snapshot, market_stream_status, market_streams_list,
+        market_symbols, market_trades_recent, start_market_stream, stop_market_stream,
+    },
+    market_windows::{
+        market_window_open, market_window_subscribe, market_windows_list, release_window,
+    },
+    webhooks::{
+        webhook_outbox_retry_failed, webhook_outbox_summary, webhook_target_delete,
+        webhook_target_upsert, webhook_targets_list,
     },
 };
 use db::initialize_pool;
+use market::alerts::AlertEngine;
+use market::binance::BinanceClient;
+use market::webhooks::WebhookDispatcher;
 use state::AppState;
 use tauri::Manager;
 
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_websocket::init())
+        .plugin(tauri_plugin_notification::init())
         .setup(|app| {
             let app_handle = app.handle().clone();
             let db_pool =
                 tauri::async_runtime::block_on(async move { initialize_pool(&app_handle).await })?;
-            app.manage(AppState::new(db_pool));
+            let webhooks = WebhookDispatcher::start(db_pool.clone())?;
+            let binance = BinanceClient::from_env();
+            let alert_engine = AlertEngine::start(
+                app.handle().clone(),
+                db_pool.clone(),
+                webhooks.clone(),
+                binance.clone(),
+            );
+            let startup_alert_engine = alert_engine.clone();
+            tauri::async_runtime::block_on(async move { startup_alert_engine.reload().await })?;
+            app.manage(AppState::new(db_pool, binance, alert_engine, webhooks));
             Ok(())
         })
+        .on_window_event(|window, event| {
+            if let tauri::WindowEvent::Destroyed = event {
+                let app_handle = window.app_handle().clone();
+                let label = window.label().to_string();
+                tauri::async_runtime::spawn(async move {
+                    release_window(&app_handle.state::<AppState>(), &label).await;
+                });
+            }
+        })
         .invoke_handler(tauri::generate_handler![
             health,
             app_info,
             start_market_stream,
             stop_market_stream,
             market_stream_status,
+            market_stream_snapshot,
+            market_streams_list,
             market_symbols,
             market_spot_symbols,
+            market_trades_recent,
+            market_history_ack,
+            market_frame_ack,
+            market_history_page,
+            market_replay_control,
+            market_import_archives,
+            market_export,
+            market_window_open,
+            market_window_subscribe,
+            market_windows_list,
+            market_api_start,
+            market_api_stop,
+            market_api_status,
             market_preferences_get,
             market_preferences_save,
             market_drawings_list,
             market_drawing_upsert,
-            market_drawing_delete
+            market_drawing_delete,
+            market_alerts_list,
+            market_alert_upsert,
+            market_alert_delete,
+            market_alert_triggers_list,
+            webhook_targets_list,
+            webhook_target_upsert,
+            webhook_target_delete,
+            webhook_outbox_summary,
+            webhook_outbox_retry_failed
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,1050 @@
+use crate::error::AppError;
+use crate::market::binance::{connect_agg_trade_stream, BinanceClient};
+use crate::market::persistence::{
+    get_market_drawing, list_enabled_market_alerts, record_market_alert_trigger,
+};
+use crate::market::pipeline::reconnect_delay;
+use crate::market::types::{
+    parse_agg_trade_payload, AggTradeEvent, MarketAlertCondition, MarketAlertCrossDirection,
+    MarketAlertDto, MarketAlertTriggerDto, MarketDrawingDto, MarketKind,
+};
+use crate::market::webhooks::{WebhookDispatcher, WebhookEvent};
+use futures_util::StreamExt;
+use parking_lot::Mutex;
+use serde::Deserialize;
+use sqlx::SqlitePool;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Runtime};
+use tauri_plugin_notification::NotificationExt;
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_util::sync::CancellationToken;
+
+use super::ALERT_TRIGGERED_EVENT;
+
+type AlertScope = (MarketKind, String);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlertTrigger {
+    pub alert_id: String,
+    pub market_kind: MarketKind,
+    pub symbol: String,
+    pub condition_type: &'static str,
+    pub label: Option<String>,
+    pub price: f64,
+    pub message: String,
+    pub triggered_at_ms: i64,
+    pub notify_os: bool,
+    pub one_shot: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum AlertLevel {
+    Horizontal(f64),
+    Trend {
+        start_ms: i64,
+        start_price: f64,
+        end_ms: i64,
+        end_price: f64,
+    },
+}
+
+impl AlertLevel {
+    fn price_at(self, timestamp_ms: i64) -> Option<f64> {
+        match self {
+            Self::Horizontal(price) => Some(price),
+            Self::Trend {
+                start_ms,
+                start_price,
+                end_ms,
+                end_price,
+            } => {
+                // Trend lines are extended to the right of their first anchor, which is how
+                // traders expect a line alert to keep working after the drawn segment ends.
+                if start_ms == end_ms || timestamp_ms < start_ms.min(end_ms) {
+                    return None;
+                }
+                let slope = (end_price - start_price) / (end_ms - start_ms) as f64;
+                Some(start_price + slope * (timestamp_ms - start_ms) as f64)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct DrawingPointWire {
+    time: f64,
+    price: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct HorizontalLinePayloadWire {
+    price: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct TrendLinePayloadWire {
+    start: DrawingPointWire,
+    end: DrawingPointWire,
+}
+
+fn drawing_point_time_ms(time: f64) -> i64 {
+    // Chart drawings store lightweight-charts UTC timestamps in seconds.
+    (time * 1_000.0).round() as i64
+}
+
+fn alert_level_from_drawing(drawing: &MarketDrawingDto) -> Result<AlertLevel, AppError> {
+    let mut payload = drawing.payload_json.clone().into_bytes();
+    match drawing.drawing_type.as_str() {
+        "horizontalLine" => {
+            let wire: HorizontalLinePayloadWire = simd_json::serde::from_slice(&mut payload)?;
+            if !wire.price.is_finite() {
+                return Err(AppError::InvalidArgument(
+                    "horizontalLine price must be finite".to_string(),
+                ));
+            }
+            Ok(AlertLevel::Horizontal(wire.price))
+        }
+        "trendLine" => {
+            let wire: TrendLinePayloadWire = simd_json::serde::from_slice(&mut payload)?;
+            let values = [
+                wire.start.time,
+                wire.start.price,
+                wire.end.time,
+                wire.end.price,
+            ];
+            if values.iter().any(|value| !value.is_finite()) {
+                return Err(AppError::InvalidArgument(
+                    "trendLine anchors must be finite".to_string(),
+                ));
+            }
+            Ok(AlertLevel::Trend {
+                start_ms: drawing_point_time_ms(wire.start.time),
+                start_price: wire.start.price,
+                end_ms: drawing_point_time_ms(wire.end.time),
+                end_price: wire.end.price,
+            })
+        }
+        other => Err(AppError::InvalidArgument(format!(
+            "drawing_type '{other}' cannot be used for alerts"
+        ))),
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct WindowEntry {
+    t: i64,
+    quantity: f64,
+    signed_quantity: f64,
+}
+
+#[derive(Debug, Clone)]
+struct RollingTradeWindow {
+    window_ms: i64,
+    entries: VecDeque<WindowEntry>,
+    min_prices: VecDeque<(i64, f64)>,
+    max_prices: VecDeque<(i64, f64)>,
+    volume: f64,
+    delta: f64,
+}
+
+impl RollingTradeWindow {
+    fn new(window_ms: i64) -> Self {
+        Self {
+            window_ms,
+            entries: VecDeque::new(),
+            min_prices: VecDeque::new(),
+            max_prices: VecDeque::new(),
+            volume: 0.0,
+            delta: 0.0,
+        }
+    }
+
+    fn push(&mut self, trade: &AggTradeEvent) {
+        let entry = WindowEntry {
+            t: trade.trade_time,
+            quantity: trade.quantity,
+            signed_quantity: trade.quantity * f64::from(trade.direction()),
+        };
+        self.entries.push_back(entry);
+        self.volume += entry.quantity;
+        self.delta += entry.signed_quantity;
+
+        while self
+            .min_prices
+            .back()
+            .is_some_and(|(_, price)| *price >= trade.price)
+        {
+            self.min_prices.pop_back();
+        }
+        self.min_prices.push_back((trade.trade_time, trade.price));
+        while self
+            .max_prices
+            .back()
+            .is_some_and(|(_, price)| *price <= trade.price)
+        {
+            self.max_prices.pop_back();
+        }
+        self.max_prices.push_back((trade.trade_time, trade.price));
+
+        self.evict(trade.trade_time);
+    }
+
+    fn evict(&mut self, now_ms: i64) {
+        let cutoff = now_ms.saturating_sub(self.window_ms);
+        while let Some(front) = self.entries.front().copied() {
+            if front.t >= cutoff {
+                break;
+            }
+            self.entries.pop_front();
+            self.volume -= front.quantity;
+            self.delta -= front.signed_quantity;
+        }
+        if self.entries.is_empty() {
+            self.volume = 0.0;
+            self.delta = 0.0;
+        }
+        while self.min_prices.front().is_some_and(|(t, _)| *t < cutoff) {
+            self.min_prices.pop_front();
+        }
+        while self.max_prices.front().is_some_and(|(t, _)| *t < cutoff) {
+            self.max_prices.pop_front();
+        }
+    }
+
+    fn min_price(&self) -> Option<f64> {
+        self.min_prices.front().map(|(_, price)| *price)
+    }
+
+    fn max_price(&self) -> Option<f64> {
+        self.max_prices.front().map(|(_, price)| *price)
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.min_prices.clear();
+        self.max_prices.clear();
+        self.volume = 0.0;
+        self.delta = 0.0;
+    }
+}
+
+#[derive(Debug, Clone)]
+enum AlertRule {
+    Cross {
+        level: AlertLevel,
+        direction: MarketAlertCrossDirection,
+        last_side: i8,
+    },
+    PercentMove {
+        percent: f64,
+        window: RollingTradeWindow,
+    },
+    DeltaSpike {
+        threshold: f64,
+        window: RollingTradeWindow,
+    },
+    VolumeSpike {
+        threshold: f64,
+        window: RollingTradeWindow,
+    },
+}
+
+impl AlertRule {
+    /// Feeds the trade to the rule. While `armed` is false the rule keeps tracking trades
+    /// but neither fires nor resets its window.
+    fn observe(&mut self, trade: &AggTradeEvent, armed: bool) -> Option<String> {
+        match self {
+            Self::Cross {
+                level,
+                direction,
+                last_side,
+            } => {
+                let Some(level_price) = level.price_at(trade.trade_time) else {
+                    *last_side = 0;
+                    return None;
+                };
+                let diff = trade.price - level_price;
+                let side = if diff > 0.0 {
+                    1
+                } else if diff < 0.0 {
+                    -1
+                } else {
+                    0
+                };
+                let previous = *last_side;
+                let crossed_up = previous < 0 && side >= 0;
+                let crossed_down = previous > 0 && side <= 0;
+                // Touching the level counts as crossing it, so the next move away can re-cross.
+                *last_side = if side != 0 { side } else { -previous };
+
+                let crossed = match direction {
+                    MarketAlertCrossDirection::Up => crossed_up,
+                    MarketAlertCrossDirection::Down => crossed_down,
+                    MarketAlertCrossDirection::Any => crossed_up || crossed_down,
+                };
+                if !crossed || !armed {
+                    return None;
+                }
+                let verb = if crossed_up { "above" } else { "below" };
+                Some(format!(
+                    "price {} crossed {verb} {level_price}",
+                    trade.price
+                ))
+            }
+            Self::PercentMove { percent, window } => {
+                window.push(trade);
+                let min_price = window.min_price()?;
+                let max_price = window.max_price()?;
+                let rise_pct = if min_price > 0.0 {
+                    (trade.price - min_price) / min_price * 100.0
+                } else {
+                    0.0
+                };
+                let drop_pct = if max_price > 0.0 {
+                    (max_price - trade.price) / max_price * 100.0
+                } else {
+                    0.0
+                };
+                let message = if rise_pct >= *percent {
+                    format!("price rose {rise_pct:.2}% to {}", trade.price)
+                } else if drop_pct >= *percent {
+                    format!("price fell {drop_pct:.2}% to {}", trade.price)
+                } else {
+                    return None;
+                };
+                if !armed {
+                    return None;
+                }
+                window.clear();
+                Some(message)
+            }
+            Self::DeltaSpike { threshold, window } => {
+                window.push(trade);
+                let delta = window.delta;
+                if delta.abs() < *threshold || !armed {
+                    return None;
+                }
+                window.clear();
+                Some(format!("delta {delta:.4} reached threshold {threshold}"))
+            }
+            Self::VolumeSpike { threshold, window } => {
+                window.push(trade);
+                let volume = window.volume;
+                if volume < *threshold || !armed {
+                    return None;
+                }
+                window.clear();
+                Some(format!("volume {volume:.4} reached threshold {threshold}"))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ArmedAlert {
+    id: String,
+    /// Last edit of the alert and, for drawing alerts, of the drawing it follows.
+    revision: (i64, Option<i64>),
+    market_kind: MarketKind,
+    symbol: String,
+    condition_type: &'static str,
+    label: Option<String>,
+    one_shot: bool,
+    cooldown_ms: i64,
+    notify_os: bool,
+    cooldown_until_ms: i64,
+    rule: AlertRule,
+}
+
+impl ArmedAlert {
+    pub fn new(
+        alert: &MarketAlertDto,
+        drawing: Option<&MarketDrawingDto>,
+    ) -> Result<Self, AppError> {
+        let rule = match &alert.condition {
+            MarketAlertCondition::PriceCross { price, direction } => AlertRule::Cross {
+                level: AlertLevel::Horizontal(*price),
+                direction: *direction,
+                last_side: 0,
+            },
+            MarketAlertCondition::PercentMove { percent, window_ms } => AlertRule::PercentMove {
+                percent: *percent,
+                window: RollingTradeWindow::new(*window_ms),
+            },
+            MarketAlertCondition::DeltaSpike {
+                threshold,
+                window_ms,
+            } => AlertRule::DeltaSpike {
+                threshold: *threshold,
+                window: RollingTradeWindow::new(*window_ms),
+            },
+            MarketAlertCondition::VolumeSpike {
+                threshold,
+                window_ms,
+            } => AlertRule::VolumeSpike {
+                threshold: *threshold,
+                window: RollingTradeWindow::new(*window_ms),
+            },
+            MarketAlertCondition::DrawingCross {
+                drawing_id,
+                direction,
+            } => {
+                let drawing = drawing.ok_or_else(|| {
+                    AppError::InvalidArgument(format!("drawing '{drawing_id}' does not exist"))
+                })?;
+                AlertRule::Cross {
+                    level: alert_level_from_drawing(drawing)?,
+                    direction: *direction,
+                    last_side: 0,
+                }
+            }
+        };
+        // Moving a drawing does not touch the alert row, so the drawing's own edit time has
+        // to invalidate the armed level.
+        let drawing_revision = match alert.condition {
+            MarketAlertCondition::DrawingCross { .. } => {
+                drawing.map(|drawing| drawing.updated_at_ms)
+            }
+            _ => None,
+        };
+
+        Ok(Self {
+            id: alert.id.clone(),
+            revision: (alert.updated_at_ms, drawing_revision),
+            market_kind: alert.market_kind,
+            symbol: alert.symbol.clone(),
+            condition_type: alert.condition.type_str(),
+            label: alert.label.clone(),
+            one_shot: alert.one_shot,
+            cooldown_ms: alert.cooldown_ms,
+            notify_os: alert.notify_os,
+            cooldown_until_ms: i64::MIN,
+            rule,
+        })
+    }
+
+    fn observe(&mut self, trade: &AggTradeEvent) -> Option<AlertTrigger> {
+        let cooling_down = trade.trade_time < self.cooldown_until_ms;
+        let message = self.rule.observe(trade, !cooling_down)?;
+        self.cooldown_until_ms = trade.trade_time.saturating_add(self.cooldown_ms);
+
+        Some(AlertTrigger {
+            alert_id: self.id.clone(),
+            market_kind: self.market_kind,
+            symbol: self.symbol.clone(),
+            condition_type: self.condition_type,
+            label: self.label.clone(),
+            price: trade.price,
+            message,
+            triggered_at_ms: trade.trade_time,
+            notify_os: self.notify_os,
+            one_shot: self.one_shot,
+        })
+    }
+}
+
+/// Armed alerts for a single (market kind, symbol) pair.
+#[derive(Debug, Default)]
+pub struct AlertEvaluator {
+    alerts: Vec<ArmedAlert>,
+}
+
+impl AlertEvaluator {
+    /// Replaces the armed set, keeping runtime state (cross side, windows, cooldown) for
+    /// alerts whose definition did not change.
+    pub fn sync(&mut self, incoming: Vec<ArmedAlert>) {
+        let mut previous = std::mem::take(&mut self.alerts);
+        self.alerts = incoming
+            .into_iter()
+            .map(|alert| {
+                match previous
+                    .iter()
+                    .position(|kept| kept.id == alert.id && kept.revision == alert.revision)
+                {
+                    Some(index) => previous.swap_remove(index),
+                    None => alert,
+                }
+            })
+            .collect();
+    }
+
+    pub fn evaluate(&mut self, trade: &AggTradeEvent, triggers: &mut Vec<AlertTrigger>) {
+        self.alerts.retain_mut(|alert| match alert.observe(trade) {
+            Some(trigger) => {
+                triggers.push(trigger);
+                !alert.one_shot
+            }
+            None => true,
+        });
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.alerts.is_empty()
+    }
+}
+
+/// Evaluates persisted alerts against every applied trade, for every symbol that has
+/// enabled alerts. Symbols streamed by the chart pipeline are fed through
+/// [`AlertEngine::evaluate_trade`]; every other symbol gets a lightweight background
+/// aggTrade monitor so alerts keep working while their chart is closed.
+pub struct AlertEngine {
+    db_pool: SqlitePool,
+    evaluators: Mutex<HashMap<MarketKind, HashMap<String, AlertEvaluator>>>,
+    attached_feeds: Mutex<HashMap<AlertScope, usize>>,
+    monitors: Mutex<HashMap<AlertScope, CancellationToken>>,
+    /// One-shot alerts that fired but whose row the dispatcher has not disabled yet, so a
+    /// reload in between does not re-arm them.
+    fired_one_shots: Arc<Mutex<HashSet<String>>>,
+    binance: BinanceClient,
+    trigger_tx: mpsc::UnboundedSender<AlertTrigger>,
+}
+
+impl AlertEngine {
+    pub fn start<R: Runtime>(
+        app_handle: AppHandle<R>,
+        db_pool: SqlitePool,
+        webhooks: Arc<WebhookDispatcher>,
+        binance: BinanceClient,
+    ) -> Arc<Self> {
+        let (trigger_tx, trigger_rx) = mpsc::unbounded_channel();
+        let dispatcher_pool = db_pool.clone();
+        let fired_one_shots = Arc::new(Mutex::new(HashSet::new()));
+        let dispatcher_fired = Arc::clone(&fired_one_shots);
+        tauri::async_runtime::spawn(async move {
+            run_alert_dispatcher(
+                app_handle,
+                dispatcher_pool,
+                webhooks,
+                dispatcher_fired,
+                trigger_rx,
+            )
+            .await;
+        });
+
+        Arc::new(Self {
+            db_pool,
+            evaluators: Mutex::new(HashMap::new()),
+            attached_feeds: Mutex::new(HashMap::new()),
+            monitors: Mutex::new(HashMap::new()),
+            fired_one_shots,
+            binance,
+            trigger_tx,
+        })
+    }
+
+    /// Reloads enabled alerts from SQLite and reconciles background monitors.
+    pub async fn reload(self: &Arc<Self>) -> Result<(), AppError> {
+        let alerts = list_enabled_market_alerts(&self.db_pool).await?;
+        let mut armed_by_scope: HashMap<MarketKind, HashMap<String, Vec<ArmedAlert>>> =
+            HashMap::new();
+
+        for alert in alerts {
+            let drawing = match &alert.condition {
+                MarketAlertCondition::DrawingCross { drawing_id, .. } => {
+                    get_market_drawing(&self.db_pool, drawing_id).await?
+                }
+                _ => None,
+            };
+            match ArmedAlert::new(&alert, drawing.as_ref()) {
+                Ok(armed) => armed_by_scope
+                    .entry(alert.market_kind)
+                    .or_default()
+                    .entry(alert.symbol.clone())
+                    .or_default()
+                    .push(armed),
+                Err(error) => eprintln!("skipping alert {}: {error}", alert.id),
+            }
+        }
+
+        {
+            let mut evaluators = self.evaluators.lock();
+            let fired_one_shots = self.fired_one_shots.lock();
+            let mut previous = std::mem::take(&mut *evaluators);
+            for (market_kind, symbols) in armed_by_scope {
+                let previous_symbols = previous.entry(market_kind).or_default();
+                let target = evaluators.entry(market_kind).or_default();
+                for (symbol, mut armed) in symbols {
+                    armed.retain(|alert| !fired_one_shots.contains(&alert.id));
+                    let mut evaluator = previous_symbols.remove(&symbol).unwrap_or_default();
+                    evaluator.sync(armed);
+                    target.insert(symbol, evaluator);
+                }
+            }
+        }
+
+        self.reconcile_monitors();
+        Ok(())
+    }
+
+    /// Hot-path entry point used by the live pipeline and the background monitors.
+    pub fn evaluate_trade(&self, market_kind: MarketKind, symbol: &str, trade: &AggTradeEvent) {
+        let mut triggers = Vec::new();
+        {
+            let mut evaluators = self.evaluators.lock();
+            let Some(evaluator) = evaluators
+                .get_mut(&market_kind)
+                .and_then(|symbols| symbols.get_mut(symbol))
+            else {
+                return;
+            };
+            evaluator.evaluate(trade, &mut triggers);
+            // Under the evaluators lock, so a concurrent reload cannot miss the entry.
+            let mut fired_one_shots = self.fired_one_shots.lock();
+            for trigger in triggers.iter().filter(|trigger| trigger.one_shot) {
+                fired_one_shots.insert(trigger.alert_id.clone());
+            }
+        }
+
+        for trigger in triggers {
+            if self.trigger_tx.send(trigger).is_err() {
+                eprintln!("alert dispatcher is not running");
+            }
+        }
+    }
+
+    /// Marks a symbol as fed by the chart pipeline, replacing its background monitor.
+    pub fn attach_feed(self: &Arc<Self>, market_kind: MarketKind, symbol: &str) {
+        {
+            let mut attached = self.attached_feeds.lock();
+            *attached
+                .entry((market_kind, symbol.to_string()))
+                .or_default() += 1;
+        }
+        self.reconcile_monitors();
+    }
+
+    pub fn detach_feed(self: &Arc<Self>, market_kind: MarketKind, symbol: &str) {
+        {
+            let mut attached = self.attached_feeds.lock();
+            let key = (market_kind, symbol.to_string());
+            if let Some(count) = attached.get_mut(&key) {
+                *count = count.saturating_sub(1);
+                if *count == 0 {
+                    attached.remove(&key);
+                }
+            }
+        }
+        self.reconcile_monitors();
+    }
+
+    fn reconcile_monitors(self: &Arc<Self>) {
+        let mut wanted: HashSet<AlertScope> = {
+            let evaluators = self.evaluators.lock();
+            evaluators
+                .iter()
+                .flat_map(|(market_kind, symbols)| {
+                    symbols
+                        .iter()
+                        .filter(|(_, evaluator)| !evaluator.is_empty())
+                        .map(|(symbol, _)| (*market_kind, symbol.clone()))
+                })
+                .collect()
+        };
+        {
+            let attached = self.attached_feeds.lock();
+            wanted.retain(|scope| !attached.contains_key(scope));
+        }
+
+        let mut monitors = self.monitors.lock();
+        monitors.retain(|scope, cancel_token| {
+            let keep = wanted.contains(scope);
+            if !keep {
+                cancel_token.cancel();
+            }
+            keep
+        });
+
+        for scope in wanted {
+            if monitors.contains_key(&scope) {
+                continue;
+            }
+            let cancel_token = CancellationToken::new();
+            let task_token = cancel_token.clone();
+            let engine = Arc::clone(self);
+            let (market_kind, symbol) = scope.clone();
+            tauri::async_runtime::spawn(async move {
+                run_alert_monitor(engine, market_kind, symbol, task_token).await;
+            });
+            monitors.insert(scope, cancel_token);
+        }
+    }
+}
+
+async fn run_alert_monitor(
+    engine: Arc<AlertEngine>,
+    market_kind: MarketKind,
+    symbol: String,
+    cancel_token: CancellationToken,
+) {
+    let mut reconnect_attempt = 0_u32;
+    let mut last_agg_id: Option<u64> = None;
+
+    while !cancel_token.is_cancelled() {
+        match connect_agg_trade_stream(&engine.binance, market_kind, &symbol).await {
+            Ok(mut websocket_stream) => {
+                reconnect_attempt = 0;
+                loop {
+                    let frame = tokio::select! {
+                        _ = cancel_token.cancelled() => break,
+                        next_message = websocket_stream.next() => next_message,
+                    };
+                    let Some(Ok(message)) = frame else {
+                        break;
+                    };
+                    let mut payload = match message {
+                        Message::Text(text_payload) => text_payload.into_bytes(),
+                        Message::Binary(binary_payload) => binary_payload,
+                        Message::Close(_) => break,
+                        _ => continue,
+                    };
+                    let Ok(trade) = parse_agg_trade_payload(payload.as_mut_slice()) else {
+                        continue;
+                    };
+                    // Alerts only need ordering; gaps are tolerated instead of resynced.
+                    if last_agg_id.is_some_and(|last| trade.aggregate_trade_id <= last) {
+                        continue;
+                    }
+                    last_agg_id = Some(trade.aggregate_trade_id);
+                    engine.evaluate_trade(market_kind, &symbol, &trade);
+                }
+            }
+            Err(error) => {
+                eprintln!("alert monitor for {symbol} failed to connect: {error}");
+            }
+        }
+
+        reconnect_attempt = reconnect_attempt.saturating_add(1);
+        tokio::select! {
+            _ = cancel_token.cancelled() => break,
+            _ = tokio::time::sleep(reconnect_delay(reconnect_attempt)) => {}
+        }
+    }
+}
+
+async fn run_alert_dispatcher<R: Runtime>(
+    app_handle: AppHandle<R>,
+    db_pool: SqlitePool,
+    webhooks: Arc<WebhookDispatcher>,
+    fired_one_shots: Arc<Mutex<HashSet<String>>>,
+    mut trigger_rx: mpsc::UnboundedReceiver<AlertTrigger>,
+) {
+    while let Some(trigger) = trigger_rx.recv().await {
+        let event = match record_market_alert_trigger(&db_pool, &trigger).await {
+            Ok(event) => {
+                // The row is disabled now, so reloads no longer list it.
+                if trigger.one_shot {
+                    fired_one_shots.lock().remove(&trigger.alert_id);
+                }
+                event
+            }
+            Err(error) => {
+                eprintln!(
+                    "failed to persist alert trigger {}: {error}",
+                    trigger.alert_id
+                );
+                continue;
+            }
+        };
+
+        if let Err(error) = app_handle.emit(ALERT_TRIGGERED_EVENT, &event) {
+            eprintln!("failed to emit alert_triggered event: {error}");
+        }
+
+        if trigger.notify_os {
+            show_alert_notification(&app_handle, &event);
+        }
+
+        if let Err(error) = webhooks.enqueue(&WebhookEvent::AlertTriggered(event)).await {
+            eprintln!(
+                "failed to enqueue alert webhook for {}: {error}",
+                trigger.alert_id
+            );
+        }
+    }
+}
+
+fn show_alert_notification<R: Runtime>(app_handle: &AppHandle<R>, event: &MarketAlertTriggerDto) {
+    let title = match event.label.as_deref() {
+        Some(label) => format!("{} alert: {label}", event.symbol),
+        None => format!("{} alert", event.symbol),
+    };
+    if let Err(error) = app_handle
+        .notification()
+        .builder()
+        .title(title)
+        .body(&event.message)
+        .show()
+    {
+        eprintln!("failed to show alert notification: {error}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_trade(
+        id: u64,
+        trade_time: i64,
+        price: f64,
+        qty: f64,
+        is_buyer_maker: bool,
+    ) -> AggTradeEvent {
+        AggTradeEvent {
+            event_time: trade_time,
+            aggregate_trade_id: id,
+            price,
+            quantity: qty,
+            trade_time,
+            is_buyer_maker,
+        }
+    }
+
+    fn sample_alert(condition: MarketAlertCondition, one_shot: bool) -> MarketAlertDto {
+        MarketAlertDto {
+            id: "alert-1".to_string(),
+            market_kind: MarketKind::Spot,
+            symbol: "BTCUSDT".to_string(),
+            condition,
+            label: None,
+            enabled: true,
+            one_shot,
+            cooldown_ms: 0,
+            notify_os: false,
+            last_triggered_at_ms: None,
+            created_at_ms: 0,
+            updated_at_ms: 0,
+        }
+    }
+
+    fn evaluator_for(alert: &MarketAlertDto, drawing: Option<&MarketDrawingDto>) -> AlertEvaluator {
+        let mut evaluator = AlertEvaluator::default();
+        evaluator.sync(vec![
+            ArmedAlert::new(alert, drawing).expect("alert should arm")
+        ]);
+        evaluator
+    }
+
+    #[test]
+    fn price_cross_triggers_once_for_one_shot_alert() {
+        let alert = sample_alert(
+            MarketAlertCondition::PriceCross {
+                price: 100.0,
+                direction: MarketAlertCrossDirection::Up,
+            },
+            true,
+        );
+        let mut evaluator = evaluator_for(&alert, None);
+        let mut triggers = Vec::new();
+
+        evaluator.evaluate(&sample_trade(1, 1_000, 99.0, 1.0, false), &mut triggers);
+        assert!(triggers.is_empty());
+        evaluator.evaluate(&sample_trade(2, 1_100, 100.5, 1.0, false), &mut triggers);
+        assert_eq!(triggers.len(), 1);
+        assert_eq!(triggers[0].price, 100.5);
+        assert!(evaluator.is_empty());
+    }
+
+    #[test]
+    fn price_cross_ignores_wrong_direction_and_respects_cooldown() {
+        let mut alert = sample_alert(
+            MarketAlertCondition::PriceCross {
+                price: 100.0,
+                direction: MarketAlertCrossDirection::Down,
+            },
+            false,
+        );
+        alert.cooldown_ms = 10_000;
+        let mut evaluator = evaluator_for(&alert, None);
+        let mut triggers = Vec::new();
+
+        evaluator.evaluate(&sample_trade(1, 1_000, 99.0, 1.0, false), &mut triggers);
+        evaluator.evaluate(&sample_trade(2, 1_100, 101.0, 1.0, false), &mut triggers);
+        assert!(triggers.is_empty());
+        evaluator.evaluate(&sample_trade(3, 1_200, 99.5, 1.0, true), &mut triggers);
+        assert_eq!(triggers.len(), 1);
+        evaluator.evaluate(&sample_trade(4, 1_300, 101.0, 1.0, false), &mut triggers);
+        evaluator.evaluate(&sample_trade(5, 1_400, 99.0, 1.0, true), &mut triggers);
+        assert_eq!(triggers.len(), 1);
+        evaluator.evaluate(&sample_trade(6, 20_000, 101.0, 1.0, false), &mut triggers);
+        evaluator.evaluate(&sample_trade(7, 20_100, 99.0, 1.0, true), &mut triggers);
+        assert_eq!(triggers.len(), 2);
+        assert!(!evaluator.is_empty());
+    }
+
+    #[test]
+    fn percent_move_uses_rolling_window() {
+        let alert = sample_alert(
+            MarketAlertCondition::PercentMove {
+                percent: 1.0,
+                window_ms: 60_000,
+            },
+            true,
+        );
+        let mut evaluator = evaluator_for(&alert, None);
+        let mut triggers = Vec::new();
+
+        evaluator.evaluate(&sample_trade(1, 0, 100.0, 1.0, false), &mut triggers);
+        // The low leaves the window before the price reaches +1%.
+        evaluator.evaluate(&sample_trade(2, 70_000, 100.6, 1.0, false), &mut triggers);
+        evaluator.evaluate(&sample_trade(3, 80_000, 101.0, 1.0, false), &mut triggers);
+        assert!(triggers.is_empty());
+        evaluator.evaluate(&sample_trade(4, 90_000, 99.9, 1.0, true), &mut triggers);
+        assert_eq!(triggers.len(), 1);
+        assert!(triggers[0].message.contains("fell"));
+    }
+
+    #[test]
+    fn delta_and_volume_spikes_sum_inside_window() {
+        let delta_alert = sample_alert(
+            MarketAlertCondition::DeltaSpike {
+                threshold: 5.0,
+                window_ms: 1_000,
+            },
+            true,
+        );
+        let mut delta_evaluator = evaluator_for(&delta_alert, None);
+        let volume_alert = sample_alert(
+            MarketAlertCondition::VolumeSpike {
+                threshold: 5.0,
+                window_ms: 1_000,
+            },
+            true,
+        );
+        let mut volume_evaluator = evaluator_for(&volume_alert, None);
+        let mut delta_triggers = Vec::new();
+        let mut volume_triggers = Vec::new();
+
+        let trades = [
+            sample_trade(1, 0, 100.0, 3.0, false),
+            sample_trade(2, 500, 100.0, 3.0, true),
+            sample_trade(3, 2_000, 100.0, 3.0, true),
+            sample_trade(4, 2_500, 100.0, 2.5, true),
+        ];
+        for trade in &trades {
+            delta_evaluator.evaluate(trade, &mut delta_triggers);
+            volume_evaluator.evaluate(trade, &mut volume_triggers);
+        }
+
+        assert_eq!(volume_triggers.len(), 1);
+        assert_eq!(volume_triggers[0].triggered_at_ms, 500);
+        assert_eq!(delta_triggers.len(), 1);
+        assert_eq!(delta_triggers[0].triggered_at_ms, 2_500);
+    }
+
+    #[test]
+    fn spike_windows_keep_accumulating_through_the_cooldown() {
+        let mut alert = sample_alert(
+            MarketAlertCondition::VolumeSpike {
+                threshold: 5.0,
+                window_ms: 10_000,
+            },
+            false,
+        );
+        alert.cooldown_ms = 1_000;
+        let mut evaluator = evaluator_for(&alert, None);
+        let mut triggers = Vec::new();
+
+        evaluator.evaluate(&sample_trade(1, 0, 100.0, 5.0, false), &mut triggers);
+        assert_eq!(triggers.len(), 1);
+        evaluator.evaluate(&sample_trade(2, 500, 100.0, 3.0, false), &mut triggers);
+        evaluator.evaluate(&sample_trade(3, 900, 100.0, 3.0, false), &mut triggers);
+        assert_eq!(triggers.len(), 1);
+        // The volume reached during the cooldown is still in the window once it ends.
+        evaluator.evaluate(&sample_trade(4, 1_000, 100.0, 0.5, false), &mut triggers);
+        assert_eq!(triggers.len(), 2);
+        assert!(triggers[1].message.contains("6.5000"));
+    }
+
+    #[test]
+    fn drawing_cross_follows_trend_line_slope() {
+        let drawing = MarketDrawingDto {
+            id: "line-1".to_string(),
+            market_kind: MarketKind::Spot,
+            symbol: "BTCUSDT".to_string(),
+            timeframe: crate::market::types::MarketTimeframe::M1,
+            drawing_type: "trendLine".to_string(),
+            color: "#0EA5E9".to_string(),
+            label: None,
+            payload_json: r#"{"start":{"time":0,"price":100},"end":{"time":100,"price":200}}"#
+                .to_string(),
+            created_at_ms: 0,
+            updated_at_ms: 0,
+        };
+        let alert = sample_alert(
+            MarketAlertCondition::DrawingCross {
+                drawing_id: "line-1".to_string(),
+                direction: MarketAlertCrossDirection::Any,
+            },
+            true,
+        );
+        let mut evaluator = evaluator_for(&alert, Some(&drawing));
+        let mut triggers = Vec::new();
+
+        // At t=50s the line sits at 150, at t=150s (extended) it sits at 250.
+        evaluator.evaluate(&sample_trade(1, 50_000, 160.0, 1.0, false), &mut triggers);
+        evaluator.evaluate(&sample_trade(2, 150_000, 240.0, 1.0, true), &mut triggers);
+        assert_eq!(triggers.len(), 1);
+        assert!(triggers[0].message.contains("below"));
+    }
+
+    #[test]
+    fn sync_rearms_drawing_alerts_when_the_drawing_moves() {
+        let mut drawing = MarketDrawingDto {
+            id: "line-1".to_string(),
+            market_kind: MarketKind::Spot,
+            symbol: "BTCUSDT".to_string(),
+            timeframe: crate::market::types::MarketTimeframe::M1,
+            drawing_type: "horizontalLine".to_string(),
+            color: "#0EA5E9".to_string(),
+            label: None,
+            payload_json: r#"{"price":100}"#.to_string(),
+            created_at_ms: 0,
+            updated_at_ms: 0,
+        };
+        let alert = sample_alert(
+            MarketAlertCondition::DrawingCross {
+                drawing_id: "line-1".to_string(),
+                direction: MarketAlertCrossDirection::Up,
+            },
+            false,
+        );
+        let mut evaluator = evaluator_for(&alert, Some(&drawing));
+        let mut triggers = Vec::new();
+        evaluator.evaluate(&sample_trade(1, 1_000, 99.0, 1.0, false), &mut triggers);
+
+        drawing.payload_json = r#"{"price":110}"#.to_string();
+        drawing.updated_at_ms = 5_000;
+        evaluator.sync(vec![
+            ArmedAlert::new(&alert, Some(&drawing)).expect("alert should arm")
+        ]);
+        evaluator.evaluate(&sample_trade(2, 6_000, 105.0, 1.0, false), &mut triggers);
+        evaluator.evaluate(&sample_trade(3, 6_100, 109.0, 1.0, false), &mut triggers);
+        assert!(triggers.is_empty());
+        evaluator.evaluate(&sample_trade(4, 6_200, 111.0, 1.0, false), &mut triggers);
+        assert_eq!(triggers.len(), 1);
+        assert!(triggers[0].message.contains("110"));
+    }
+
+    #[test]
+    fn sync_keeps_runtime_state_for_unchanged_alerts() {
+        let alert = sample_alert(
+            MarketAlertCondition::PriceCross {
+                price: 100.0,
+                direction: MarketAlertCrossDirection::Up,
+            },
+            true,
+        );
+        let mut evaluator = evaluator_for(&alert, None);
+        let mut triggers = Vec::new();
+        evaluator.evaluate(&sample_trade(1, 1_000, 99.0, 1.0, false), &mut triggers);
+
+        evaluator.sync(vec![
+            ArmedAlert::new(&alert, None).expect("alert should arm")
+        ]);
+        evaluator.evaluate(&sample_trade(2, 1_100, 101.0, 1.0, false), &mut triggers);
+        assert_eq!(triggers.len(), 1);
+    }
+}
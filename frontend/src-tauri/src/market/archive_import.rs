@@ -0,0 +1,474 @@
+use crate::error::AppError;
+use crate::market::binance::kline_pair_from_fields;
+use crate::market::kline_store::{store_kline_range, KlineSeries};
+use crate::market::pipeline::now_unix_ms;
+use crate::market::recorder::SegmentImporter;
+use crate::market::types::{
+    AggTradeEvent, MarketKind, MarketTimeframe, UiArchiveImportResult, UiCandle, UiDeltaCandle,
+    UiHistoryLoadProgress,
+};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use zip::ZipArchive;
+
+use super::ARCHIVE_IMPORT_PROGRESS_EVENT;
+
+const ARCHIVE_PROGRESS_EMIT_THROTTLE_MS: u64 = 250;
+/// Spot archives switched to microsecond timestamps in 2025; anything this large is not a
+/// millisecond time.
+const MICROSECOND_TIMESTAMP_THRESHOLD: i64 = 100_000_000_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarketArchiveKind {
+    AggTrades,
+    Klines(MarketTimeframe),
+}
+
+/// A data.binance.vision archive, identified from its file name, e.g.
+/// `BTCUSDT-aggTrades-2024-01.zip` or `BTCUSDT-1m-2024-01-15.zip`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarketArchive {
+    pub path: PathBuf,
+    pub symbol: String,
+    pub kind: MarketArchiveKind,
+}
+
+impl MarketArchive {
+    pub fn from_path(path: impl Into<PathBuf>) -> Result<Self, AppError> {
+        let path = path.into();
+        let invalid = || {
+            AppError::InvalidArgument(format!(
+                "'{}' is not a Binance aggTrades or klines archive name",
+                path.display()
+            ))
+        };
+
+        let stem = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .ok_or_else(invalid)?;
+        let parts: Vec<&str> = stem.split('-').collect();
+        let dated = matches!(parts.len(), 4 | 5)
+            && parts[2..]
+                .iter()
+                .all(|part| !part.is_empty() && part.chars().all(|ch| ch.is_ascii_digit()));
+        let symbol = parts[0];
+        if !dated || symbol.is_empty() || !symbol.chars().all(|ch| ch.is_ascii_alphanumeric()) {
+            return Err(invalid());
+        }
+
+        let kind = match parts[1] {
+            "aggTrades" => MarketArchiveKind::AggTrades,
+            "1mo" => MarketArchiveKind::Klines(MarketTimeframe::Mo1),
+            interval => MarketArchiveKind::Klines(MarketTimeframe::parse_str(interval)?),
+        };
+
+        Ok(Self {
+            symbol: symbol.to_ascii_uppercase(),
+            kind,
+            path,
+        })
+    }
+
+    /// Trade archives feed every timeframe; their progress is reported against 1m.
+    fn progress_timeframe(&self) -> MarketTimeframe {
+        match self.kind {
+            MarketArchiveKind::AggTrades => MarketTimeframe::M1,
+            MarketArchiveKind::Klines(timeframe) => timeframe,
+        }
+    }
+}
+
+/// Imports archives one after another, klines into the kline cache and aggTrades into the
+/// recorder's day segments, emitting `archive_import_progress` along the way.
+pub async fn import_market_archives(
+    app_handle: &AppHandle,
+    db_pool: &sqlx::SqlitePool,
+    recordings_root: &Path,
+    market_kind: MarketKind,
+    archives: Vec<MarketArchive>,
+) -> Result<UiArchiveImportResult, AppError> {
+    let archive_count = archives.len() as u32;
+    let mut result = UiArchiveImportResult {
+        archives_imported: 0,
+        candles_imported: 0,
+        trades_imported: 0,
+    };
+
+    for archive in archives {
+        let rows_before = result.candles_imported + result.trades_imported;
+        let progress = ArchiveProgress {
+            app_handle: app_handle.clone(),
+            market_kind,
+            symbol: archive.symbol.clone(),
+            timeframe: archive.progress_timeframe(),
+            archives_done: result.archives_imported,
+            archive_count,
+            rows_before,
+        };
+
+        match archive.kind {
+            MarketArchiveKind::Klines(timeframe) => {
+                let path = archive.path.clone();
+                let reporter = progress.clone();
+                let (candles, delta_candles) = tauri::async_runtime::spawn_blocking(move || {
+                    read_kline_archive(&path, |rows, fraction| reporter.emit(rows, fraction, false))
+                })
+                .await
+                .map_err(|error| {
+                    AppError::Internal(format!("archive import task failed: {error}"))
+                })??;
+
+                store_kline_range(
+                    db_pool,
+                    KlineSeries::new(market_kind, &archive.symbol, timeframe),
+                    &candles,
+                    &delta_candles,
+                    now_unix_ms(),
+                )
+                .await?;
+                result.candles_imported += candles.len() as u64;
+            }
+            MarketArchiveKind::AggTrades => {
+                let path = archive.path.clone();
+                let root = recordings_root.to_path_buf();
+                let symbol = archive.symbol.clone();
+                let reporter = progress.clone();
+                let imported = tauri::async_runtime::spawn_blocking(move || {
+                    import_trade_archive(&path, &root, market_kind, &symbol, |rows, fraction| {
+                        reporter.emit(rows, fraction, false)
+                    })
+                })
+                .await
+                .map_err(|error| {
+                    AppError::Internal(format!("archive import task failed: {error}"))
+                })??;
+                result.trades_imported += imported;
+            }
+        }
+
+        result.archives_imported += 1;
+        let rows = result.candles_imported + result.trades_imported - rows_before;
+        progress.emit(rows, 1.0, result.archives_imported == archive_count);
+    }
+
+    Ok(result)
+}
+
+#[derive(Clone)]
+struct ArchiveProgress {
+    app_handle: AppHandle,
+    market_kind: MarketKind,
+    symbol: String,
+    timeframe: MarketTimeframe,
+    archives_done: u32,
+    archive_count: u32,
+    rows_before: u64,
+}
+
+impl ArchiveProgress {
+    fn emit(&self, rows: u64, fraction: f64, done: bool) {
+        let archives = f64::from(self.archives_done) + fraction.clamp(0.0, 1.0);
+        let payload = UiHistoryLoadProgress {
+            market_kind: self.market_kind,
+            symbol: self.symbol.clone(),
+            timeframe: self.timeframe,
+            pages_fetched: self.archives_done,
+            candles_fetched: self.rows_before + rows,
+            estimated_total_candles: None,
+            progress_pct: Some(archives / f64::from(self.archive_count.max(1)) * 100.0),
+            done,
+        };
+        if let Err(error) = self.app_handle.emit(ARCHIVE_IMPORT_PROGRESS_EVENT, payload) {
+            eprintln!("failed to emit archive_import_progress event: {error}");
+        }
+    }
+}
+
+/// Streams the single CSV inside an archive line by line. `on_line` also receives the share
+/// of the uncompressed entry read so far.
+fn for_each_archive_line(
+    path: &Path,
+    mut on_line: impl FnMut(&str, f64) -> Result<(), AppError>,
+) -> Result<(), AppError> {
+    let mut archive = ZipArchive::new(File::open(path)?)?;
+    if archive.len() != 1 {
+        return Err(AppError::InvalidArgument(format!(
+            "'{}' should contain exactly one CSV file",
+            path.display()
+        )));
+    }
+
+    let entry = archive.by_index(0)?;
+    let total_bytes = entry.size().max(1) as f64;
+    let mut reader = BufReader::new(entry);
+    let mut line = String::new();
+    let mut read_bytes = 0_u64;
+    loop {
+        line.clear();
+        let read = reader.read_line(&mut line)?;
+        if read == 0 {
+            return Ok(());
+        }
+        read_bytes += read as u64;
+        let trimmed = line.trim_end();
+        if !trimmed.is_empty() {
+            on_line(trimmed, read_bytes as f64 / total_bytes)?;
+        }
+    }
+}
+
+fn read_kline_archive(
+    path: &Path,
+    mut on_progress: impl FnMut(u64, f64),
+) -> Result<(Vec<UiCandle>, Vec<UiDeltaCandle>), AppError> {
+    let mut candles = Vec::new();
+    let mut delta_candles = Vec::new();
+    let mut throttle = ProgressThrottle::default();
+
+    for_each_archive_line(path, |line, fraction| {
+        if let Some((candle, delta_candle)) = parse_kline_row(line)? {
+            candles.push(candle);
+            delta_candles.push(delta_candle);
+        }
+        if throttle.ready() {
+            on_progress(candles.len() as u64, fraction);
+        }
+        Ok(())
+    })?;
+
+    Ok((candles, delta_candles))
+}
+
+fn import_trade_archive(
+    path: &Path,
+    recordings_root: &Path,
+    market_kind: MarketKind,
+    symbol: &str,
+    mut on_progress: impl FnMut(u64, f64),
+) -> Result<u64, AppError> {
+    let mut importer = SegmentImporter::new(recordings_root, market_kind, symbol)?;
+    let mut imported = 0_u64;
+    let mut throttle = ProgressThrottle::default();
+
+    for_each_archive_line(path, |line, fraction| {
+        if let Some(trade) = parse_agg_trade_row(line)? {
+            importer.push(trade)?;
+            imported += 1;
+        }
+        if throttle.ready() {
+            on_progress(imported, fraction);
+        }
+        Ok(())
+    })?;
+
+    importer.finish()?;
+    Ok(imported)
+}
+
+#[derive(Default)]
+struct ProgressThrottle {
+    last_emit_at: Option<Instant>,
+}
+
+impl ProgressThrottle {
+    fn ready(&mut self) -> bool {
+        let now = Instant::now();
+        let ready = self.last_emit_at.is_none_or(|last| {
+            now.saturating_duration_since(last)
+                >= Duration::from_millis(ARCHIVE_PROGRESS_EMIT_THROTTLE_MS)
+        });
+        if ready {
+            self.last_emit_at = Some(now);
+        }
+        ready
+    }
+}
+
+fn archive_time_ms(raw: i64) -> i64 {
+    if raw >= MICROSECOND_TIMESTAMP_THRESHOLD {
+        raw / 1_000
+    } else {
+        raw
+    }
+}
+
+/// Futures archives start with a header row, spot archives do not.
+fn is_header_row(line: &str) -> bool {
+    !line.starts_with(|ch: char| ch.is_ascii_digit())
+}
+
+fn invalid_row(kind: &str, line: &str) -> AppError {
+    AppError::InvalidArgument(format!("invalid {kind} archive row '{line}'"))
+}
+
+/// `agg_trade_id,price,quantity,first_trade_id,last_trade_id,transact_time,is_buyer_maker[,is_best_match]`
+fn parse_agg_trade_row(line: &str) -> Result<Option<AggTradeEvent>, AppError> {
+    if is_header_row(line) {
+        return Ok(None);
+    }
+
+    let fields: Vec<&str> = line.split(',').collect();
+    if fields.len() < 7 {
+        return Err(invalid_row("aggTrades", line));
+    }
+    let aggregate_trade_id = fields[0]
+        .parse::<u64>()
+        .map_err(|_| invalid_row("aggTrades", line))?;
+    let trade_time = fields[5]
+        .parse::<i64>()
+        .map(archive_time_ms)
+        .map_err(|_| invalid_row("aggTrades", line))?;
+    let is_buyer_maker = match fields[6].to_ascii_lowercase().as_str() {
+        "true" => true,
+        "false" => false,
+        _ => return Err(invalid_row("aggTrades", line)),
+    };
+
+    Ok(Some(AggTradeEvent {
+        event_time: trade_time,
+        aggregate_trade_id,
+        price: fields[1].parse::<f64>()?,
+        quantity: fields[2].parse::<f64>()?,
+        trade_time,
+        is_buyer_maker,
+    }))
+}
+
+/// `open_time,open,high,low,close,volume,close_time,quote_volume,count,taker_buy_volume,...`
+fn parse_kline_row(line: &str) -> Result<Option<(UiCandle, UiDeltaCandle)>, AppError> {
+    if is_header_row(line) {
+        return Ok(None);
+    }
+
+    let fields: Vec<&str> = line.split(',').collect();
+    if fields.len() < 10 {
+        return Err(invalid_row("klines", line));
+    }
+    let open_time = fields[0]
+        .parse::<i64>()
+        .map(archive_time_ms)
+        .map_err(|_| invalid_row("klines", line))?;
+
+    kline_pair_from_fields(
+        open_time, fields[1], fields[2], fields[3], fields[4], fields[5], fields[9],
+    )
+    .map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn identifies_archives_from_file_names() {
+        let trades = MarketArchive::from_path("/data/BTCUSDT-aggTrades-2024-01.zip")
+            .expect("monthly trade archive");
+        assert_eq!(trades.symbol, "BTCUSDT");
+        assert_eq!(trades.kind, MarketArchiveKind::AggTrades);
+
+        let klines = MarketArchive::from_path("ethusdt-5m-2024-01-15.zip").expect("daily klines");
+        assert_eq!(klines.symbol, "ETHUSDT");
+        assert_eq!(klines.kind, MarketArchiveKind::Klines(MarketTimeframe::M5));
+        assert_eq!(
+            MarketArchive::from_path("BTCUSDT-1mo-2024-01.zip")
+                .expect("monthly candles")
+                .kind,
+            MarketArchiveKind::Klines(MarketTimeframe::Mo1)
+        );
+
+        assert!(MarketArchive::from_path("BTCUSDT-3m-2024-01.zip").is_err());
+        assert!(MarketArchive::from_path("BTCUSDT-trades-2024-01.zip").is_err());
+        assert!(MarketArchive::from_path("notes.zip").is_err());
+    }
+
+    #[test]
+    fn parses_spot_and_futures_rows() {
+        assert_eq!(
+            parse_agg_trade_row("agg_trade_id,price,quantity,first_trade_id,last_trade_id,transact_time,is_buyer_maker")
+                .expect("header parses"),
+            None
+        );
+        let spot = parse_agg_trade_row("42,65000.5,0.25,100,101,1735689600000123,True,True")
+            .expect("spot row parses")
+            .expect("spot row is a trade");
+        assert_eq!(spot.aggregate_trade_id, 42);
+        assert_eq!(spot.trade_time, 1_735_689_600_000);
+        assert!(spot.is_buyer_maker);
+
+        let futures = parse_agg_trade_row("7,3000.1,2,10,12,1704067200000,false")
+            .expect("futures row parses")
+            .expect("futures row is a trade");
+        assert_eq!(futures.trade_time, 1_704_067_200_000);
+        assert!(!futures.is_buyer_maker);
+        assert!(parse_agg_trade_row("7,3000.1,2").is_err());
+
+        let (candle, delta) =
+            parse_kline_row("1704067200000,100,110,90,105,10,1704067259999,1000,50,7,700,0")
+                .expect("kline parses")
+                .expect("kline row is a candle");
+        assert_eq!(candle.t, 1_704_067_200_000);
+        assert_eq!(candle.h, 110.0);
+        assert_eq!(delta.c, 4.0);
+    }
+
+    #[test]
+    fn imports_trade_archive_into_segments() {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .expect("system clock should be after unix epoch")
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("archive-import-{timestamp}"));
+        std::fs::create_dir_all(&root).expect("temp root");
+        let path = root.join("BTCUSDT-aggTrades-2024-01-01.zip");
+
+        let mut writer = zip::ZipWriter::new(File::create(&path).expect("archive creates"));
+        writer
+            .start_file(
+                "BTCUSDT-aggTrades-2024-01-01.csv",
+                zip::write::SimpleFileOptions::default(),
+            )
+            .expect("entry starts");
+        for id in 1..=10_u64 {
+            writeln!(
+                writer,
+                "{id},100.{id},1.5,{id},{id},{},false,true",
+                1_704_067_200_000 + id as i64
+            )
+            .expect("row writes");
+        }
+        writer.finish().expect("archive finishes");
+
+        let mut reports = 0;
+        let imported = import_trade_archive(
+            &path,
+            &root.join("recordings"),
+            MarketKind::Spot,
+            "BTCUSDT",
+            |_, _| reports += 1,
+        )
+        .expect("archive imports");
+        assert_eq!(imported, 10);
+        assert!(reports >= 1);
+
+        let mut cursor = crate::market::recorder::RecordedTradeCursor::open(
+            &root.join("recordings"),
+            MarketKind::Spot,
+            "BTCUSDT",
+        )
+        .expect("cursor opens");
+        cursor.seek(1_704_067_200_005).expect("seek");
+        let trade = cursor
+            .next_trade()
+            .expect("trade reads")
+            .expect("trade exists");
+        assert_eq!(trade.aggregate_trade_id, 5);
+        assert_eq!(trade.price, 100.5);
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+}
@@ -0,0 +1,262 @@
+use crate::market::types::{MarketEventSeq, MAX_EMIT_INTERVAL_MS};
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Unacknowledged frames at which the emitter doubles its interval.
+const FRAME_LAG_WIDEN: usize = 3;
+/// Unacknowledged frames at or below which the emitter narrows its interval again.
+const FRAME_LAG_NARROW: usize = 1;
+const MAX_FRAMES_IN_FLIGHT: usize = 256;
+
+/// Latest `market_frame_update` a window of the stream reported as applied. Kept per stream
+/// id so acks reach the running emitter; the emitter ignores acks of other generations.
+#[derive(Debug, Default)]
+pub struct FrameAcks {
+    latest: Mutex<Option<MarketEventSeq>>,
+}
+
+impl FrameAcks {
+    /// Returns false when a later frame was already acknowledged.
+    pub fn ack(&self, frame: MarketEventSeq) -> bool {
+        let mut latest = self.latest.lock();
+        if let Some(current) = *latest {
+            if (frame.generation, frame.seq) < (current.generation, current.seq) {
+                return false;
+            }
+        }
+        *latest = Some(frame);
+        true
+    }
+
+    pub fn latest(&self) -> Option<MarketEventSeq> {
+        *self.latest.lock()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EmitPacerStats {
+    pub interval_ms: u64,
+    pub active: bool,
+    pub frames_in_flight: u32,
+    pub frames_merged: u64,
+    pub frames_dropped: u64,
+}
+
+/// Decides when the consumer loop emits the next frame. It follows the configured interval
+/// until a window acknowledges frames; from then on it doubles the interval, up to
+/// `MAX_EMIT_INTERVAL_MS`, whenever `FRAME_LAG_WIDEN` frames are unacknowledged at emit time,
+/// and narrows it by a quarter once the window has caught up. A window that stops
+/// acknowledging is stalled, not gone, so the interval only returns to the configured one
+/// when no window receives the stream any more. Updates held back meanwhile stay in the
+/// conflated state and go out merged into the next frame.
+#[derive(Debug)]
+pub struct EmitPacer {
+    base_interval_ms: u64,
+    interval_ms: u64,
+    generation: Option<u64>,
+    acks_active: bool,
+    in_flight: VecDeque<u64>,
+    last_emit_at: Option<Instant>,
+    frames_merged: u64,
+    frames_dropped: u64,
+}
+
+impl EmitPacer {
+    pub fn new(base_interval_ms: u64) -> Self {
+        let base_interval_ms = base_interval_ms.clamp(1, MAX_EMIT_INTERVAL_MS);
+        Self {
+            base_interval_ms,
+            interval_ms: base_interval_ms,
+            generation: None,
+            acks_active: false,
+            in_flight: VecDeque::new(),
+            last_emit_at: None,
+            frames_merged: 0,
+            frames_dropped: 0,
+        }
+    }
+
+    /// Whether a frame is due at `now`. Called on every tick of the configured interval, so a
+    /// frame is due once at least half a tick short of the current interval has passed.
+    /// `watched` is whether any window that acknowledges frames receives the stream.
+    pub fn is_due(&mut self, acked: Option<MarketEventSeq>, watched: bool, now: Instant) -> bool {
+        self.apply_ack(acked, watched);
+        match self.last_emit_at {
+            Some(last_emit_at) => {
+                let elapsed = now.saturating_duration_since(last_emit_at);
+                elapsed + Duration::from_millis(self.base_interval_ms / 2)
+                    >= Duration::from_millis(self.interval_ms)
+            }
+            None => true,
+        }
+    }
+
+    /// Records a frame that went out as `frame` and adapts the interval to the frames still
+    /// unacknowledged before it.
+    pub fn record_emit(&mut self, frame: MarketEventSeq, now: Instant) {
+        if self.generation != Some(frame.generation) {
+            self.generation = Some(frame.generation);
+            self.acks_active = false;
+            self.in_flight.clear();
+            self.interval_ms = self.base_interval_ms;
+        }
+        self.last_emit_at = Some(now);
+        if !self.acks_active {
+            return;
+        }
+
+        let lag = self.in_flight.len();
+        if lag >= FRAME_LAG_WIDEN {
+            self.interval_ms = self.interval_ms.saturating_mul(2).min(MAX_EMIT_INTERVAL_MS);
+        } else if lag <= FRAME_LAG_NARROW {
+            self.interval_ms = (self.interval_ms - self.interval_ms / 4).max(self.base_interval_ms);
+        }
+
+        if self.in_flight.len() == MAX_FRAMES_IN_FLIGHT {
+            self.in_flight.pop_front();
+        }
+        self.in_flight.push_back(frame.seq);
+    }
+
+    /// A tick that was not due while an update was pending; the update goes out with the
+    /// next frame.
+    pub fn record_merged(&mut self) {
+        self.frames_merged = self.frames_merged.saturating_add(1);
+    }
+
+    /// A frame that failed to emit.
+    pub fn record_dropped(&mut self) {
+        self.frames_dropped = self.frames_dropped.saturating_add(1);
+    }
+
+    pub fn stats(&self) -> EmitPacerStats {
+        EmitPacerStats {
+            interval_ms: self.interval_ms,
+            active: self.interval_ms > self.base_interval_ms,
+            frames_in_flight: self.in_flight.len() as u32,
+            frames_merged: self.frames_merged,
+            frames_dropped: self.frames_dropped,
+        }
+    }
+
+    fn apply_ack(&mut self, acked: Option<MarketEventSeq>, watched: bool) {
+        if !watched {
+            self.acks_active = false;
+            self.in_flight.clear();
+            self.interval_ms = self.base_interval_ms;
+            return;
+        }
+        let Some(frame) = acked.filter(|frame| Some(frame.generation) == self.generation) else {
+            return;
+        };
+
+        self.acks_active = true;
+        while self.in_flight.front().is_some_and(|seq| *seq <= frame.seq) {
+            self.in_flight.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(seq: u64) -> MarketEventSeq {
+        MarketEventSeq { generation: 7, seq }
+    }
+
+    #[test]
+    fn widens_while_acks_lag_and_narrows_after_catching_up() {
+        let start = Instant::now();
+        let mut pacer = EmitPacer::new(16);
+        let mut now = start;
+        let mut seq = 0;
+        let mut acked = None;
+
+        // Frames 1..=2 go out before the window starts acknowledging.
+        for _ in 0..2 {
+            assert!(pacer.is_due(acked, true, now));
+            seq += 1;
+            pacer.record_emit(frame(seq), now);
+            now += Duration::from_millis(16);
+        }
+        acked = Some(frame(1));
+
+        // The window stops acknowledging after frame 1.
+        let mut widened = Vec::new();
+        for _ in 0..200 {
+            if pacer.is_due(acked, true, now) {
+                seq += 1;
+                pacer.record_emit(frame(seq), now);
+                widened.push(pacer.stats().interval_ms);
+            } else {
+                pacer.record_merged();
+            }
+            now += Duration::from_millis(16);
+        }
+        assert_eq!(pacer.stats().interval_ms, MAX_EMIT_INTERVAL_MS);
+        assert!(pacer.stats().active);
+        assert!(pacer.stats().frames_merged > 0);
+        assert!(widened.windows(2).all(|pair| pair[0] <= pair[1]));
+
+        // It catches up.
+        acked = Some(frame(seq));
+        for _ in 0..400 {
+            if pacer.is_due(acked, true, now) {
+                seq += 1;
+                pacer.record_emit(frame(seq), now);
+                acked = Some(frame(seq));
+            }
+            now += Duration::from_millis(16);
+        }
+        assert_eq!(pacer.stats().interval_ms, 16);
+        assert!(!pacer.stats().active);
+    }
+
+    #[test]
+    fn ignores_foreign_acks_and_holds_through_a_stall() {
+        let start = Instant::now();
+        let mut pacer = EmitPacer::new(16);
+        let mut now = start;
+        let foreign = MarketEventSeq {
+            generation: 3,
+            seq: 1,
+        };
+        for seq in 1..=10 {
+            pacer.is_due(Some(foreign), true, now);
+            pacer.record_emit(frame(seq), now);
+            now += Duration::from_millis(16);
+        }
+        assert_eq!(pacer.stats().interval_ms, 16);
+
+        // The window acknowledges frame 10, then stops for far longer than any frame takes.
+        let mut seq = 10;
+        for _ in 0..2_000 {
+            if pacer.is_due(Some(frame(10)), true, now) {
+                seq += 1;
+                pacer.record_emit(frame(seq), now);
+            }
+            now += Duration::from_millis(16);
+        }
+        assert_eq!(pacer.stats().interval_ms, MAX_EMIT_INTERVAL_MS);
+        assert!(pacer.stats().frames_in_flight > 0);
+
+        // Once no window receives the stream, acks can no longer arrive.
+        assert!(pacer.is_due(Some(frame(10)), false, now));
+        assert_eq!(pacer.stats().interval_ms, 16);
+        assert_eq!(pacer.stats().frames_in_flight, 0);
+    }
+
+    #[test]
+    fn keeps_the_latest_ack() {
+        let acks = FrameAcks::default();
+        assert!(acks.ack(frame(5)));
+        assert!(!acks.ack(frame(4)));
+        assert!(acks.ack(MarketEventSeq {
+            generation: 8,
+            seq: 1,
+        }));
+        assert_eq!(acks.latest().map(|frame| frame.seq), Some(1));
+    }
+}
@@ -3,8 +3,10 @@ use crate::market::types::{
     AggTradeSnapshot, AggTradeSnapshotWire, KlineWire, MarketKind, MarketTimeframe, UiCandle,
     UiDeltaCandle,
 };
-use reqwest::Client;
+use reqwest::header::RETRY_AFTER;
+use reqwest::{Client, RequestBuilder, Response};
 use serde::Deserialize;
+use std::sync::Arc;
 use tokio::net::TcpStream;
 use tokio_tungstenite::tungstenite::protocol::WebSocketConfig;
 use tokio_tungstenite::{connect_async_with_config, MaybeTlsStream, WebSocketStream};
@@ -14,9 +16,98 @@ const BINANCE_SPOT_REST_BASE_URL: &str = "https://api.binance.com";
 const BINANCE_FUTURES_USDM_STREAM_BASE_URL: &str = "wss://fstream.binance.com/ws";
 const BINANCE_FUTURES_USDM_REST_BASE_URL: &str = "https://fapi.binance.com";
 const BINANCE_MAX_KLINES_PER_REQUEST: usize = 1_000;
+const BINANCE_INVALID_SYMBOL_CODE: i64 = -1121;
 
 pub type BinanceWsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
 
+/// Base URLs every exchange call goes to. Production Binance by default; [`Self::from_env`]
+/// reads overrides from `BINANCE_SPOT_STREAM_URL`, `BINANCE_SPOT_REST_URL`,
+/// `BINANCE_FUTURES_USDM_STREAM_URL` and `BINANCE_FUTURES_USDM_REST_URL`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BinanceEndpoints {
+    pub spot_stream: String,
+    pub spot_rest: String,
+    pub futures_usdm_stream: String,
+    pub futures_usdm_rest: String,
+}
+
+impl Default for BinanceEndpoints {
+    fn default() -> Self {
+        Self {
+            spot_stream: BINANCE_SPOT_STREAM_BASE_URL.to_string(),
+            spot_rest: BINANCE_SPOT_REST_BASE_URL.to_string(),
+            futures_usdm_stream: BINANCE_FUTURES_USDM_STREAM_BASE_URL.to_string(),
+            futures_usdm_rest: BINANCE_FUTURES_USDM_REST_BASE_URL.to_string(),
+        }
+    }
+}
+
+impl BinanceEndpoints {
+    pub fn from_env() -> Self {
+        let read = |name: &str, fallback: String| {
+            std::env::var(name)
+                .ok()
+                .map(|value| value.trim().trim_end_matches('/').to_string())
+                .filter(|value| !value.is_empty())
+                .unwrap_or(fallback)
+        };
+        let defaults = Self::default();
+        Self {
+            spot_stream: read("BINANCE_SPOT_STREAM_URL", defaults.spot_stream),
+            spot_rest: read("BINANCE_SPOT_REST_URL", defaults.spot_rest),
+            futures_usdm_stream: read(
+                "BINANCE_FUTURES_USDM_STREAM_URL",
+                defaults.futures_usdm_stream,
+            ),
+            futures_usdm_rest: read("BINANCE_FUTURES_USDM_REST_URL", defaults.futures_usdm_rest),
+        }
+    }
+
+    /// Moves an endpoint built against the production base URLs onto these ones.
+    fn locate(&self, endpoint: String) -> String {
+        let bases = [
+            (BINANCE_SPOT_STREAM_BASE_URL, &self.spot_stream),
+            (BINANCE_SPOT_REST_BASE_URL, &self.spot_rest),
+            (
+                BINANCE_FUTURES_USDM_STREAM_BASE_URL,
+                &self.futures_usdm_stream,
+            ),
+            (BINANCE_FUTURES_USDM_REST_BASE_URL, &self.futures_usdm_rest),
+        ];
+        for (production, configured) in bases {
+            if let Some(path) = endpoint.strip_prefix(production) {
+                return format!("{configured}{path}");
+            }
+        }
+        endpoint
+    }
+}
+
+/// HTTP client bound to the endpoints it talks to. Callers own one and pass it down, so a
+/// test can point a stream at a fake exchange while others keep talking to production.
+#[derive(Debug, Clone, Default)]
+pub struct BinanceClient {
+    http: Client,
+    endpoints: Arc<BinanceEndpoints>,
+}
+
+impl BinanceClient {
+    pub fn new(endpoints: BinanceEndpoints) -> Self {
+        Self {
+            http: Client::new(),
+            endpoints: Arc::new(endpoints),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(BinanceEndpoints::from_env())
+    }
+
+    fn get(&self, endpoint: String) -> RequestBuilder {
+        self.http.get(self.endpoints.locate(endpoint))
+    }
+}
+
 fn stream_base_url(market_kind: MarketKind) -> &'static str {
     match market_kind {
         MarketKind::Spot => BINANCE_SPOT_STREAM_BASE_URL,
@@ -99,6 +190,7 @@ fn futures_usdm_symbols_endpoint() -> String {
 }
 
 pub async fn connect_agg_trade_stream(
+    client: &BinanceClient,
     market_kind: MarketKind,
     symbol: &str,
 ) -> Result<BinanceWsStream, AppError> {
@@ -108,23 +200,67 @@ pub async fn connect_agg_trade_stream(
         ..Default::default()
     };
 
-    let request = ws_endpoint(market_kind, symbol);
+    let request = client.endpoints.locate(ws_endpoint(market_kind, symbol));
     let (stream, _) = connect_async_with_config(request, Some(ws_config), true).await?;
     Ok(stream)
 }
 
+#[derive(Debug, Deserialize)]
+struct BinanceErrorWire {
+    code: i64,
+    msg: String,
+}
+
+/// Passes successful responses through and turns the rest into typed errors. `symbol` is the
+/// one the request asked for, reported back when Binance rejects it as unknown.
+async fn ensure_success(response: Response, symbol: Option<&str>) -> Result<Response, AppError> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let retry_after = response
+        .headers()
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let body = response.text().await.unwrap_or_default();
+    Err(status_error(
+        status.as_u16(),
+        retry_after.as_deref(),
+        body,
+        symbol,
+    ))
+}
+
+fn status_error(
+    status: u16,
+    retry_after: Option<&str>,
+    body: String,
+    symbol: Option<&str>,
+) -> AppError {
+    let mut bytes = body.clone().into_bytes();
+    let wire = simd_json::serde::from_slice::<BinanceErrorWire>(&mut bytes).ok();
+    if let (Some(wire), Some(symbol)) = (&wire, symbol) {
+        if status == 400 && wire.code == BINANCE_INVALID_SYMBOL_CODE {
+            return AppError::InvalidSymbol(symbol.to_string());
+        }
+    }
+    let message = wire.map(|wire| wire.msg).unwrap_or(body);
+    AppError::from_http_status(status, retry_after, message)
+}
+
 pub async fn fetch_latest_agg_trade_snapshot(
-    client: &Client,
+    client: &BinanceClient,
     market_kind: MarketKind,
     symbol: &str,
 ) -> Result<AggTradeSnapshot, AppError> {
     let endpoint = snapshot_endpoint(market_kind, symbol);
-    let response = client.get(endpoint).send().await?.error_for_status()?;
+    let response = ensure_success(client.get(endpoint).send().await?, Some(symbol)).await?;
     let payload = response.json::<Vec<AggTradeSnapshotWire>>().await?;
     let latest = payload
         .into_iter()
         .next()
-        .ok_or_else(|| AppError::InvalidArgument("empty aggTrades snapshot payload".to_string()))?;
+        .ok_or_else(|| AppError::Unavailable("empty aggTrades snapshot payload".to_string()))?;
     latest.try_into()
 }
 
@@ -135,11 +271,11 @@ struct BinanceServerTimeWire {
 }
 
 pub async fn fetch_server_time_ms(
-    client: &Client,
+    client: &BinanceClient,
     market_kind: MarketKind,
 ) -> Result<i64, AppError> {
     let endpoint = server_time_endpoint(market_kind);
-    let response = client.get(endpoint).send().await?.error_for_status()?;
+    let response = ensure_success(client.get(endpoint).send().await?, None).await?;
     let payload = response.json::<BinanceServerTimeWire>().await?;
     Ok(payload.server_time)
 }
@@ -153,162 +289,168 @@ pub struct KlineHistoryProgress {
     pub done: bool,
 }
 
-pub async fn fetch_klines_history_bundle(
-    client: &Client,
-    market_kind: MarketKind,
-    symbol: &str,
-    timeframe: MarketTimeframe,
-    limit: u32,
-    history_all: bool,
-) -> Result<(Vec<UiCandle>, Vec<UiDeltaCandle>), AppError> {
-    fetch_klines_history_bundle_with_progress(
-        client,
-        market_kind,
-        symbol,
-        timeframe,
-        limit,
-        history_all,
-        |_| Ok(()),
-    )
-    .await
+/// One REST page of klines, oldest first within the page.
+#[derive(Debug, Clone)]
+pub struct KlineHistoryPage {
+    pub candles: Vec<UiCandle>,
+    pub delta_candles: Vec<UiDeltaCandle>,
+    pub progress: KlineHistoryProgress,
 }
 
-pub async fn fetch_klines_history_bundle_with_progress<F>(
-    client: &Client,
+/// Walks klines backwards from the newest bucket one page at a time, so callers can forward
+/// history without holding all of it in memory.
+pub struct KlineHistoryPager<'a> {
+    client: &'a BinanceClient,
     market_kind: MarketKind,
-    symbol: &str,
+    symbol: &'a str,
     timeframe: MarketTimeframe,
-    limit: u32,
-    history_all: bool,
-    mut on_progress: F,
-) -> Result<(Vec<UiCandle>, Vec<UiDeltaCandle>), AppError>
-where
-    F: FnMut(KlineHistoryProgress) -> Result<(), AppError>,
-{
-    if !history_all && limit == 0 {
-        return Ok((Vec::new(), Vec::new()));
-    }
+    target_limit: Option<usize>,
+    fetched: usize,
+    end_time: Option<i64>,
+    previous_oldest_open_time: Option<i64>,
+    pages_fetched: u32,
+    oldest_open_time_known: Option<i64>,
+    newest_open_time_seen: Option<i64>,
+    exhausted: bool,
+}
 
-    let target_limit = if history_all {
-        None
-    } else {
-        Some(limit as usize)
-    };
+impl<'a> KlineHistoryPager<'a> {
+    pub async fn new(
+        client: &'a BinanceClient,
+        market_kind: MarketKind,
+        symbol: &'a str,
+        timeframe: MarketTimeframe,
+        limit: u32,
+        history_all: bool,
+    ) -> Result<Self, AppError> {
+        let oldest_open_time_known = if history_all {
+            fetch_oldest_kline_open_time(client, market_kind, symbol, timeframe).await?
+        } else {
+            None
+        };
 
-    let mut candles_rev = target_limit.map_or_else(Vec::new, Vec::with_capacity);
-    let mut delta_candles_rev = target_limit.map_or_else(Vec::new, Vec::with_capacity);
-    let mut end_time: Option<i64> = None;
-    let mut previous_oldest_open_time: Option<i64> = None;
-    let timeframe_ms = timeframe.duration_ms().max(1);
-    let mut pages_fetched: u32 = 0;
-    let oldest_open_time_known = if history_all {
-        fetch_oldest_kline_open_time(client, market_kind, symbol, timeframe).await?
-    } else {
-        None
-    };
-    let mut newest_open_time_seen: Option<i64> = None;
+        Ok(Self {
+            client,
+            market_kind,
+            symbol,
+            timeframe,
+            target_limit: if history_all {
+                None
+            } else {
+                Some(limit as usize)
+            },
+            fetched: 0,
+            end_time: None,
+            previous_oldest_open_time: None,
+            pages_fetched: 0,
+            oldest_open_time_known,
+            newest_open_time_seen: None,
+            exhausted: !history_all && limit == 0,
+        })
+    }
 
-    loop {
-        let request_limit = target_limit
+    pub async fn next_page(&mut self) -> Result<Option<KlineHistoryPage>, AppError> {
+        if self.exhausted {
+            return Ok(None);
+        }
+
+        let request_limit = self
+            .target_limit
             .map(|target| {
-                let remaining = target.saturating_sub(candles_rev.len());
+                let remaining = target.saturating_sub(self.fetched);
                 remaining.min(BINANCE_MAX_KLINES_PER_REQUEST)
             })
             .unwrap_or(BINANCE_MAX_KLINES_PER_REQUEST);
-
         if request_limit == 0 {
-            break;
+            self.exhausted = true;
+            return Ok(None);
         }
 
         let endpoint = klines_endpoint(
-            market_kind,
-            symbol,
-            timeframe,
+            self.market_kind,
+            self.symbol,
+            self.timeframe,
             request_limit as u16,
-            end_time,
+            self.end_time,
         );
-        let response = client.get(endpoint).send().await?.error_for_status()?;
+        let response =
+            ensure_success(self.client.get(endpoint).send().await?, Some(self.symbol)).await?;
         let page = response.json::<Vec<KlineWire>>().await?;
         if page.is_empty() {
-            break;
+            self.exhausted = true;
+            return Ok(None);
         }
 
         let oldest_open_time = page[0].0;
-        if newest_open_time_seen.is_none() {
-            newest_open_time_seen = page.last().map(|entry| entry.0);
+        if self.newest_open_time_seen.is_none() {
+            self.newest_open_time_seen = page.last().map(|entry| entry.0);
         }
         let received = page.len();
-        pages_fetched = pages_fetched.saturating_add(1);
-        for kline in page.into_iter().rev() {
-            let (candle, delta_candle) = kline_to_domain_pair(kline)?;
-            candles_rev.push(candle);
-            delta_candles_rev.push(delta_candle);
-        }
+        self.pages_fetched = self.pages_fetched.saturating_add(1);
 
-        if history_all {
-            let progress = compute_history_progress(
-                pages_fetched,
-                candles_rev.len() as u64,
-                oldest_open_time_known,
-                newest_open_time_seen,
-                oldest_open_time,
-                timeframe_ms,
-                false,
-            );
-            on_progress(progress)?;
+        let mut candles = Vec::with_capacity(received);
+        let mut delta_candles = Vec::with_capacity(received);
+        for kline in page {
+            let (candle, delta_candle) = kline_to_domain_pair(kline)?;
+            candles.push(candle);
+            delta_candles.push(delta_candle);
         }
-
-        if let Some(target) = target_limit {
-            if candles_rev.len() >= target {
-                break;
+        if let Some(target) = self.target_limit {
+            let remaining = target.saturating_sub(self.fetched);
+            if candles.len() > remaining {
+                let excess = candles.len() - remaining;
+                candles.drain(..excess);
+                delta_candles.drain(..excess);
             }
         }
+        self.fetched = self.fetched.saturating_add(candles.len());
+
+        let progress = compute_history_progress(
+            self.pages_fetched,
+            self.fetched as u64,
+            self.oldest_open_time_known,
+            self.newest_open_time_seen,
+            oldest_open_time,
+            self.timeframe.duration_ms().max(1),
+            false,
+        );
 
-        if received < request_limit {
-            break;
-        }
-        if let Some(previous_oldest) = previous_oldest_open_time {
-            if oldest_open_time >= previous_oldest {
-                break;
-            }
-        }
-        if oldest_open_time <= 0 {
-            break;
+        let reached_target = self
+            .target_limit
+            .map(|target| self.fetched >= target)
+            .unwrap_or(false);
+        let stalled = self
+            .previous_oldest_open_time
+            .map(|previous_oldest| oldest_open_time >= previous_oldest)
+            .unwrap_or(false);
+        if reached_target || received < request_limit || stalled || oldest_open_time <= 0 {
+            self.exhausted = true;
+        } else {
+            self.previous_oldest_open_time = Some(oldest_open_time);
+            self.end_time = Some(oldest_open_time - 1);
         }
 
-        previous_oldest_open_time = Some(oldest_open_time);
-        end_time = Some(oldest_open_time - 1);
-    }
-
-    if let Some(target) = target_limit {
-        if candles_rev.len() > target {
-            candles_rev.truncate(target);
-        }
-        if delta_candles_rev.len() > target {
-            delta_candles_rev.truncate(target);
-        }
+        Ok(Some(KlineHistoryPage {
+            candles,
+            delta_candles,
+            progress,
+        }))
     }
 
-    if history_all {
-        let done_progress = compute_history_progress(
-            pages_fetched,
-            candles_rev.len() as u64,
-            oldest_open_time_known,
-            newest_open_time_seen,
-            oldest_open_time_known.unwrap_or_default(),
-            timeframe_ms,
+    pub fn done_progress(&self) -> KlineHistoryProgress {
+        compute_history_progress(
+            self.pages_fetched,
+            self.fetched as u64,
+            self.oldest_open_time_known,
+            self.newest_open_time_seen,
+            self.oldest_open_time_known.unwrap_or_default(),
+            self.timeframe.duration_ms().max(1),
             true,
-        );
-        on_progress(done_progress)?;
+        )
     }
-
-    candles_rev.reverse();
-    delta_candles_rev.reverse();
-
-    Ok((candles_rev, delta_candles_rev))
 }
 
-fn compute_history_progress(
+pub fn compute_history_progress(
     pages_fetched: u32,
     candles_fetched: u64,
     oldest_known: Option<i64>,
@@ -351,8 +493,8 @@ fn compute_history_progress(
     }
 }
 
-async fn fetch_oldest_kline_open_time(
-    client: &Client,
+pub async fn fetch_oldest_kline_open_time(
+    client: &BinanceClient,
     market_kind: MarketKind,
     symbol: &str,
     timeframe: MarketTimeframe,
@@ -360,18 +502,83 @@ async fn fetch_oldest_kline_open_time(
     let mut endpoint = klines_endpoint(market_kind, symbol, timeframe, 1, None);
     endpoint.push_str("&startTime=0");
 
-    let response = client.get(endpoint).send().await?.error_for_status()?;
+    let response = ensure_success(client.get(endpoint).send().await?, Some(symbol)).await?;
     let payload = response.json::<Vec<KlineWire>>().await?;
     Ok(payload.first().map(|kline| kline.0))
 }
 
+/// Klines opening at or after `start_time`, oldest first. Used to patch buckets the live
+/// stream could not observe, so a single page is enough.
+pub async fn fetch_klines_since(
+    client: &BinanceClient,
+    market_kind: MarketKind,
+    symbol: &str,
+    timeframe: MarketTimeframe,
+    start_time: i64,
+    limit: u16,
+) -> Result<(Vec<UiCandle>, Vec<UiDeltaCandle>), AppError> {
+    let limit = limit.clamp(1, BINANCE_MAX_KLINES_PER_REQUEST as u16);
+    let mut endpoint = klines_endpoint(market_kind, symbol, timeframe, limit, None);
+    endpoint.push_str(&format!("&startTime={start_time}"));
+
+    fetch_kline_pairs(client, endpoint, symbol).await
+}
+
+/// Up to `limit` klines opening strictly before `before_t`, oldest first.
+pub async fn fetch_klines_before(
+    client: &BinanceClient,
+    market_kind: MarketKind,
+    symbol: &str,
+    timeframe: MarketTimeframe,
+    before_t: i64,
+    limit: u16,
+) -> Result<(Vec<UiCandle>, Vec<UiDeltaCandle>), AppError> {
+    let limit = limit.clamp(1, BINANCE_MAX_KLINES_PER_REQUEST as u16);
+    let endpoint = klines_endpoint(market_kind, symbol, timeframe, limit, Some(before_t - 1));
+
+    fetch_kline_pairs(client, endpoint, symbol).await
+}
+
+async fn fetch_kline_pairs(
+    client: &BinanceClient,
+    endpoint: String,
+    symbol: &str,
+) -> Result<(Vec<UiCandle>, Vec<UiDeltaCandle>), AppError> {
+    let response = ensure_success(client.get(endpoint).send().await?, Some(symbol)).await?;
+    let page = response.json::<Vec<KlineWire>>().await?;
+    let mut candles = Vec::with_capacity(page.len());
+    let mut delta_candles = Vec::with_capacity(page.len());
+    for kline in page {
+        let (candle, delta_candle) = kline_to_domain_pair(kline)?;
+        candles.push(candle);
+        delta_candles.push(delta_candle);
+    }
+    Ok((candles, delta_candles))
+}
+
 fn kline_to_domain_pair(kline: KlineWire) -> Result<(UiCandle, UiDeltaCandle), AppError> {
-    let open = kline.1.parse::<f64>()?;
-    let high = kline.2.parse::<f64>()?;
-    let low = kline.3.parse::<f64>()?;
-    let close = kline.4.parse::<f64>()?;
-    let volume = kline.5.parse::<f64>()?;
-    let taker_buy_volume = kline.9.parse::<f64>()?;
+    kline_pair_from_fields(
+        kline.0, &kline.1, &kline.2, &kline.3, &kline.4, &kline.5, &kline.9,
+    )
+}
+
+/// Builds a candle and its taker-flow delta candle from the string fields Binance uses for
+/// klines, both over REST and in the public data archives.
+pub fn kline_pair_from_fields(
+    open_time: i64,
+    open: &str,
+    high: &str,
+    low: &str,
+    close: &str,
+    volume: &str,
+    taker_buy_volume: &str,
+) -> Result<(UiCandle, UiDeltaCandle), AppError> {
+    let open = open.parse::<f64>()?;
+    let high = high.parse::<f64>()?;
+    let low = low.parse::<f64>()?;
+    let close = close.parse::<f64>()?;
+    let volume = volume.parse::<f64>()?;
+    let taker_buy_volume = taker_buy_volume.parse::<f64>()?;
 
     if !open.is_finite()
         || !high.is_finite()
@@ -388,7 +595,7 @@ fn kline_to_domain_pair(kline: KlineWire) -> Result<(UiCandle, UiDeltaCandle), A
     }
 
     let candle = UiCandle {
-        t: kline.0,
+        t: open_time,
         o: open,
         h: high,
         l: low,
@@ -397,7 +604,7 @@ fn kline_to_domain_pair(kline: KlineWire) -> Result<(UiCandle, UiDeltaCandle), A
     };
     let signed_delta = taker_buy_volume - (volume - taker_buy_volume);
     let delta_candle = UiDeltaCandle {
-        t: kline.0,
+        t: open_time,
         o: 0.0,
         h: signed_delta.max(0.0),
         l: signed_delta.min(0.0),
@@ -434,7 +641,7 @@ struct BinanceFuturesSymbolWire {
 }
 
 pub async fn fetch_market_symbols(
-    client: &Client,
+    client: &BinanceClient,
     market_kind: MarketKind,
 ) -> Result<Vec<String>, AppError> {
     match market_kind {
@@ -443,9 +650,9 @@ pub async fn fetch_market_symbols(
     }
 }
 
-pub async fn fetch_spot_symbols(client: &Client) -> Result<Vec<String>, AppError> {
+pub async fn fetch_spot_symbols(client: &BinanceClient) -> Result<Vec<String>, AppError> {
     let endpoint = spot_symbols_endpoint();
-    let response = client.get(endpoint).send().await?.error_for_status()?;
+    let response = ensure_success(client.get(endpoint).send().await?, None).await?;
     let payload = response.json::<BinanceExchangeInfoWire>().await?;
 
     let mut symbols: Vec<String> = payload
@@ -462,9 +669,9 @@ pub async fn fetch_spot_symbols(client: &Client) -> Result<Vec<String>, AppError
     Ok(symbols)
 }
 
-pub async fn fetch_futures_usdm_symbols(client: &Client) -> Result<Vec<String>, AppError> {
+pub async fn fetch_futures_usdm_symbols(client: &BinanceClient) -> Result<Vec<String>, AppError> {
     let endpoint = futures_usdm_symbols_endpoint();
-    let response = client.get(endpoint).send().await?.error_for_status()?;
+    let response = ensure_success(client.get(endpoint).send().await?, None).await?;
     let payload = response.json::<BinanceFuturesExchangeInfoWire>().await?;
 
     let mut symbols: Vec<String> = payload
@@ -486,6 +693,47 @@ pub async fn fetch_futures_usdm_symbols(client: &Client) -> Result<Vec<String>,
 mod tests {
     use super::*;
 
+    #[test]
+    fn maps_binance_error_responses() {
+        let invalid = status_error(
+            400,
+            None,
+            r#"{"code":-1121,"msg":"Invalid symbol."}"#.to_string(),
+            Some("NOPEUSDT"),
+        );
+        assert!(matches!(invalid, AppError::InvalidSymbol(ref symbol) if symbol == "NOPEUSDT"));
+
+        let limited = status_error(
+            429,
+            Some("30"),
+            r#"{"code":-1003,"msg":"Too many requests."}"#.to_string(),
+            Some("BTCUSDT"),
+        );
+        assert!(matches!(
+            limited,
+            AppError::RateLimited {
+                status: 429,
+                retry_after_ms: Some(30_000)
+            }
+        ));
+
+        let other = status_error(
+            400,
+            None,
+            r#"{"code":-1100,"msg":"Illegal characters."}"#.to_string(),
+            None,
+        );
+        assert!(matches!(
+            other,
+            AppError::HttpStatus { status: 400, ref message } if message == "Illegal characters."
+        ));
+        let unavailable = status_error(502, None, "bad gateway".to_string(), None);
+        assert!(matches!(
+            unavailable,
+            AppError::HttpStatus { status: 502, ref message } if message == "bad gateway"
+        ));
+    }
+
     #[test]
     fn websocket_endpoint_uses_lowercase_symbol() {
         let endpoint = ws_endpoint(MarketKind::Spot, "BTCUSDT");
@@ -555,4 +803,25 @@ mod tests {
         let futures_endpoint = futures_usdm_symbols_endpoint();
         assert!(futures_endpoint.ends_with("/fapi/v1/exchangeInfo"));
     }
+
+    #[test]
+    fn locates_endpoints_on_configured_base_urls() {
+        let endpoints = BinanceEndpoints {
+            spot_stream: "ws://127.0.0.1:9001/ws".to_string(),
+            futures_usdm_rest: "http://127.0.0.1:9000".to_string(),
+            ..BinanceEndpoints::default()
+        };
+        assert_eq!(
+            endpoints.locate(ws_endpoint(MarketKind::Spot, "BTCUSDT")),
+            "ws://127.0.0.1:9001/ws/btcusdt@aggTrade"
+        );
+        assert_eq!(
+            endpoints.locate(futures_usdm_symbols_endpoint()),
+            "http://127.0.0.1:9000/fapi/v1/exchangeInfo"
+        );
+        assert_eq!(
+            endpoints.locate(server_time_endpoint(MarketKind::Spot)),
+            server_time_endpoint(MarketKind::Spot)
+        );
+    }
 }
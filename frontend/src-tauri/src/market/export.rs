@@ -0,0 +1,658 @@
+use crate::error::AppError;
+use crate::market::binance::BinanceClient;
+use crate::market::kline_store::{read_closed_klines_from, sync_kline_range, KlineSeries};
+use crate::market::pipeline::now_unix_ms;
+use crate::market::recorder::RecordedTradeCursor;
+use crate::market::sink::{MarketEvent, MarketEventSink};
+use crate::market::types::{
+    AggTradeEvent, MarketExportDataset, MarketExportFormat, MarketExportRequest, UiCandle,
+    UiDeltaCandle, UiExportProgress, UiExportResult,
+};
+use parquet::basic::Compression;
+use parquet::data_type::{BoolType, DoubleType, Int64Type};
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::SerializedFileWriter;
+use parquet::schema::parser::parse_message_type;
+use sqlx::SqlitePool;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+const EXPORT_CANDLE_PAGE_LIMIT: u32 = 10_000;
+const EXPORT_TRADE_BATCH_ROWS: usize = 65_536;
+const PARQUET_ROW_GROUP_ROWS: usize = 131_072;
+const EXPORT_PROGRESS_EMIT_THROTTLE_MS: u64 = 250;
+const EXPORT_CHANNEL_CAPACITY: usize = 4;
+
+#[derive(Debug, Clone, Copy)]
+enum ColumnKind {
+    TimestampMillis,
+    Int64,
+    Double,
+    Boolean,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ColumnSpec {
+    name: &'static str,
+    kind: ColumnKind,
+}
+
+const fn column(name: &'static str, kind: ColumnKind) -> ColumnSpec {
+    ColumnSpec { name, kind }
+}
+
+const CANDLE_COLUMNS: &[ColumnSpec] = &[
+    column("t", ColumnKind::TimestampMillis),
+    column("o", ColumnKind::Double),
+    column("h", ColumnKind::Double),
+    column("l", ColumnKind::Double),
+    column("c", ColumnKind::Double),
+    column("v", ColumnKind::Double),
+];
+
+const TRADE_COLUMNS: &[ColumnSpec] = &[
+    column("id", ColumnKind::Int64),
+    column("t", ColumnKind::TimestampMillis),
+    column("event_time", ColumnKind::TimestampMillis),
+    column("price", ColumnKind::Double),
+    column("quantity", ColumnKind::Double),
+    column("is_buyer_maker", ColumnKind::Boolean),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+enum ColumnValues {
+    Int64(Vec<i64>),
+    Double(Vec<f64>),
+    Boolean(Vec<bool>),
+}
+
+impl ColumnValues {
+    fn empty(kind: ColumnKind) -> Self {
+        match kind {
+            ColumnKind::TimestampMillis | ColumnKind::Int64 => Self::Int64(Vec::new()),
+            ColumnKind::Double => Self::Double(Vec::new()),
+            ColumnKind::Boolean => Self::Boolean(Vec::new()),
+        }
+    }
+
+    fn append(&mut self, other: &mut Self) {
+        match (self, other) {
+            (Self::Int64(into), Self::Int64(from)) => into.append(from),
+            (Self::Double(into), Self::Double(from)) => into.append(from),
+            (Self::Boolean(into), Self::Boolean(from)) => into.append(from),
+            _ => unreachable!("export batches share one column layout"),
+        }
+    }
+
+    fn write_csv_value(&self, row: usize, out: &mut impl Write) -> std::io::Result<()> {
+        match self {
+            Self::Int64(values) => write!(out, "{}", values[row]),
+            Self::Double(values) => write!(out, "{}", values[row]),
+            Self::Boolean(values) => write!(out, "{}", values[row]),
+        }
+    }
+}
+
+/// Column-major rows in the layout of one dataset.
+#[derive(Debug, Clone, PartialEq)]
+struct ExportBatch {
+    columns: Vec<ColumnValues>,
+    rows: usize,
+}
+
+impl ExportBatch {
+    fn empty(specs: &[ColumnSpec]) -> Self {
+        Self {
+            columns: specs
+                .iter()
+                .map(|spec| ColumnValues::empty(spec.kind))
+                .collect(),
+            rows: 0,
+        }
+    }
+
+    fn from_ohlcv(rows: impl ExactSizeIterator<Item = (i64, [f64; 5])>) -> Self {
+        let count = rows.len();
+        let mut times = Vec::with_capacity(count);
+        let mut values: [Vec<f64>; 5] = std::array::from_fn(|_| Vec::with_capacity(count));
+        for (t, ohlcv) in rows {
+            times.push(t);
+            for (column, value) in values.iter_mut().zip(ohlcv) {
+                column.push(value);
+            }
+        }
+
+        let mut columns = vec![ColumnValues::Int64(times)];
+        columns.extend(values.into_iter().map(ColumnValues::Double));
+        Self {
+            columns,
+            rows: count,
+        }
+    }
+
+    fn from_candles(candles: &[UiCandle]) -> Self {
+        Self::from_ohlcv(
+            candles
+                .iter()
+                .map(|candle| (candle.t, [candle.o, candle.h, candle.l, candle.c, candle.v])),
+        )
+    }
+
+    fn from_delta_candles(candles: &[UiDeltaCandle]) -> Self {
+        Self::from_ohlcv(
+            candles
+                .iter()
+                .map(|candle| (candle.t, [candle.o, candle.h, candle.l, candle.c, candle.v])),
+        )
+    }
+
+    fn from_trades(trades: &[AggTradeEvent]) -> Self {
+        Self {
+            columns: vec![
+                ColumnValues::Int64(
+                    trades
+                        .iter()
+                        .map(|trade| trade.aggregate_trade_id as i64)
+                        .collect(),
+                ),
+                ColumnValues::Int64(trades.iter().map(|trade| trade.trade_time).collect()),
+                ColumnValues::Int64(trades.iter().map(|trade| trade.event_time).collect()),
+                ColumnValues::Double(trades.iter().map(|trade| trade.price).collect()),
+                ColumnValues::Double(trades.iter().map(|trade| trade.quantity).collect()),
+                ColumnValues::Boolean(trades.iter().map(|trade| trade.is_buyer_maker).collect()),
+            ],
+            rows: trades.len(),
+        }
+    }
+
+    fn append(&mut self, mut other: ExportBatch) {
+        for (into, from) in self.columns.iter_mut().zip(other.columns.iter_mut()) {
+            into.append(from);
+        }
+        self.rows += other.rows;
+    }
+}
+
+enum ExportSink {
+    Csv(BufWriter<File>),
+    Parquet {
+        writer: Box<SerializedFileWriter<File>>,
+        specs: &'static [ColumnSpec],
+        pending: ExportBatch,
+    },
+}
+
+impl ExportSink {
+    fn create(
+        path: &Path,
+        format: MarketExportFormat,
+        specs: &'static [ColumnSpec],
+    ) -> Result<Self, AppError> {
+        let file = File::create(path)?;
+        match format {
+            MarketExportFormat::Csv => {
+                let mut out = BufWriter::new(file);
+                let header: Vec<&str> = specs.iter().map(|spec| spec.name).collect();
+                writeln!(out, "{}", header.join(","))?;
+                Ok(Self::Csv(out))
+            }
+            MarketExportFormat::Parquet => {
+                let schema = Arc::new(parse_message_type(&parquet_message_type(specs))?);
+                let properties = Arc::new(
+                    WriterProperties::builder()
+                        .set_compression(Compression::SNAPPY)
+                        .build(),
+                );
+                Ok(Self::Parquet {
+                    writer: Box::new(SerializedFileWriter::new(file, schema, properties)?),
+                    specs,
+                    pending: ExportBatch::empty(specs),
+                })
+            }
+        }
+    }
+
+    fn write(&mut self, batch: ExportBatch) -> Result<(), AppError> {
+        match self {
+            Self::Csv(out) => {
+                for row in 0..batch.rows {
+                    for (index, column) in batch.columns.iter().enumerate() {
+                        if index > 0 {
+                            out.write_all(b",")?;
+                        }
+                        column.write_csv_value(row, out)?;
+                    }
+                    out.write_all(b"\n")?;
+                }
+                Ok(())
+            }
+            Self::Parquet {
+                writer,
+                specs,
+                pending,
+            } => {
+                pending.append(batch);
+                if pending.rows >= PARQUET_ROW_GROUP_ROWS {
+                    let full = std::mem::replace(pending, ExportBatch::empty(specs));
+                    write_row_group(writer, &full)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn finish(self) -> Result<(), AppError> {
+        match self {
+            Self::Csv(mut out) => {
+                out.flush()?;
+                out.get_ref().sync_all()?;
+            }
+            Self::Parquet {
+                mut writer,
+                pending,
+                ..
+            } => {
+                if pending.rows > 0 {
+                    write_row_group(&mut writer, &pending)?;
+                }
+                writer.close()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn parquet_message_type(specs: &[ColumnSpec]) -> String {
+    let fields: Vec<String> = specs
+        .iter()
+        .map(|spec| match spec.kind {
+            ColumnKind::TimestampMillis => {
+                format!("REQUIRED INT64 {} (TIMESTAMP(MILLIS,true));", spec.name)
+            }
+            ColumnKind::Int64 => format!("REQUIRED INT64 {};", spec.name),
+            ColumnKind::Double => format!("REQUIRED DOUBLE {};", spec.name),
+            ColumnKind::Boolean => format!("REQUIRED BOOLEAN {};", spec.name),
+        })
+        .collect();
+    format!("message export {{ {} }}", fields.join(" "))
+}
+
+fn write_row_group(
+    writer: &mut SerializedFileWriter<File>,
+    batch: &ExportBatch,
+) -> Result<(), AppError> {
+    let mut row_group = writer.next_row_group()?;
+    let mut columns = batch.columns.iter();
+    while let Some(mut column_writer) = row_group.next_column()? {
+        match columns.next() {
+            Some(ColumnValues::Int64(values)) => {
+                column_writer
+                    .typed::<Int64Type>()
+                    .write_batch(values, None, None)?;
+            }
+            Some(ColumnValues::Double(values)) => {
+                column_writer
+                    .typed::<DoubleType>()
+                    .write_batch(values, None, None)?;
+            }
+            Some(ColumnValues::Boolean(values)) => {
+                column_writer
+                    .typed::<BoolType>()
+                    .write_batch(values, None, None)?;
+            }
+            None => {
+                return Err(AppError::Internal(
+                    "export batch is missing a column".to_string(),
+                ))
+            }
+        }
+        column_writer.close()?;
+    }
+    row_group.close()?;
+    Ok(())
+}
+
+/// Writes every batch `next_batch` yields to a `.partial` file that only replaces `path` once
+/// the export completed, so an aborted export never leaves a truncated file behind.
+fn write_export(
+    path: &Path,
+    format: MarketExportFormat,
+    specs: &'static [ColumnSpec],
+    mut next_batch: impl FnMut() -> Result<Option<ExportBatch>, AppError>,
+) -> Result<u64, AppError> {
+    let mut partial_name = path.file_name().unwrap_or_default().to_os_string();
+    partial_name.push(".partial");
+    let partial = path.with_file_name(partial_name);
+
+    let mut write_all = || -> Result<u64, AppError> {
+        let mut sink = ExportSink::create(&partial, format, specs)?;
+        let mut rows = 0_u64;
+        while let Some(batch) = next_batch()? {
+            rows += batch.rows as u64;
+            sink.write(batch)?;
+        }
+        sink.finish()?;
+        Ok(rows)
+    };
+
+    match write_all() {
+        Ok(rows) => {
+            fs::rename(&partial, path)?;
+            Ok(rows)
+        }
+        Err(error) => {
+            let _ = fs::remove_file(&partial);
+            Err(error)
+        }
+    }
+}
+
+enum ExportMessage {
+    Batch(ExportBatch),
+    Finish,
+}
+
+#[derive(Clone)]
+struct ExportProgress {
+    events: Arc<dyn MarketEventSink>,
+    path: String,
+    from_t: i64,
+    to_t: i64,
+    last_emit_at: Option<Instant>,
+}
+
+impl ExportProgress {
+    fn report(&mut self, rows_written: u64, reached_t: i64) {
+        let now = Instant::now();
+        let due = self.last_emit_at.is_none_or(|last| {
+            now.saturating_duration_since(last)
+                >= Duration::from_millis(EXPORT_PROGRESS_EMIT_THROTTLE_MS)
+        });
+        if !due {
+            return;
+        }
+        self.last_emit_at = Some(now);
+
+        let span = (self.to_t - self.from_t).max(1) as f64;
+        let progress_pct = ((reached_t - self.from_t) as f64 / span * 100.0).clamp(0.0, 100.0);
+        self.emit(rows_written, Some(progress_pct), false);
+    }
+
+    fn emit(&self, rows_written: u64, progress_pct: Option<f64>, done: bool) {
+        let payload = UiExportProgress {
+            path: self.path.clone(),
+            rows_written,
+            progress_pct,
+            done,
+        };
+        if let Err(error) = self.events.emit(MarketEvent::ExportProgress(&payload)) {
+            eprintln!("failed to emit export_progress event: {error}");
+        }
+    }
+}
+
+/// Exports a closed-candle range from the kline cache, syncing missing spans from REST
+/// first, or a range of recorded trades, to CSV or Parquet at `request.path`.
+pub async fn export_market_data(
+    events: Arc<dyn MarketEventSink>,
+    db_pool: &SqlitePool,
+    binance: &BinanceClient,
+    recordings_root: &Path,
+    request: MarketExportRequest,
+) -> Result<UiExportResult, AppError> {
+    let path_label = request.path.display().to_string();
+    let mut progress = ExportProgress {
+        events,
+        path: path_label.clone(),
+        from_t: request.from_t,
+        to_t: request.to_t,
+        last_emit_at: None,
+    };
+    progress.emit(0, Some(0.0), false);
+
+    let rows_written = match request.dataset {
+        MarketExportDataset::Trades => {
+            let root = recordings_root.to_path_buf();
+            let mut trade_progress = progress.clone();
+            let request = request.clone();
+            tauri::async_runtime::spawn_blocking(move || {
+                export_trades(&root, &request, |rows, reached_t| {
+                    trade_progress.report(rows, reached_t)
+                })
+            })
+            .await
+            .map_err(|error| AppError::Internal(format!("export task failed: {error}")))??
+        }
+        MarketExportDataset::Candles | MarketExportDataset::DeltaCandles => {
+            export_candles(db_pool, binance, &request, &mut progress).await?
+        }
+    };
+
+    progress.emit(rows_written, Some(100.0), true);
+    Ok(UiExportResult {
+        path: path_label,
+        rows_written,
+    })
+}
+
+async fn export_candles(
+    db_pool: &SqlitePool,
+    binance: &BinanceClient,
+    request: &MarketExportRequest,
+    progress: &mut ExportProgress,
+) -> Result<u64, AppError> {
+    let series = KlineSeries::new(request.market_kind, &request.symbol, request.timeframe);
+    sync_kline_range(
+        db_pool,
+        binance,
+        series,
+        request.from_t,
+        request.to_t,
+        now_unix_ms(),
+    )
+    .await?;
+
+    let (sender, mut receiver) = mpsc::channel::<ExportMessage>(EXPORT_CHANNEL_CAPACITY);
+    let path = request.path.clone();
+    let format = request.format;
+    let writer = tauri::async_runtime::spawn_blocking(move || {
+        write_export(&path, format, CANDLE_COLUMNS, || {
+            match receiver.blocking_recv() {
+                Some(ExportMessage::Batch(batch)) => Ok(Some(batch)),
+                Some(ExportMessage::Finish) => Ok(None),
+                None => Err(AppError::Cancelled("export was aborted".to_string())),
+            }
+        })
+    });
+
+    let mut cursor = request.from_t;
+    let mut rows_sent = 0_u64;
+    let read_outcome: Result<(), AppError> = async {
+        loop {
+            let (candles, delta_candles) = read_closed_klines_from(
+                db_pool,
+                series,
+                cursor,
+                request.to_t,
+                EXPORT_CANDLE_PAGE_LIMIT,
+            )
+            .await?;
+            let Some(last) = candles.last().map(|candle| candle.t) else {
+                break;
+            };
+            let batch = match request.dataset {
+                MarketExportDataset::DeltaCandles => {
+                    ExportBatch::from_delta_candles(&delta_candles)
+                }
+                _ => ExportBatch::from_candles(&candles),
+            };
+            rows_sent += batch.rows as u64;
+            if sender.send(ExportMessage::Batch(batch)).await.is_err() {
+                break;
+            }
+            progress.report(rows_sent, last);
+            cursor = last + 1;
+        }
+        Ok(())
+    }
+    .await;
+
+    if read_outcome.is_ok() {
+        let _ = sender.send(ExportMessage::Finish).await;
+    }
+    drop(sender);
+    let written = writer
+        .await
+        .map_err(|error| AppError::Internal(format!("export task failed: {error}")))?;
+    read_outcome?;
+    written
+}
+
+fn export_trades(
+    recordings_root: &Path,
+    request: &MarketExportRequest,
+    mut on_progress: impl FnMut(u64, i64),
+) -> Result<u64, AppError> {
+    let mut cursor =
+        RecordedTradeCursor::open(recordings_root, request.market_kind, &request.symbol)?;
+    cursor.seek(request.from_t)?;
+
+    let mut rows_read = 0_u64;
+    let mut exhausted = false;
+    write_export(&request.path, request.format, TRADE_COLUMNS, || {
+        if exhausted {
+            return Ok(None);
+        }
+        let mut trades = Vec::with_capacity(EXPORT_TRADE_BATCH_ROWS);
+        while trades.len() < EXPORT_TRADE_BATCH_ROWS {
+            match cursor.next_trade()? {
+                Some(trade) if trade.trade_time <= request.to_t => trades.push(trade),
+                _ => {
+                    exhausted = true;
+                    break;
+                }
+            }
+        }
+        let Some(last) = trades.last().map(|trade| trade.trade_time) else {
+            return Ok(None);
+        };
+        rows_read += trades.len() as u64;
+        on_progress(rows_read, last);
+        Ok(Some(ExportBatch::from_trades(&trades)))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn unique_path(name: &str) -> PathBuf {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .expect("system clock should be after unix epoch")
+            .as_nanos();
+        std::env::temp_dir().join(format!("market-export-{timestamp}-{name}"))
+    }
+
+    fn candles() -> Vec<UiCandle> {
+        vec![
+            UiCandle::from_trade(60_000, 100.5, 2.0),
+            UiCandle::from_trade(120_000, 101.25, 0.5),
+        ]
+    }
+
+    #[test]
+    fn writes_candles_as_csv() {
+        let path = unique_path("candles.csv");
+        let mut batches = vec![ExportBatch::from_candles(&candles())].into_iter();
+        let rows = write_export(&path, MarketExportFormat::Csv, CANDLE_COLUMNS, || {
+            Ok(batches.next())
+        })
+        .expect("csv export succeeds");
+
+        assert_eq!(rows, 2);
+        let written = fs::read_to_string(&path).expect("csv exists");
+        assert_eq!(
+            written,
+            "t,o,h,l,c,v\n60000,100.5,100.5,100.5,100.5,2\n120000,101.25,101.25,101.25,101.25,0.5\n"
+        );
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn writes_trades_as_parquet_row_groups() {
+        use parquet::file::reader::{FileReader, SerializedFileReader};
+
+        let path = unique_path("trades.parquet");
+        let trades: Vec<AggTradeEvent> = (1..=3_u64)
+            .map(|id| AggTradeEvent {
+                event_time: 1_000 + id as i64,
+                aggregate_trade_id: id,
+                price: 50.0 + id as f64,
+                quantity: 0.1,
+                trade_time: 1_000 + id as i64,
+                is_buyer_maker: id == 2,
+            })
+            .collect();
+        let mut batches = vec![
+            ExportBatch::from_trades(&trades[..2]),
+            ExportBatch::from_trades(&trades[2..]),
+        ]
+        .into_iter();
+        let rows = write_export(&path, MarketExportFormat::Parquet, TRADE_COLUMNS, || {
+            Ok(batches.next())
+        })
+        .expect("parquet export succeeds");
+        assert_eq!(rows, 3);
+
+        let reader =
+            SerializedFileReader::new(File::open(&path).expect("parquet exists")).expect("reads");
+        let metadata = reader.metadata();
+        assert_eq!(metadata.file_metadata().num_rows(), 3);
+        assert_eq!(metadata.num_row_groups(), 1);
+        let names: Vec<&str> = metadata
+            .file_metadata()
+            .schema_descr()
+            .columns()
+            .iter()
+            .map(|column| column.name())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "id",
+                "t",
+                "event_time",
+                "price",
+                "quantity",
+                "is_buyer_maker"
+            ]
+        );
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn aborted_export_leaves_no_file() {
+        let path = unique_path("aborted.csv");
+        let mut calls = 0;
+        let result = write_export(&path, MarketExportFormat::Csv, CANDLE_COLUMNS, || {
+            calls += 1;
+            if calls == 1 {
+                Ok(Some(ExportBatch::from_candles(&candles())))
+            } else {
+                Err(AppError::Internal("reader failed".to_string()))
+            }
+        });
+
+        assert!(result.is_err());
+        assert!(!path.exists());
+        let mut partial_name = path.file_name().expect("file name").to_os_string();
+        partial_name.push(".partial");
+        assert!(!path.with_file_name(partial_name).exists());
+    }
+}
@@ -0,0 +1,490 @@
+use crate::market::binance::{BinanceClient, BinanceEndpoints};
+use crate::market::mock::agg_trade_payload;
+use crate::market::types::{AggTradeEvent, MarketTimeframe, UiCandle};
+use futures_util::{SinkExt, StreamExt};
+use parking_lot::Mutex;
+use std::collections::{HashMap, VecDeque};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::handshake::server::{Request, Response};
+use tokio_tungstenite::tungstenite::Message;
+
+const MAX_REQUEST_HEAD_BYTES: usize = 16 * 1024;
+const DEFAULT_KLINES_LIMIT: usize = 500;
+
+/// One scripted step of an aggTrade websocket session.
+#[derive(Debug, Clone)]
+pub enum FakeFrame {
+    Text(String),
+    Close,
+    Pause(Duration),
+}
+
+impl FakeFrame {
+    pub fn trade(trade: &AggTradeEvent) -> Self {
+        Self::Text(agg_trade_payload(trade))
+    }
+}
+
+/// A kline as the fake exchange serves it; the delta candle is derived from the taker buy
+/// volume exactly as for real klines.
+#[derive(Debug, Clone)]
+pub struct FakeKline {
+    pub candle: UiCandle,
+    pub taker_buy_volume: f64,
+}
+
+#[derive(Debug, Default)]
+struct FakeBinanceState {
+    agg_trade_sessions: VecDeque<Vec<FakeFrame>>,
+    agg_trade_connections: Vec<String>,
+    snapshot: Option<(u64, f64)>,
+    klines: Vec<FakeKline>,
+    server_time_ms: i64,
+    spot_symbols: Vec<String>,
+    futures_usdm_symbols: Vec<String>,
+    requests: Vec<String>,
+}
+
+/// In-process stand-in for the Binance endpoints `binance.rs` talks to: the aggTrade stream,
+/// the aggTrades snapshot, klines, server time and exchangeInfo, for both spot and USD-M
+/// futures. Payloads are scripted by the test; calls made through [`FakeBinance::client`]
+/// reach it.
+pub struct FakeBinance {
+    state: Arc<Mutex<FakeBinanceState>>,
+    tasks: Vec<JoinHandle<()>>,
+    endpoints: BinanceEndpoints,
+}
+
+impl FakeBinance {
+    pub async fn start() -> Self {
+        let state = Arc::new(Mutex::new(FakeBinanceState::default()));
+
+        let http_listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("fake binance http listener binds");
+        let ws_listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("fake binance websocket listener binds");
+        let http_addr = http_listener.local_addr().expect("http address");
+        let ws_addr = ws_listener.local_addr().expect("websocket address");
+
+        let tasks = vec![
+            tokio::spawn(accept_loop(http_listener, Arc::clone(&state), serve_http)),
+            tokio::spawn(accept_loop(
+                ws_listener,
+                Arc::clone(&state),
+                serve_websocket,
+            )),
+        ];
+
+        Self {
+            state,
+            tasks,
+            endpoints: fake_endpoints(http_addr, ws_addr),
+        }
+    }
+
+    /// A client whose exchange calls all go to this server.
+    pub fn client(&self) -> BinanceClient {
+        BinanceClient::new(self.endpoints.clone())
+    }
+
+    /// Queues the frames the next aggTrade connection receives. A connection with nothing
+    /// queued is dropped during the handshake, like an unreachable exchange.
+    pub fn push_agg_trade_session(&self, frames: Vec<FakeFrame>) {
+        self.state.lock().agg_trade_sessions.push_back(frames);
+    }
+
+    pub fn set_snapshot(&self, aggregate_trade_id: u64, price: f64) {
+        self.state.lock().snapshot = Some((aggregate_trade_id, price));
+    }
+
+    pub fn set_klines(&self, mut klines: Vec<FakeKline>) {
+        klines.sort_by_key(|kline| kline.candle.t);
+        self.state.lock().klines = klines;
+    }
+
+    pub fn set_server_time(&self, server_time_ms: i64) {
+        self.state.lock().server_time_ms = server_time_ms;
+    }
+
+    pub fn set_symbols(&self, spot: &[&str], futures_usdm: &[&str]) {
+        let mut writable = self.state.lock();
+        writable.spot_symbols = spot.iter().map(|symbol| symbol.to_string()).collect();
+        writable.futures_usdm_symbols = futures_usdm
+            .iter()
+            .map(|symbol| symbol.to_string())
+            .collect();
+    }
+
+    /// Paths (with query) of every REST request served so far.
+    pub fn requests(&self) -> Vec<String> {
+        self.state.lock().requests.clone()
+    }
+
+    /// Paths of every aggTrade websocket connection accepted so far.
+    pub fn agg_trade_connections(&self) -> Vec<String> {
+        self.state.lock().agg_trade_connections.clone()
+    }
+}
+
+impl Drop for FakeBinance {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+fn fake_endpoints(http_addr: SocketAddr, ws_addr: SocketAddr) -> BinanceEndpoints {
+    BinanceEndpoints {
+        spot_stream: format!("ws://{ws_addr}/ws"),
+        spot_rest: format!("http://{http_addr}"),
+        futures_usdm_stream: format!("ws://{ws_addr}/fws"),
+        futures_usdm_rest: format!("http://{http_addr}"),
+    }
+}
+
+async fn accept_loop<F, Fut>(listener: TcpListener, state: Arc<Mutex<FakeBinanceState>>, serve: F)
+where
+    F: Fn(TcpStream, Arc<Mutex<FakeBinanceState>>) -> Fut,
+    Fut: std::future::Future<Output = ()> + Send + 'static,
+{
+    while let Ok((stream, _)) = listener.accept().await {
+        tokio::spawn(serve(stream, Arc::clone(&state)));
+    }
+}
+
+// The handshake callback signature is fixed by tungstenite.
+#[allow(clippy::result_large_err)]
+async fn serve_websocket(stream: TcpStream, state: Arc<Mutex<FakeBinanceState>>) {
+    let Some(frames) = state.lock().agg_trade_sessions.pop_front() else {
+        return;
+    };
+
+    let mut path = String::new();
+    let handshake = tokio_tungstenite::accept_hdr_async(stream, |request: &Request, response| {
+        path = request.uri().to_string();
+        Ok::<Response, _>(response)
+    })
+    .await;
+    let Ok(mut websocket) = handshake else {
+        return;
+    };
+    state.lock().agg_trade_connections.push(path);
+
+    for frame in frames {
+        let sent = match frame {
+            FakeFrame::Text(payload) => websocket.send(Message::Text(payload)).await,
+            FakeFrame::Close => {
+                let _ = websocket.close(None).await;
+                return;
+            }
+            FakeFrame::Pause(duration) => {
+                tokio::time::sleep(duration).await;
+                Ok(())
+            }
+        };
+        if sent.is_err() {
+            return;
+        }
+    }
+
+    // Keep the session open until the client hangs up, as the exchange would.
+    while let Some(Ok(_)) = websocket.next().await {}
+}
+
+async fn serve_http(mut stream: TcpStream, state: Arc<Mutex<FakeBinanceState>>) {
+    let mut head = Vec::new();
+    let mut buffer = [0_u8; 1024];
+    while !head.windows(4).any(|window| window == b"\r\n\r\n") {
+        match stream.read(&mut buffer).await {
+            Ok(0) | Err(_) => return,
+            Ok(read) => head.extend_from_slice(&buffer[..read]),
+        }
+        if head.len() > MAX_REQUEST_HEAD_BYTES {
+            return;
+        }
+    }
+
+    let head = String::from_utf8_lossy(&head);
+    let target = head
+        .lines()
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .unwrap_or("/")
+        .to_string();
+    let (status, body) = {
+        let mut writable = state.lock();
+        writable.requests.push(target.clone());
+        route(&writable, &target)
+    };
+
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+fn route(state: &FakeBinanceState, target: &str) -> (&'static str, String) {
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let params: HashMap<&str, &str> = query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .collect();
+
+    match path {
+        "/api/v3/aggTrades" | "/fapi/v1/aggTrades" => match state.snapshot {
+            Some((aggregate_trade_id, price)) => (
+                "200 OK",
+                format!(r#"[{{"a":{aggregate_trade_id},"p":"{price}","q":"1","T":0,"m":false}}]"#),
+            ),
+            None => ("503 Service Unavailable", r#"{"code":-1}"#.to_string()),
+        },
+        "/api/v3/time" | "/fapi/v1/time" => (
+            "200 OK",
+            format!(r#"{{"serverTime":{}}}"#, state.server_time_ms),
+        ),
+        "/api/v3/klines" | "/fapi/v1/klines" => ("200 OK", klines_body(state, &params)),
+        "/api/v3/exchangeInfo" => {
+            let symbols: Vec<String> = state
+                .spot_symbols
+                .iter()
+                .map(|symbol| {
+                    format!(
+                        r#"{{"symbol":"{symbol}","status":"TRADING","isSpotTradingAllowed":true}}"#
+                    )
+                })
+                .collect();
+            (
+                "200 OK",
+                format!(r#"{{"symbols":[{}]}}"#, symbols.join(",")),
+            )
+        }
+        "/fapi/v1/exchangeInfo" => {
+            let symbols: Vec<String> = state
+                .futures_usdm_symbols
+                .iter()
+                .map(|symbol| {
+                    format!(
+                        r#"{{"symbol":"{symbol}","status":"TRADING","contractType":"PERPETUAL"}}"#
+                    )
+                })
+                .collect();
+            (
+                "200 OK",
+                format!(r#"{{"symbols":[{}]}}"#, symbols.join(",")),
+            )
+        }
+        _ => ("404 Not Found", r#"{"code":-1}"#.to_string()),
+    }
+}
+
+/// Mirrors the exchange's paging rules: `startTime` pages forward from the oldest match,
+/// otherwise the newest `limit` klines at or before `endTime` are returned.
+fn klines_body(state: &FakeBinanceState, params: &HashMap<&str, &str>) -> String {
+    let parse = |name: &str| params.get(name).and_then(|value| value.parse::<i64>().ok());
+    let limit = params
+        .get("limit")
+        .and_then(|value| value.parse::<usize>().ok())
+        .unwrap_or(DEFAULT_KLINES_LIMIT);
+    let timeframe_ms = params
+        .get("interval")
+        .and_then(|value| MarketTimeframe::parse_str(value).ok())
+        .map(MarketTimeframe::duration_ms)
+        .unwrap_or(60_000);
+    let start_time = parse("startTime");
+    let end_time = parse("endTime").unwrap_or(i64::MAX);
+
+    let matching: Vec<&FakeKline> = state
+        .klines
+        .iter()
+        .filter(|kline| {
+            kline.candle.t <= end_time && start_time.is_none_or(|start| kline.candle.t >= start)
+        })
+        .collect();
+    let page = if start_time.is_some() {
+        &matching[..matching.len().min(limit)]
+    } else {
+        &matching[matching.len().saturating_sub(limit)..]
+    };
+
+    let rows: Vec<String> = page
+        .iter()
+        .map(|kline| {
+            let candle = &kline.candle;
+            format!(
+                r#"[{},"{}","{}","{}","{}","{}",{},"0",1,"{}","0","0"]"#,
+                candle.t,
+                candle.o,
+                candle.h,
+                candle.l,
+                candle.c,
+                candle.v,
+                candle.t + timeframe_ms - 1,
+                kline.taker_buy_volume
+            )
+        })
+        .collect();
+    format!("[{}]", rows.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::market::binance::{
+        connect_agg_trade_stream, fetch_klines_since, fetch_latest_agg_trade_snapshot,
+        fetch_market_symbols, fetch_server_time_ms, KlineHistoryPager,
+    };
+    use crate::market::types::{parse_agg_trade_payload, MarketKind};
+
+    fn trade(aggregate_trade_id: u64) -> AggTradeEvent {
+        AggTradeEvent {
+            event_time: 1_000 + aggregate_trade_id as i64,
+            aggregate_trade_id,
+            price: 100.0 + aggregate_trade_id as f64,
+            quantity: 0.5,
+            trade_time: 1_000 + aggregate_trade_id as i64,
+            is_buyer_maker: false,
+        }
+    }
+
+    fn klines(count: i64) -> Vec<FakeKline> {
+        (0..count)
+            .map(|index| FakeKline {
+                candle: UiCandle {
+                    t: index * 60_000,
+                    o: 10.0,
+                    h: 12.0,
+                    l: 9.0,
+                    c: 11.0,
+                    v: 4.0,
+                },
+                taker_buy_volume: 3.0,
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn serves_scripted_rest_payloads() {
+        let fake = FakeBinance::start().await;
+        fake.set_snapshot(42, 65_000.5);
+        fake.set_server_time(1_700_000_000_000);
+        fake.set_symbols(&["ETHUSDT", "BTCUSDT"], &["BTCUSDT"]);
+        let client = fake.client();
+
+        let snapshot = fetch_latest_agg_trade_snapshot(&client, MarketKind::FuturesUsdm, "btcusdt")
+            .await
+            .expect("snapshot");
+        assert_eq!(snapshot.aggregate_trade_id, 42);
+        assert_eq!(snapshot.price, 65_000.5);
+        assert_eq!(
+            fetch_server_time_ms(&client, MarketKind::Spot)
+                .await
+                .expect("server time"),
+            1_700_000_000_000
+        );
+        assert_eq!(
+            fetch_market_symbols(&client, MarketKind::Spot)
+                .await
+                .expect("spot symbols"),
+            vec!["BTCUSDT".to_string(), "ETHUSDT".to_string()]
+        );
+        assert!(fake
+            .requests()
+            .iter()
+            .any(|request| request.starts_with("/fapi/v1/aggTrades?symbol=BTCUSDT")));
+    }
+
+    #[tokio::test]
+    async fn pages_klines_like_the_exchange() {
+        let fake = FakeBinance::start().await;
+        fake.set_klines(klines(2_500));
+        let client = fake.client();
+
+        let mut pager = KlineHistoryPager::new(
+            &client,
+            MarketKind::Spot,
+            "BTCUSDT",
+            MarketTimeframe::M1,
+            2_200,
+            false,
+        )
+        .await
+        .expect("pager");
+        let mut pages = Vec::new();
+        while let Some(page) = pager.next_page().await.expect("page") {
+            pages.push(page);
+        }
+        assert_eq!(pages.len(), 3);
+        assert_eq!(pages[0].candles.last().expect("newest").t, 2_499 * 60_000);
+        assert_eq!(pages[2].candles[0].t, 300 * 60_000);
+        assert_eq!(pages[0].delta_candles[0].c, 2.0);
+
+        let (since, _) = fetch_klines_since(
+            &client,
+            MarketKind::Spot,
+            "BTCUSDT",
+            MarketTimeframe::M1,
+            2_490 * 60_000,
+            100,
+        )
+        .await
+        .expect("klines since");
+        assert_eq!(since.len(), 10);
+    }
+
+    #[tokio::test]
+    async fn streams_scripted_agg_trade_sessions() {
+        let fake = FakeBinance::start().await;
+        fake.push_agg_trade_session(vec![
+            FakeFrame::trade(&trade(1)),
+            FakeFrame::trade(&trade(2)),
+            FakeFrame::Close,
+        ]);
+        fake.push_agg_trade_session(vec![FakeFrame::trade(&trade(5))]);
+        let client = fake.client();
+
+        let mut first = connect_agg_trade_stream(&client, MarketKind::Spot, "BTCUSDT")
+            .await
+            .expect("first session");
+        let mut ids = Vec::new();
+        while let Some(Ok(message)) = first.next().await {
+            match message {
+                Message::Text(text) => ids.push(
+                    parse_agg_trade_payload(&mut text.into_bytes())
+                        .expect("trade payload")
+                        .aggregate_trade_id,
+                ),
+                Message::Close(_) => break,
+                _ => {}
+            }
+        }
+        assert_eq!(ids, vec![1, 2]);
+
+        let mut second = connect_agg_trade_stream(&client, MarketKind::Spot, "BTCUSDT")
+            .await
+            .expect("second session");
+        let Some(Ok(Message::Text(text))) = second.next().await else {
+            panic!("second session should send a trade");
+        };
+        assert!(text.contains(r#""a":5"#));
+        assert_eq!(
+            fake.agg_trade_connections(),
+            vec!["/ws/btcusdt@aggTrade".to_string(); 2]
+        );
+
+        assert!(
+            connect_agg_trade_stream(&client, MarketKind::Spot, "BTCUSDT")
+                .await
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,250 @@
+use crate::error::AppError;
+use crate::market::types::{UiCandle, UiDeltaCandle};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tauri::ipc::{Channel, InvokeResponseBody};
+use tokio::sync::watch;
+
+pub const HISTORY_CHUNK_MAGIC: [u8; 4] = *b"HCK1";
+pub const HISTORY_CHUNK_HEADER_LEN: usize = 24;
+pub const HISTORY_CHUNK_FLAG_FINAL: u32 = 1;
+/// Chunks the webview may hold unacknowledged before the core stops sending.
+pub const HISTORY_CHUNK_ACK_WINDOW: u32 = 2;
+const HISTORY_CHUNK_ACK_TIMEOUT_MS: u64 = 30_000;
+
+/// Encodes one history chunk as little-endian columns so the webview can read them through
+/// typed-array views without parsing:
+///
+/// ```text
+/// magic "HCK1" | flags u32 | bootstrap_id u64 | seq u32 | count u32      (24 bytes)
+/// t i64[count]
+/// o, h, l, c, v f64[count] each
+/// delta h, delta l, delta c f64[count] each   (delta open is always 0, volume is v)
+/// ```
+///
+/// Rows inside a chunk are oldest first; chunks themselves are sent newest first.
+pub fn encode_history_chunk(
+    bootstrap_id: u64,
+    seq: u32,
+    is_final: bool,
+    candles: &[UiCandle],
+    delta_candles: &[UiDeltaCandle],
+) -> Result<Vec<u8>, AppError> {
+    if candles.len() != delta_candles.len() {
+        return Err(AppError::Internal(format!(
+            "history chunk has {} candles but {} delta candles",
+            candles.len(),
+            delta_candles.len()
+        )));
+    }
+    let count = u32::try_from(candles.len())
+        .map_err(|_| AppError::Internal("history chunk is too large".to_string()))?;
+
+    let mut bytes = Vec::with_capacity(HISTORY_CHUNK_HEADER_LEN + candles.len() * 9 * 8);
+    bytes.extend_from_slice(&HISTORY_CHUNK_MAGIC);
+    let flags = if is_final {
+        HISTORY_CHUNK_FLAG_FINAL
+    } else {
+        0
+    };
+    bytes.extend_from_slice(&flags.to_le_bytes());
+    bytes.extend_from_slice(&bootstrap_id.to_le_bytes());
+    bytes.extend_from_slice(&seq.to_le_bytes());
+    bytes.extend_from_slice(&count.to_le_bytes());
+
+    for candle in candles {
+        bytes.extend_from_slice(&candle.t.to_le_bytes());
+    }
+    let candle_columns: [fn(&UiCandle) -> f64; 5] = [
+        |candle| candle.o,
+        |candle| candle.h,
+        |candle| candle.l,
+        |candle| candle.c,
+        |candle| candle.v,
+    ];
+    for column in candle_columns {
+        for candle in candles {
+            bytes.extend_from_slice(&column(candle).to_le_bytes());
+        }
+    }
+    let delta_columns: [fn(&UiDeltaCandle) -> f64; 3] =
+        [|candle| candle.h, |candle| candle.l, |candle| candle.c];
+    for column in delta_columns {
+        for candle in delta_candles {
+            bytes.extend_from_slice(&column(candle).to_le_bytes());
+        }
+    }
+
+    Ok(bytes)
+}
+
+/// Tracks the highest chunk the webview has acknowledged for the current bootstrap. Each
+/// stream start begins a new bootstrap so late acks from a previous stream are ignored.
+#[derive(Debug)]
+pub struct HistoryChunkAcks {
+    next_bootstrap_id: AtomicU64,
+    acked: watch::Sender<(u64, u32)>,
+}
+
+impl Default for HistoryChunkAcks {
+    fn default() -> Self {
+        let (acked, _) = watch::channel((0, 0));
+        Self {
+            next_bootstrap_id: AtomicU64::new(1),
+            acked,
+        }
+    }
+}
+
+impl HistoryChunkAcks {
+    pub fn begin(&self, channel: Channel<InvokeResponseBody>) -> HistoryChunkSink {
+        let bootstrap_id = self.next_bootstrap_id.fetch_add(1, Ordering::Relaxed);
+        self.acked.send_replace((bootstrap_id, 0));
+        HistoryChunkSink {
+            bootstrap_id,
+            channel,
+            acked: self.acked.subscribe(),
+            next_seq: 1,
+        }
+    }
+
+    /// Returns false when the ack belongs to a bootstrap that is no longer current.
+    pub fn ack(&self, bootstrap_id: u64, seq: u32) -> bool {
+        let mut current = false;
+        self.acked.send_if_modified(|(active_id, acked_seq)| {
+            if *active_id != bootstrap_id {
+                return false;
+            }
+            current = true;
+            if seq <= *acked_seq {
+                return false;
+            }
+            *acked_seq = seq;
+            true
+        });
+        current
+    }
+}
+
+/// Sending half of a chunked history bootstrap, owned by the stream task.
+pub struct HistoryChunkSink {
+    bootstrap_id: u64,
+    channel: Channel<InvokeResponseBody>,
+    acked: watch::Receiver<(u64, u32)>,
+    next_seq: u32,
+}
+
+impl HistoryChunkSink {
+    pub fn bootstrap_id(&self) -> u64 {
+        self.bootstrap_id
+    }
+
+    pub async fn send_chunk(
+        &mut self,
+        is_final: bool,
+        candles: &[UiCandle],
+        delta_candles: &[UiDeltaCandle],
+    ) -> Result<(), AppError> {
+        let seq = self.next_seq;
+        self.wait_for_window(seq).await?;
+
+        let bytes = encode_history_chunk(self.bootstrap_id, seq, is_final, candles, delta_candles)?;
+        self.channel.send(InvokeResponseBody::Raw(bytes))?;
+        self.next_seq = seq.saturating_add(1);
+        Ok(())
+    }
+
+    async fn wait_for_window(&mut self, seq: u32) -> Result<(), AppError> {
+        let required = seq.saturating_sub(HISTORY_CHUNK_ACK_WINDOW);
+        if required == 0 {
+            return Ok(());
+        }
+
+        let bootstrap_id = self.bootstrap_id;
+        let waited = tokio::time::timeout(
+            Duration::from_millis(HISTORY_CHUNK_ACK_TIMEOUT_MS),
+            self.acked.wait_for(|(active_id, acked_seq)| {
+                *active_id != bootstrap_id || *acked_seq >= required
+            }),
+        )
+        .await
+        .map_err(|_| AppError::Timeout(format!("history chunk {required} was not acknowledged")))?;
+
+        match waited {
+            Ok(state) if state.0 == bootstrap_id => Ok(()),
+            _ => Err(AppError::Cancelled(
+                "history bootstrap was superseded".to_string(),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(bytes[offset..offset + 4].try_into().expect("u32 slice"))
+    }
+
+    fn read_f64_column(bytes: &[u8], column: usize, count: usize, row: usize) -> f64 {
+        let offset = HISTORY_CHUNK_HEADER_LEN + (count * 8) + (column * count * 8) + row * 8;
+        f64::from_le_bytes(bytes[offset..offset + 8].try_into().expect("f64 slice"))
+    }
+
+    #[test]
+    fn encodes_columnar_history_chunk() {
+        let candles = vec![
+            UiCandle::from_trade(60_000, 100.0, 1.5),
+            UiCandle::from_trade(120_000, 101.0, 2.0),
+        ];
+        let delta_candles = vec![
+            UiDeltaCandle::from_signed_volume(60_000, -1.5, 1.5),
+            UiDeltaCandle::from_signed_volume(120_000, 2.0, 2.0),
+        ];
+
+        let bytes =
+            encode_history_chunk(7, 3, true, &candles, &delta_candles).expect("chunk encodes");
+        assert_eq!(bytes.len(), HISTORY_CHUNK_HEADER_LEN + 2 * 9 * 8);
+        assert_eq!(&bytes[..4], &HISTORY_CHUNK_MAGIC);
+        assert_eq!(read_u32(&bytes, 4), HISTORY_CHUNK_FLAG_FINAL);
+        assert_eq!(
+            u64::from_le_bytes(bytes[8..16].try_into().expect("u64 slice")),
+            7
+        );
+        assert_eq!(read_u32(&bytes, 16), 3);
+        assert_eq!(read_u32(&bytes, 20), 2);
+        assert_eq!(
+            i64::from_le_bytes(bytes[32..40].try_into().expect("i64 slice")),
+            120_000
+        );
+        assert_eq!(read_f64_column(&bytes, 3, 2, 1), 101.0);
+        assert_eq!(read_f64_column(&bytes, 4, 2, 0), 1.5);
+        assert_eq!(read_f64_column(&bytes, 7, 2, 0), -1.5);
+
+        assert!(encode_history_chunk(1, 1, false, &candles, &delta_candles[..1]).is_err());
+    }
+
+    #[tokio::test]
+    async fn waits_for_acks_beyond_window() {
+        let acks = HistoryChunkAcks::default();
+        let mut sink = acks.begin(Channel::new(|_| Ok(())));
+        let bootstrap_id = sink.bootstrap_id();
+
+        for _ in 0..HISTORY_CHUNK_ACK_WINDOW {
+            sink.send_chunk(false, &[], &[])
+                .await
+                .expect("inside ack window");
+        }
+
+        let blocked =
+            tokio::time::timeout(Duration::from_millis(20), sink.send_chunk(false, &[], &[])).await;
+        assert!(blocked.is_err());
+
+        assert!(!acks.ack(bootstrap_id + 1, 1));
+        assert!(acks.ack(bootstrap_id, 1));
+        sink.send_chunk(true, &[], &[])
+            .await
+            .expect("ack should reopen the window");
+    }
+}
@@ -0,0 +1,781 @@
+use crate::error::AppError;
+use crate::market::binance::{
+    compute_history_progress, fetch_klines_before, fetch_klines_since,
+    fetch_oldest_kline_open_time, BinanceClient, KlineHistoryPage, KlineHistoryProgress,
+};
+use crate::market::types::{
+    HistoryPageQuery, MarketKind, MarketTimeframe, UiCandle, UiDeltaCandle, UiHistoryPage,
+};
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Row, SqlitePool};
+
+const KLINE_STORE_PAGE_LIMIT: u16 = 1_000;
+
+/// One candle series in the local kline cache.
+#[derive(Debug, Clone, Copy)]
+pub struct KlineSeries<'a> {
+    pub market_kind: MarketKind,
+    pub symbol: &'a str,
+    pub timeframe: MarketTimeframe,
+}
+
+impl<'a> KlineSeries<'a> {
+    pub fn new(market_kind: MarketKind, symbol: &'a str, timeframe: MarketTimeframe) -> Self {
+        Self {
+            market_kind,
+            symbol,
+            timeframe,
+        }
+    }
+}
+
+/// Latest `limit` candles for the series, oldest first, with the still-open candle last.
+///
+/// Only the tail after the newest cached kline and any uncovered holes inside the requested
+/// window are fetched from REST, so restarting a stream on already-seen data costs a single
+/// request for the candles that opened since.
+pub async fn load_recent_klines(
+    pool: &SqlitePool,
+    client: &BinanceClient,
+    series: KlineSeries<'_>,
+    limit: u32,
+    now_ms: i64,
+) -> Result<(Vec<UiCandle>, Vec<UiDeltaCandle>), AppError> {
+    let timeframe_ms = series.timeframe.duration_ms();
+    let window_start = now_ms.saturating_sub((i64::from(limit) + 1) * timeframe_ms);
+
+    let coverage = kline_coverage(pool, series).await?;
+    let tail_start = coverage
+        .last()
+        .map(|(_, to)| to.saturating_add(1))
+        .unwrap_or(window_start)
+        .max(window_start);
+    fill_range(pool, client, series, tail_start, i64::MAX, now_ms).await?;
+
+    let coverage = kline_coverage(pool, series).await?;
+    for (from, to) in uncovered_ranges(&coverage, window_start, tail_start - 1) {
+        fill_range(pool, client, series, from, to, now_ms).await?;
+    }
+
+    read_klines_before(pool, series, i64::MIN, i64::MAX, limit, now_ms).await
+}
+
+/// Older-history page for `query`, served from the cache when the whole page is covered.
+pub async fn load_history_page(
+    pool: &SqlitePool,
+    client: &BinanceClient,
+    query: &HistoryPageQuery,
+    now_ms: i64,
+) -> Result<UiHistoryPage, AppError> {
+    let series = KlineSeries::new(query.market_kind, &query.symbol, query.timeframe);
+    let limit = query.limit as usize;
+
+    let coverage = kline_coverage(pool, series).await?;
+    let covering = coverage
+        .iter()
+        .find(|(from, to)| *from < query.before_t && query.before_t - 1 <= *to);
+    if let Some((from, _)) = covering {
+        let (candles, delta_candles) = read_klines_before(
+            pool,
+            series,
+            *from,
+            query.before_t,
+            u32::from(query.limit),
+            now_ms,
+        )
+        .await?;
+        let reaches_first_kline = *from == i64::MIN && candles.len() < limit;
+        if candles.len() == limit || reaches_first_kline {
+            return Ok(history_page(
+                query,
+                candles,
+                delta_candles,
+                !reaches_first_kline,
+                true,
+            ));
+        }
+    }
+
+    let (candles, delta_candles) = fetch_klines_before(
+        client,
+        query.market_kind,
+        &query.symbol,
+        query.timeframe,
+        query.before_t,
+        query.limit,
+    )
+    .await?;
+    store_klines(pool, series, &candles, &delta_candles, now_ms).await?;
+
+    let reaches_first_kline = candles.len() < limit;
+    let from = match candles.first() {
+        Some(candle) if !reaches_first_kline => candle.t,
+        _ => i64::MIN,
+    };
+    let timeframe_ms = query.timeframe.duration_ms();
+    let to = candles
+        .iter()
+        .find(|candle| candle.t + timeframe_ms > now_ms)
+        .map(|open_candle| open_candle.t - 1)
+        .unwrap_or(now_ms - timeframe_ms)
+        .min(query.before_t - 1);
+    if to >= from {
+        mark_kline_coverage(pool, series, from, to).await?;
+    }
+
+    Ok(history_page(
+        query,
+        candles,
+        delta_candles,
+        !reaches_first_kline,
+        false,
+    ))
+}
+
+/// Walks the whole history of a series newest page first through the cache, for
+/// `history_all` loads. Covered pages are read from SQLite; the uncovered tail and head are
+/// fetched from REST a page at a time and stored on the way, so a second full load only asks
+/// the exchange for what opened since.
+pub struct CachedHistoryPager<'a> {
+    pool: &'a SqlitePool,
+    client: &'a BinanceClient,
+    series: KlineSeries<'a>,
+    now_ms: i64,
+    oldest_open_time_known: Option<i64>,
+    newest_open_time_seen: Option<i64>,
+    before_t: Option<i64>,
+    pages_fetched: u32,
+    candles_fetched: u64,
+    exhausted: bool,
+}
+
+impl<'a> CachedHistoryPager<'a> {
+    pub async fn new(
+        pool: &'a SqlitePool,
+        client: &'a BinanceClient,
+        series: KlineSeries<'a>,
+        now_ms: i64,
+    ) -> Result<Self, AppError> {
+        let oldest_open_time_known = fetch_oldest_kline_open_time(
+            client,
+            series.market_kind,
+            series.symbol,
+            series.timeframe,
+        )
+        .await?;
+
+        Ok(Self {
+            pool,
+            client,
+            series,
+            now_ms,
+            oldest_open_time_known,
+            newest_open_time_seen: None,
+            before_t: None,
+            pages_fetched: 0,
+            candles_fetched: 0,
+            exhausted: false,
+        })
+    }
+
+    pub async fn next_page(&mut self) -> Result<Option<KlineHistoryPage>, AppError> {
+        if self.exhausted {
+            return Ok(None);
+        }
+
+        let page_limit = usize::from(KLINE_STORE_PAGE_LIMIT);
+        let (candles, delta_candles, has_more) = match self.before_t {
+            None => {
+                let (candles, delta_candles) = load_recent_klines(
+                    self.pool,
+                    self.client,
+                    self.series,
+                    u32::from(KLINE_STORE_PAGE_LIMIT),
+                    self.now_ms,
+                )
+                .await?;
+                let has_more = candles.len() == page_limit;
+                (candles, delta_candles, has_more)
+            }
+            Some(before_t) => {
+                let query = HistoryPageQuery {
+                    market_kind: self.series.market_kind,
+                    symbol: self.series.symbol.to_string(),
+                    timeframe: self.series.timeframe,
+                    before_t,
+                    limit: KLINE_STORE_PAGE_LIMIT,
+                };
+                let page = load_history_page(self.pool, self.client, &query, self.now_ms).await?;
+                (page.candles, page.delta_candles, page.has_more)
+            }
+        };
+        let (Some(oldest), Some(newest)) = (candles.first(), candles.last()) else {
+            self.exhausted = true;
+            return Ok(None);
+        };
+        let oldest_open_time = oldest.t;
+        self.newest_open_time_seen.get_or_insert(newest.t);
+        self.pages_fetched = self.pages_fetched.saturating_add(1);
+        self.candles_fetched = self.candles_fetched.saturating_add(candles.len() as u64);
+
+        let reached_first_kline = self
+            .oldest_open_time_known
+            .is_some_and(|first| oldest_open_time <= first);
+        let stalled = self
+            .before_t
+            .is_some_and(|before_t| oldest_open_time >= before_t);
+        if !has_more || reached_first_kline || stalled {
+            self.exhausted = true;
+        } else {
+            self.before_t = Some(oldest_open_time);
+        }
+
+        let progress = compute_history_progress(
+            self.pages_fetched,
+            self.candles_fetched,
+            self.oldest_open_time_known,
+            self.newest_open_time_seen,
+            oldest_open_time,
+            self.series.timeframe.duration_ms().max(1),
+            false,
+        );
+        Ok(Some(KlineHistoryPage {
+            candles,
+            delta_candles,
+            progress,
+        }))
+    }
+
+    pub fn done_progress(&self) -> KlineHistoryProgress {
+        compute_history_progress(
+            self.pages_fetched,
+            self.candles_fetched,
+            self.oldest_open_time_known,
+            self.newest_open_time_seen,
+            self.oldest_open_time_known.unwrap_or_default(),
+            self.series.timeframe.duration_ms().max(1),
+            true,
+        )
+    }
+}
+
+fn history_page(
+    query: &HistoryPageQuery,
+    candles: Vec<UiCandle>,
+    delta_candles: Vec<UiDeltaCandle>,
+    has_more: bool,
+    from_cache: bool,
+) -> UiHistoryPage {
+    UiHistoryPage {
+        market_kind: query.market_kind,
+        symbol: query.symbol.clone(),
+        timeframe: query.timeframe,
+        candles,
+        delta_candles,
+        has_more,
+        from_cache,
+    }
+}
+
+/// Fetches klines opening at or after `from` until `to` is reached or the open candle is hit,
+/// recording which span is now fully cached.
+async fn fill_range(
+    pool: &SqlitePool,
+    client: &BinanceClient,
+    series: KlineSeries<'_>,
+    from: i64,
+    to: i64,
+    now_ms: i64,
+) -> Result<(), AppError> {
+    let timeframe_ms = series.timeframe.duration_ms();
+    let mut cursor = from;
+
+    loop {
+        let (candles, delta_candles) = fetch_klines_since(
+            client,
+            series.market_kind,
+            series.symbol,
+            series.timeframe,
+            cursor,
+            KLINE_STORE_PAGE_LIMIT,
+        )
+        .await?;
+        store_klines(pool, series, &candles, &delta_candles, now_ms).await?;
+
+        let open_time = candles
+            .iter()
+            .find(|candle| candle.t + timeframe_ms > now_ms)
+            .map(|candle| candle.t);
+        let last_time = candles.last().map(|candle| candle.t);
+        let covered_to = open_time.map(|open| open - 1).or(last_time);
+        if let Some(covered_to) = covered_to.map(|value| value.min(to)) {
+            if covered_to >= cursor {
+                mark_kline_coverage(pool, series, cursor, covered_to).await?;
+            }
+        }
+
+        let page_full = candles.len() == KLINE_STORE_PAGE_LIMIT as usize;
+        match last_time {
+            Some(last) if page_full && open_time.is_none() && last < to => cursor = last + 1,
+            _ => return Ok(()),
+        }
+    }
+}
+
+/// Stores an ordered run of klines from an offline source such as a data archive and marks
+/// the span it covers as cached, so later loads only go to REST for what the archive lacked.
+pub async fn store_kline_range(
+    pool: &SqlitePool,
+    series: KlineSeries<'_>,
+    candles: &[UiCandle],
+    delta_candles: &[UiDeltaCandle],
+    now_ms: i64,
+) -> Result<(), AppError> {
+    store_klines(pool, series, candles, delta_candles, now_ms).await?;
+
+    let timeframe_ms = series.timeframe.duration_ms();
+    let closed_to = candles
+        .iter()
+        .take_while(|candle| candle.t + timeframe_ms <= now_ms)
+        .last()
+        .map(|candle| candle.t);
+    if let (Some(first), Some(closed_to)) = (candles.first(), closed_to) {
+        mark_kline_coverage(pool, series, first.t, closed_to).await?;
+    }
+    Ok(())
+}
+
+/// Upserts candles; those still open at `now_ms` are flagged so they are overwritten by the
+/// next fetch instead of being trusted.
+pub async fn store_klines(
+    pool: &SqlitePool,
+    series: KlineSeries<'_>,
+    candles: &[UiCandle],
+    delta_candles: &[UiDeltaCandle],
+    now_ms: i64,
+) -> Result<(), AppError> {
+    if candles.len() != delta_candles.len() {
+        return Err(AppError::Internal(
+            "candles and delta candles must have the same length".to_string(),
+        ));
+    }
+
+    let timeframe_ms = series.timeframe.duration_ms();
+    let mut transaction = pool.begin().await?;
+    for (candle, delta_candle) in candles.iter().zip(delta_candles) {
+        let is_closed = candle.t + timeframe_ms <= now_ms;
+        sqlx::query(
+            "INSERT INTO kline_cache (market_kind, symbol, timeframe, open_time, open, high, low, close, volume, delta_high, delta_low, delta_close, is_closed, updated_at_ms) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?) \
+             ON CONFLICT(market_kind, symbol, timeframe, open_time) DO UPDATE SET \
+               open=excluded.open, \
+               high=excluded.high, \
+               low=excluded.low, \
+               close=excluded.close, \
+               volume=excluded.volume, \
+               delta_high=excluded.delta_high, \
+               delta_low=excluded.delta_low, \
+               delta_close=excluded.delta_close, \
+               is_closed=excluded.is_closed, \
+               updated_at_ms=excluded.updated_at_ms",
+        )
+        .bind(series.market_kind.as_str())
+        .bind(series.symbol)
+        .bind(series.timeframe.as_str())
+        .bind(candle.t)
+        .bind(candle.o)
+        .bind(candle.h)
+        .bind(candle.l)
+        .bind(candle.c)
+        .bind(candle.v)
+        .bind(delta_candle.h)
+        .bind(delta_candle.l)
+        .bind(delta_candle.c)
+        .bind(i64::from(is_closed))
+        .bind(now_ms)
+        .execute(&mut *transaction)
+        .await?;
+    }
+    transaction.commit().await?;
+
+    Ok(())
+}
+
+/// Up to `limit` cached candles with `from <= t < before`, oldest first. Open candles left
+/// over from an earlier session are skipped; only a candle open at `now_ms` is returned.
+async fn read_klines_before(
+    pool: &SqlitePool,
+    series: KlineSeries<'_>,
+    from: i64,
+    before: i64,
+    limit: u32,
+    now_ms: i64,
+) -> Result<(Vec<UiCandle>, Vec<UiDeltaCandle>), AppError> {
+    let rows = sqlx::query(
+        "SELECT open_time, open, high, low, close, volume, delta_high, delta_low, delta_close \
+         FROM kline_cache \
+         WHERE market_kind = ? AND symbol = ? AND timeframe = ? \
+           AND open_time >= ? AND open_time < ? \
+           AND (is_closed = 1 OR open_time > ?) \
+         ORDER BY open_time DESC \
+         LIMIT ?",
+    )
+    .bind(series.market_kind.as_str())
+    .bind(series.symbol)
+    .bind(series.timeframe.as_str())
+    .bind(from)
+    .bind(before)
+    .bind(now_ms - series.timeframe.duration_ms())
+    .bind(i64::from(limit))
+    .fetch_all(pool)
+    .await?;
+
+    rows_to_kline_pairs(rows.iter().rev())
+}
+
+/// Makes sure every kline opening in `[from, to]` is cached, fetching only uncovered spans.
+pub async fn sync_kline_range(
+    pool: &SqlitePool,
+    client: &BinanceClient,
+    series: KlineSeries<'_>,
+    from: i64,
+    to: i64,
+    now_ms: i64,
+) -> Result<(), AppError> {
+    let coverage = kline_coverage(pool, series).await?;
+    for (gap_from, gap_to) in uncovered_ranges(&coverage, from, to) {
+        fill_range(pool, client, series, gap_from, gap_to, now_ms).await?;
+    }
+    Ok(())
+}
+
+/// Up to `limit` closed cached candles with `from <= t <= to`, oldest first.
+pub async fn read_closed_klines_from(
+    pool: &SqlitePool,
+    series: KlineSeries<'_>,
+    from: i64,
+    to: i64,
+    limit: u32,
+) -> Result<(Vec<UiCandle>, Vec<UiDeltaCandle>), AppError> {
+    let rows = sqlx::query(
+        "SELECT open_time, open, high, low, close, volume, delta_high, delta_low, delta_close \
+         FROM kline_cache \
+         WHERE market_kind = ? AND symbol = ? AND timeframe = ? \
+           AND open_time >= ? AND open_time <= ? AND is_closed = 1 \
+         ORDER BY open_time ASC \
+         LIMIT ?",
+    )
+    .bind(series.market_kind.as_str())
+    .bind(series.symbol)
+    .bind(series.timeframe.as_str())
+    .bind(from)
+    .bind(to)
+    .bind(i64::from(limit))
+    .fetch_all(pool)
+    .await?;
+
+    rows_to_kline_pairs(rows.iter())
+}
+
+fn rows_to_kline_pairs<'r>(
+    rows: impl ExactSizeIterator<Item = &'r SqliteRow>,
+) -> Result<(Vec<UiCandle>, Vec<UiDeltaCandle>), AppError> {
+    let mut candles = Vec::with_capacity(rows.len());
+    let mut delta_candles = Vec::with_capacity(rows.len());
+    for row in rows {
+        let t: i64 = row.try_get("open_time")?;
+        let volume: f64 = row.try_get("volume")?;
+        candles.push(UiCandle {
+            t,
+            o: row.try_get("open")?,
+            h: row.try_get("high")?,
+            l: row.try_get("low")?,
+            c: row.try_get("close")?,
+            v: volume,
+        });
+        delta_candles.push(UiDeltaCandle {
+            t,
+            o: 0.0,
+            h: row.try_get("delta_high")?,
+            l: row.try_get("delta_low")?,
+            c: row.try_get("delta_close")?,
+            v: volume,
+        });
+    }
+
+    Ok((candles, delta_candles))
+}
+
+/// Sorted, disjoint `[from, to]` open-time ranges in which every kline the exchange has is
+/// cached. A range starting at `i64::MIN` reaches the first kline of the symbol.
+async fn kline_coverage(
+    pool: &SqlitePool,
+    series: KlineSeries<'_>,
+) -> Result<Vec<(i64, i64)>, AppError> {
+    let rows = sqlx::query(
+        "SELECT from_time, to_time FROM kline_cache_coverage \
+         WHERE market_kind = ? AND symbol = ? AND timeframe = ? \
+         ORDER BY from_time ASC",
+    )
+    .bind(series.market_kind.as_str())
+    .bind(series.symbol)
+    .bind(series.timeframe.as_str())
+    .fetch_all(pool)
+    .await?;
+
+    let mut ranges = Vec::with_capacity(rows.len());
+    for row in rows {
+        ranges.push((row.try_get("from_time")?, row.try_get("to_time")?));
+    }
+    Ok(ranges)
+}
+
+/// Records `[from, to]` as fully cached, merging it with overlapping or adjacent ranges.
+async fn mark_kline_coverage(
+    pool: &SqlitePool,
+    series: KlineSeries<'_>,
+    from: i64,
+    to: i64,
+) -> Result<(), AppError> {
+    let mut transaction = pool.begin().await?;
+    let touching = sqlx::query(
+        "SELECT from_time, to_time FROM kline_cache_coverage \
+         WHERE market_kind = ? AND symbol = ? AND timeframe = ? \
+           AND from_time <= ? AND to_time >= ?",
+    )
+    .bind(series.market_kind.as_str())
+    .bind(series.symbol)
+    .bind(series.timeframe.as_str())
+    .bind(to.saturating_add(1))
+    .bind(from.saturating_sub(1))
+    .fetch_all(&mut *transaction)
+    .await?;
+
+    let mut merged_from = from;
+    let mut merged_to = to;
+    for row in &touching {
+        let range_from: i64 = row.try_get("from_time")?;
+        let range_to: i64 = row.try_get("to_time")?;
+        merged_from = merged_from.min(range_from);
+        merged_to = merged_to.max(range_to);
+    }
+
+    sqlx::query(
+        "DELETE FROM kline_cache_coverage \
+         WHERE market_kind = ? AND symbol = ? AND timeframe = ? \
+           AND from_time >= ? AND from_time <= ?",
+    )
+    .bind(series.market_kind.as_str())
+    .bind(series.symbol)
+    .bind(series.timeframe.as_str())
+    .bind(merged_from)
+    .bind(merged_to)
+    .execute(&mut *transaction)
+    .await?;
+
+    sqlx::query(
+        "INSERT INTO kline_cache_coverage (market_kind, symbol, timeframe, from_time, to_time) \
+         VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(series.market_kind.as_str())
+    .bind(series.symbol)
+    .bind(series.timeframe.as_str())
+    .bind(merged_from)
+    .bind(merged_to)
+    .execute(&mut *transaction)
+    .await?;
+    transaction.commit().await?;
+
+    Ok(())
+}
+
+/// Holes in `coverage` within `[from, to]`, i.e. the head of the window and any gaps inside
+/// the cache that still have to be fetched.
+fn uncovered_ranges(coverage: &[(i64, i64)], from: i64, to: i64) -> Vec<(i64, i64)> {
+    let mut gaps = Vec::new();
+    let mut cursor = from;
+    for (range_from, range_to) in coverage {
+        if cursor > to {
+            break;
+        }
+        if *range_to < cursor {
+            continue;
+        }
+        if *range_from > cursor {
+            gaps.push((cursor, (*range_from - 1).min(to)));
+        }
+        cursor = range_to.saturating_add(1);
+    }
+    if cursor <= to {
+        gaps.push((cursor, to));
+    }
+    gaps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::run_migrations;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    const MINUTE_MS: i64 = 60_000;
+
+    async fn test_pool() -> SqlitePool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("in-memory sqlite should initialize");
+        run_migrations(&pool)
+            .await
+            .expect("migrations should apply");
+        pool
+    }
+
+    fn series() -> KlineSeries<'static> {
+        KlineSeries::new(MarketKind::Spot, "BTCUSDT", MarketTimeframe::M1)
+    }
+
+    fn minute_rows(from_minute: i64, count: i64) -> (Vec<UiCandle>, Vec<UiDeltaCandle>) {
+        (from_minute..from_minute + count)
+            .map(|minute| {
+                let t = minute * MINUTE_MS;
+                (
+                    UiCandle::from_trade(t, 100.0 + minute as f64, 1.0),
+                    UiDeltaCandle::from_signed_volume(t, 1.0, 1.0),
+                )
+            })
+            .unzip()
+    }
+
+    async fn walk_full_history(
+        pool: &SqlitePool,
+        client: &BinanceClient,
+        now_ms: i64,
+    ) -> (Vec<i64>, KlineHistoryProgress) {
+        let mut pager = CachedHistoryPager::new(pool, client, series(), now_ms)
+            .await
+            .expect("pager");
+        let mut pages = Vec::new();
+        while let Some(page) = pager.next_page().await.expect("page") {
+            pages.push(
+                page.candles
+                    .iter()
+                    .map(|candle| candle.t)
+                    .collect::<Vec<_>>(),
+            );
+        }
+        let times = pages.into_iter().rev().flatten().collect();
+        (times, pager.done_progress())
+    }
+
+    #[tokio::test]
+    async fn full_history_is_served_from_the_cache_on_reload() {
+        use crate::market::fake_binance::{FakeBinance, FakeKline};
+
+        let fake = FakeBinance::start().await;
+        let (candles, _) = minute_rows(0, 2_500);
+        fake.set_klines(
+            candles
+                .into_iter()
+                .map(|candle| FakeKline {
+                    candle,
+                    taker_buy_volume: 0.5,
+                })
+                .collect(),
+        );
+        let pool = test_pool().await;
+        let client = fake.client();
+        let now_ms = 2_500 * MINUTE_MS;
+        let expected: Vec<i64> = (0..2_500).map(|minute| minute * MINUTE_MS).collect();
+
+        let (first_walk, progress) = walk_full_history(&pool, &client, now_ms).await;
+        assert_eq!(first_walk, expected);
+        assert_eq!(progress.candles_fetched, 2_500);
+        assert_eq!(progress.estimated_total_candles, Some(2_500));
+        let kline_requests = |fake: &FakeBinance| {
+            fake.requests()
+                .iter()
+                .filter(|request| request.starts_with("/api/v3/klines"))
+                .count()
+        };
+        let first_walk_requests = kline_requests(&fake);
+
+        let (second_walk, _) = walk_full_history(&pool, &client, now_ms).await;
+        assert_eq!(second_walk, expected);
+        // Only the oldest-kline probe and the empty tail after the newest cached kline.
+        assert_eq!(kline_requests(&fake) - first_walk_requests, 2);
+    }
+
+    #[test]
+    fn finds_head_and_inner_gaps() {
+        let coverage = [(10, 19), (30, 39), (60, 100)];
+        assert_eq!(
+            uncovered_ranges(&coverage, 0, 70),
+            vec![(0, 9), (20, 29), (40, 59)]
+        );
+        assert_eq!(uncovered_ranges(&coverage, 12, 35), vec![(20, 29)]);
+        assert!(uncovered_ranges(&coverage, 60, 90).is_empty());
+        assert_eq!(uncovered_ranges(&[], 5, 8), vec![(5, 8)]);
+    }
+
+    #[tokio::test]
+    async fn merges_adjacent_coverage() {
+        let pool = test_pool().await;
+        mark_kline_coverage(&pool, series(), 100, 199)
+            .await
+            .expect("coverage should save");
+        mark_kline_coverage(&pool, series(), 300, 399)
+            .await
+            .expect("coverage should save");
+        assert_eq!(
+            kline_coverage(&pool, series())
+                .await
+                .expect("coverage should load"),
+            vec![(100, 199), (300, 399)]
+        );
+
+        mark_kline_coverage(&pool, series(), 200, 299)
+            .await
+            .expect("coverage should save");
+        assert_eq!(
+            kline_coverage(&pool, series())
+                .await
+                .expect("coverage should load"),
+            vec![(100, 399)]
+        );
+    }
+
+    #[tokio::test]
+    async fn keeps_open_candle_mutable() {
+        let pool = test_pool().await;
+        let now_ms = 10 * MINUTE_MS + 30_000;
+        let (mut candles, delta_candles) = minute_rows(5, 6);
+        store_klines(&pool, series(), &candles, &delta_candles, now_ms)
+            .await
+            .expect("klines should store");
+
+        candles[5].c = 999.0;
+        store_klines(&pool, series(), &candles[5..], &delta_candles[5..], now_ms)
+            .await
+            .expect("open kline should update");
+
+        let (cached, cached_deltas) =
+            read_klines_before(&pool, series(), i64::MIN, i64::MAX, 100, now_ms)
+                .await
+                .expect("klines should load");
+        assert_eq!(cached.len(), 6);
+        assert_eq!(cached_deltas.len(), 6);
+        assert_eq!(cached[5].c, 999.0);
+
+        let later_ms = 20 * MINUTE_MS;
+        let (stale_skipped, _) =
+            read_klines_before(&pool, series(), i64::MIN, i64::MAX, 100, later_ms)
+                .await
+                .expect("klines should load");
+        assert_eq!(
+            stale_skipped.last().map(|candle| candle.t),
+            Some(9 * MINUTE_MS)
+        );
+    }
+}
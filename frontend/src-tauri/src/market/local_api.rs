@@ -0,0 +1,628 @@
+use crate::error::AppError;
+use crate::market::kline_store::{read_closed_klines_from, KlineSeries};
+use crate::market::pipeline::now_unix_ms;
+use crate::market::sink::{MarketEvent, MarketEventSink, SequencedEvent};
+use crate::market::tape::TradeTape;
+use crate::market::types::{
+    MarketKind, MarketStreamStatusSnapshot, MarketTimeframe, MarketTradeSide,
+    MarketTradesRecentArgs, UiHistoryPage, UiMarketApiStatus, DEFAULT_MARKET_API_HISTORY_LIMIT,
+    MAX_MARKET_API_HISTORY_LIMIT,
+};
+use futures_util::{SinkExt, StreamExt};
+use parking_lot::Mutex;
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{broadcast, RwLock};
+use tokio_tungstenite::tungstenite::handshake::derive_accept_key;
+use tokio_tungstenite::tungstenite::protocol::Role;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::WebSocketStream;
+use tokio_util::sync::CancellationToken;
+
+const MARKET_API_FEED_CAPACITY: usize = 4_096;
+const MAX_REQUEST_HEAD_BYTES: usize = 8 * 1024;
+const MARKET_API_WS_PATH: &str = "/ws";
+
+/// One serialised feed event, tagged with its name so subscribers can filter without parsing.
+type FeedMessage = (&'static str, Arc<str>);
+
+/// What the HTTP endpoints answer from: the kline cache and the running stream's state.
+#[derive(Clone)]
+pub struct MarketApiContext {
+    pub db_pool: SqlitePool,
+    pub status_store: Arc<RwLock<MarketStreamStatusSnapshot>>,
+    pub trade_tape: Arc<Mutex<TradeTape>>,
+}
+
+struct RunningMarketApi {
+    address: SocketAddr,
+    cancel_token: CancellationToken,
+    join_handle: tauri::async_runtime::JoinHandle<()>,
+}
+
+/// Read-only localhost API over the same conflated, gap-checked feed the chart receives.
+///
+/// `GET /ws` upgrades to a websocket that pushes `{"event", "payload"}` text messages
+/// (optionally filtered with `?events=market_frame_update,market_status`). Plain HTTP serves
+/// `GET /status`, `GET /trades` and `GET /history` from the running stream and the kline
+/// cache. The server binds to 127.0.0.1 only and never talks to the exchange itself.
+pub struct MarketApiServer {
+    feed: broadcast::Sender<FeedMessage>,
+    clients: Arc<AtomicU32>,
+    running: tokio::sync::Mutex<Option<RunningMarketApi>>,
+}
+
+impl Default for MarketApiServer {
+    fn default() -> Self {
+        let (feed, _) = broadcast::channel(MARKET_API_FEED_CAPACITY);
+        Self {
+            feed,
+            clients: Arc::new(AtomicU32::new(0)),
+            running: tokio::sync::Mutex::new(None),
+        }
+    }
+}
+
+impl MarketApiServer {
+    /// Binds `127.0.0.1:port`, replacing a server that is already running.
+    pub async fn start(
+        &self,
+        port: u16,
+        context: MarketApiContext,
+    ) -> Result<UiMarketApiStatus, AppError> {
+        let mut running = self.running.lock().await;
+        if let Some(previous) = running.take() {
+            previous.cancel_token.cancel();
+            let _ = previous.join_handle.await;
+        }
+
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port)).await?;
+        let address = listener.local_addr()?;
+        let cancel_token = CancellationToken::new();
+        let join_handle = tauri::async_runtime::spawn(accept_connections(
+            listener,
+            context,
+            self.feed.clone(),
+            Arc::clone(&self.clients),
+            cancel_token.clone(),
+        ));
+        *running = Some(RunningMarketApi {
+            address,
+            cancel_token,
+            join_handle,
+        });
+
+        Ok(self.status_for(Some(address)))
+    }
+
+    pub async fn stop(&self) -> UiMarketApiStatus {
+        let previous = self.running.lock().await.take();
+        if let Some(previous) = previous {
+            previous.cancel_token.cancel();
+            let _ = previous.join_handle.await;
+        }
+        self.status_for(None)
+    }
+
+    pub async fn status(&self) -> UiMarketApiStatus {
+        let address = self
+            .running
+            .lock()
+            .await
+            .as_ref()
+            .map(|running| running.address);
+        self.status_for(address)
+    }
+
+    fn status_for(&self, address: Option<SocketAddr>) -> UiMarketApiStatus {
+        UiMarketApiStatus {
+            running: address.is_some(),
+            address: address.map(|address| address.to_string()),
+            clients: self.clients.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct FeedEnvelope<'a> {
+    event: &'static str,
+    payload: SequencedEvent<'a>,
+}
+
+/// Bootstraps are skipped: they can hold millions of candles, and clients page the same data
+/// from `/history` instead.
+fn is_rebroadcast(event: &MarketEvent<'_>) -> bool {
+    matches!(
+        event,
+        MarketEvent::Frame(_)
+            | MarketEvent::PriceUpdate(_)
+            | MarketEvent::CandleUpdate(_)
+            | MarketEvent::DeltaCandleUpdate(_)
+            | MarketEvent::TradeTape(_)
+            | MarketEvent::Status(_)
+            | MarketEvent::Perf(_)
+            | MarketEvent::CandlesRepair(_)
+    )
+}
+
+impl MarketEventSink for MarketApiServer {
+    fn publish(&self, event: SequencedEvent<'_>) -> Result<(), AppError> {
+        if self.feed.receiver_count() == 0 || !is_rebroadcast(&event.event) {
+            return Ok(());
+        }
+        let message = simd_json::serde::to_string(&FeedEnvelope {
+            event: event.name(),
+            payload: event,
+        })?;
+        let _ = self.feed.send((event.name(), Arc::from(message)));
+        Ok(())
+    }
+}
+
+async fn accept_connections(
+    listener: TcpListener,
+    context: MarketApiContext,
+    feed: broadcast::Sender<FeedMessage>,
+    clients: Arc<AtomicU32>,
+    cancel_token: CancellationToken,
+) {
+    loop {
+        let stream = tokio::select! {
+            _ = cancel_token.cancelled() => break,
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => stream,
+                Err(error) => {
+                    eprintln!("market api accept failed: {error}");
+                    continue;
+                }
+            },
+        };
+
+        let context = context.clone();
+        let feed = feed.clone();
+        let clients = Arc::clone(&clients);
+        let cancel_token = cancel_token.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(error) =
+                serve_connection(stream, &context, &feed, &clients, &cancel_token).await
+            {
+                eprintln!("market api connection failed: {error}");
+            }
+        });
+    }
+}
+
+struct RequestHead {
+    method: String,
+    path: String,
+    query: HashMap<String, String>,
+    headers: HashMap<String, String>,
+}
+
+impl RequestHead {
+    fn parse(raw: &str) -> Option<Self> {
+        let mut lines = raw.split("\r\n");
+        let mut request_line = lines.next()?.split_whitespace();
+        let method = request_line.next()?.to_string();
+        let target = request_line.next()?;
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        let query = query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        let headers = lines
+            .filter_map(|line| line.split_once(':'))
+            .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string()))
+            .collect();
+
+        Some(Self {
+            method,
+            path: path.to_string(),
+            query,
+            headers,
+        })
+    }
+
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).map(String::as_str)
+    }
+
+    fn wants_websocket(&self) -> bool {
+        self.header("upgrade")
+            .is_some_and(|value| value.eq_ignore_ascii_case("websocket"))
+    }
+}
+
+async fn read_request_head(stream: &mut TcpStream) -> Result<Option<RequestHead>, AppError> {
+    let mut head = Vec::new();
+    let mut buffer = [0_u8; 1_024];
+    while !head.windows(4).any(|window| window == b"\r\n\r\n") {
+        let read = stream.read(&mut buffer).await?;
+        if read == 0 {
+            return Ok(None);
+        }
+        head.extend_from_slice(&buffer[..read]);
+        if head.len() > MAX_REQUEST_HEAD_BYTES {
+            return Err(AppError::InvalidArgument(
+                "market api request head is too large".to_string(),
+            ));
+        }
+    }
+    Ok(RequestHead::parse(&String::from_utf8_lossy(&head)))
+}
+
+async fn serve_connection(
+    mut stream: TcpStream,
+    context: &MarketApiContext,
+    feed: &broadcast::Sender<FeedMessage>,
+    clients: &AtomicU32,
+    cancel_token: &CancellationToken,
+) -> Result<(), AppError> {
+    let Some(request) = read_request_head(&mut stream).await? else {
+        return Ok(());
+    };
+
+    if request.path == MARKET_API_WS_PATH && request.wants_websocket() {
+        return serve_websocket(stream, &request, feed, clients, cancel_token).await;
+    }
+
+    let (status, body) = if request.method != "GET" {
+        (
+            "405 Method Not Allowed",
+            error_body("only GET is supported"),
+        )
+    } else {
+        match route_http(context, &request).await {
+            Ok(Some(body)) => ("200 OK", body),
+            Ok(None) => ("404 Not Found", error_body("unknown endpoint")),
+            Err(error) => ("400 Bad Request", error_body(&error.to_string())),
+        }
+    };
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+fn error_body(message: &str) -> String {
+    #[derive(Serialize)]
+    struct ErrorBody<'a> {
+        error: &'a str,
+    }
+    simd_json::serde::to_string(&ErrorBody { error: message })
+        .unwrap_or_else(|_| r#"{"error":"internal error"}"#.to_string())
+}
+
+async fn route_http(
+    context: &MarketApiContext,
+    request: &RequestHead,
+) -> Result<Option<String>, AppError> {
+    let body = match request.path.as_str() {
+        "/status" => {
+            let status = context.status_store.read().await.clone();
+            simd_json::serde::to_string(&status)?
+        }
+        "/trades" => {
+            let query = MarketTradesRecentArgs {
+                stream_id: None,
+                limit: parse_query(&request.query, "limit")?,
+                min_notional: parse_query(&request.query, "minNotional")?,
+                side: match request.query.get("side").map(String::as_str) {
+                    None => None,
+                    Some("buy") => Some(MarketTradeSide::Buy),
+                    Some("sell") => Some(MarketTradeSide::Sell),
+                    Some(other) => {
+                        return Err(AppError::InvalidArgument(format!(
+                            "unsupported trade side '{other}'"
+                        )))
+                    }
+                },
+            }
+            .normalize()?;
+            let snapshot = context.trade_tape.lock().recent(&query);
+            simd_json::serde::to_string(&snapshot)?
+        }
+        "/history" => simd_json::serde::to_string(&cached_history(context, request).await?)?,
+        _ => return Ok(None),
+    };
+    Ok(Some(body))
+}
+
+fn parse_query<T: std::str::FromStr>(
+    query: &HashMap<String, String>,
+    name: &str,
+) -> Result<Option<T>, AppError> {
+    query
+        .get(name)
+        .map(|value| {
+            value
+                .parse::<T>()
+                .map_err(|_| AppError::InvalidArgument(format!("invalid '{name}' value '{value}'")))
+        })
+        .transpose()
+}
+
+/// Closed candles from the local kline cache only, oldest first from `from`.
+async fn cached_history(
+    context: &MarketApiContext,
+    request: &RequestHead,
+) -> Result<UiHistoryPage, AppError> {
+    let query = &request.query;
+    let market_kind = match query.get("market") {
+        Some(value) => MarketKind::parse_str(value)?,
+        None => MarketKind::Spot,
+    };
+    let symbol = query
+        .get("symbol")
+        .map(|symbol| symbol.trim().to_ascii_uppercase())
+        .filter(|symbol| !symbol.is_empty())
+        .ok_or_else(|| AppError::InvalidArgument("history requires a symbol".to_string()))?;
+    let timeframe = match query.get("timeframe") {
+        Some(value) => MarketTimeframe::parse_str(value)?,
+        None => MarketTimeframe::M1,
+    };
+    let from = parse_query(query, "from")?.unwrap_or(0);
+    let to = parse_query(query, "to")?.unwrap_or_else(now_unix_ms);
+    let limit = parse_query(query, "limit")?.unwrap_or(DEFAULT_MARKET_API_HISTORY_LIMIT);
+    if limit == 0 || limit > MAX_MARKET_API_HISTORY_LIMIT {
+        return Err(AppError::InvalidArgument(format!(
+            "history limit must be between 1 and {MAX_MARKET_API_HISTORY_LIMIT}"
+        )));
+    }
+
+    let series = KlineSeries::new(market_kind, &symbol, timeframe);
+    let (candles, delta_candles) =
+        read_closed_klines_from(&context.db_pool, series, from, to, limit).await?;
+    Ok(UiHistoryPage {
+        market_kind,
+        symbol: symbol.clone(),
+        timeframe,
+        has_more: candles.len() == limit as usize,
+        candles,
+        delta_candles,
+        from_cache: true,
+    })
+}
+
+async fn serve_websocket(
+    mut stream: TcpStream,
+    request: &RequestHead,
+    feed: &broadcast::Sender<FeedMessage>,
+    clients: &AtomicU32,
+    cancel_token: &CancellationToken,
+) -> Result<(), AppError> {
+    let Some(key) = request.header("sec-websocket-key") else {
+        stream
+            .write_all(b"HTTP/1.1 400 Bad Request\r\nConnection: close\r\n\r\n")
+            .await?;
+        return Ok(());
+    };
+    let filter: Option<Vec<String>> = request
+        .query
+        .get("events")
+        .map(|events| events.split(',').map(str::to_string).collect());
+
+    // Subscribe before completing the handshake so a client never misses events emitted
+    // right after it connects.
+    let mut receiver = feed.subscribe();
+    let handshake = format!(
+        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+        derive_accept_key(key.as_bytes())
+    );
+    stream.write_all(handshake.as_bytes()).await?;
+    let mut websocket = WebSocketStream::from_raw_socket(stream, Role::Server, None).await;
+
+    clients.fetch_add(1, Ordering::Relaxed);
+    let outcome = async {
+        loop {
+            tokio::select! {
+                _ = cancel_token.cancelled() => {
+                    let _ = websocket.close(None).await;
+                    return Ok(());
+                }
+                incoming = websocket.next() => match incoming {
+                    Some(Ok(Message::Close(_))) | None => return Ok(()),
+                    Some(Err(error)) => return Err(AppError::from(error)),
+                    // The API is read-only; anything the client sends is ignored.
+                    Some(Ok(_)) => {}
+                },
+                message = receiver.recv() => match message {
+                    Ok((event, payload)) => {
+                        let wanted = filter
+                            .as_ref()
+                            .is_none_or(|events| events.iter().any(|name| name == event));
+                        if wanted {
+                            websocket.send(Message::Text(payload.to_string())).await?;
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        let notice =
+                            format!(r#"{{"event":"lagged","payload":{{"skipped":{skipped}}}}}"#);
+                        websocket.send(Message::Text(notice)).await?;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return Ok(()),
+                },
+            }
+        }
+    }
+    .await;
+    clients.fetch_sub(1, Ordering::Relaxed);
+    outcome
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::run_migrations;
+    use crate::market::kline_store::store_klines;
+    use crate::market::types::{UiCandle, UiDeltaCandle, UiTick};
+    use crate::market::{MARKET_STATUS_EVENT, PRICE_UPDATE_EVENT};
+    use simd_json::prelude::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+    use std::time::Duration;
+
+    async fn test_context() -> MarketApiContext {
+        let db_pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("in-memory sqlite should initialize");
+        run_migrations(&db_pool)
+            .await
+            .expect("migrations should apply");
+        MarketApiContext {
+            db_pool,
+            status_store: Arc::new(RwLock::new(MarketStreamStatusSnapshot::stopped(
+                "BTCUSDT".to_string(),
+                None,
+            ))),
+            trade_tape: Arc::new(Mutex::new(TradeTape::default())),
+        }
+    }
+
+    async fn http_get(address: &str, target: &str) -> (String, simd_json::OwnedValue) {
+        let mut stream = TcpStream::connect(address).await.expect("connect");
+        let request = format!("GET {target} HTTP/1.1\r\nHost: {address}\r\n\r\n");
+        stream.write_all(request.as_bytes()).await.expect("write");
+        let mut response = String::new();
+        stream
+            .read_to_string(&mut response)
+            .await
+            .expect("read response");
+        let (head, body) = response.split_once("\r\n\r\n").expect("response head");
+        let status = head.lines().next().unwrap_or_default().to_string();
+        let mut body = body.as_bytes().to_vec();
+        let value = simd_json::to_owned_value(&mut body).expect("json body");
+        (status, value)
+    }
+
+    #[tokio::test]
+    async fn websocket_rebroadcasts_the_filtered_feed() {
+        let server = MarketApiServer::default();
+        let status = server.start(0, test_context().await).await.expect("start");
+        let address = status.address.expect("bound address");
+
+        let (mut client, _) = tokio_tungstenite::connect_async(format!(
+            "ws://{address}/ws?events={MARKET_STATUS_EVENT}"
+        ))
+        .await
+        .expect("websocket connect");
+        while server.status().await.clients == 0 {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+
+        let tick = UiTick {
+            t: 1_000,
+            p: 100.0,
+            v: 1.0,
+            d: 1,
+        };
+        let snapshot = MarketStreamStatusSnapshot::stopped("ETHUSDT".to_string(), None);
+        server.emit(MarketEvent::PriceUpdate(&tick)).expect("emit");
+        server.emit(MarketEvent::Status(&snapshot)).expect("emit");
+
+        let message = tokio::time::timeout(Duration::from_secs(2), client.next())
+            .await
+            .expect("feed message")
+            .expect("open socket")
+            .expect("valid frame");
+        let mut bytes = message.into_data();
+        let value = simd_json::to_owned_value(&mut bytes).expect("json message");
+        assert_eq!(
+            value.get("event").and_then(|v| v.as_str()),
+            Some(MARKET_STATUS_EVENT)
+        );
+        assert_eq!(
+            value
+                .get("payload")
+                .and_then(|payload| payload.get("symbol"))
+                .and_then(|v| v.as_str()),
+            Some("ETHUSDT")
+        );
+        assert_ne!(
+            value.get("event").and_then(|v| v.as_str()),
+            Some(PRICE_UPDATE_EVENT)
+        );
+
+        let stopped = server.stop().await;
+        assert!(!stopped.running);
+    }
+
+    #[tokio::test]
+    async fn http_serves_status_and_cached_history() {
+        let context = test_context().await;
+        let candles: Vec<UiCandle> = (0..3)
+            .map(|index| UiCandle {
+                t: index * 60_000,
+                o: 100.0,
+                h: 101.0,
+                l: 99.0,
+                c: 100.5,
+                v: 2.0,
+            })
+            .collect();
+        let delta_candles: Vec<UiDeltaCandle> = candles
+            .iter()
+            .map(|candle| UiDeltaCandle {
+                t: candle.t,
+                o: 0.0,
+                h: 1.0,
+                l: -1.0,
+                c: 0.5,
+                v: candle.v,
+            })
+            .collect();
+        store_klines(
+            &context.db_pool,
+            KlineSeries::new(MarketKind::Spot, "BTCUSDT", MarketTimeframe::M1),
+            &candles,
+            &delta_candles,
+            now_unix_ms(),
+        )
+        .await
+        .expect("store klines");
+
+        let server = MarketApiServer::default();
+        let address = server
+            .start(0, context)
+            .await
+            .expect("start")
+            .address
+            .expect("bound address");
+
+        let (status, body) = http_get(&address, "/status").await;
+        assert!(status.ends_with("200 OK"));
+        assert_eq!(body.get("symbol").and_then(|v| v.as_str()), Some("BTCUSDT"));
+
+        let (status, body) =
+            http_get(&address, "/history?symbol=btcusdt&timeframe=1m&limit=2").await;
+        assert!(status.ends_with("200 OK"));
+        assert_eq!(
+            body.get("candles")
+                .and_then(|candles| candles.as_array())
+                .map(|candles| candles.len()),
+            Some(2)
+        );
+        assert_eq!(body.get("hasMore").and_then(|v| v.as_bool()), Some(true));
+        assert_eq!(body.get("fromCache").and_then(|v| v.as_bool()), Some(true));
+
+        let (status, body) = http_get(&address, "/history?timeframe=1m").await;
+        assert!(status.ends_with("400 Bad Request"));
+        assert!(body.get("error").is_some());
+
+        let (status, _) = http_get(&address, "/orders").await;
+        assert!(status.ends_with("404 Not Found"));
+
+        server.stop().await;
+    }
+}
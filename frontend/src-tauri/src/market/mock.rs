@@ -0,0 +1,564 @@
+use crate::error::AppError;
+use crate::market::pipeline::now_unix_ms;
+use crate::market::types::{
+    AggTradeEvent, AggTradeSnapshot, MarketTimeframe, MockEpisode, MockFaultConfig, MockPriceModel,
+    MockScenarioConfig, MockTradeSize, UiCandle, UiDeltaCandle,
+};
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_tungstenite::tungstenite::Message;
+
+const PRICE_TICK: f64 = 0.01;
+const QUANTITY_STEP: f64 = 0.000_01;
+const HISTORY_STEPS_PER_CANDLE: u32 = 8;
+const FAULT_SEED_SALT: u64 = 0xFA01_7FA0_17FA_017F;
+const MAX_CATCH_UP_MS: i64 = 1_000;
+
+/// SplitMix64. The mock tape must be identical for a given seed across releases, which a
+/// general purpose RNG crate does not promise, so the generator lives here.
+#[derive(Debug, Clone)]
+struct MockRng {
+    state: u64,
+}
+
+impl MockRng {
+    fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut mixed = self.state;
+        mixed = (mixed ^ (mixed >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        mixed = (mixed ^ (mixed >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        mixed ^ (mixed >> 31)
+    }
+
+    /// Uniform in (0, 1]; never 0 so it is safe to take the logarithm.
+    fn next_unit(&mut self) -> f64 {
+        ((self.next_u64() >> 11) + 1) as f64 / (1_u64 << 53) as f64
+    }
+
+    fn next_normal(&mut self) -> f64 {
+        let radius = (-2.0 * self.next_unit().ln()).sqrt();
+        let angle = std::f64::consts::TAU * self.next_unit();
+        radius * angle.cos()
+    }
+
+    fn next_exponential(&mut self, mean: f64) -> f64 {
+        -self.next_unit().ln() * mean
+    }
+
+    fn chance(&mut self, probability: f64) -> bool {
+        self.next_unit() <= probability
+    }
+}
+
+#[derive(Debug, Clone)]
+struct EpisodeState {
+    episode: Option<MockEpisode>,
+    active_until_ms: f64,
+}
+
+impl EpisodeState {
+    fn new(episode: Option<MockEpisode>) -> Self {
+        Self {
+            episode,
+            active_until_ms: f64::NEG_INFINITY,
+        }
+    }
+
+    /// Advances the episode over `[now_ms - dt_ms, now_ms]` and returns the multiplier in
+    /// effect at `now_ms`.
+    fn advance(&mut self, rng: &mut MockRng, now_ms: f64, dt_ms: f64) -> f64 {
+        let Some(episode) = self.episode else {
+            return 1.0;
+        };
+        if now_ms >= self.active_until_ms {
+            let rate_per_ms = episode.per_minute / 60_000.0;
+            if rate_per_ms > 0.0 && rng.chance(1.0 - (-rate_per_ms * dt_ms).exp()) {
+                self.active_until_ms = now_ms + episode.duration_ms as f64;
+            }
+        }
+        if now_ms < self.active_until_ms {
+            episode.multiplier
+        } else {
+            1.0
+        }
+    }
+}
+
+/// Seeded synthetic market behind mock mode. History and the live tape come from the same
+/// generator, so a stream started with a given seed, timeframe and history limit always
+/// produces the same candles and the same trade sequence.
+#[derive(Debug, Clone)]
+pub struct MockMarketSimulator {
+    scenario: MockScenarioConfig,
+    rng: MockRng,
+    price: f64,
+    clock_ms: f64,
+    next_trade_at_ms: f64,
+    aggregate_trade_id: u64,
+    volatility_spikes: EpisodeState,
+    bursts: EpisodeState,
+    quiet_periods: EpisodeState,
+}
+
+impl MockMarketSimulator {
+    pub fn new(scenario: &MockScenarioConfig) -> Self {
+        Self {
+            scenario: scenario.clone(),
+            rng: MockRng::new(scenario.seed),
+            price: scenario.start_price,
+            clock_ms: 0.0,
+            next_trade_at_ms: 0.0,
+            aggregate_trade_id: 0,
+            volatility_spikes: EpisodeState::new(scenario.volatility_spikes),
+            bursts: EpisodeState::new(scenario.bursts),
+            quiet_periods: EpisodeState::new(scenario.quiet_periods),
+        }
+    }
+
+    /// Builds `limit` closed candles ending at the bucket that contains `now_ms`, leaving the
+    /// simulated price at the last close so the live tape continues from it.
+    pub fn build_history(
+        &mut self,
+        timeframe: MarketTimeframe,
+        limit: u32,
+        now_ms: i64,
+    ) -> (Vec<UiCandle>, Vec<UiDeltaCandle>) {
+        let timeframe_ms = timeframe.duration_ms();
+        let aligned_now = now_ms - now_ms.rem_euclid(timeframe_ms);
+        let start = aligned_now - limit as i64 * timeframe_ms;
+        let step_ms = timeframe_ms as f64 / HISTORY_STEPS_PER_CANDLE as f64;
+        let mut candles = Vec::with_capacity(limit as usize);
+        let mut delta_candles = Vec::with_capacity(limit as usize);
+        self.clock_ms = start as f64;
+
+        for index in 0..limit {
+            let open_time = start + index as i64 * timeframe_ms;
+            let mut candle = UiCandle {
+                t: open_time,
+                o: self.price,
+                h: self.price,
+                l: self.price,
+                c: self.price,
+                v: 0.0,
+            };
+            let mut delta_candle = UiDeltaCandle::from_signed_volume(open_time, 0.0, 0.0);
+
+            for _ in 0..HISTORY_STEPS_PER_CANDLE {
+                self.clock_ms += step_ms;
+                let rate_multiplier = self.advance_episodes(step_ms);
+                let previous = self.price;
+                self.step_price(step_ms);
+
+                let expected_trades =
+                    self.scenario.trades_per_second * rate_multiplier * step_ms / 1_000.0;
+                let volume = self.sample_quantity() * expected_trades;
+                let signed_volume = if self.price >= previous {
+                    volume
+                } else {
+                    -volume
+                };
+                candle.h = candle.h.max(self.price);
+                candle.l = candle.l.min(self.price);
+                candle.c = self.price;
+                candle.v += volume;
+                delta_candle.apply_signed_volume(signed_volume, volume);
+            }
+
+            candles.push(candle);
+            delta_candles.push(delta_candle);
+        }
+
+        (candles, delta_candles)
+    }
+
+    /// Moves the live clock to `now_ms`, discarding any trades that would have happened while
+    /// the producer was not polling.
+    pub fn start_live(&mut self, now_ms: i64) {
+        self.clock_ms = now_ms as f64;
+        let rate_multiplier = self.advance_episodes(0.0);
+        self.schedule_next_trade(rate_multiplier);
+    }
+
+    pub fn next_trade_time(&self) -> i64 {
+        self.next_trade_at_ms as i64
+    }
+
+    pub fn next_trade(&mut self) -> AggTradeEvent {
+        let dt_ms = (self.next_trade_at_ms - self.clock_ms).max(0.0);
+        self.clock_ms = self.next_trade_at_ms;
+        let rate_multiplier = self.advance_episodes(dt_ms);
+        let previous = self.price;
+        self.step_price(dt_ms);
+
+        let is_buyer_maker = if self.price == previous {
+            self.rng.chance(0.5)
+        } else {
+            self.price < previous
+        };
+        self.aggregate_trade_id = self.aggregate_trade_id.saturating_add(1);
+        let trade_time = self.clock_ms as i64;
+        let trade = AggTradeEvent {
+            event_time: trade_time,
+            aggregate_trade_id: self.aggregate_trade_id,
+            price: self.price,
+            quantity: self.sample_quantity(),
+            trade_time,
+            is_buyer_maker,
+        };
+        self.schedule_next_trade(rate_multiplier);
+        trade
+    }
+
+    fn schedule_next_trade(&mut self, rate_multiplier: f64) {
+        let rate_per_ms = self.scenario.trades_per_second * rate_multiplier / 1_000.0;
+        self.next_trade_at_ms = self.clock_ms + self.rng.next_exponential(1.0 / rate_per_ms);
+    }
+
+    /// Returns the combined trade-rate multiplier after advancing every episode.
+    fn advance_episodes(&mut self, dt_ms: f64) -> f64 {
+        let now_ms = self.clock_ms;
+        self.volatility_spikes.advance(&mut self.rng, now_ms, dt_ms);
+        self.bursts.advance(&mut self.rng, now_ms, dt_ms)
+            * self.quiet_periods.advance(&mut self.rng, now_ms, dt_ms)
+    }
+
+    fn step_price(&mut self, dt_ms: f64) {
+        let dt_seconds = dt_ms / 1_000.0;
+        let volatility = self.scenario.volatility * self.volatility_multiplier();
+        let shock = self.rng.next_normal() * dt_seconds.sqrt();
+        let next = match self.scenario.price_model {
+            MockPriceModel::RandomWalk => {
+                self.price + self.scenario.drift * dt_seconds + volatility * shock
+            }
+            MockPriceModel::Gbm => {
+                self.price
+                    * ((self.scenario.drift - 0.5 * volatility * volatility) * dt_seconds
+                        + volatility * shock)
+                        .exp()
+            }
+        };
+        self.price = round_to_step(next, PRICE_TICK).max(PRICE_TICK);
+    }
+
+    fn volatility_multiplier(&self) -> f64 {
+        match self.volatility_spikes.episode {
+            Some(episode) if self.clock_ms < self.volatility_spikes.active_until_ms => {
+                episode.multiplier
+            }
+            _ => 1.0,
+        }
+    }
+
+    fn sample_quantity(&mut self) -> f64 {
+        let quantity = match self.scenario.trade_size {
+            MockTradeSize::Fixed { quantity } => quantity,
+            MockTradeSize::Exponential { mean } => self.rng.next_exponential(mean),
+            MockTradeSize::LogNormal { median, sigma } => {
+                median * (sigma * self.rng.next_normal()).exp()
+            }
+        };
+        round_to_step(quantity, QUANTITY_STEP).max(QUANTITY_STEP)
+    }
+}
+
+/// Exchange-side view of a faulted mock stream: the newest trade generated, whether or not it
+/// reached the pipeline. Snapshot resyncs read it instead of the exchange REST API.
+#[derive(Debug, Default)]
+pub struct MockExchange {
+    latest: Mutex<Option<AggTradeSnapshot>>,
+}
+
+impl MockExchange {
+    pub fn snapshot(&self) -> Result<AggTradeSnapshot, AppError> {
+        self.latest
+            .lock()
+            .clone()
+            .ok_or_else(|| AppError::Unavailable("mock exchange has not traded yet".to_string()))
+    }
+
+    fn record(&self, trade: &AggTradeEvent) {
+        *self.latest.lock() = Some(AggTradeSnapshot {
+            aggregate_trade_id: trade.aggregate_trade_id,
+            price: trade.price,
+        });
+    }
+}
+
+/// Stands in for the exchange websocket in mock mode with faults: serialises simulated trades
+/// as aggTrade frames and injects gaps, duplicates, reordering, corrupt payloads, close frames,
+/// delivery latency and clock skew from its own seeded generator.
+pub struct MockTradeFeed {
+    simulator: MockMarketSimulator,
+    faults: MockFaultConfig,
+    rng: MockRng,
+    exchange: Arc<MockExchange>,
+    pending: VecDeque<Message>,
+    held_back: Option<AggTradeEvent>,
+    delivery_delay_ms: i64,
+}
+
+impl MockTradeFeed {
+    pub fn new(
+        simulator: MockMarketSimulator,
+        faults: MockFaultConfig,
+        exchange: Arc<MockExchange>,
+    ) -> Self {
+        let seed = simulator.scenario.seed ^ FAULT_SEED_SALT;
+        Self {
+            simulator,
+            faults,
+            rng: MockRng::new(seed),
+            exchange,
+            pending: VecDeque::new(),
+            held_back: None,
+            delivery_delay_ms: faults.latency_ms as i64,
+        }
+    }
+
+    /// Starts a new connection: frames queued for the previous one are lost, like on a real
+    /// socket, while trade ids carry on from the exchange's sequence.
+    pub fn reconnect(&mut self, now_ms: i64) {
+        self.pending.clear();
+        self.held_back = None;
+        self.simulator.start_live(now_ms);
+    }
+
+    pub async fn next_frame(&mut self) -> Message {
+        loop {
+            if let Some(frame) = self.pending.pop_front() {
+                return frame;
+            }
+
+            let now_ms = now_unix_ms();
+            let due_ms = self.simulator.next_trade_time() + self.delivery_delay_ms;
+            if now_ms - due_ms > MAX_CATCH_UP_MS {
+                self.simulator.start_live(now_ms);
+                continue;
+            }
+            if due_ms > now_ms {
+                tokio::time::sleep(Duration::from_millis((due_ms - now_ms) as u64)).await;
+            }
+            self.generate_frames();
+        }
+    }
+
+    /// Generates the next trade and queues the frames it turns into after faults.
+    fn generate_frames(&mut self) {
+        let mut trade = self.simulator.next_trade();
+        trade.event_time += self.faults.clock_skew_ms;
+        self.exchange.record(&trade);
+        self.delivery_delay_ms = self.faults.latency_ms as i64
+            + (self.rng.next_unit() * self.faults.latency_jitter_ms as f64) as i64;
+
+        if self.rng.chance(self.faults.gap_probability) {
+            return;
+        }
+        if self.rng.chance(self.faults.corrupt_probability) {
+            let mut payload = agg_trade_payload(&trade);
+            payload.truncate(payload.len() / 2);
+            self.pending.push_back(Message::Text(payload));
+        }
+        if self.held_back.is_none() && self.rng.chance(self.faults.out_of_order_probability) {
+            self.held_back = Some(trade);
+            return;
+        }
+
+        let payload = agg_trade_payload(&trade);
+        if self.rng.chance(self.faults.duplicate_probability) {
+            self.pending.push_back(Message::Text(payload.clone()));
+        }
+        self.pending.push_back(Message::Text(payload));
+        if let Some(held_back) = self.held_back.take() {
+            self.pending
+                .push_back(Message::Text(agg_trade_payload(&held_back)));
+        }
+        if self.rng.chance(self.faults.disconnect_probability) {
+            self.pending.push_back(Message::Close(None));
+        }
+    }
+}
+
+/// Serialises a trade the way the exchange sends it on the aggTrade stream.
+pub fn agg_trade_payload(trade: &AggTradeEvent) -> String {
+    format!(
+        r#"{{"e":"aggTrade","E":{},"a":{},"p":"{}","q":"{}","T":{},"m":{}}}"#,
+        trade.event_time,
+        trade.aggregate_trade_id,
+        trade.price,
+        trade.quantity,
+        trade.trade_time,
+        trade.is_buyer_maker
+    )
+}
+
+fn round_to_step(value: f64, step: f64) -> f64 {
+    (value / step).round() * step
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scenario(seed: u64) -> MockScenarioConfig {
+        MockScenarioConfig {
+            seed,
+            ..MockScenarioConfig::default()
+        }
+    }
+
+    fn live_trades(simulator: &mut MockMarketSimulator, count: usize) -> Vec<AggTradeEvent> {
+        simulator.start_live(1_700_000_000_000);
+        (0..count).map(|_| simulator.next_trade()).collect()
+    }
+
+    #[test]
+    fn same_seed_reproduces_history_and_tape() {
+        let now_ms = 1_700_000_123_456;
+        let mut first = MockMarketSimulator::new(&scenario(7));
+        let mut second = MockMarketSimulator::new(&scenario(7));
+        let first_history = first.build_history(MarketTimeframe::M1, 50, now_ms);
+        let second_history = second.build_history(MarketTimeframe::M1, 50, now_ms + 5);
+        assert_eq!(first_history, second_history);
+
+        let last_close = first_history.0.last().expect("history").c;
+        let first_tape = live_trades(&mut first, 200);
+        assert_eq!(first_tape, live_trades(&mut second, 200));
+        assert!((first_tape[0].price - last_close).abs() < 50.0);
+
+        let mut other = MockMarketSimulator::new(&scenario(8));
+        other.build_history(MarketTimeframe::M1, 50, now_ms);
+        assert_ne!(first_tape, live_trades(&mut other, 200));
+    }
+
+    #[test]
+    fn trade_rate_and_ids_follow_scenario() {
+        let mut simulator = MockMarketSimulator::new(&MockScenarioConfig {
+            trades_per_second: 1_000.0,
+            price_model: MockPriceModel::Gbm,
+            volatility: 0.000_2,
+            trade_size: MockTradeSize::Fixed { quantity: 0.5 },
+            ..MockScenarioConfig::default()
+        });
+        let trades = live_trades(&mut simulator, 5_000);
+
+        let span_ms = trades.last().expect("trades").trade_time - trades[0].trade_time;
+        assert!((4_000..=6_000).contains(&span_ms), "span was {span_ms}ms");
+        assert!(trades.windows(2).all(|pair| pair[1].aggregate_trade_id
+            == pair[0].aggregate_trade_id + 1
+            && pair[1].trade_time >= pair[0].trade_time));
+        assert!(trades
+            .iter()
+            .all(|trade| trade.quantity == 0.5 && trade.price > 0.0));
+    }
+
+    fn faulted_feed(faults: MockFaultConfig) -> (MockTradeFeed, Arc<MockExchange>) {
+        let exchange = Arc::new(MockExchange::default());
+        let mut simulator = MockMarketSimulator::new(&scenario(3));
+        simulator.start_live(1_700_000_000_000);
+        (
+            MockTradeFeed::new(simulator, faults, Arc::clone(&exchange)),
+            exchange,
+        )
+    }
+
+    fn drain_frames(feed: &mut MockTradeFeed, trades: usize) -> Vec<Message> {
+        for _ in 0..trades {
+            feed.generate_frames();
+        }
+        feed.pending.drain(..).collect()
+    }
+
+    #[test]
+    fn clean_feed_emits_parseable_contiguous_trades() {
+        let (mut feed, exchange) = faulted_feed(MockFaultConfig {
+            clock_skew_ms: 500,
+            ..MockFaultConfig::default()
+        });
+        let frames = drain_frames(&mut feed, 50);
+        assert_eq!(frames.len(), 50);
+
+        let trades: Vec<AggTradeEvent> = frames
+            .into_iter()
+            .map(|frame| match frame {
+                Message::Text(text) => {
+                    crate::market::types::parse_agg_trade_payload(&mut text.into_bytes())
+                        .expect("mock payload parses")
+                }
+                other => panic!("unexpected frame {other:?}"),
+            })
+            .collect();
+        assert!(trades
+            .windows(2)
+            .all(|pair| pair[1].aggregate_trade_id == pair[0].aggregate_trade_id + 1));
+        assert!(trades
+            .iter()
+            .all(|trade| trade.event_time == trade.trade_time + 500));
+        assert_eq!(
+            exchange.snapshot().expect("snapshot").aggregate_trade_id,
+            trades.last().expect("trades").aggregate_trade_id
+        );
+    }
+
+    #[test]
+    fn injects_each_fault_kind_deterministically() {
+        let faults = MockFaultConfig {
+            gap_probability: 0.05,
+            duplicate_probability: 0.05,
+            out_of_order_probability: 0.05,
+            corrupt_probability: 0.05,
+            disconnect_probability: 0.05,
+            ..MockFaultConfig::default()
+        };
+        let frames = drain_frames(&mut faulted_feed(faults).0, 2_000);
+        assert_eq!(frames, drain_frames(&mut faulted_feed(faults).0, 2_000));
+
+        let mut closes = 0;
+        let mut corrupt = 0;
+        let mut ids = Vec::new();
+        for frame in frames {
+            match frame {
+                Message::Close(_) => closes += 1,
+                Message::Text(text) => {
+                    match crate::market::types::parse_agg_trade_payload(&mut text.into_bytes()) {
+                        Ok(trade) => ids.push(trade.aggregate_trade_id),
+                        Err(_) => corrupt += 1,
+                    }
+                }
+                other => panic!("unexpected frame {other:?}"),
+            }
+        }
+        assert!(closes > 0 && corrupt > 0);
+        let duplicates = ids.windows(2).filter(|pair| pair[0] == pair[1]).count();
+        let reordered = ids.windows(2).filter(|pair| pair[1] < pair[0]).count();
+        let gaps = ids.windows(2).filter(|pair| pair[1] > pair[0] + 1).count();
+        assert!(duplicates > 0 && reordered > 0 && gaps > 0);
+    }
+
+    #[test]
+    fn bursts_raise_the_trade_rate() {
+        let calm = MockScenarioConfig {
+            trades_per_second: 100.0,
+            ..MockScenarioConfig::default()
+        };
+        let bursty = MockScenarioConfig {
+            bursts: Some(MockEpisode {
+                per_minute: 30.0,
+                duration_ms: 1_000,
+                multiplier: 10.0,
+            }),
+            ..calm.clone()
+        };
+
+        let span = |scenario: &MockScenarioConfig| {
+            let trades = live_trades(&mut MockMarketSimulator::new(scenario), 10_000);
+            trades.last().expect("trades").trade_time - trades[0].trade_time
+        };
+        assert!(span(&bursty) * 2 < span(&calm));
+    }
+}
@@ -1,7 +1,23 @@
+pub mod alerts;
+pub mod archive_import;
+pub mod backpressure;
 pub mod binance;
+pub mod export;
+#[cfg(test)]
+pub mod fake_binance;
+pub mod history_stream;
+pub mod kline_store;
+pub mod local_api;
+pub mod mock;
 pub mod persistence;
 pub mod pipeline;
+pub mod recorder;
+pub mod replay;
+pub mod sink;
+pub mod tape;
 pub mod types;
+pub mod webhooks;
+pub mod windows;
 
 pub const PRICE_UPDATE_EVENT: &str = "price_update";
 pub const MARKET_STATUS_EVENT: &str = "market_status";
@@ -9,6 +25,12 @@ pub const MARKET_FRAME_UPDATE_EVENT: &str = "market_frame_update";
 pub const MARKET_PERF_EVENT: &str = "market_perf";
 pub const CANDLE_UPDATE_EVENT: &str = "candle_update";
 pub const CANDLES_BOOTSTRAP_EVENT: &str = "candles_bootstrap";
+pub const CANDLES_REPAIR_EVENT: &str = "candles_repair";
 pub const DELTA_CANDLE_UPDATE_EVENT: &str = "delta_candle_update";
 pub const DELTA_CANDLES_BOOTSTRAP_EVENT: &str = "delta_candles_bootstrap";
 pub const HISTORY_LOAD_PROGRESS_EVENT: &str = "history_load_progress";
+pub const ARCHIVE_IMPORT_PROGRESS_EVENT: &str = "archive_import_progress";
+pub const EXPORT_PROGRESS_EVENT: &str = "export_progress";
+pub const ALERT_TRIGGERED_EVENT: &str = "alert_triggered";
+pub const TRADE_TAPE_EVENT: &str = "trade_tape";
+pub const REPLAY_STATUS_EVENT: &str = "replay_status";
@@ -1,9 +1,15 @@
 use crate::error::AppError;
+use crate::market::alerts::AlertTrigger;
 use crate::market::types::{
+    MarketAlertCondition, MarketAlertDeleteArgs, MarketAlertDeleteResult, MarketAlertDto,
+    MarketAlertTriggerDto, MarketAlertTriggersArgs, MarketAlertUpsertArgs, MarketAlertsListArgs,
     MarketDrawingDeleteArgs, MarketDrawingDeleteResult, MarketDrawingDto, MarketDrawingUpsertArgs,
     MarketDrawingsScopeArgs, MarketKind, MarketPreferencesSnapshot, MarketTimeframe,
-    SaveMarketPreferencesArgs, DEFAULT_MARKET_KIND, DEFAULT_SYMBOL, DEFAULT_TIMEFRAME,
+    SaveMarketPreferencesArgs, WebhookEventType, WebhookHeader, WebhookTargetDeleteArgs,
+    WebhookTargetDeleteResult, WebhookTargetDto, WebhookTargetUpsertArgs, ALERTABLE_DRAWING_TYPES,
+    DEFAULT_MARKET_KIND, DEFAULT_SYMBOL, DEFAULT_TIMEFRAME,
 };
+use crate::market::webhooks::validate_body_template;
 use sqlx::{Row, SqlitePool};
 use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -62,6 +68,65 @@ fn map_drawing_row(row: &sqlx::sqlite::SqliteRow) -> Result<MarketDrawingDto, Ap
     })
 }
 
+fn map_alert_row(row: &sqlx::sqlite::SqliteRow) -> Result<MarketAlertDto, AppError> {
+    let market_kind_raw: String = row.try_get("market_kind")?;
+    let condition_json: String = row.try_get("condition_json")?;
+    let mut condition_bytes = condition_json.into_bytes();
+    let condition: MarketAlertCondition = simd_json::serde::from_slice(&mut condition_bytes)?;
+
+    Ok(MarketAlertDto {
+        id: row.try_get("id")?,
+        market_kind: MarketKind::parse_str(&market_kind_raw)?,
+        symbol: row.try_get("symbol")?,
+        condition,
+        label: row.try_get("label")?,
+        enabled: sqlite_to_bool(row.try_get("enabled")?),
+        one_shot: sqlite_to_bool(row.try_get("one_shot")?),
+        cooldown_ms: row.try_get("cooldown_ms")?,
+        notify_os: sqlite_to_bool(row.try_get("notify_os")?),
+        last_triggered_at_ms: row.try_get("last_triggered_at_ms")?,
+        created_at_ms: row.try_get("created_at_ms")?,
+        updated_at_ms: row.try_get("updated_at_ms")?,
+    })
+}
+
+fn map_alert_trigger_row(row: &sqlx::sqlite::SqliteRow) -> Result<MarketAlertTriggerDto, AppError> {
+    let market_kind_raw: String = row.try_get("market_kind")?;
+
+    Ok(MarketAlertTriggerDto {
+        id: row.try_get("id")?,
+        alert_id: row.try_get("alert_id")?,
+        market_kind: MarketKind::parse_str(&market_kind_raw)?,
+        symbol: row.try_get("symbol")?,
+        condition_type: row.try_get("condition_type")?,
+        label: row.try_get("label")?,
+        price: row.try_get("price")?,
+        message: row.try_get("message")?,
+        triggered_at_ms: row.try_get("triggered_at_ms")?,
+    })
+}
+
+fn map_webhook_target_row(row: &sqlx::sqlite::SqliteRow) -> Result<WebhookTargetDto, AppError> {
+    let headers_json: String = row.try_get("headers_json")?;
+    let mut headers_bytes = headers_json.into_bytes();
+    let headers: Vec<WebhookHeader> = simd_json::serde::from_slice(&mut headers_bytes)?;
+    let event_types_json: String = row.try_get("event_types_json")?;
+    let mut event_types_bytes = event_types_json.into_bytes();
+    let event_types: Vec<WebhookEventType> = simd_json::serde::from_slice(&mut event_types_bytes)?;
+
+    Ok(WebhookTargetDto {
+        id: row.try_get("id")?,
+        label: row.try_get("label")?,
+        url: row.try_get("url")?,
+        headers,
+        body_template: row.try_get("body_template")?,
+        event_types,
+        enabled: sqlite_to_bool(row.try_get("enabled")?),
+        created_at_ms: row.try_get("created_at_ms")?,
+        updated_at_ms: row.try_get("updated_at_ms")?,
+    })
+}
+
 async fn ensure_market_preferences_seed(pool: &SqlitePool) -> Result<(), AppError> {
     let updated_at_ms = now_unix_ms();
     sqlx::query(
@@ -205,3 +270,302 @@ pub async fn delete_market_drawing(
         deleted: result.rows_affected() > 0,
     })
 }
+
+pub async fn get_market_drawing(
+    pool: &SqlitePool,
+    drawing_id: &str,
+) -> Result<Option<MarketDrawingDto>, AppError> {
+    let row = sqlx::query(
+        "SELECT id, market_kind, symbol, timeframe, drawing_type, color, label, payload_json, created_at_ms, updated_at_ms \
+         FROM market_drawings WHERE id = ?",
+    )
+    .bind(drawing_id)
+    .fetch_optional(pool)
+    .await?;
+
+    row.as_ref().map(map_drawing_row).transpose()
+}
+
+const ALERT_COLUMNS: &str = "id, market_kind, symbol, condition_type, condition_json, label, enabled, one_shot, cooldown_ms, notify_os, last_triggered_at_ms, created_at_ms, updated_at_ms";
+
+pub async fn list_market_alerts(
+    pool: &SqlitePool,
+    args: MarketAlertsListArgs,
+) -> Result<Vec<MarketAlertDto>, AppError> {
+    let normalized = args.normalize()?;
+    let rows = sqlx::query(&format!(
+        "SELECT {ALERT_COLUMNS} FROM market_alerts \
+         WHERE (? IS NULL OR market_kind = ?) AND (? IS NULL OR symbol = ?) \
+         ORDER BY created_at_ms ASC, id ASC"
+    ))
+    .bind(normalized.market_kind.map(MarketKind::as_str))
+    .bind(normalized.market_kind.map(MarketKind::as_str))
+    .bind(normalized.symbol.as_deref())
+    .bind(normalized.symbol.as_deref())
+    .fetch_all(pool)
+    .await?;
+
+    let mut alerts = Vec::with_capacity(rows.len());
+    for row in rows {
+        alerts.push(map_alert_row(&row)?);
+    }
+
+    Ok(alerts)
+}
+
+pub async fn list_enabled_market_alerts(
+    pool: &SqlitePool,
+) -> Result<Vec<MarketAlertDto>, AppError> {
+    let rows = sqlx::query(&format!(
+        "SELECT {ALERT_COLUMNS} FROM market_alerts WHERE enabled = 1 ORDER BY created_at_ms ASC, id ASC"
+    ))
+    .fetch_all(pool)
+    .await?;
+
+    let mut alerts = Vec::with_capacity(rows.len());
+    for row in rows {
+        alerts.push(map_alert_row(&row)?);
+    }
+
+    Ok(alerts)
+}
+
+pub async fn upsert_market_alert(
+    pool: &SqlitePool,
+    args: MarketAlertUpsertArgs,
+) -> Result<MarketAlertDto, AppError> {
+    let normalized = args.normalize()?;
+
+    if let MarketAlertCondition::DrawingCross { drawing_id, .. } = &normalized.condition {
+        let drawing = get_market_drawing(pool, drawing_id).await?.ok_or_else(|| {
+            AppError::InvalidArgument(format!("drawing '{drawing_id}' does not exist"))
+        })?;
+        if !ALERTABLE_DRAWING_TYPES.contains(&drawing.drawing_type.as_str()) {
+            return Err(AppError::InvalidArgument(format!(
+                "drawing_type '{}' cannot be used for alerts",
+                drawing.drawing_type
+            )));
+        }
+        if drawing.market_kind != normalized.market_kind || drawing.symbol != normalized.symbol {
+            return Err(AppError::InvalidArgument(
+                "drawing must belong to the alert market kind and symbol".to_string(),
+            ));
+        }
+    }
+
+    let now_ms = now_unix_ms();
+    let created_at_ms = normalized.created_at_ms.unwrap_or(now_ms);
+    let condition_json = simd_json::serde::to_string(&normalized.condition)?;
+    let alert_id = normalized.id.clone();
+
+    sqlx::query(
+        "INSERT INTO market_alerts (id, market_kind, symbol, condition_type, condition_json, label, enabled, one_shot, cooldown_ms, notify_os, created_at_ms, updated_at_ms) \
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?) \
+         ON CONFLICT(id) DO UPDATE SET \
+           market_kind=excluded.market_kind, \
+           symbol=excluded.symbol, \
+           condition_type=excluded.condition_type, \
+           condition_json=excluded.condition_json, \
+           label=excluded.label, \
+           enabled=excluded.enabled, \
+           one_shot=excluded.one_shot, \
+           cooldown_ms=excluded.cooldown_ms, \
+           notify_os=excluded.notify_os, \
+           updated_at_ms=excluded.updated_at_ms",
+    )
+    .bind(&alert_id)
+    .bind(normalized.market_kind.as_str())
+    .bind(normalized.symbol)
+    .bind(normalized.condition.type_str())
+    .bind(condition_json)
+    .bind(normalized.label)
+    .bind(bool_to_sqlite(normalized.enabled))
+    .bind(bool_to_sqlite(normalized.one_shot))
+    .bind(normalized.cooldown_ms)
+    .bind(bool_to_sqlite(normalized.notify_os))
+    .bind(created_at_ms)
+    .bind(now_ms)
+    .execute(pool)
+    .await?;
+
+    let row = sqlx::query(&format!(
+        "SELECT {ALERT_COLUMNS} FROM market_alerts WHERE id = ?"
+    ))
+    .bind(alert_id)
+    .fetch_one(pool)
+    .await?;
+
+    map_alert_row(&row)
+}
+
+pub async fn delete_market_alert(
+    pool: &SqlitePool,
+    args: MarketAlertDeleteArgs,
+) -> Result<MarketAlertDeleteResult, AppError> {
+    let normalized = args.normalize()?;
+
+    let result = sqlx::query("DELETE FROM market_alerts WHERE id = ?")
+        .bind(normalized.id)
+        .execute(pool)
+        .await?;
+
+    Ok(MarketAlertDeleteResult {
+        deleted: result.rows_affected() > 0,
+    })
+}
+
+pub async fn record_market_alert_trigger(
+    pool: &SqlitePool,
+    trigger: &AlertTrigger,
+) -> Result<MarketAlertTriggerDto, AppError> {
+    let mut transaction = pool.begin().await?;
+
+    let trigger_id = sqlx::query(
+        "INSERT INTO market_alert_triggers (alert_id, market_kind, symbol, condition_type, label, price, message, triggered_at_ms) \
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&trigger.alert_id)
+    .bind(trigger.market_kind.as_str())
+    .bind(&trigger.symbol)
+    .bind(trigger.condition_type)
+    .bind(&trigger.label)
+    .bind(trigger.price)
+    .bind(&trigger.message)
+    .bind(trigger.triggered_at_ms)
+    .execute(&mut *transaction)
+    .await?
+    .last_insert_rowid();
+
+    sqlx::query(
+        "UPDATE market_alerts SET \
+           last_triggered_at_ms = ?, \
+           enabled = CASE WHEN one_shot = 1 THEN 0 ELSE enabled END \
+         WHERE id = ?",
+    )
+    .bind(trigger.triggered_at_ms)
+    .bind(&trigger.alert_id)
+    .execute(&mut *transaction)
+    .await?;
+
+    transaction.commit().await?;
+
+    Ok(MarketAlertTriggerDto {
+        id: trigger_id,
+        alert_id: trigger.alert_id.clone(),
+        market_kind: trigger.market_kind,
+        symbol: trigger.symbol.clone(),
+        condition_type: trigger.condition_type.to_string(),
+        label: trigger.label.clone(),
+        price: trigger.price,
+        message: trigger.message.clone(),
+        triggered_at_ms: trigger.triggered_at_ms,
+    })
+}
+
+pub async fn list_market_alert_triggers(
+    pool: &SqlitePool,
+    args: MarketAlertTriggersArgs,
+) -> Result<Vec<MarketAlertTriggerDto>, AppError> {
+    let (alert_id, limit) = args.normalize()?;
+    let rows = sqlx::query(
+        "SELECT id, alert_id, market_kind, symbol, condition_type, label, price, message, triggered_at_ms \
+         FROM market_alert_triggers \
+         WHERE (? IS NULL OR alert_id = ?) \
+         ORDER BY triggered_at_ms DESC, id DESC \
+         LIMIT ?",
+    )
+    .bind(alert_id.as_deref())
+    .bind(alert_id.as_deref())
+    .bind(i64::from(limit))
+    .fetch_all(pool)
+    .await?;
+
+    let mut triggers = Vec::with_capacity(rows.len());
+    for row in rows {
+        triggers.push(map_alert_trigger_row(&row)?);
+    }
+
+    Ok(triggers)
+}
+
+const WEBHOOK_TARGET_COLUMNS: &str = "id, label, url, headers_json, body_template, event_types_json, enabled, created_at_ms, updated_at_ms";
+
+pub async fn list_webhook_targets(pool: &SqlitePool) -> Result<Vec<WebhookTargetDto>, AppError> {
+    let rows = sqlx::query(&format!(
+        "SELECT {WEBHOOK_TARGET_COLUMNS} FROM webhook_targets ORDER BY created_at_ms ASC, id ASC"
+    ))
+    .fetch_all(pool)
+    .await?;
+
+    let mut targets = Vec::with_capacity(rows.len());
+    for row in rows {
+        targets.push(map_webhook_target_row(&row)?);
+    }
+
+    Ok(targets)
+}
+
+pub async fn upsert_webhook_target(
+    pool: &SqlitePool,
+    args: WebhookTargetUpsertArgs,
+) -> Result<WebhookTargetDto, AppError> {
+    let normalized = args.normalize()?;
+    if let Some(template) = normalized.body_template.as_deref() {
+        validate_body_template(template, &normalized.event_types)?;
+    }
+
+    let now_ms = now_unix_ms();
+    let created_at_ms = normalized.created_at_ms.unwrap_or(now_ms);
+    let headers_json = simd_json::serde::to_string(&normalized.headers)?;
+    let event_types_json = simd_json::serde::to_string(&normalized.event_types)?;
+    let target_id = normalized.id.clone();
+
+    sqlx::query(
+        "INSERT INTO webhook_targets (id, label, url, headers_json, body_template, event_types_json, enabled, created_at_ms, updated_at_ms) \
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?) \
+         ON CONFLICT(id) DO UPDATE SET \
+           label=excluded.label, \
+           url=excluded.url, \
+           headers_json=excluded.headers_json, \
+           body_template=excluded.body_template, \
+           event_types_json=excluded.event_types_json, \
+           enabled=excluded.enabled, \
+           updated_at_ms=excluded.updated_at_ms",
+    )
+    .bind(&target_id)
+    .bind(normalized.label)
+    .bind(normalized.url)
+    .bind(headers_json)
+    .bind(normalized.body_template)
+    .bind(event_types_json)
+    .bind(bool_to_sqlite(normalized.enabled))
+    .bind(created_at_ms)
+    .bind(now_ms)
+    .execute(pool)
+    .await?;
+
+    let row = sqlx::query(&format!(
+        "SELECT {WEBHOOK_TARGET_COLUMNS} FROM webhook_targets WHERE id = ?"
+    ))
+    .bind(target_id)
+    .fetch_one(pool)
+    .await?;
+
+    map_webhook_target_row(&row)
+}
+
+pub async fn delete_webhook_target(
+    pool: &SqlitePool,
+    args: WebhookTargetDeleteArgs,
+) -> Result<WebhookTargetDeleteResult, AppError> {
+    let normalized = args.normalize()?;
+
+    let result = sqlx::query("DELETE FROM webhook_targets WHERE id = ?")
+        .bind(normalized.id)
+        .execute(pool)
+        .await?;
+
+    Ok(WebhookTargetDeleteResult {
+        deleted: result.rows_affected() > 0,
+    })
+}
@@ -1,33 +1,39 @@
 use crate::error::AppError;
+use crate::market::alerts::AlertEngine;
+use crate::market::backpressure::{EmitPacer, EmitPacerStats, FrameAcks};
 use crate::market::binance::{
-    connect_agg_trade_stream, fetch_klines_history_bundle,
-    fetch_klines_history_bundle_with_progress, fetch_latest_agg_trade_snapshot,
-    fetch_server_time_ms,
+    connect_agg_trade_stream, fetch_klines_since, fetch_latest_agg_trade_snapshot,
+    fetch_server_time_ms, BinanceClient, BinanceWsStream, KlineHistoryProgress,
 };
+use crate::market::history_stream::HistoryChunkSink;
+use crate::market::kline_store::{
+    load_recent_klines, store_klines, CachedHistoryPager, KlineSeries,
+};
+use crate::market::mock::{MockExchange, MockMarketSimulator, MockTradeFeed};
+use crate::market::recorder::{RecordedTradeCursor, TradeRecorder};
+use crate::market::replay::{ReplayControl, ReplaySource};
+use crate::market::sink::{EventSequence, MarketEvent, MarketEventSink, SequencedEventSink};
+use crate::market::tape::TradeTape;
 use crate::market::types::{
-    parse_agg_trade_payload, AggTradeEvent, MarketConnectionState, MarketKind, MarketPerfSnapshot,
-    MarketStartupMode, MarketStreamConfig, MarketStreamStatusSnapshot, MarketTimeframe, UiCandle,
-    UiCandlesBootstrap, UiDeltaCandle, UiDeltaCandlesBootstrap, UiHistoryLoadProgress,
-    UiMarketFrameUpdate, UiTick, DEFAULT_HISTORY_LIMIT,
+    parse_agg_trade_payload, AggTradeEvent, MarketCandleDivergence, MarketConnectionState,
+    MarketKind, MarketPerfSnapshot, MarketStartupMode, MarketStreamConfig,
+    MarketStreamStatusSnapshot, MarketTimeframe, UiCandle, UiCandlesBootstrap, UiCandlesRepair,
+    UiDeltaCandle, UiDeltaCandlesBootstrap, UiHistoryLoadProgress, UiMarketFrameUpdate, UiTick,
+    DEFAULT_HISTORY_LIMIT, MAX_SNAPSHOT_HISTORY_LIMIT,
 };
+use crate::market::webhooks::{is_webhook_connection_transition, WebhookDispatcher, WebhookEvent};
 use futures_util::StreamExt;
 use parking_lot::Mutex;
-use reqwest::Client;
+use sqlx::SqlitePool;
+use std::collections::VecDeque;
 use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
-use tauri::{AppHandle, Emitter, Manager, WebviewWindow};
 use tokio::sync::RwLock;
 use tokio::time::MissedTickBehavior;
-use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::tungstenite::{Error as WsError, Message};
 use tokio_util::sync::CancellationToken;
 
-use super::{
-    CANDLES_BOOTSTRAP_EVENT, CANDLE_UPDATE_EVENT, DELTA_CANDLES_BOOTSTRAP_EVENT,
-    DELTA_CANDLE_UPDATE_EVENT, HISTORY_LOAD_PROGRESS_EVENT, MARKET_FRAME_UPDATE_EVENT,
-    MARKET_PERF_EVENT, MARKET_STATUS_EVENT, PRICE_UPDATE_EVENT,
-};
-
 const STATUS_HEARTBEAT_MS: u64 = 1_000;
 const STATUS_ERROR_THROTTLE_MS: u64 = 500;
 const PERF_WINDOW_CAPACITY: usize = 2_048;
@@ -37,6 +43,14 @@ const CLOCK_SYNC_MAX_VALID_RTT_MS: i64 = 2_000;
 const CLOCK_SYNC_MIN_DELAY_MS: u64 = 10_000;
 const CLOCK_SYNC_MAX_DELAY_MS: u64 = 90_000;
 const HISTORY_PROGRESS_EMIT_THROTTLE_MS: u64 = 250;
+const MAX_OUTAGE_REPAIR_CANDLES: u16 = 1_000;
+const HISTORY_CHUNK_CANDLES: usize = 1_000;
+const BUCKET_RECONCILE_DELAY_MS: u64 = 1_500;
+const CANDLE_DIVERGENCE_TOLERANCE: f64 = 1e-9;
+const REPLAY_STATUS_EMIT_THROTTLE_MS: u64 = 250;
+const REPLAY_MAX_SPEED_YIELD_EVERY: u32 = 1_024;
+const MOCK_TICK_MS: u64 = 4;
+const MOCK_MAX_CATCH_UP_MS: i64 = 1_000;
 
 #[derive(Debug, Clone, Copy)]
 struct ClockOffsetProbe {
@@ -58,6 +72,31 @@ pub struct ConflatedMarketState {
     pending_candle: Option<UiCandle>,
     last_delta_candle: Option<UiDeltaCandle>,
     pending_delta_candle: Option<UiDeltaCandle>,
+    closed_candle: Option<UiCandle>,
+    closed_delta_candle: Option<UiDeltaCandle>,
+    candle_history: VecDeque<UiCandle>,
+    delta_candle_history: VecDeque<UiDeltaCandle>,
+}
+
+/// What a reloaded webview needs to redraw a running stream. `candles` and `delta_candles`
+/// hold closed buckets only, oldest first; the open buckets are `last_candle` and
+/// `last_delta_candle`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChartStateSnapshot {
+    pub last_agg_id: Option<u64>,
+    pub last_price: Option<f64>,
+    pub last_candle: Option<UiCandle>,
+    pub last_delta_candle: Option<UiDeltaCandle>,
+    pub candles: Vec<UiCandle>,
+    pub delta_candles: Vec<UiDeltaCandle>,
+}
+
+/// Live-built candles for a bucket that just closed, awaiting reconciliation against the
+/// exchange kline.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClosedBucket {
+    pub candle: UiCandle,
+    pub delta_candle: Option<UiDeltaCandle>,
 }
 
 #[derive(Debug, Clone, Copy, Default)]
@@ -231,6 +270,10 @@ struct PerformanceTelemetry {
     local_pipeline_ms: RollingWindowU32,
     ingest_count: u64,
     emit_count: u64,
+    reconcile_checks: u64,
+    reconcile_divergences: u64,
+    last_candle_divergence: Option<MarketCandleDivergence>,
+    backpressure: EmitPacerStats,
 }
 
 impl PerformanceTelemetry {
@@ -248,6 +291,18 @@ impl PerformanceTelemetry {
         self.emit_count = self.emit_count.saturating_add(1);
     }
 
+    fn record_reconcile(&mut self, divergence: Option<MarketCandleDivergence>) {
+        self.reconcile_checks = self.reconcile_checks.saturating_add(1);
+        if divergence.is_some() {
+            self.reconcile_divergences = self.reconcile_divergences.saturating_add(1);
+            self.last_candle_divergence = divergence;
+        }
+    }
+
+    fn set_backpressure(&mut self, stats: EmitPacerStats) {
+        self.backpressure = stats;
+    }
+
     fn snapshot(&self, now_ms: i64) -> MarketPerfSnapshot {
         let (parse_p50_us, parse_p95_us, parse_p99_us) = self.parse_us.percentiles();
         let (apply_p50_us, apply_p95_us, apply_p99_us) = self.apply_us.percentiles();
@@ -267,6 +322,14 @@ impl PerformanceTelemetry {
             local_pipeline_p99_ms,
             ingest_count: self.ingest_count,
             emit_count: self.emit_count,
+            reconcile_checks: self.reconcile_checks,
+            reconcile_divergences: self.reconcile_divergences,
+            last_candle_divergence: self.last_candle_divergence,
+            emit_interval_ms: self.backpressure.interval_ms,
+            backpressure_active: self.backpressure.active,
+            frames_in_flight: self.backpressure.frames_in_flight,
+            frames_merged: self.backpressure.frames_merged,
+            frames_dropped: self.backpressure.frames_dropped,
         }
     }
 }
@@ -321,16 +384,38 @@ struct StatusPublishThrottle {
 
 struct StreamRuntimeContext<'a> {
     config: &'a MarketStreamConfig,
-    http_client: &'a Client,
+    http_client: &'a BinanceClient,
+    db_pool: &'a SqlitePool,
     shared_market_state: &'a Arc<Mutex<ConflatedMarketState>>,
     telemetry: &'a Arc<MarketTelemetryAtomics>,
     perf_telemetry: &'a Arc<Mutex<PerformanceTelemetry>>,
     status_store: &'a Arc<RwLock<MarketStreamStatusSnapshot>>,
     status_throttle: &'a Arc<Mutex<StatusPublishThrottle>>,
-    window: &'a WebviewWindow,
+    alert_engine: Option<&'a Arc<AlertEngine>>,
+    webhooks: Option<&'a Arc<WebhookDispatcher>>,
+    trade_tape: &'a Arc<Mutex<TradeTape>>,
+    trade_recorder: Option<&'a TradeRecorder>,
+    mock_exchange: Option<&'a MockExchange>,
+    events: &'a Arc<dyn MarketEventSink>,
     cancel_token: &'a CancellationToken,
 }
 
+/// Where the live loop reads aggTrade frames from: the exchange websocket, or the mock feed
+/// when mock mode injects faults.
+enum TradeFeed<'a> {
+    Exchange(Box<BinanceWsStream>),
+    Mock(&'a mut MockTradeFeed),
+}
+
+impl TradeFeed<'_> {
+    async fn next_frame(&mut self) -> Option<Result<Message, WsError>> {
+        match self {
+            Self::Exchange(stream) => stream.next().await,
+            Self::Mock(feed) => Some(Ok(feed.next_frame().await)),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum TradeApplyOutcome {
     Applied { eligible_for_ui: bool },
@@ -402,7 +487,10 @@ fn update_candle_from_trade(
         _ => {
             let next = UiCandle::from_trade(bucket_open, trade.price, trade.quantity);
             state.pending_candle = Some(next.clone());
-            state.last_candle = Some(next);
+            if let Some(closed) = state.last_candle.replace(next) {
+                push_history(&mut state.candle_history, &closed, |candle| candle.t);
+                state.closed_candle = Some(closed);
+            }
         }
     }
 }
@@ -427,7 +515,10 @@ fn update_delta_candle_from_trade(
             let next =
                 UiDeltaCandle::from_signed_volume(bucket_open, signed_volume, absolute_volume);
             state.pending_delta_candle = Some(next.clone());
-            state.last_delta_candle = Some(next);
+            if let Some(closed) = state.last_delta_candle.replace(next) {
+                push_history(&mut state.delta_candle_history, &closed, |candle| candle.t);
+                state.closed_delta_candle = Some(closed);
+            }
         }
     }
 }
@@ -444,6 +535,10 @@ fn apply_history_snapshot(state: &mut ConflatedMarketState, candles: &[UiCandle]
             state.last_price = Some(last_candle.c);
         }
     }
+    let open_time = state.last_candle.as_ref().map(|candle| candle.t);
+    merge_history(&mut state.candle_history, candles, open_time, |candle| {
+        candle.t
+    });
 }
 
 fn apply_delta_history_snapshot(state: &mut ConflatedMarketState, candles: &[UiDeltaCandle]) {
@@ -457,6 +552,211 @@ fn apply_delta_history_snapshot(state: &mut ConflatedMarketState, candles: &[UiD
             state.last_delta_candle = Some(last_candle.clone());
         }
     }
+    let open_time = state.last_delta_candle.as_ref().map(|candle| candle.t);
+    merge_history(
+        &mut state.delta_candle_history,
+        candles,
+        open_time,
+        |candle| candle.t,
+    );
+}
+
+/// Appends a bucket that just closed, keeping at most [`MAX_SNAPSHOT_HISTORY_LIMIT`] buckets.
+fn push_history<T: Clone>(history: &mut VecDeque<T>, closed: &T, open_time: fn(&T) -> i64) {
+    // A bucket already there came from the exchange or the cache and wins over the live one.
+    if history
+        .back()
+        .is_some_and(|newest| open_time(newest) >= open_time(closed))
+    {
+        return;
+    }
+    history.push_back(closed.clone());
+    if history.len() > MAX_SNAPSHOT_HISTORY_LIMIT as usize {
+        history.pop_front();
+    }
+}
+
+/// Merges sorted `incoming` buckets older than `open_bucket` into the closed-bucket history.
+/// Incoming buckets come from the exchange or the cache and replace live-built ones.
+fn merge_history<T: Clone>(
+    history: &mut VecDeque<T>,
+    incoming: &[T],
+    open_bucket: Option<i64>,
+    open_time: fn(&T) -> i64,
+) {
+    let capacity = MAX_SNAPSHOT_HISTORY_LIMIT as usize;
+    let closed_len =
+        incoming.partition_point(|candle| open_bucket.is_none_or(|open| open_time(candle) < open));
+    let incoming = &incoming[closed_len.saturating_sub(capacity)..closed_len];
+    if incoming.is_empty() {
+        return;
+    }
+
+    let mut merged = VecDeque::with_capacity((history.len() + incoming.len()).min(capacity));
+    let mut existing = std::mem::take(history).into_iter().peekable();
+    let mut incoming = incoming.iter().peekable();
+    loop {
+        let next = match (existing.peek(), incoming.peek()) {
+            (Some(current), Some(fetched)) => {
+                let (current_t, fetched_t) = (open_time(current), open_time(fetched));
+                if current_t < fetched_t {
+                    existing.next()
+                } else {
+                    if current_t == fetched_t {
+                        existing.next();
+                    }
+                    incoming.next().cloned()
+                }
+            }
+            (Some(_), None) => existing.next(),
+            (None, Some(_)) => incoming.next().cloned(),
+            (None, None) => break,
+        };
+        merged.extend(next);
+    }
+    while merged.len() > capacity {
+        merged.pop_front();
+    }
+    *history = merged;
+}
+
+/// Patches the live candles with exchange klines covering a stream outage. Returns the
+/// fetched buckets that differ from the pipeline's view; buckets it never built are always
+/// returned.
+pub fn apply_kline_repair(
+    state: &mut ConflatedMarketState,
+    candles: &[UiCandle],
+    delta_candles: &[UiDeltaCandle],
+) -> (Vec<UiCandle>, Vec<UiDeltaCandle>) {
+    let changed_candles = merge_repaired_candles(
+        &mut state.last_candle,
+        &mut state.pending_candle,
+        candles,
+        |candle| candle.t,
+    );
+    let changed_delta_candles = merge_repaired_candles(
+        &mut state.last_delta_candle,
+        &mut state.pending_delta_candle,
+        delta_candles,
+        |candle| candle.t,
+    );
+    let open_time = state.last_candle.as_ref().map(|candle| candle.t);
+    merge_history(&mut state.candle_history, candles, open_time, |candle| {
+        candle.t
+    });
+    let open_time = state.last_delta_candle.as_ref().map(|candle| candle.t);
+    merge_history(
+        &mut state.delta_candle_history,
+        delta_candles,
+        open_time,
+        |candle| candle.t,
+    );
+    (changed_candles, changed_delta_candles)
+}
+
+/// Drops fetched klines from `live_bucket_open` on. The live stream owns those buckets:
+/// patching them with exchange klines would count the trades still queued in the socket twice.
+fn retain_buckets_before<T>(klines: &mut Vec<T>, live_bucket_open: i64, open_time: fn(&T) -> i64) {
+    klines.retain(|kline| open_time(kline) < live_bucket_open);
+}
+
+fn merge_repaired_candles<T: Clone + PartialEq>(
+    current: &mut Option<T>,
+    pending: &mut Option<T>,
+    fetched: &[T],
+    open_time: fn(&T) -> i64,
+) -> Vec<T> {
+    let changed: Vec<T> = fetched
+        .iter()
+        .filter(|candle| {
+            current
+                .as_ref()
+                .map(|live| open_time(live) != open_time(candle) || live != *candle)
+                .unwrap_or(true)
+        })
+        .cloned()
+        .collect();
+
+    if let Some(newest) = fetched.last() {
+        let should_replace = current
+            .as_ref()
+            .map(|live| open_time(newest) >= open_time(live) && live != newest)
+            .unwrap_or(true);
+        if should_replace {
+            *current = Some(newest.clone());
+            *pending = Some(newest.clone());
+        }
+    }
+
+    changed
+}
+
+/// The live chart plus up to `history_limit` of the most recent closed buckets.
+pub fn snapshot_chart_state(
+    state: &ConflatedMarketState,
+    history_limit: usize,
+) -> ChartStateSnapshot {
+    let tail = |len: usize| len.saturating_sub(history_limit);
+    ChartStateSnapshot {
+        last_agg_id: state.last_agg_id,
+        last_price: state.last_price,
+        last_candle: state.last_candle.clone(),
+        last_delta_candle: state.last_delta_candle.clone(),
+        candles: state
+            .candle_history
+            .range(tail(state.candle_history.len())..)
+            .cloned()
+            .collect(),
+        delta_candles: state
+            .delta_candle_history
+            .range(tail(state.delta_candle_history.len())..)
+            .cloned()
+            .collect(),
+    }
+}
+
+pub fn take_closed_bucket(state: &mut ConflatedMarketState) -> Option<ClosedBucket> {
+    let candle = state.closed_candle.take()?;
+    let delta_candle = state
+        .closed_delta_candle
+        .take()
+        .filter(|delta_candle| delta_candle.t == candle.t);
+    Some(ClosedBucket {
+        candle,
+        delta_candle,
+    })
+}
+
+/// Compares a closed live bucket with the exchange kline. Returns `None` when they agree
+/// within float noise.
+pub fn candle_divergence(
+    local: &ClosedBucket,
+    exchange: &UiCandle,
+    exchange_delta: &UiDeltaCandle,
+) -> Option<MarketCandleDivergence> {
+    let divergence = MarketCandleDivergence {
+        t: exchange.t,
+        open_diff: local.candle.o - exchange.o,
+        high_diff: local.candle.h - exchange.h,
+        low_diff: local.candle.l - exchange.l,
+        close_diff: local.candle.c - exchange.c,
+        volume_diff: local.candle.v - exchange.v,
+        delta_diff: local
+            .delta_candle
+            .as_ref()
+            .map(|delta_candle| delta_candle.c - exchange_delta.c)
+            .unwrap_or(0.0),
+    };
+    let exceeds = |diff: f64, reference: f64| {
+        diff.abs() > CANDLE_DIVERGENCE_TOLERANCE * reference.abs().max(1.0)
+    };
+    let diverged = exceeds(divergence.open_diff, exchange.o)
+        || exceeds(divergence.high_diff, exchange.h)
+        || exceeds(divergence.low_diff, exchange.l)
+        || exceeds(divergence.close_diff, exchange.c)
+        || exceeds(divergence.volume_diff, exchange.v)
+        || exceeds(divergence.delta_diff, exchange.v);
+    diverged.then_some(divergence)
 }
 
 fn candle_bucket_open_time(timestamp_ms: i64, timeframe_ms: i64) -> i64 {
@@ -502,6 +802,12 @@ pub fn drain_ui_delta_candle(state: &mut ConflatedMarketState) -> Option<UiDelta
     state.pending_delta_candle.take()
 }
 
+pub fn has_pending_frame(state: &ConflatedMarketState) -> bool {
+    state.pending_price.is_some()
+        || state.pending_candle.is_some()
+        || state.pending_delta_candle.is_some()
+}
+
 pub fn drain_market_frame(
     state: &mut ConflatedMarketState,
     emitted_at: Instant,
@@ -533,46 +839,42 @@ enum StreamDirective {
     Cancelled,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn run_market_stream(
-    app_handle: AppHandle,
+    events: Arc<dyn MarketEventSink>,
     config: MarketStreamConfig,
+    db_pool: SqlitePool,
+    http_client: BinanceClient,
     status_store: Arc<RwLock<MarketStreamStatusSnapshot>>,
+    shared_market_state: Arc<Mutex<ConflatedMarketState>>,
+    event_sequence: Arc<EventSequence>,
+    frame_acks: Arc<FrameAcks>,
+    alert_engine: Option<Arc<AlertEngine>>,
+    webhooks: Arc<WebhookDispatcher>,
+    trade_tape: Arc<Mutex<TradeTape>>,
+    trade_recorder: Option<TradeRecorder>,
+    history_sink: Option<HistoryChunkSink>,
+    replay_source: Option<ReplaySource>,
     cancel_token: CancellationToken,
 ) {
-    let window = match app_handle.get_webview_window("main") {
-        Some(window) => window,
-        None => {
-            let snapshot = MarketStreamStatusSnapshot {
-                state: MarketConnectionState::Error,
-                market_kind: config.market_kind,
-                symbol: config.symbol,
-                timeframe: config.timeframe,
-                last_agg_id: None,
-                latency_ms: None,
-                raw_exchange_latency_ms: None,
-                clock_offset_ms: None,
-                adjusted_network_latency_ms: None,
-                local_pipeline_latency_ms: None,
-                reason: Some(AppError::WindowNotFound("main".to_string()).to_string()),
-            };
-            let mut writable = status_store.write().await;
-            *writable = snapshot;
-            return;
-        }
-    };
-
-    let shared_market_state = Arc::new(Mutex::new(ConflatedMarketState::default()));
+    event_sequence.restart();
+    let sequenced_events = Arc::new(SequencedEventSink::new(events, event_sequence));
+    let events: Arc<dyn MarketEventSink> = sequenced_events.clone();
+    *shared_market_state.lock() = ConflatedMarketState::default();
+    trade_tape.lock().reset(config.market_kind, &config.symbol);
     let telemetry = Arc::new(MarketTelemetryAtomics::default());
     let perf_telemetry = Arc::new(Mutex::new(PerformanceTelemetry::default()));
     let status_throttle = Arc::new(Mutex::new(StatusPublishThrottle::default()));
-    let http_client = Client::new();
+    // Mock faults and replays only simulate outages, so connection webhooks are live-only.
+    let webhooks = Some(webhooks).filter(|_| !config.mock_mode && config.replay.is_none());
 
     let history_handle = match config.startup_mode {
         MarketStartupMode::HistoryFirst => {
             publish_status(
                 &status_store,
-                &window,
+                &events,
                 &telemetry,
+                webhooks.as_ref(),
                 MarketConnectionState::Connecting,
                 config.market_kind,
                 &config.symbol,
@@ -583,19 +885,23 @@ pub async fn run_market_stream(
 
             if let Err(error) = load_and_emit_history(
                 &config,
+                &db_pool,
                 &http_client,
-                &window,
+                &events,
                 &shared_market_state,
                 &telemetry,
+                webhooks.as_ref(),
                 &status_store,
+                history_sink,
                 &cancel_token,
             )
             .await
             {
                 publish_status(
                     &status_store,
-                    &window,
+                    &events,
                     &telemetry,
+                    webhooks.as_ref(),
                     MarketConnectionState::Error,
                     config.market_kind,
                     &config.symbol,
@@ -610,8 +916,9 @@ pub async fn run_market_stream(
         MarketStartupMode::LiveFirst => {
             publish_status(
                 &status_store,
-                &window,
+                &events,
                 &telemetry,
+                webhooks.as_ref(),
                 MarketConnectionState::Connecting,
                 config.market_kind,
                 &config.symbol,
@@ -622,10 +929,12 @@ pub async fn run_market_stream(
 
             let history_config = config.clone();
             let history_client = http_client.clone();
-            let history_window = window.clone();
+            let history_db_pool = db_pool.clone();
+            let history_events = Arc::clone(&events);
             let history_state = Arc::clone(&shared_market_state);
             let history_telemetry = Arc::clone(&telemetry);
             let history_status_store = Arc::clone(&status_store);
+            let history_webhooks = webhooks.clone();
             let history_cancel = cancel_token.clone();
 
             Some(tauri::async_runtime::spawn(async move {
@@ -635,11 +944,14 @@ pub async fn run_market_stream(
 
                 if let Err(error) = load_and_emit_history(
                     &history_config,
+                    &history_db_pool,
                     &history_client,
-                    &history_window,
+                    &history_events,
                     &history_state,
                     &history_telemetry,
+                    history_webhooks.as_ref(),
                     &history_status_store,
+                    history_sink,
                     &history_cancel,
                 )
                 .await
@@ -650,8 +962,9 @@ pub async fn run_market_stream(
                     };
                     publish_status(
                         &history_status_store,
-                        &history_window,
+                        &history_events,
                         &history_telemetry,
+                        history_webhooks.as_ref(),
                         current_state,
                         history_config.market_kind,
                         &history_config.symbol,
@@ -666,10 +979,14 @@ pub async fn run_market_stream(
 
     let consumer_cancel = cancel_token.clone();
     let consumer_status_store = Arc::clone(&status_store);
+    let consumer_webhooks = webhooks.clone();
     let consumer_state = Arc::clone(&shared_market_state);
+    let consumer_trade_tape = Arc::clone(&trade_tape);
     let consumer_telemetry = Arc::clone(&telemetry);
     let consumer_perf_telemetry = Arc::clone(&perf_telemetry);
-    let consumer_window = window.clone();
+    let consumer_events = Arc::clone(&events);
+    let consumer_sequenced_events = sequenced_events;
+    let consumer_frame_acks = frame_acks;
     let consumer_market_kind = config.market_kind;
     let consumer_symbol = config.symbol.clone();
     let consumer_interval_ms = config.emit_interval_ms;
@@ -680,6 +997,10 @@ pub async fn run_market_stream(
     let consumer_handle = tauri::async_runtime::spawn(async move {
         let mut ticker = tokio::time::interval(Duration::from_millis(consumer_interval_ms));
         ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        let mut pacer = EmitPacer::new(consumer_interval_ms);
+        consumer_perf_telemetry
+            .lock()
+            .set_backpressure(pacer.stats());
 
         loop {
             tokio::select! {
@@ -687,6 +1008,22 @@ pub async fn run_market_stream(
                     break;
                 }
                 _ = ticker.tick() => {
+                    let watched = consumer_events.reaches_windows();
+                    if !pacer.is_due(consumer_frame_acks.latest(), watched, Instant::now()) {
+                        if has_pending_frame(&consumer_state.lock()) {
+                            pacer.record_merged();
+                        }
+                        consumer_perf_telemetry.lock().set_backpressure(pacer.stats());
+                        continue;
+                    }
+
+                    let maybe_tape = consumer_trade_tape.lock().drain_append();
+                    if let Some(tape) = maybe_tape {
+                        if let Err(error) = consumer_events.emit(MarketEvent::TradeTape(&tape)) {
+                            eprintln!("failed to emit trade_tape event: {error}");
+                        }
+                    }
+
                     let maybe_frame = {
                         let emitted_at = Instant::now();
                         let mut writable = consumer_state.lock();
@@ -694,6 +1031,7 @@ pub async fn run_market_stream(
                     };
 
                     let Some(frame) = maybe_frame else {
+                        consumer_perf_telemetry.lock().set_backpressure(pacer.stats());
                         continue;
                     };
 
@@ -701,15 +1039,24 @@ pub async fn run_market_stream(
                         consumer_telemetry
                             .set_local_pipeline_latency_ms(local_pipeline_latency_ms.max(0));
                     }
-                    consumer_perf_telemetry
-                        .lock()
-                        .record_emit(frame.local_pipeline_latency_ms);
 
-                    if let Err(error) = consumer_window.emit(MARKET_FRAME_UPDATE_EVENT, &frame) {
+                    let emitted = consumer_sequenced_events.emit_sequenced(MarketEvent::Frame(&frame));
+                    match &emitted {
+                        Ok(seq) => pacer.record_emit(*seq, Instant::now()),
+                        Err(_) => pacer.record_dropped(),
+                    }
+                    {
+                        let mut perf = consumer_perf_telemetry.lock();
+                        perf.record_emit(frame.local_pipeline_latency_ms);
+                        perf.set_backpressure(pacer.stats());
+                    }
+
+                    if let Err(error) = emitted {
                         publish_status(
                             &consumer_status_store,
-                            &consumer_window,
+                            &consumer_events,
                             &consumer_telemetry,
+                            consumer_webhooks.as_ref(),
                             MarketConnectionState::Error,
                             consumer_market_kind,
                             &consumer_symbol,
@@ -720,12 +1067,13 @@ pub async fn run_market_stream(
                     }
 
                     if consumer_emit_legacy_price_event {
-                        if let Some(ui_tick) = frame.tick.clone() {
-                            if let Err(error) = consumer_window.emit(PRICE_UPDATE_EVENT, ui_tick) {
+                        if let Some(ui_tick) = frame.tick.as_ref() {
+                            if let Err(error) = consumer_events.emit(MarketEvent::PriceUpdate(ui_tick)) {
                                 publish_status(
                                     &consumer_status_store,
-                                    &consumer_window,
+                                    &consumer_events,
                                     &consumer_telemetry,
+                                    consumer_webhooks.as_ref(),
                                     MarketConnectionState::Error,
                                     consumer_market_kind,
                                     &consumer_symbol,
@@ -737,12 +1085,13 @@ pub async fn run_market_stream(
                     }
 
                     if consumer_emit_legacy_frame_events {
-                        if let Some(candle) = frame.candle.clone() {
-                            if let Err(error) = consumer_window.emit(CANDLE_UPDATE_EVENT, candle) {
+                        if let Some(candle) = frame.candle.as_ref() {
+                            if let Err(error) = consumer_events.emit(MarketEvent::CandleUpdate(candle)) {
                                 publish_status(
                                     &consumer_status_store,
-                                    &consumer_window,
+                                    &consumer_events,
                                     &consumer_telemetry,
+                                    consumer_webhooks.as_ref(),
                                     MarketConnectionState::Error,
                                     consumer_market_kind,
                                     &consumer_symbol,
@@ -752,12 +1101,13 @@ pub async fn run_market_stream(
                             }
                         }
 
-                        if let Some(delta_candle) = frame.delta_candle {
-                            if let Err(error) = consumer_window.emit(DELTA_CANDLE_UPDATE_EVENT, delta_candle) {
+                        if let Some(delta_candle) = frame.delta_candle.as_ref() {
+                            if let Err(error) = consumer_events.emit(MarketEvent::DeltaCandleUpdate(delta_candle)) {
                                 publish_status(
                                     &consumer_status_store,
-                                    &consumer_window,
+                                    &consumer_events,
                                     &consumer_telemetry,
+                                    consumer_webhooks.as_ref(),
                                     MarketConnectionState::Error,
                                     consumer_market_kind,
                                     &consumer_symbol,
@@ -774,9 +1124,10 @@ pub async fn run_market_stream(
 
     let heartbeat_cancel = cancel_token.clone();
     let heartbeat_status_store = Arc::clone(&status_store);
+    let heartbeat_webhooks = webhooks.clone();
     let heartbeat_telemetry = Arc::clone(&telemetry);
     let heartbeat_perf_telemetry = Arc::clone(&perf_telemetry);
-    let heartbeat_window = window.clone();
+    let heartbeat_events = Arc::clone(&events);
     let heartbeat_market_kind = config.market_kind;
     let heartbeat_symbol = config.symbol.clone();
     let heartbeat_timeframe = config.timeframe;
@@ -795,8 +1146,9 @@ pub async fn run_market_stream(
                     };
                     publish_status(
                         &heartbeat_status_store,
-                        &heartbeat_window,
+                        &heartbeat_events,
                         &heartbeat_telemetry,
+                        heartbeat_webhooks.as_ref(),
                         current_state,
                         heartbeat_market_kind,
                         &heartbeat_symbol,
@@ -809,7 +1161,7 @@ pub async fn run_market_stream(
                             let readable = heartbeat_perf_telemetry.lock();
                             readable.snapshot(now_unix_ms())
                         };
-                        if let Err(error) = heartbeat_window.emit(MARKET_PERF_EVENT, snapshot) {
+                        if let Err(error) = heartbeat_events.emit(MarketEvent::Perf(&snapshot)) {
                             eprintln!("failed to emit market_perf event: {error}");
                         }
                     }
@@ -851,13 +1203,15 @@ pub async fn run_market_stream(
         }
     });
 
-    if config.mock_mode {
+    if config.mock_mode && config.mock_faults.is_none() {
         run_mock_producer(
             &config,
             &shared_market_state,
+            &trade_tape,
             &telemetry,
+            webhooks.as_ref(),
             &status_store,
-            &window,
+            &events,
             &cancel_token,
         )
         .await;
@@ -872,8 +1226,9 @@ pub async fn run_market_stream(
 
         publish_status(
             &status_store,
-            &window,
+            &events,
             &telemetry,
+            webhooks.as_ref(),
             MarketConnectionState::Stopped,
             config.market_kind,
             &config.symbol,
@@ -884,16 +1239,85 @@ pub async fn run_market_stream(
         return;
     }
 
+    if let Some(replay_source) = replay_source {
+        let mut replay_source = replay_source;
+        let outcome = run_replay_producer(
+            &config,
+            &mut replay_source,
+            &shared_market_state,
+            &trade_tape,
+            &telemetry,
+            webhooks.as_ref(),
+            &status_store,
+            &events,
+            &cancel_token,
+        )
+        .await;
+        replay_source.control.end();
+
+        cancel_token.cancel();
+        let _ = consumer_handle.await;
+        let _ = heartbeat_handle.await;
+        let _ = clock_handle.await;
+        if let Some(handle) = history_handle {
+            let _ = handle.await;
+        }
+
+        let (state, reason) = match outcome {
+            Ok(()) => (MarketConnectionState::Stopped, "replay stopped".to_string()),
+            Err(error) => (
+                MarketConnectionState::Error,
+                format!("replay failed: {error}"),
+            ),
+        };
+        publish_status(
+            &status_store,
+            &events,
+            &telemetry,
+            webhooks.as_ref(),
+            state,
+            config.market_kind,
+            &config.symbol,
+            config.timeframe,
+            Some(reason),
+        )
+        .await;
+        return;
+    }
+
+    // Mock trades are synthetic, so they never reach persisted alerts.
+    let alert_engine = alert_engine.filter(|_| !config.mock_mode);
+    if let Some(alert_engine) = &alert_engine {
+        alert_engine.attach_feed(config.market_kind, &config.symbol);
+    }
+    let mock_exchange = config
+        .mock_faults
+        .is_some()
+        .then(|| Arc::new(MockExchange::default()));
+    let mut mock_feed = config
+        .mock_faults
+        .zip(mock_exchange.clone())
+        .map(|(faults, exchange)| {
+            let (simulator, _, _) = mock_simulator_with_history(&config, now_unix_ms());
+            MockTradeFeed::new(simulator, faults, exchange)
+        });
     let mut reconnect_attempt = 0_u32;
+    let mut outage_from_ms: Option<i64> = None;
     let stream_context = StreamRuntimeContext {
         config: &config,
         http_client: &http_client,
+        db_pool: &db_pool,
         shared_market_state: &shared_market_state,
         telemetry: &telemetry,
         perf_telemetry: &perf_telemetry,
         status_store: &status_store,
         status_throttle: &status_throttle,
-        window: &window,
+        alert_engine: alert_engine.as_ref(),
+        webhooks: webhooks.as_ref(),
+        trade_tape: &trade_tape,
+        trade_recorder: trade_recorder.as_ref(),
+        mock_exchange: mock_exchange.as_deref(),
+        events: &events,
         cancel_token: &cancel_token,
     };
     while !cancel_token.is_cancelled() {
@@ -911,8 +1335,9 @@ pub async fn run_market_stream(
 
         publish_status(
             &status_store,
-            &window,
+            &events,
             &telemetry,
+            webhooks.as_ref(),
             phase,
             config.market_kind,
             &config.symbol,
@@ -921,13 +1346,23 @@ pub async fn run_market_stream(
         )
         .await;
 
-        match connect_agg_trade_stream(config.market_kind, &config.symbol).await {
+        let connection = match mock_feed.as_mut() {
+            Some(feed) => {
+                feed.reconnect(now_unix_ms());
+                Ok(TradeFeed::Mock(feed))
+            }
+            None => connect_agg_trade_stream(&http_client, config.market_kind, &config.symbol)
+                .await
+                .map(|stream| TradeFeed::Exchange(Box::new(stream))),
+        };
+        match connection {
             Ok(mut websocket_stream) => {
                 reconnect_attempt = 0;
                 publish_status(
                     &status_store,
-                    &window,
+                    &events,
                     &telemetry,
+                    webhooks.as_ref(),
                     MarketConnectionState::Live,
                     config.market_kind,
                     &config.symbol,
@@ -936,13 +1371,17 @@ pub async fn run_market_stream(
                 )
                 .await;
 
+                // The repair waits for the first trade of the new socket: the exchange
+                // kline of that trade's bucket already counts the trades buffered behind it.
+                let mut pending_outage_repair = outage_from_ms.take().filter(|_| !config.mock_mode);
+
                 let mut immediate_reconnect = false;
                 loop {
                     let frame = tokio::select! {
                         _ = cancel_token.cancelled() => {
                             break;
                         }
-                        next_message = websocket_stream.next() => next_message,
+                        next_message = websocket_stream.next_frame() => next_message,
                     };
 
                     let Some(frame_result) = frame else {
@@ -950,7 +1389,13 @@ pub async fn run_market_stream(
                     };
 
                     match frame_result {
-                        Ok(message) => match handle_message(message, &stream_context).await {
+                        Ok(message) => match handle_message(
+                            message,
+                            &stream_context,
+                            &mut pending_outage_repair,
+                        )
+                        .await
+                        {
                             StreamDirective::Continue => {}
                             StreamDirective::ImmediateReconnect => {
                                 immediate_reconnect = true;
@@ -977,12 +1422,19 @@ pub async fn run_market_stream(
                     break;
                 }
 
+                if outage_from_ms.is_none() {
+                    outage_from_ms = outage_window_start(&shared_market_state);
+                }
+
                 if immediate_reconnect {
                     reconnect_attempt = 0;
                     continue;
                 }
             }
             Err(error) => {
+                if outage_from_ms.is_none() {
+                    outage_from_ms = outage_window_start(&shared_market_state);
+                }
                 publish_status_throttled(
                     &stream_context,
                     MarketConnectionState::Reconnecting,
@@ -1000,6 +1452,9 @@ pub async fn run_market_stream(
         }
     }
 
+    if let Some(alert_engine) = &alert_engine {
+        alert_engine.detach_feed(config.market_kind, &config.symbol);
+    }
     cancel_token.cancel();
     let _ = consumer_handle.await;
     let _ = heartbeat_handle.await;
@@ -1010,8 +1465,9 @@ pub async fn run_market_stream(
 
     publish_status(
         &status_store,
-        &window,
+        &events,
         &telemetry,
+        webhooks.as_ref(),
         MarketConnectionState::Stopped,
         config.market_kind,
         &config.symbol,
@@ -1021,93 +1477,72 @@ pub async fn run_market_stream(
     .await;
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn load_and_emit_history(
     config: &MarketStreamConfig,
-    http_client: &Client,
-    window: &WebviewWindow,
+    db_pool: &SqlitePool,
+    http_client: &BinanceClient,
+    events: &dyn MarketEventSink,
     shared_market_state: &Arc<Mutex<ConflatedMarketState>>,
     telemetry: &Arc<MarketTelemetryAtomics>,
+    webhooks: Option<&Arc<WebhookDispatcher>>,
     status_store: &Arc<RwLock<MarketStreamStatusSnapshot>>,
+    history_sink: Option<HistoryChunkSink>,
     cancel_token: &CancellationToken,
 ) -> Result<(), AppError> {
     if cancel_token.is_cancelled() {
         return Ok(());
     }
 
-    let (candles, delta_candles) = if config.mock_mode {
-        let history_limit = if config.history_all {
-            DEFAULT_HISTORY_LIMIT
-        } else {
-            config.history_limit
-        };
-        (
-            build_mock_history(config.timeframe, history_limit, now_unix_ms()),
-            build_mock_delta_history(config.timeframe, history_limit, now_unix_ms()),
-        )
-    } else if config.history_all {
-        window.emit(
-            HISTORY_LOAD_PROGRESS_EVENT,
-            UiHistoryLoadProgress {
-                market_kind: config.market_kind,
-                symbol: config.symbol.clone(),
-                timeframe: config.timeframe,
-                pages_fetched: 0,
-                candles_fetched: 0,
-                estimated_total_candles: None,
-                progress_pct: Some(0.0),
-                done: false,
-            },
-        )?;
-
-        let progress_started_at = Instant::now();
-        let mut last_progress_emit_at = progress_started_at
-            .checked_sub(Duration::from_millis(HISTORY_PROGRESS_EMIT_THROTTLE_MS))
-            .unwrap_or(progress_started_at);
+    // Replay rebuilds the chart from recorded trades, so the replay producer emits an empty
+    // bootstrap itself and history only has to close the chunk stream.
+    if config.replay.is_some() {
+        if let Some(mut sink) = history_sink {
+            send_history_chunk(&mut sink, cancel_token, true, &[], &[]).await?;
+        }
+        return Ok(());
+    }
 
-        fetch_klines_history_bundle_with_progress(
+    if let Some(sink) = history_sink {
+        let completed = stream_history_chunks(
+            config,
+            db_pool,
             http_client,
-            config.market_kind,
-            &config.symbol,
-            config.timeframe,
-            config.history_limit,
-            true,
-            |progress| {
-                if cancel_token.is_cancelled() {
-                    return Ok(());
-                }
-
-                let now = Instant::now();
-                let should_emit = progress.done
-                    || now.duration_since(last_progress_emit_at)
-                        >= Duration::from_millis(HISTORY_PROGRESS_EMIT_THROTTLE_MS);
-                if !should_emit {
-                    return Ok(());
-                }
-
-                last_progress_emit_at = now;
-                let payload = UiHistoryLoadProgress {
-                    market_kind: config.market_kind,
-                    symbol: config.symbol.clone(),
-                    timeframe: config.timeframe,
-                    pages_fetched: progress.pages_fetched,
-                    candles_fetched: progress.candles_fetched,
-                    estimated_total_candles: progress.estimated_total_candles,
-                    progress_pct: progress.progress_pct,
-                    done: progress.done,
-                };
-                window.emit(HISTORY_LOAD_PROGRESS_EVENT, payload)?;
-                Ok(())
-            },
+            events,
+            shared_market_state,
+            sink,
+            cancel_token,
         )
-        .await?
+        .await?;
+        if completed {
+            publish_status(
+                status_store,
+                events,
+                telemetry,
+                webhooks,
+                current_operational_state(status_store).await,
+                config.market_kind,
+                &config.symbol,
+                config.timeframe,
+                Some("historical candles loaded".to_string()),
+            )
+            .await;
+        }
+        return Ok(());
+    }
+
+    // Full history only travels as chunks; `start_market_stream` rejects `historyAll`
+    // without a history channel, so this JSON bootstrap is always bounded.
+    let (candles, delta_candles) = if config.mock_mode {
+        let (_, candles, delta_candles) = mock_simulator_with_history(config, now_unix_ms());
+        (candles, delta_candles)
     } else {
-        fetch_klines_history_bundle(
+        load_recent_klines(
+            db_pool,
             http_client,
-            config.market_kind,
-            &config.symbol,
-            config.timeframe,
+            KlineSeries::new(config.market_kind, &config.symbol, config.timeframe),
             config.history_limit,
-            false,
+            now_unix_ms(),
         )
         .await?
     };
@@ -1128,18 +1563,19 @@ async fn load_and_emit_history(
         candles,
     };
 
-    window.emit(CANDLES_BOOTSTRAP_EVENT, payload)?;
+    events.emit(MarketEvent::CandlesBootstrap(&payload))?;
     let delta_payload = UiDeltaCandlesBootstrap {
         symbol: config.symbol.clone(),
         timeframe: config.timeframe,
         candles: delta_candles,
     };
-    window.emit(DELTA_CANDLES_BOOTSTRAP_EVENT, delta_payload)?;
+    events.emit(MarketEvent::DeltaCandlesBootstrap(&delta_payload))?;
 
     publish_status(
         status_store,
-        window,
+        events,
         telemetry,
+        webhooks,
         current_operational_state(status_store).await,
         config.market_kind,
         &config.symbol,
@@ -1151,6 +1587,154 @@ async fn load_and_emit_history(
     Ok(())
 }
 
+/// Streams history to the webview newest page first as binary chunks, waiting for acks so a
+/// multi-million candle bootstrap never piles up in the webview. Both bounded and
+/// `history_all` loads go through the local kline cache. Returns false if cancelled.
+async fn stream_history_chunks(
+    config: &MarketStreamConfig,
+    db_pool: &SqlitePool,
+    http_client: &BinanceClient,
+    events: &dyn MarketEventSink,
+    shared_market_state: &Arc<Mutex<ConflatedMarketState>>,
+    mut sink: HistoryChunkSink,
+    cancel_token: &CancellationToken,
+) -> Result<bool, AppError> {
+    if config.mock_mode || !config.history_all {
+        let (candles, delta_candles) = if config.mock_mode {
+            let (_, candles, delta_candles) = mock_simulator_with_history(config, now_unix_ms());
+            (candles, delta_candles)
+        } else {
+            load_recent_klines(
+                db_pool,
+                http_client,
+                KlineSeries::new(config.market_kind, &config.symbol, config.timeframe),
+                config.history_limit,
+                now_unix_ms(),
+            )
+            .await?
+        };
+        if cancel_token.is_cancelled() {
+            return Ok(false);
+        }
+        {
+            let mut writable = shared_market_state.lock();
+            apply_history_snapshot(&mut writable, &candles);
+            apply_delta_history_snapshot(&mut writable, &delta_candles);
+        }
+
+        let chunks = candles
+            .rchunks(HISTORY_CHUNK_CANDLES)
+            .zip(delta_candles.rchunks(HISTORY_CHUNK_CANDLES));
+        for (candle_chunk, delta_chunk) in chunks {
+            if !send_history_chunk(&mut sink, cancel_token, false, candle_chunk, delta_chunk)
+                .await?
+            {
+                return Ok(false);
+            }
+        }
+        return send_history_chunk(&mut sink, cancel_token, true, &[], &[]).await;
+    }
+
+    emit_history_progress(events, config, None)?;
+
+    let mut pager = CachedHistoryPager::new(
+        db_pool,
+        http_client,
+        KlineSeries::new(config.market_kind, &config.symbol, config.timeframe),
+        now_unix_ms(),
+    )
+    .await?;
+    let mut last_progress_emit_at: Option<Instant> = None;
+    let mut is_newest_page = true;
+
+    while let Some(page) = pager.next_page().await? {
+        if cancel_token.is_cancelled() {
+            return Ok(false);
+        }
+
+        if is_newest_page {
+            let mut writable = shared_market_state.lock();
+            apply_history_snapshot(&mut writable, &page.candles);
+            apply_delta_history_snapshot(&mut writable, &page.delta_candles);
+            is_newest_page = false;
+        }
+
+        if !send_history_chunk(
+            &mut sink,
+            cancel_token,
+            false,
+            &page.candles,
+            &page.delta_candles,
+        )
+        .await?
+        {
+            return Ok(false);
+        }
+
+        let now = Instant::now();
+        let should_emit = last_progress_emit_at
+            .map(|emitted_at| {
+                now.duration_since(emitted_at)
+                    >= Duration::from_millis(HISTORY_PROGRESS_EMIT_THROTTLE_MS)
+            })
+            .unwrap_or(true);
+        if should_emit {
+            last_progress_emit_at = Some(now);
+            emit_history_progress(events, config, Some(page.progress))?;
+        }
+    }
+
+    if !send_history_chunk(&mut sink, cancel_token, true, &[], &[]).await? {
+        return Ok(false);
+    }
+    emit_history_progress(events, config, Some(pager.done_progress()))?;
+    Ok(true)
+}
+
+async fn send_history_chunk(
+    sink: &mut HistoryChunkSink,
+    cancel_token: &CancellationToken,
+    is_final: bool,
+    candles: &[UiCandle],
+    delta_candles: &[UiDeltaCandle],
+) -> Result<bool, AppError> {
+    tokio::select! {
+        _ = cancel_token.cancelled() => Ok(false),
+        sent = sink.send_chunk(is_final, candles, delta_candles) => sent.map(|_| true),
+    }
+}
+
+fn emit_history_progress(
+    events: &dyn MarketEventSink,
+    config: &MarketStreamConfig,
+    progress: Option<KlineHistoryProgress>,
+) -> Result<(), AppError> {
+    let payload = match progress {
+        Some(progress) => UiHistoryLoadProgress {
+            market_kind: config.market_kind,
+            symbol: config.symbol.clone(),
+            timeframe: config.timeframe,
+            pages_fetched: progress.pages_fetched,
+            candles_fetched: progress.candles_fetched,
+            estimated_total_candles: progress.estimated_total_candles,
+            progress_pct: progress.progress_pct,
+            done: progress.done,
+        },
+        None => UiHistoryLoadProgress {
+            market_kind: config.market_kind,
+            symbol: config.symbol.clone(),
+            timeframe: config.timeframe,
+            pages_fetched: 0,
+            candles_fetched: 0,
+            estimated_total_candles: None,
+            progress_pct: Some(0.0),
+            done: false,
+        },
+    };
+    events.emit(MarketEvent::HistoryLoadProgress(&payload))?;
+    Ok(())
+}
+
 async fn current_operational_state(
     status_store: &Arc<RwLock<MarketStreamStatusSnapshot>>,
 ) -> MarketConnectionState {
@@ -1163,91 +1747,51 @@ async fn current_operational_state(
     }
 }
 
-fn build_mock_history(
-    timeframe: MarketTimeframe,
-    history_limit: u32,
-    now_ms: i64,
-) -> Vec<UiCandle> {
-    let timeframe_ms = timeframe.duration_ms();
-    let aligned_now = candle_bucket_open_time(now_ms, timeframe_ms);
-    let start = aligned_now - (history_limit as i64 * timeframe_ms);
-    let mut candles = Vec::with_capacity(history_limit as usize);
-    let mut price = 100_000.0;
-
-    for step in 0..history_limit {
-        let open_time = start + step as i64 * timeframe_ms;
-        let drift = ((step % 7) as f64 - 3.0) * 2.1;
-        let open = price;
-        let close = (open + drift).max(1.0);
-        let high = open.max(close) + 1.25;
-        let low = open.min(close) - 1.1;
-        let volume = 2.0 + (step % 5) as f64 * 0.3;
-        candles.push(UiCandle {
-            t: open_time,
-            o: open,
-            h: high,
-            l: low,
-            c: close,
-            v: volume,
-        });
-        price = close;
-    }
-
-    candles
-}
-
-fn build_mock_delta_history(
-    timeframe: MarketTimeframe,
-    history_limit: u32,
+/// Seeds a simulator from the stream's scenario and walks it through the mock history, so the
+/// history and the live tape of one stream continue the same price path.
+fn mock_simulator_with_history(
+    config: &MarketStreamConfig,
     now_ms: i64,
-) -> Vec<UiDeltaCandle> {
-    let timeframe_ms = timeframe.duration_ms();
-    let aligned_now = candle_bucket_open_time(now_ms, timeframe_ms);
-    let start = aligned_now - (history_limit as i64 * timeframe_ms);
-    let mut candles = Vec::with_capacity(history_limit as usize);
-
-    for step in 0..history_limit {
-        let open_time = start + step as i64 * timeframe_ms;
-        let direction = if step % 2 == 0 { 1.0 } else { -1.0 };
-        let magnitude = 1.0 + (step % 7) as f64 * 0.35;
-        let close = direction * magnitude;
-        candles.push(UiDeltaCandle {
-            t: open_time,
-            o: 0.0,
-            h: close.max(0.0),
-            l: close.min(0.0),
-            c: close,
-            v: magnitude.abs(),
-        });
-    }
-
-    candles
+) -> (MockMarketSimulator, Vec<UiCandle>, Vec<UiDeltaCandle>) {
+    let history_limit = if config.history_all {
+        DEFAULT_HISTORY_LIMIT
+    } else {
+        config.history_limit
+    };
+    let mut simulator = MockMarketSimulator::new(&config.mock_scenario.clone().unwrap_or_default());
+    let (candles, delta_candles) = simulator.build_history(config.timeframe, history_limit, now_ms);
+    (simulator, candles, delta_candles)
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn run_mock_producer(
     config: &MarketStreamConfig,
     shared_market_state: &Arc<Mutex<ConflatedMarketState>>,
+    trade_tape: &Arc<Mutex<TradeTape>>,
     telemetry: &Arc<MarketTelemetryAtomics>,
+    webhooks: Option<&Arc<WebhookDispatcher>>,
     status_store: &Arc<RwLock<MarketStreamStatusSnapshot>>,
-    window: &WebviewWindow,
+    events: &dyn MarketEventSink,
     cancel_token: &CancellationToken,
 ) {
     publish_status(
         status_store,
-        window,
+        events,
         telemetry,
+        webhooks,
         MarketConnectionState::Connecting,
         config.market_kind,
         &config.symbol,
         config.timeframe,
-        Some("starting deterministic mock stream".to_string()),
+        Some("starting seeded mock stream".to_string()),
     )
     .await;
 
     publish_status(
         status_store,
-        window,
+        events,
         telemetry,
+        webhooks,
         MarketConnectionState::Live,
         config.market_kind,
         &config.symbol,
@@ -1256,9 +1800,9 @@ async fn run_mock_producer(
     )
     .await;
 
-    let mut aggregate_trade_id = 0_u64;
-    let mut synthetic_price = 100_000.0_f64;
-    let mut ticker = tokio::time::interval(Duration::from_millis(4));
+    let (mut simulator, _, _) = mock_simulator_with_history(config, now_unix_ms());
+    simulator.start_live(now_unix_ms());
+    let mut ticker = tokio::time::interval(Duration::from_millis(MOCK_TICK_MS));
     ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
 
     while !cancel_token.is_cancelled() {
@@ -1267,41 +1811,253 @@ async fn run_mock_producer(
                 break;
             }
             _ = ticker.tick() => {
-                aggregate_trade_id = aggregate_trade_id.saturating_add(1);
-                let upward_move = aggregate_trade_id.is_multiple_of(2);
-                synthetic_price += if upward_move { 0.6 } else { -0.4 };
-                let quantity = 0.12 + ((aggregate_trade_id % 5) as f64 * 0.01);
                 let now_ms = now_unix_ms();
-                let ingest_started_at = Instant::now();
-                let synthetic_event = AggTradeEvent {
-                    event_time: now_ms,
-                    aggregate_trade_id,
-                    price: synthetic_price,
-                    quantity,
-                    trade_time: now_ms,
-                    is_buyer_maker: !upward_move,
-                };
+                if now_ms - simulator.next_trade_time() > MOCK_MAX_CATCH_UP_MS {
+                    simulator.start_live(now_ms);
+                }
 
-                {
-                    let mut writable = shared_market_state.lock();
-                    let _ = apply_trade_event(
-                        &mut writable,
-                        &synthetic_event,
-                        config.min_notional_usdt,
-                        config.timeframe,
-                        now_ms,
-                        ingest_started_at,
-                    );
+                while simulator.next_trade_time() <= now_ms {
+                    let ingest_started_at = Instant::now();
+                    let synthetic_event = simulator.next_trade();
+                    let outcome = {
+                        let mut writable = shared_market_state.lock();
+                        apply_trade_event(
+                            &mut writable,
+                            &synthetic_event,
+                            config.min_notional_usdt,
+                            config.timeframe,
+                            now_ms,
+                            ingest_started_at,
+                        )
+                    };
+                    if matches!(outcome, TradeApplyOutcome::Applied { .. }) {
+                        trade_tape.lock().push(&synthetic_event);
+                    }
+                    telemetry.set_last_agg_id(synthetic_event.aggregate_trade_id);
                 }
-                telemetry.set_last_agg_id(synthetic_event.aggregate_trade_id);
                 telemetry.set_network_latencies(0, telemetry.clock_offset_ms(), 0);
             }
         }
     }
 }
 
-async fn handle_message(message: Message, context: &StreamRuntimeContext<'_>) -> StreamDirective {
-    let ingest_started_at = Instant::now();
+/// Feeds recorded trades through `apply_trade_event` so the consumer emits the same frames as
+/// a live stream. Playback is paced against recorded trade times and steered by the shared
+/// `ReplayControl`; after the last trade the producer idles until a seek or cancellation.
+#[allow(clippy::too_many_arguments)]
+async fn run_replay_producer(
+    config: &MarketStreamConfig,
+    replay_source: &mut ReplaySource,
+    shared_market_state: &Arc<Mutex<ConflatedMarketState>>,
+    trade_tape: &Arc<Mutex<TradeTape>>,
+    telemetry: &Arc<MarketTelemetryAtomics>,
+    webhooks: Option<&Arc<WebhookDispatcher>>,
+    status_store: &Arc<RwLock<MarketStreamStatusSnapshot>>,
+    events: &dyn MarketEventSink,
+    cancel_token: &CancellationToken,
+) -> Result<(), AppError> {
+    let Some(replay) = config.replay.as_ref() else {
+        return Ok(());
+    };
+    let ReplaySource { cursor, control } = replay_source;
+
+    restart_replay(
+        config,
+        cursor,
+        replay.from_t,
+        shared_market_state,
+        trade_tape,
+        events,
+    )?;
+    publish_status(
+        status_store,
+        events,
+        telemetry,
+        webhooks,
+        MarketConnectionState::Live,
+        config.market_kind,
+        &config.symbol,
+        config.timeframe,
+        Some("replaying recorded trades".to_string()),
+    )
+    .await;
+    emit_replay_status(events, control)?;
+
+    let mut pending_trade: Option<AggTradeEvent> = None;
+    let mut pace_anchor: Option<(i64, Instant, f64)> = None;
+    let mut last_status_emit_at = Instant::now();
+    let mut applied_since_yield = 0_u32;
+
+    while !cancel_token.is_cancelled() {
+        if let Some(seek_to) = control.take_seek() {
+            restart_replay(
+                config,
+                cursor,
+                seek_to,
+                shared_market_state,
+                trade_tape,
+                events,
+            )?;
+            pending_trade = None;
+            pace_anchor = None;
+            emit_replay_status(events, control)?;
+        }
+
+        let playback = control.playback();
+        let stepping = playback.paused && control.take_step();
+        if playback.paused && !stepping {
+            pace_anchor = None;
+            emit_replay_status(events, control)?;
+            tokio::select! {
+                _ = cancel_token.cancelled() => break,
+                _ = control.changed() => continue,
+            }
+        }
+
+        if pending_trade.is_none() {
+            pending_trade = cursor
+                .next_trade()?
+                .filter(|trade| replay.to_t.is_none_or(|to_t| trade.trade_time <= to_t));
+        }
+        let Some(trade) = pending_trade.take() else {
+            control.finish();
+            emit_replay_status(events, control)?;
+            publish_status(
+                status_store,
+                events,
+                telemetry,
+                webhooks,
+                MarketConnectionState::Live,
+                config.market_kind,
+                &config.symbol,
+                config.timeframe,
+                Some("replay finished".to_string()),
+            )
+            .await;
+            tokio::select! {
+                _ = cancel_token.cancelled() => break,
+                _ = control.changed() => continue,
+            }
+        };
+
+        if !stepping && playback.speed > 0.0 {
+            let (anchor_t, anchor_at, _) = match pace_anchor {
+                Some(anchor) if anchor.2 == playback.speed => anchor,
+                _ => (trade.trade_time, Instant::now(), playback.speed),
+            };
+            pace_anchor = Some((anchor_t, anchor_at, playback.speed));
+            let offset_ms = trade.trade_time.saturating_sub(anchor_t).max(0) as f64;
+            let due_at = anchor_at + Duration::from_secs_f64(offset_ms / playback.speed / 1_000.0);
+            if due_at > Instant::now() {
+                pending_trade = Some(trade);
+                tokio::select! {
+                    _ = cancel_token.cancelled() => break,
+                    _ = tokio::time::sleep_until(tokio::time::Instant::from_std(due_at)) => {}
+                    _ = control.changed() => {}
+                }
+                continue;
+            }
+        }
+
+        let outcome = {
+            let mut writable = shared_market_state.lock();
+            apply_replayed_trade(&mut writable, &trade, config)
+        };
+        if matches!(outcome, TradeApplyOutcome::Applied { .. }) {
+            trade_tape.lock().push(&trade);
+        }
+        telemetry.set_last_agg_id(trade.aggregate_trade_id);
+        telemetry.set_network_latencies(0, telemetry.clock_offset_ms(), 0);
+        control.set_cursor(trade.trade_time);
+
+        if stepping
+            || last_status_emit_at.elapsed()
+                >= Duration::from_millis(REPLAY_STATUS_EMIT_THROTTLE_MS)
+        {
+            emit_replay_status(events, control)?;
+            last_status_emit_at = Instant::now();
+        }
+
+        if playback.speed == 0.0 && !stepping {
+            applied_since_yield += 1;
+            if applied_since_yield >= REPLAY_MAX_SPEED_YIELD_EVERY {
+                applied_since_yield = 0;
+                tokio::task::yield_now().await;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Applies a recorded trade with its own event time as "now". Recordings span reconnects, so
+/// an id gap is a hole in the recording rather than a lost trade and playback continues past it.
+fn apply_replayed_trade(
+    state: &mut ConflatedMarketState,
+    trade: &AggTradeEvent,
+    config: &MarketStreamConfig,
+) -> TradeApplyOutcome {
+    let apply = |state: &mut ConflatedMarketState| {
+        apply_trade_event(
+            state,
+            trade,
+            config.min_notional_usdt,
+            config.timeframe,
+            trade.event_time,
+            Instant::now(),
+        )
+    };
+
+    match apply(state) {
+        TradeApplyOutcome::GapDetected { found, .. } => {
+            state.last_agg_id = Some(found - 1);
+            apply(state)
+        }
+        outcome => outcome,
+    }
+}
+
+fn restart_replay(
+    config: &MarketStreamConfig,
+    cursor: &mut RecordedTradeCursor,
+    from_t: i64,
+    shared_market_state: &Arc<Mutex<ConflatedMarketState>>,
+    trade_tape: &Arc<Mutex<TradeTape>>,
+    events: &dyn MarketEventSink,
+) -> Result<(), AppError> {
+    cursor.seek(from_t)?;
+    *shared_market_state.lock() = ConflatedMarketState::default();
+    trade_tape.lock().reset(config.market_kind, &config.symbol);
+
+    events.emit(MarketEvent::CandlesBootstrap(&UiCandlesBootstrap {
+        symbol: config.symbol.clone(),
+        timeframe: config.timeframe,
+        candles: Vec::new(),
+    }))?;
+    events.emit(MarketEvent::DeltaCandlesBootstrap(
+        &UiDeltaCandlesBootstrap {
+            symbol: config.symbol.clone(),
+            timeframe: config.timeframe,
+            candles: Vec::new(),
+        },
+    ))?;
+    Ok(())
+}
+
+fn emit_replay_status(
+    events: &dyn MarketEventSink,
+    control: &ReplayControl,
+) -> Result<(), AppError> {
+    events.emit(MarketEvent::ReplayStatus(&control.status()))?;
+    Ok(())
+}
+
+async fn handle_message(
+    message: Message,
+    context: &StreamRuntimeContext<'_>,
+    pending_outage_repair: &mut Option<i64>,
+) -> StreamDirective {
+    let mut ingest_started_at = Instant::now();
     let parse_started_at = Instant::now();
     let trade_event = match message {
         Message::Text(text_payload) => {
@@ -1338,30 +2094,50 @@ async fn handle_message(message: Message, context: &StreamRuntimeContext<'_>) ->
     };
     let parse_elapsed_us = parse_started_at.elapsed().as_micros().min(u32::MAX as u128) as u32;
 
+    if let Some(from_ms) = pending_outage_repair.take() {
+        repair_candles_after_outage(context, from_ms, trade_event.trade_time).await;
+        ingest_started_at = Instant::now();
+    }
+
     let now_ms = now_unix_ms();
     let apply_started_at = Instant::now();
-    let outcome = {
+    let (outcome, closed_bucket) = {
         let mut writable = context.shared_market_state.lock();
-        apply_trade_event(
+        let outcome = apply_trade_event(
             &mut writable,
             &trade_event,
             context.config.min_notional_usdt,
             context.config.timeframe,
             now_ms,
             ingest_started_at,
-        )
+        );
+        (outcome, take_closed_bucket(&mut writable))
     };
     let apply_elapsed_us = apply_started_at.elapsed().as_micros().min(u32::MAX as u128) as u32;
     context
         .perf_telemetry
         .lock()
         .record_ingest(parse_elapsed_us, apply_elapsed_us);
+    if let Some(closed_bucket) = closed_bucket.filter(|_| !context.config.mock_mode) {
+        spawn_bucket_reconcile(context, closed_bucket);
+    }
 
     match outcome {
         TradeApplyOutcome::Applied { .. } => {
             context
                 .telemetry
                 .set_last_agg_id(trade_event.aggregate_trade_id);
+            context.trade_tape.lock().push(&trade_event);
+            if let Some(recorder) = context.trade_recorder {
+                recorder.record(&trade_event);
+            }
+            if let Some(alert_engine) = context.alert_engine {
+                alert_engine.evaluate_trade(
+                    context.config.market_kind,
+                    &context.config.symbol,
+                    &trade_event,
+                );
+            }
             let raw_exchange_latency_ms = signed_time_delta_ms(now_ms, trade_event.event_time);
             let clock_offset_ms = context.telemetry.clock_offset_ms();
             let adjusted_network_latency_ms =
@@ -1377,8 +2153,9 @@ async fn handle_message(message: Message, context: &StreamRuntimeContext<'_>) ->
         TradeApplyOutcome::GapDetected { expected, found } => {
             publish_status(
                 context.status_store,
-                context.window,
+                context.events,
                 context.telemetry,
+                context.webhooks,
                 MarketConnectionState::Desynced,
                 context.config.market_kind,
                 &context.config.symbol,
@@ -1400,13 +2177,159 @@ async fn handle_message(message: Message, context: &StreamRuntimeContext<'_>) ->
     }
 }
 
+/// Open time of the newest bucket the pipeline built before the stream went away; every
+/// bucket from there on may be missing trades.
+fn outage_window_start(shared_market_state: &Arc<Mutex<ConflatedMarketState>>) -> Option<i64> {
+    let readable = shared_market_state.lock();
+    readable.last_candle.as_ref().map(|candle| candle.t)
+}
+
+/// Repairs the buckets from `from_ms` up to, but excluding, the bucket of the first live
+/// trade at `first_live_trade_ms`. That bucket is built from the buffered trades and
+/// converges with the exchange when it closes (see `spawn_bucket_reconcile`).
+async fn repair_candles_after_outage(
+    context: &StreamRuntimeContext<'_>,
+    from_ms: i64,
+    first_live_trade_ms: i64,
+) {
+    let timeframe_ms = context.config.timeframe.duration_ms();
+    let earliest_repairable = candle_bucket_open_time(now_unix_ms(), timeframe_ms)
+        .saturating_sub(timeframe_ms.saturating_mul(i64::from(MAX_OUTAGE_REPAIR_CANDLES) - 1));
+    let start_time = from_ms.max(earliest_repairable);
+
+    let (mut candles, mut delta_candles) = match fetch_klines_since(
+        context.http_client,
+        context.config.market_kind,
+        &context.config.symbol,
+        context.config.timeframe,
+        start_time,
+        MAX_OUTAGE_REPAIR_CANDLES,
+    )
+    .await
+    {
+        Ok(fetched) => fetched,
+        Err(error) => {
+            eprintln!("failed to fetch klines for outage repair: {error}");
+            return;
+        }
+    };
+    let live_bucket_open = candle_bucket_open_time(first_live_trade_ms, timeframe_ms);
+    retain_buckets_before(&mut candles, live_bucket_open, |candle| candle.t);
+    retain_buckets_before(&mut delta_candles, live_bucket_open, |candle| candle.t);
+
+    let (changed_candles, changed_delta_candles) = {
+        let mut writable = context.shared_market_state.lock();
+        apply_kline_repair(&mut writable, &candles, &delta_candles)
+    };
+    if changed_candles.is_empty() && changed_delta_candles.is_empty() {
+        return;
+    }
+
+    let payload = UiCandlesRepair {
+        symbol: context.config.symbol.clone(),
+        timeframe: context.config.timeframe,
+        candles: changed_candles,
+        delta_candles: changed_delta_candles,
+    };
+    if let Err(error) = context.events.emit(MarketEvent::CandlesRepair(&payload)) {
+        eprintln!("failed to emit candles_repair event: {error}");
+    }
+}
+
+/// Fetches the exchange kline for a bucket the live stream just closed, caches it and, when
+/// they disagree, replaces the chart candle in the state and in the UI. Startup buckets mix
+/// REST history with live trades, so this is where the two sources converge.
+fn spawn_bucket_reconcile(context: &StreamRuntimeContext<'_>, closed_bucket: ClosedBucket) {
+    let http_client = context.http_client.clone();
+    let db_pool = context.db_pool.clone();
+    let shared_market_state = Arc::clone(context.shared_market_state);
+    let events = Arc::clone(context.events);
+    let perf_telemetry = Arc::clone(context.perf_telemetry);
+    let cancel_token = context.cancel_token.clone();
+    let market_kind = context.config.market_kind;
+    let symbol = context.config.symbol.clone();
+    let timeframe = context.config.timeframe;
+
+    tauri::async_runtime::spawn(async move {
+        tokio::select! {
+            _ = cancel_token.cancelled() => return,
+            _ = tokio::time::sleep(Duration::from_millis(BUCKET_RECONCILE_DELAY_MS)) => {}
+        }
+
+        let (candles, delta_candles) = match fetch_klines_since(
+            &http_client,
+            market_kind,
+            &symbol,
+            timeframe,
+            closed_bucket.candle.t,
+            1,
+        )
+        .await
+        {
+            Ok(fetched) => fetched,
+            Err(error) => {
+                eprintln!("failed to fetch kline for bucket reconcile: {error}");
+                return;
+            }
+        };
+        let (Some(exchange), Some(exchange_delta)) = (candles.first(), delta_candles.first())
+        else {
+            return;
+        };
+        if exchange.t != closed_bucket.candle.t {
+            return;
+        }
+        let series = KlineSeries::new(market_kind, &symbol, timeframe);
+        if let Err(error) = store_klines(
+            &db_pool,
+            series,
+            std::slice::from_ref(exchange),
+            std::slice::from_ref(exchange_delta),
+            now_unix_ms(),
+        )
+        .await
+        {
+            eprintln!("failed to cache reconciled kline: {error}");
+        }
+
+        let divergence = candle_divergence(&closed_bucket, exchange, exchange_delta);
+        perf_telemetry.lock().record_reconcile(divergence);
+        if divergence.is_none() {
+            return;
+        }
+        {
+            // A restarted stream resets the shared state; this bucket belongs to the old one.
+            let mut writable = shared_market_state.lock();
+            if cancel_token.is_cancelled() {
+                return;
+            }
+            let _ = apply_kline_repair(
+                &mut writable,
+                std::slice::from_ref(exchange),
+                std::slice::from_ref(exchange_delta),
+            );
+        }
+
+        let payload = UiCandlesRepair {
+            symbol,
+            timeframe,
+            candles: vec![exchange.clone()],
+            delta_candles: vec![exchange_delta.clone()],
+        };
+        if let Err(error) = events.emit(MarketEvent::CandlesRepair(&payload)) {
+            eprintln!("failed to emit candles_repair event: {error}");
+        }
+    });
+}
+
 async fn resync_with_snapshot(context: &StreamRuntimeContext<'_>) -> bool {
     let mut attempt = 0_u32;
     while !context.cancel_token.is_cancelled() {
         publish_status(
             context.status_store,
-            context.window,
+            context.events,
             context.telemetry,
+            context.webhooks,
             MarketConnectionState::Reconnecting,
             context.config.market_kind,
             &context.config.symbol,
@@ -1415,13 +2338,18 @@ async fn resync_with_snapshot(context: &StreamRuntimeContext<'_>) -> bool {
         )
         .await;
 
-        match fetch_latest_agg_trade_snapshot(
-            context.http_client,
-            context.config.market_kind,
-            &context.config.symbol,
-        )
-        .await
-        {
+        let snapshot = match context.mock_exchange {
+            Some(exchange) => exchange.snapshot(),
+            None => {
+                fetch_latest_agg_trade_snapshot(
+                    context.http_client,
+                    context.config.market_kind,
+                    &context.config.symbol,
+                )
+                .await
+            }
+        };
+        match snapshot {
             Ok(snapshot) => {
                 {
                     let mut writable = context.shared_market_state.lock();
@@ -1433,8 +2361,9 @@ async fn resync_with_snapshot(context: &StreamRuntimeContext<'_>) -> bool {
 
                 publish_status(
                     context.status_store,
-                    context.window,
+                    context.events,
                     context.telemetry,
+                    context.webhooks,
                     MarketConnectionState::Live,
                     context.config.market_kind,
                     &context.config.symbol,
@@ -1468,8 +2397,9 @@ async fn resync_with_snapshot(context: &StreamRuntimeContext<'_>) -> bool {
 #[allow(clippy::too_many_arguments)]
 async fn publish_status(
     status_store: &Arc<RwLock<MarketStreamStatusSnapshot>>,
-    window: &WebviewWindow,
+    events: &dyn MarketEventSink,
     telemetry: &Arc<MarketTelemetryAtomics>,
+    webhooks: Option<&Arc<WebhookDispatcher>>,
     state: MarketConnectionState,
     market_kind: MarketKind,
     symbol: &str,
@@ -1491,12 +2421,18 @@ async fn publish_status(
         reason,
     };
 
-    {
+    let previous_state = {
         let mut writable = status_store.write().await;
-        *writable = snapshot.clone();
+        std::mem::replace(&mut *writable, snapshot.clone()).state
+    };
+
+    if let Some(webhooks) =
+        webhooks.filter(|_| is_webhook_connection_transition(previous_state, state))
+    {
+        webhooks.enqueue_detached(WebhookEvent::connection_state(previous_state, &snapshot));
     }
 
-    if let Err(error) = window.emit(MARKET_STATUS_EVENT, snapshot) {
+    if let Err(error) = events.emit(MarketEvent::Status(&snapshot)) {
         eprintln!("failed to emit market status event: {error}");
     }
 }
@@ -1543,8 +2479,9 @@ async fn publish_status_throttled(
 
     publish_status(
         context.status_store,
-        context.window,
+        context.events,
         context.telemetry,
+        context.webhooks,
         state,
         context.config.market_kind,
         &context.config.symbol,
@@ -1555,7 +2492,7 @@ async fn publish_status_throttled(
 }
 
 async fn fetch_clock_offset_ms(
-    client: &Client,
+    client: &BinanceClient,
     market_kind: MarketKind,
 ) -> Result<ClockOffsetProbe, AppError> {
     let mut probes: Vec<ClockOffsetProbe> = Vec::with_capacity(CLOCK_SYNC_PROBE_COUNT);
@@ -1573,7 +2510,7 @@ async fn fetch_clock_offset_ms(
     }
 
     if probes.is_empty() {
-        return Err(AppError::InvalidArgument(
+        return Err(AppError::Unavailable(
             "clock sync probes failed".to_string(),
         ));
     }
@@ -1599,7 +2536,7 @@ async fn fetch_clock_offset_ms(
 }
 
 async fn fetch_clock_offset_probe(
-    client: &Client,
+    client: &BinanceClient,
     market_kind: MarketKind,
 ) -> Result<ClockOffsetProbe, AppError> {
     let request_started_ms = now_unix_ms();
@@ -1642,14 +2579,14 @@ fn signed_time_delta_ms(lhs_ms: i64, rhs_ms: i64) -> i64 {
     delta.clamp(i64::MIN as i128, i64::MAX as i128) as i64
 }
 
-fn reconnect_delay(attempt: u32) -> Duration {
+pub(crate) fn reconnect_delay(attempt: u32) -> Duration {
     let exponent = attempt.min(6);
     let base_ms = 200_u64.saturating_mul(1_u64 << exponent);
     let jitter_ms = (now_unix_ms().unsigned_abs() % 250).min(249);
     Duration::from_millis((base_ms + jitter_ms).min(5_000))
 }
 
-fn now_unix_ms() -> i64 {
+pub fn now_unix_ms() -> i64 {
     match SystemTime::now().duration_since(UNIX_EPOCH) {
         Ok(duration) => duration.as_millis().min(i64::MAX as u128) as i64,
         Err(_) => 0,
@@ -1751,6 +2688,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn replay_continues_across_recording_gaps() {
+        let config = crate::market::types::StartMarketStreamArgs::default()
+            .normalize()
+            .expect("default config");
+        let mut state = ConflatedMarketState::default();
+        let first = sample_trade(10, 60_000, 1_000.0, 1.0, false);
+        let after_gap = sample_trade(40, 3_600_000, 1_010.0, 1.0, true);
+        let stale = sample_trade(39, 3_600_100, 1_020.0, 1.0, true);
+
+        apply_replayed_trade(&mut state, &first, &config);
+        assert_eq!(
+            apply_replayed_trade(&mut state, &after_gap, &config),
+            TradeApplyOutcome::Applied {
+                eligible_for_ui: true
+            }
+        );
+        assert_eq!(state.last_agg_id, Some(40));
+        assert_eq!(state.last_latency_ms, Some(0));
+        assert!(matches!(
+            apply_replayed_trade(&mut state, &stale, &config),
+            TradeApplyOutcome::Stale { .. }
+        ));
+    }
+
     #[test]
     fn filters_noise_by_notional_without_losing_state() {
         let mut state = ConflatedMarketState::default();
@@ -1845,4 +2807,374 @@ mod tests {
         assert_eq!(state.last_price, Some(500.0));
         assert!(state.last_candle.is_some());
     }
+
+    #[test]
+    fn snapshots_history_and_live_buckets_for_reload() {
+        let mut state = ConflatedMarketState::default();
+        let history: Vec<UiCandle> = [0, 60_000, 120_000]
+            .into_iter()
+            .map(|t| UiCandle::from_trade(t, 100.0, 1.0))
+            .collect();
+        apply_history_snapshot(&mut state, &history);
+
+        let trade = sample_trade(9, 180_100, 105.0, 0.5, false);
+        let _ = apply_trade_event_for_test(&mut state, &trade, 1.0, MarketTimeframe::M1, 180_100);
+
+        let snapshot = snapshot_chart_state(&state, 2);
+        assert_eq!(snapshot.last_agg_id, Some(9));
+        assert_eq!(snapshot.last_price, Some(105.0));
+        assert_eq!(
+            snapshot.last_candle.as_ref().map(|candle| candle.t),
+            Some(180_000)
+        );
+        let times: Vec<i64> = snapshot.candles.iter().map(|candle| candle.t).collect();
+        assert_eq!(times, vec![60_000, 120_000]);
+
+        let repaired = UiCandle {
+            v: 3.0,
+            ..history[1].clone()
+        };
+        let _ = apply_kline_repair(&mut state, std::slice::from_ref(&repaired), &[]);
+        let snapshot = snapshot_chart_state(&state, 10);
+        assert_eq!(snapshot.candles.len(), 3);
+        assert_eq!(snapshot.candles[1], repaired);
+    }
+
+    #[test]
+    fn repairs_candles_missed_during_outage() {
+        let mut state = ConflatedMarketState::default();
+        let before_outage = sample_trade(1, 60_100, 100.0, 0.2, false);
+        let _ = apply_trade_event_for_test(
+            &mut state,
+            &before_outage,
+            1.0,
+            MarketTimeframe::M1,
+            60_100,
+        );
+        let _ = drain_ui_candle(&mut state);
+
+        let fetched = vec![
+            UiCandle {
+                t: 60_000,
+                o: 100.0,
+                h: 104.0,
+                l: 99.0,
+                c: 103.0,
+                v: 2.5,
+            },
+            UiCandle {
+                t: 120_000,
+                o: 103.0,
+                h: 105.0,
+                l: 102.0,
+                c: 104.0,
+                v: 1.0,
+            },
+        ];
+        let (changed, changed_delta) = apply_kline_repair(&mut state, &fetched, &[]);
+        assert_eq!(changed, fetched);
+        assert!(changed_delta.is_empty());
+        assert_eq!(
+            state.last_candle.as_ref().map(|candle| candle.t),
+            Some(120_000)
+        );
+        assert_eq!(drain_ui_candle(&mut state), Some(fetched[1].clone()));
+
+        let (unchanged, _) = apply_kline_repair(&mut state, &fetched[1..], &[]);
+        assert!(unchanged.is_empty());
+        assert!(drain_ui_candle(&mut state).is_none());
+    }
+
+    #[test]
+    fn outage_repair_leaves_the_live_bucket_to_buffered_trades() {
+        let mut state = ConflatedMarketState::default();
+        let before_outage = sample_trade(1, 60_100, 100.0, 0.2, false);
+        let _ = apply_trade_event_for_test(
+            &mut state,
+            &before_outage,
+            1.0,
+            MarketTimeframe::M1,
+            60_100,
+        );
+
+        // The exchange kline of the live bucket already counts the buffered trade.
+        let first_live = sample_trade(2, 120_500, 104.0, 0.4, false);
+        let mut fetched = vec![
+            UiCandle {
+                t: 60_000,
+                o: 100.0,
+                h: 104.0,
+                l: 99.0,
+                c: 103.0,
+                v: 2.5,
+            },
+            UiCandle {
+                t: 120_000,
+                o: 103.0,
+                h: 105.0,
+                l: 102.0,
+                c: 104.0,
+                v: 1.0,
+            },
+        ];
+        retain_buckets_before(
+            &mut fetched,
+            candle_bucket_open_time(first_live.trade_time, MarketTimeframe::M1.duration_ms()),
+            |candle| candle.t,
+        );
+        assert_eq!(fetched.len(), 1);
+        let (changed, _) = apply_kline_repair(&mut state, &fetched, &[]);
+        assert_eq!(changed, fetched);
+
+        let _ =
+            apply_trade_event_for_test(&mut state, &first_live, 1.0, MarketTimeframe::M1, 120_500);
+        let live = state.last_candle.clone().expect("live bucket should open");
+        assert_eq!(live.t, 120_000);
+        assert!((live.v - 0.4).abs() < 1e-9);
+        let history = snapshot_chart_state(&state, 10).candles;
+        assert_eq!(history[0], fetched[0]);
+    }
+
+    #[test]
+    fn reconciles_closed_bucket_against_exchange_kline() {
+        let mut state = ConflatedMarketState::default();
+        let first = sample_trade(1, 60_100, 100.0, 0.2, false);
+        let second = sample_trade(2, 120_050, 102.0, 0.5, true);
+        let _ = apply_trade_event_for_test(&mut state, &first, 1.0, MarketTimeframe::M1, 60_100);
+        assert!(take_closed_bucket(&mut state).is_none());
+        let _ = apply_trade_event_for_test(&mut state, &second, 1.0, MarketTimeframe::M1, 120_050);
+
+        let closed = take_closed_bucket(&mut state).expect("first bucket should close");
+        assert_eq!(closed.candle.t, 60_000);
+        assert!(take_closed_bucket(&mut state).is_none());
+
+        let matching_delta = closed
+            .delta_candle
+            .clone()
+            .expect("delta bucket should close with the candle");
+        assert!(candle_divergence(&closed, &closed.candle, &matching_delta).is_none());
+
+        let exchange = UiCandle {
+            v: 0.7,
+            h: 101.0,
+            ..closed.candle.clone()
+        };
+        let divergence = candle_divergence(&closed, &exchange, &matching_delta)
+            .expect("missing volume should diverge");
+        assert!((divergence.volume_diff + 0.5).abs() < 1e-9);
+        assert!((divergence.high_diff + 1.0).abs() < 1e-9);
+        assert_eq!(divergence.close_diff, 0.0);
+
+        let live = state.last_candle.clone();
+        let _ = apply_kline_repair(
+            &mut state,
+            std::slice::from_ref(&exchange),
+            std::slice::from_ref(&matching_delta),
+        );
+        let snapshot = snapshot_chart_state(&state, 10);
+        assert_eq!(snapshot.candles, vec![exchange]);
+        assert_eq!(snapshot.last_candle, live);
+    }
+
+    #[tokio::test]
+    async fn mock_stream_runs_headless_into_a_memory_sink() {
+        use crate::db::run_migrations;
+        use crate::market::sink::MemoryEventSink;
+        use crate::market::types::StartMarketStreamArgs;
+        use crate::market::{
+            CANDLES_BOOTSTRAP_EVENT, MARKET_FRAME_UPDATE_EVENT, MARKET_STATUS_EVENT,
+        };
+        use simd_json::prelude::*;
+        use sqlx::sqlite::SqlitePoolOptions;
+
+        let db_pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("in-memory sqlite should initialize");
+        run_migrations(&db_pool)
+            .await
+            .expect("migrations should apply");
+        let config = StartMarketStreamArgs {
+            mock_mode: Some(true),
+            history_limit: Some(50),
+            ..Default::default()
+        }
+        .normalize()
+        .expect("mock config");
+        let events = Arc::new(MemoryEventSink::default());
+        let status_store = Arc::new(RwLock::new(MarketStreamStatusSnapshot::stopped(
+            config.symbol.clone(),
+            None,
+        )));
+        let webhooks = WebhookDispatcher::start(db_pool.clone()).expect("webhooks");
+        let cancel_token = CancellationToken::new();
+
+        let stream = tokio::spawn(run_market_stream(
+            events.clone(),
+            config,
+            db_pool,
+            BinanceClient::default(),
+            Arc::clone(&status_store),
+            Arc::default(),
+            Arc::default(),
+            Arc::default(),
+            None,
+            webhooks,
+            Arc::new(Mutex::new(TradeTape::default())),
+            None,
+            None,
+            None,
+            cancel_token.clone(),
+        ));
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while events.payloads(MARKET_FRAME_UPDATE_EVENT).len() < 3 && Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        cancel_token.cancel();
+        stream.await.expect("stream task");
+
+        let bootstrap = events.payloads(CANDLES_BOOTSTRAP_EVENT);
+        assert_eq!(bootstrap.len(), 1);
+        assert_eq!(
+            bootstrap[0]
+                .get("candles")
+                .and_then(|candles| candles.as_array())
+                .map(Vec::len),
+            Some(50)
+        );
+        assert!(events.payloads(MARKET_FRAME_UPDATE_EVENT).len() >= 3);
+        let statuses = events.payloads(MARKET_STATUS_EVENT);
+        assert_eq!(
+            statuses.last().and_then(|status| status.get_str("state")),
+            Some("stopped")
+        );
+        assert_eq!(
+            status_store.read().await.state,
+            MarketConnectionState::Stopped
+        );
+    }
+
+    #[tokio::test]
+    async fn live_stream_reconnects_and_resyncs_a_gap_against_the_fake_exchange() {
+        use crate::db::run_migrations;
+        use crate::market::fake_binance::{FakeBinance, FakeFrame, FakeKline};
+        use crate::market::sink::MemoryEventSink;
+        use crate::market::types::StartMarketStreamArgs;
+        use crate::market::MARKET_STATUS_EVENT;
+        use simd_json::prelude::*;
+        use sqlx::sqlite::SqlitePoolOptions;
+
+        let now_ms = now_unix_ms();
+        let minute_open = now_ms - now_ms.rem_euclid(60_000);
+        let fake = FakeBinance::start().await;
+        fake.set_server_time(now_ms);
+        fake.set_klines(
+            (1..=30)
+                .map(|age| FakeKline {
+                    candle: UiCandle {
+                        t: minute_open - age * 60_000,
+                        o: 100.0,
+                        h: 101.0,
+                        l: 99.0,
+                        c: 100.5,
+                        v: 2.0,
+                    },
+                    taker_buy_volume: 1.0,
+                })
+                .collect(),
+        );
+        fake.set_snapshot(12, 100.25);
+        let trade = |aggregate_trade_id: u64| {
+            FakeFrame::trade(&AggTradeEvent {
+                event_time: now_ms,
+                aggregate_trade_id,
+                price: 100.0,
+                quantity: 0.5,
+                trade_time: now_ms,
+                is_buyer_maker: false,
+            })
+        };
+        // The first session drops, the second skips ids 4..=9 and the third resumes after
+        // the snapshot.
+        fake.push_agg_trade_session(vec![trade(1), trade(2), trade(3), FakeFrame::Close]);
+        fake.push_agg_trade_session(vec![trade(10)]);
+        fake.push_agg_trade_session(vec![trade(13), trade(14)]);
+
+        let db_pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("in-memory sqlite should initialize");
+        run_migrations(&db_pool)
+            .await
+            .expect("migrations should apply");
+        let config = StartMarketStreamArgs {
+            symbol: Some("BTCUSDT".to_string()),
+            history_limit: Some(20),
+            ..Default::default()
+        }
+        .normalize()
+        .expect("live config");
+        let events = Arc::new(MemoryEventSink::default());
+        let status_store = Arc::new(RwLock::new(MarketStreamStatusSnapshot::stopped(
+            config.symbol.clone(),
+            None,
+        )));
+        let webhooks = WebhookDispatcher::start(db_pool.clone()).expect("webhooks");
+        let cancel_token = CancellationToken::new();
+
+        let stream = tokio::spawn(run_market_stream(
+            events.clone(),
+            config,
+            db_pool,
+            fake.client(),
+            Arc::clone(&status_store),
+            Arc::default(),
+            Arc::default(),
+            Arc::default(),
+            None,
+            webhooks,
+            Arc::new(Mutex::new(TradeTape::default())),
+            None,
+            None,
+            None,
+            cancel_token.clone(),
+        ));
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while status_store.read().await.last_agg_id != Some(14) && Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        cancel_token.cancel();
+        stream.await.expect("stream task");
+
+        assert_eq!(
+            fake.agg_trade_connections(),
+            vec!["/ws/btcusdt@aggTrade".to_string(); 3]
+        );
+        let requests = fake.requests();
+        assert!(requests
+            .iter()
+            .any(|request| request.starts_with("/api/v3/klines?symbol=BTCUSDT")));
+        assert!(requests
+            .iter()
+            .any(|request| request.starts_with("/api/v3/aggTrades?symbol=BTCUSDT")));
+        let reasons: Vec<String> = events
+            .payloads(MARKET_STATUS_EVENT)
+            .iter()
+            .filter_map(|status| status.get_str("reason").map(str::to_string))
+            .collect();
+        for expected in [
+            "aggTrade gap detected (expected 4, found 10)",
+            "snapshot resync completed",
+        ] {
+            assert!(
+                reasons.iter().any(|reason| reason == expected),
+                "{expected} in {reasons:?}"
+            );
+        }
+        let stopped = status_store.read().await.clone();
+        assert_eq!(stopped.state, MarketConnectionState::Stopped);
+        assert_eq!(stopped.last_agg_id, Some(14));
+    }
 }
@@ -0,0 +1,878 @@
+use crate::error::AppError;
+use crate::market::types::{AggTradeEvent, MarketKind};
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+pub const TRADE_BLOCK_MAGIC: [u8; 4] = *b"TRB1";
+/// Trades accepted by the recorder are on disk and fsynced within this interval.
+pub const RECORDER_FSYNC_INTERVAL_MS: u64 = 1_000;
+const TRADE_BLOCK_HEADER_LEN: usize = 48;
+const TRADE_RECORD_LEN: usize = 41;
+const INDEX_ENTRY_LEN: usize = 48;
+const MAX_TRADES_PER_BLOCK: usize = 4_096;
+const MS_PER_DAY: i64 = 86_400_000;
+const SEGMENT_EXTENSION: &str = "trades";
+const INDEX_EXTENSION: &str = "idx";
+const RECORDINGS_DIR_NAME: &str = "recordings";
+
+/// Location and key range of one compressed block inside a day segment. The `.idx` sidecar is
+/// a flat array of these and is rebuilt from the block headers whenever it is stale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TradeBlockIndexEntry {
+    pub offset: u64,
+    pub block_len: u32,
+    pub count: u32,
+    pub first_id: u64,
+    pub last_id: u64,
+    pub first_t: i64,
+    pub last_t: i64,
+}
+
+impl TradeBlockIndexEntry {
+    fn end(&self) -> u64 {
+        self.offset + u64::from(self.block_len)
+    }
+
+    fn to_bytes(self) -> [u8; INDEX_ENTRY_LEN] {
+        let mut bytes = [0_u8; INDEX_ENTRY_LEN];
+        bytes[0..8].copy_from_slice(&self.offset.to_le_bytes());
+        bytes[8..12].copy_from_slice(&self.block_len.to_le_bytes());
+        bytes[12..16].copy_from_slice(&self.count.to_le_bytes());
+        bytes[16..24].copy_from_slice(&self.first_id.to_le_bytes());
+        bytes[24..32].copy_from_slice(&self.last_id.to_le_bytes());
+        bytes[32..40].copy_from_slice(&self.first_t.to_le_bytes());
+        bytes[40..48].copy_from_slice(&self.last_t.to_le_bytes());
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Self {
+        Self {
+            offset: read_u64(bytes, 0),
+            block_len: read_u32(bytes, 8),
+            count: read_u32(bytes, 12),
+            first_id: read_u64(bytes, 16),
+            last_id: read_u64(bytes, 24),
+            first_t: read_u64(bytes, 32) as i64,
+            last_t: read_u64(bytes, 40) as i64,
+        }
+    }
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    let mut raw = [0_u8; 4];
+    raw.copy_from_slice(&bytes[offset..offset + 4]);
+    u32::from_le_bytes(raw)
+}
+
+fn read_u64(bytes: &[u8], offset: usize) -> u64 {
+    let mut raw = [0_u8; 8];
+    raw.copy_from_slice(&bytes[offset..offset + 8]);
+    u64::from_le_bytes(raw)
+}
+
+fn read_f64(bytes: &[u8], offset: usize) -> f64 {
+    f64::from_bits(read_u64(bytes, offset))
+}
+
+pub fn recordings_root(app_handle: &AppHandle) -> Result<PathBuf, AppError> {
+    Ok(recordings_root_in(&app_handle.path().app_data_dir()?))
+}
+
+pub fn recordings_root_in(data_dir: &Path) -> PathBuf {
+    data_dir.join(RECORDINGS_DIR_NAME)
+}
+
+pub fn series_dir(root: &Path, market_kind: MarketKind, symbol: &str) -> PathBuf {
+    root.join(market_kind.as_str())
+        .join(symbol.to_ascii_uppercase())
+}
+
+fn segment_path(dir: &Path, day: i64) -> PathBuf {
+    dir.join(format!("{}.{SEGMENT_EXTENSION}", utc_day_label(day)))
+}
+
+/// `YYYY-MM-DD` for a day count since the unix epoch.
+pub fn utc_day_label(day: i64) -> String {
+    let shifted = day + 719_468;
+    let era = shifted.div_euclid(146_097);
+    let day_of_era = shifted.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day_of_month = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day_of_month:02}")
+}
+
+/// Day count since the unix epoch for a `YYYY-MM-DD` label; the inverse of [`utc_day_label`].
+pub fn parse_utc_day_label(label: &str) -> Option<i64> {
+    let mut parts = label.trim().splitn(3, '-');
+    let year = parts.next()?.parse::<i64>().ok()?;
+    let month = parts.next()?.parse::<i64>().ok()?;
+    let day_of_month = parts.next()?.parse::<i64>().ok()?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day_of_month) {
+        return None;
+    }
+
+    let year = year - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month_index = if month > 2 { month - 3 } else { month + 9 };
+    let day_of_year = (153 * month_index + 2) / 5 + day_of_month - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let day = era * 146_097 + day_of_era - 719_468;
+    (utc_day_label(day) == label.trim()).then_some(day)
+}
+
+/// Day segments recorded for a series, oldest first.
+pub fn list_segments(
+    root: &Path,
+    market_kind: MarketKind,
+    symbol: &str,
+) -> Result<Vec<PathBuf>, AppError> {
+    let dir = series_dir(root, market_kind, symbol);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut segments = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|ext| ext.to_str()) == Some(SEGMENT_EXTENSION) {
+            segments.push(path);
+        }
+    }
+    segments.sort();
+    Ok(segments)
+}
+
+/// Block index for a segment, read from the sidecar when it matches the segment length.
+pub fn read_segment_index(segment: &Path) -> Result<Vec<TradeBlockIndexEntry>, AppError> {
+    let segment_len = fs::metadata(segment)?.len();
+    if let Ok(raw) = fs::read(segment.with_extension(INDEX_EXTENSION)) {
+        if raw.len() % INDEX_ENTRY_LEN == 0 {
+            let entries: Vec<TradeBlockIndexEntry> = raw
+                .chunks_exact(INDEX_ENTRY_LEN)
+                .map(TradeBlockIndexEntry::from_bytes)
+                .collect();
+            let indexed_len = entries.last().map(TradeBlockIndexEntry::end).unwrap_or(0);
+            if indexed_len == segment_len {
+                return Ok(entries);
+            }
+        }
+    }
+
+    let mut file = File::open(segment)?;
+    let (entries, _) = scan_segment(&mut file)?;
+    Ok(entries)
+}
+
+pub fn read_block(
+    segment: &Path,
+    entry: &TradeBlockIndexEntry,
+) -> Result<Vec<AggTradeEvent>, AppError> {
+    let mut file = File::open(segment)?;
+    file.seek(SeekFrom::Start(entry.offset))?;
+    let mut block = vec![0_u8; entry.block_len as usize];
+    file.read_exact(&mut block)?;
+    decode_block(&block)
+}
+
+/// First block that may contain `aggregate_trade_id`.
+pub fn find_block_by_trade_id(index: &[TradeBlockIndexEntry], aggregate_trade_id: u64) -> usize {
+    index.partition_point(|entry| entry.last_id < aggregate_trade_id)
+}
+
+/// First block holding trades at or after `trade_time`.
+pub fn find_block_by_time(index: &[TradeBlockIndexEntry], trade_time: i64) -> usize {
+    index.partition_point(|entry| entry.last_t < trade_time)
+}
+
+fn encode_block(trades: &[AggTradeEvent]) -> Result<Vec<u8>, AppError> {
+    let (Some(first), Some(last)) = (trades.first(), trades.last()) else {
+        return Err(AppError::Internal(
+            "trade block must not be empty".to_string(),
+        ));
+    };
+
+    let mut raw = Vec::with_capacity(trades.len() * TRADE_RECORD_LEN);
+    for trade in trades {
+        raw.extend_from_slice(&trade.aggregate_trade_id.to_le_bytes());
+        raw.extend_from_slice(&trade.trade_time.to_le_bytes());
+        raw.extend_from_slice(&trade.event_time.to_le_bytes());
+        raw.extend_from_slice(&trade.price.to_bits().to_le_bytes());
+        raw.extend_from_slice(&trade.quantity.to_bits().to_le_bytes());
+        raw.push(u8::from(trade.is_buyer_maker));
+    }
+    let payload = lz4_flex::compress_prepend_size(&raw);
+    let payload_len = u32::try_from(payload.len())
+        .map_err(|_| AppError::Internal("trade block is too large".to_string()))?;
+
+    let mut block = Vec::with_capacity(TRADE_BLOCK_HEADER_LEN + payload.len());
+    block.extend_from_slice(&TRADE_BLOCK_MAGIC);
+    block.extend_from_slice(&payload_len.to_le_bytes());
+    block.extend_from_slice(&(trades.len() as u32).to_le_bytes());
+    block.extend_from_slice(&crc32fast::hash(&payload).to_le_bytes());
+    block.extend_from_slice(&first.aggregate_trade_id.to_le_bytes());
+    block.extend_from_slice(&last.aggregate_trade_id.to_le_bytes());
+    block.extend_from_slice(&first.trade_time.to_le_bytes());
+    block.extend_from_slice(&last.trade_time.to_le_bytes());
+    block.extend_from_slice(&payload);
+    Ok(block)
+}
+
+fn decode_block(block: &[u8]) -> Result<Vec<AggTradeEvent>, AppError> {
+    if block.len() < TRADE_BLOCK_HEADER_LEN || block[0..4] != TRADE_BLOCK_MAGIC {
+        return Err(AppError::Corrupt(
+            "trade block header is invalid".to_string(),
+        ));
+    }
+    let count = read_u32(block, 8) as usize;
+    let payload = &block[TRADE_BLOCK_HEADER_LEN..];
+    if crc32fast::hash(payload) != read_u32(block, 12) {
+        return Err(AppError::Corrupt(
+            "trade block checksum mismatch".to_string(),
+        ));
+    }
+
+    let raw = lz4_flex::decompress_size_prepended(payload)
+        .map_err(|error| AppError::Corrupt(format!("trade block decompression failed: {error}")))?;
+    if raw.len() != count * TRADE_RECORD_LEN {
+        return Err(AppError::Corrupt(
+            "trade block length does not match its count".to_string(),
+        ));
+    }
+
+    Ok(raw
+        .chunks_exact(TRADE_RECORD_LEN)
+        .map(|record| AggTradeEvent {
+            aggregate_trade_id: read_u64(record, 0),
+            trade_time: read_u64(record, 8) as i64,
+            event_time: read_u64(record, 16) as i64,
+            price: read_f64(record, 24),
+            quantity: read_f64(record, 32),
+            is_buyer_maker: record[40] != 0,
+        })
+        .collect())
+}
+
+/// Walks block headers from the start of the segment and stops at the first torn or corrupt
+/// block. Returns the valid blocks and the byte length they cover.
+fn scan_segment(file: &mut File) -> Result<(Vec<TradeBlockIndexEntry>, u64), AppError> {
+    let file_len = file.metadata()?.len();
+    let mut entries = Vec::new();
+    let mut offset = 0_u64;
+    let mut header = [0_u8; TRADE_BLOCK_HEADER_LEN];
+
+    while offset + TRADE_BLOCK_HEADER_LEN as u64 <= file_len {
+        file.seek(SeekFrom::Start(offset))?;
+        file.read_exact(&mut header)?;
+        if header[0..4] != TRADE_BLOCK_MAGIC {
+            break;
+        }
+        let payload_len = read_u32(&header, 4);
+        let block_len = TRADE_BLOCK_HEADER_LEN as u64 + u64::from(payload_len);
+        if offset + block_len > file_len {
+            break;
+        }
+        let mut payload = vec![0_u8; payload_len as usize];
+        file.read_exact(&mut payload)?;
+        if crc32fast::hash(&payload) != read_u32(&header, 12) {
+            break;
+        }
+
+        entries.push(TradeBlockIndexEntry {
+            offset,
+            block_len: block_len as u32,
+            count: read_u32(&header, 8),
+            first_id: read_u64(&header, 16),
+            last_id: read_u64(&header, 24),
+            first_t: read_u64(&header, 32) as i64,
+            last_t: read_u64(&header, 40) as i64,
+        });
+        offset += block_len;
+    }
+
+    Ok((entries, offset))
+}
+
+/// Append handle for one UTC day. Opening truncates a torn tail left by a crash and rewrites
+/// the index sidecar from the surviving blocks.
+struct SegmentWriter {
+    day: i64,
+    file: File,
+    index_file: File,
+    len: u64,
+    sync_each_block: bool,
+}
+
+impl SegmentWriter {
+    fn open(dir: &Path, day: i64) -> Result<Self, AppError> {
+        Self::open_at(&segment_path(dir, day), day, true)
+    }
+
+    fn open_at(path: &Path, day: i64, sync_each_block: bool) -> Result<Self, AppError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+
+        let (entries, valid_len) = scan_segment(&mut file)?;
+        if valid_len < file.metadata()?.len() {
+            file.set_len(valid_len)?;
+            file.sync_all()?;
+        }
+        file.seek(SeekFrom::Start(valid_len))?;
+
+        let mut index_file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path.with_extension(INDEX_EXTENSION))?;
+        for entry in &entries {
+            index_file.write_all(&entry.to_bytes())?;
+        }
+
+        Ok(Self {
+            day,
+            file,
+            index_file,
+            len: valid_len,
+            sync_each_block,
+        })
+    }
+
+    fn append(&mut self, trades: &[AggTradeEvent]) -> Result<(), AppError> {
+        let block = encode_block(trades)?;
+        self.file.write_all(&block)?;
+        if self.sync_each_block {
+            self.file.sync_data()?;
+        }
+
+        let entry = TradeBlockIndexEntry {
+            offset: self.len,
+            block_len: block.len() as u32,
+            count: trades.len() as u32,
+            first_id: trades[0].aggregate_trade_id,
+            last_id: trades[trades.len() - 1].aggregate_trade_id,
+            first_t: trades[0].trade_time,
+            last_t: trades[trades.len() - 1].trade_time,
+        };
+        self.index_file.write_all(&entry.to_bytes())?;
+        self.len = entry.end();
+        Ok(())
+    }
+}
+
+const IMPORT_STAGING_DIR_NAME: &str = ".import";
+
+/// Writes archived trades into day segments. Each imported day is staged next to the series
+/// and then replaces the existing segment, keeping any trades the old segment held after the
+/// archive's last id so a day recorded live past the archive cut-off is not lost.
+pub struct SegmentImporter {
+    dir: PathBuf,
+    staging_dir: PathBuf,
+    writer: Option<SegmentWriter>,
+    last_id: Option<u64>,
+    pending: Vec<AggTradeEvent>,
+}
+
+impl SegmentImporter {
+    pub fn new(root: &Path, market_kind: MarketKind, symbol: &str) -> Result<Self, AppError> {
+        let dir = series_dir(root, market_kind, symbol);
+        let staging_dir = dir.join(IMPORT_STAGING_DIR_NAME);
+        fs::create_dir_all(&staging_dir)?;
+        Ok(Self {
+            dir,
+            staging_dir,
+            writer: None,
+            last_id: None,
+            pending: Vec::with_capacity(MAX_TRADES_PER_BLOCK),
+        })
+    }
+
+    /// Trades must arrive in aggregate id order, as they do in the archives.
+    pub fn push(&mut self, trade: AggTradeEvent) -> Result<(), AppError> {
+        if self
+            .last_id
+            .is_some_and(|last_id| trade.aggregate_trade_id <= last_id)
+        {
+            return Err(AppError::InvalidArgument(format!(
+                "archived trade {} is out of order",
+                trade.aggregate_trade_id
+            )));
+        }
+
+        let day = trade.trade_time.div_euclid(MS_PER_DAY);
+        if self.writer.as_ref().is_some_and(|writer| writer.day != day) {
+            self.finish_day()?;
+        }
+        if self.writer.is_none() {
+            let staged = segment_path(&self.staging_dir, day);
+            let _ = fs::remove_file(&staged);
+            self.writer = Some(SegmentWriter::open_at(&staged, day, false)?);
+        }
+
+        self.last_id = Some(trade.aggregate_trade_id);
+        self.pending.push(trade);
+        if self.pending.len() >= MAX_TRADES_PER_BLOCK {
+            self.flush_pending()?;
+        }
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> Result<(), AppError> {
+        self.finish_day()?;
+        let _ = fs::remove_dir(&self.staging_dir);
+        Ok(())
+    }
+
+    fn flush_pending(&mut self) -> Result<(), AppError> {
+        if let (Some(writer), false) = (self.writer.as_mut(), self.pending.is_empty()) {
+            writer.append(&self.pending)?;
+        }
+        self.pending.clear();
+        Ok(())
+    }
+
+    fn finish_day(&mut self) -> Result<(), AppError> {
+        self.flush_pending()?;
+        let Some(mut writer) = self.writer.take() else {
+            return Ok(());
+        };
+
+        let target = segment_path(&self.dir, writer.day);
+        if target.exists() {
+            let last_id = self.last_id.unwrap_or(0);
+            for entry in read_segment_index(&target)? {
+                if entry.last_id <= last_id {
+                    continue;
+                }
+                let newer: Vec<AggTradeEvent> = read_block(&target, &entry)?
+                    .into_iter()
+                    .filter(|trade| trade.aggregate_trade_id > last_id)
+                    .collect();
+                if !newer.is_empty() {
+                    writer.append(&newer)?;
+                }
+            }
+        }
+
+        writer.file.sync_all()?;
+        writer.index_file.sync_all()?;
+        let staged = segment_path(&self.staging_dir, writer.day);
+        drop(writer);
+        fs::rename(&staged, &target)?;
+        fs::rename(
+            staged.with_extension(INDEX_EXTENSION),
+            target.with_extension(INDEX_EXTENSION),
+        )?;
+        Ok(())
+    }
+}
+
+/// Background writer for every trade applied by a live stream. Trades are buffered into
+/// compressed blocks and fsynced at least every `RECORDER_FSYNC_INTERVAL_MS`; dropping the
+/// recorder flushes what is left.
+pub struct TradeRecorder {
+    sender: Option<mpsc::Sender<AggTradeEvent>>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl TradeRecorder {
+    pub fn start(root: &Path, market_kind: MarketKind, symbol: &str) -> Result<Self, AppError> {
+        let dir = series_dir(root, market_kind, symbol);
+        fs::create_dir_all(&dir)?;
+
+        let (sender, receiver) = mpsc::channel();
+        let worker = std::thread::Builder::new()
+            .name("trade-recorder".to_string())
+            .spawn(move || run_recorder(&dir, receiver))?;
+
+        Ok(Self {
+            sender: Some(sender),
+            worker: Some(worker),
+        })
+    }
+
+    pub fn record(&self, event: &AggTradeEvent) {
+        if let Some(sender) = &self.sender {
+            let _ = sender.send(event.clone());
+        }
+    }
+}
+
+impl Drop for TradeRecorder {
+    fn drop(&mut self) {
+        self.sender.take();
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+fn run_recorder(dir: &Path, receiver: mpsc::Receiver<AggTradeEvent>) {
+    let flush_interval = Duration::from_millis(RECORDER_FSYNC_INTERVAL_MS);
+    let mut writer: Option<SegmentWriter> = None;
+    let mut pending: Vec<AggTradeEvent> = Vec::with_capacity(MAX_TRADES_PER_BLOCK);
+    let mut flush_deadline = Instant::now() + flush_interval;
+
+    loop {
+        let timeout = flush_deadline.saturating_duration_since(Instant::now());
+        match receiver.recv_timeout(timeout) {
+            Ok(event) => {
+                let day = event.trade_time.div_euclid(MS_PER_DAY);
+                let crosses_day = pending
+                    .first()
+                    .is_some_and(|first| first.trade_time.div_euclid(MS_PER_DAY) != day);
+                if crosses_day {
+                    flush_pending(dir, &mut writer, &mut pending);
+                }
+                pending.push(event);
+                if pending.len() >= MAX_TRADES_PER_BLOCK {
+                    flush_pending(dir, &mut writer, &mut pending);
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => {
+                flush_pending(dir, &mut writer, &mut pending);
+                return;
+            }
+        }
+
+        if Instant::now() >= flush_deadline {
+            flush_pending(dir, &mut writer, &mut pending);
+            flush_deadline = Instant::now() + flush_interval;
+        }
+    }
+}
+
+fn flush_pending(dir: &Path, writer: &mut Option<SegmentWriter>, pending: &mut Vec<AggTradeEvent>) {
+    let Some(first) = pending.first() else {
+        return;
+    };
+    let day = first.trade_time.div_euclid(MS_PER_DAY);
+
+    if writer.as_ref().is_none_or(|current| current.day != day) {
+        *writer = match SegmentWriter::open(dir, day) {
+            Ok(opened) => Some(opened),
+            Err(error) => {
+                eprintln!("failed to open trade recording segment: {error}");
+                None
+            }
+        };
+    }
+    if let Some(current) = writer.as_mut() {
+        if let Err(error) = current.append(pending) {
+            eprintln!("failed to append trade recording block: {error}");
+            *writer = None;
+        }
+    }
+    pending.clear();
+}
+
+/// Reads recorded trades in order, one block at a time, across day segments.
+pub struct RecordedTradeCursor {
+    segments: Vec<PathBuf>,
+    segment_pos: usize,
+    index: Vec<TradeBlockIndexEntry>,
+    block_pos: usize,
+    buffered: VecDeque<AggTradeEvent>,
+}
+
+impl RecordedTradeCursor {
+    pub fn open(root: &Path, market_kind: MarketKind, symbol: &str) -> Result<Self, AppError> {
+        let segments = list_segments(root, market_kind, symbol)?;
+        if segments.is_empty() {
+            return Err(AppError::InvalidArgument(format!(
+                "no recorded trades for {} {}",
+                market_kind.as_str(),
+                symbol.to_ascii_uppercase()
+            )));
+        }
+
+        Ok(Self {
+            segments,
+            segment_pos: 0,
+            index: Vec::new(),
+            block_pos: 0,
+            buffered: VecDeque::new(),
+        })
+    }
+
+    /// Positions the cursor on the first trade at or after `trade_time`.
+    pub fn seek(&mut self, trade_time: i64) -> Result<(), AppError> {
+        let day_label = utc_day_label(trade_time.div_euclid(MS_PER_DAY));
+        self.segment_pos = self.segments.partition_point(|segment| {
+            segment
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .is_some_and(|stem| stem < day_label.as_str())
+        });
+        self.index.clear();
+        self.block_pos = 0;
+        self.buffered.clear();
+
+        while self.segment_pos < self.segments.len() {
+            self.index = read_segment_index(&self.segments[self.segment_pos])?;
+            self.block_pos = find_block_by_time(&self.index, trade_time);
+            if self.block_pos < self.index.len() {
+                break;
+            }
+            self.segment_pos += 1;
+        }
+
+        if self.load_next_block()? {
+            while self
+                .buffered
+                .front()
+                .is_some_and(|trade| trade.trade_time < trade_time)
+            {
+                self.buffered.pop_front();
+            }
+        }
+        Ok(())
+    }
+
+    pub fn next_trade(&mut self) -> Result<Option<AggTradeEvent>, AppError> {
+        loop {
+            if let Some(trade) = self.buffered.pop_front() {
+                return Ok(Some(trade));
+            }
+            if !self.load_next_block()? {
+                return Ok(None);
+            }
+        }
+    }
+
+    fn load_next_block(&mut self) -> Result<bool, AppError> {
+        while self.segment_pos < self.segments.len() {
+            if self.block_pos < self.index.len() {
+                let segment = &self.segments[self.segment_pos];
+                let trades = read_block(segment, &self.index[self.block_pos])?;
+                self.block_pos += 1;
+                self.buffered.extend(trades);
+                return Ok(true);
+            }
+
+            self.segment_pos += 1;
+            self.block_pos = 0;
+            self.index = match self.segments.get(self.segment_pos) {
+                Some(segment) => read_segment_index(segment)?,
+                None => Vec::new(),
+            };
+        }
+        Ok(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unique_root(name: &str) -> PathBuf {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .expect("system clock should be after unix epoch")
+            .as_nanos();
+        std::env::temp_dir().join(format!("trade-recorder-{name}-{timestamp}"))
+    }
+
+    fn trade(id: u64, trade_time: i64) -> AggTradeEvent {
+        AggTradeEvent {
+            event_time: trade_time + 1,
+            aggregate_trade_id: id,
+            price: 100.0 + id as f64 * 0.5,
+            quantity: 0.25,
+            trade_time,
+            is_buyer_maker: id.is_multiple_of(2),
+        }
+    }
+
+    #[test]
+    fn formats_utc_day_labels() {
+        assert_eq!(utc_day_label(0), "1970-01-01");
+        assert_eq!(utc_day_label(19_782), "2024-02-29");
+        assert_eq!(utc_day_label(20_088), "2024-12-31");
+        assert_eq!(parse_utc_day_label("2024-02-29"), Some(19_782));
+        assert_eq!(parse_utc_day_label("1970-01-01"), Some(0));
+        assert_eq!(parse_utc_day_label("2023-02-29"), None);
+        assert_eq!(parse_utc_day_label("yesterday"), None);
+    }
+
+    #[test]
+    fn records_day_segments_with_index() {
+        let root = unique_root("segments");
+        let day_end_ms = 20_089 * MS_PER_DAY;
+        {
+            let recorder = TradeRecorder::start(&root, MarketKind::Spot, "btcusdt")
+                .expect("recorder should start");
+            for id in 1..=5_000_u64 {
+                recorder.record(&trade(id, day_end_ms - 5_001 + id as i64));
+            }
+            recorder.record(&trade(5_001, day_end_ms + 1_000));
+        }
+
+        let segments =
+            list_segments(&root, MarketKind::Spot, "BTCUSDT").expect("segments should list");
+        assert_eq!(segments.len(), 2);
+        assert!(segments[0].ends_with("2024-12-31.trades"));
+
+        let index = read_segment_index(&segments[0]).expect("index should load");
+        assert_eq!(index.len(), 2);
+        assert_eq!(index.iter().map(|entry| entry.count).sum::<u32>(), 5_000);
+
+        let by_id = find_block_by_trade_id(&index, 4_500);
+        let trades = read_block(&segments[0], &index[by_id]).expect("block should decode");
+        let found = trades
+            .iter()
+            .find(|trade| trade.aggregate_trade_id == 4_500)
+            .expect("trade should be in the block");
+        assert_eq!(found, &trade(4_500, day_end_ms - 501));
+        assert_eq!(find_block_by_time(&index, day_end_ms - 5_000), 0);
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn truncates_torn_tail_on_reopen() {
+        let root = unique_root("torn");
+        let dir = series_dir(&root, MarketKind::FuturesUsdm, "ETHUSDT");
+        let trades: Vec<AggTradeEvent> = (1..=10).map(|id| trade(id, id as i64)).collect();
+        {
+            let mut writer = SegmentWriter::open(&dir, 0).expect("segment should open");
+            writer.append(&trades).expect("block should append");
+        }
+
+        let path = segment_path(&dir, 0);
+        let intact_len = fs::metadata(&path).expect("segment exists").len();
+        let torn_block = encode_block(&trades).expect("block should encode");
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .expect("segment should open for append");
+        file.write_all(&torn_block[..torn_block.len() / 2])
+            .expect("torn write");
+        drop(file);
+
+        let mut writer = SegmentWriter::open(&dir, 0).expect("segment should recover");
+        assert_eq!(
+            fs::metadata(&path).expect("segment exists").len(),
+            intact_len
+        );
+        writer.append(&trades).expect("append after recovery");
+
+        let index = read_segment_index(&path).expect("index should load");
+        assert_eq!(index.len(), 2);
+        assert_eq!(index[1].offset, intact_len);
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn cursor_seeks_across_segments() {
+        let root = unique_root("cursor");
+        let dir = series_dir(&root, MarketKind::Spot, "BTCUSDT");
+        {
+            let mut first_day = SegmentWriter::open(&dir, 0).expect("segment should open");
+            first_day
+                .append(
+                    &(1..=3)
+                        .map(|id| trade(id, id as i64 * 1_000))
+                        .collect::<Vec<_>>(),
+                )
+                .expect("block should append");
+            first_day
+                .append(
+                    &(4..=6)
+                        .map(|id| trade(id, id as i64 * 1_000))
+                        .collect::<Vec<_>>(),
+                )
+                .expect("block should append");
+            let mut second_day = SegmentWriter::open(&dir, 1).expect("segment should open");
+            second_day
+                .append(&[trade(7, MS_PER_DAY + 500)])
+                .expect("block should append");
+        }
+
+        let mut cursor =
+            RecordedTradeCursor::open(&root, MarketKind::Spot, "btcusdt").expect("cursor opens");
+        cursor.seek(4_500).expect("seek inside first day");
+        let ids: Vec<u64> = std::iter::from_fn(|| cursor.next_trade().expect("trade reads"))
+            .map(|trade| trade.aggregate_trade_id)
+            .collect();
+        assert_eq!(ids, vec![5, 6, 7]);
+
+        cursor.seek(7_000).expect("seek past first day");
+        assert_eq!(
+            cursor
+                .next_trade()
+                .expect("trade reads")
+                .map(|trade| trade.aggregate_trade_id),
+            Some(7)
+        );
+        cursor.seek(MS_PER_DAY * 5).expect("seek past the end");
+        assert!(cursor.next_trade().expect("end reads").is_none());
+
+        assert!(RecordedTradeCursor::open(&root, MarketKind::FuturesUsdm, "BTCUSDT").is_err());
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn importer_replaces_days_and_keeps_newer_recorded_trades() {
+        let root = unique_root("import");
+        let dir = series_dir(&root, MarketKind::Spot, "BTCUSDT");
+        {
+            let mut recorded = SegmentWriter::open(&dir, 1).expect("segment should open");
+            recorded
+                .append(&[trade(50, MS_PER_DAY + 10), trade(61, MS_PER_DAY + 900)])
+                .expect("block should append");
+        }
+
+        let mut importer =
+            SegmentImporter::new(&root, MarketKind::Spot, "BTCUSDT").expect("importer opens");
+        for id in 1..=60_u64 {
+            let trade_time = if id <= 40 {
+                id as i64
+            } else {
+                MS_PER_DAY + id as i64
+            };
+            importer.push(trade(id, trade_time)).expect("trade imports");
+        }
+        assert!(importer.push(trade(60, MS_PER_DAY + 61)).is_err());
+        importer.finish().expect("import finishes");
+
+        let segments = list_segments(&root, MarketKind::Spot, "BTCUSDT").expect("segments list");
+        assert_eq!(segments.len(), 2);
+        assert!(!dir.join(IMPORT_STAGING_DIR_NAME).exists());
+
+        let mut cursor =
+            RecordedTradeCursor::open(&root, MarketKind::Spot, "BTCUSDT").expect("cursor opens");
+        cursor.seek(0).expect("seek to start");
+        let ids: Vec<u64> = std::iter::from_fn(|| cursor.next_trade().expect("trade reads"))
+            .map(|trade| trade.aggregate_trade_id)
+            .collect();
+        let mut expected: Vec<u64> = (1..=60).collect();
+        expected.push(61);
+        assert_eq!(ids, expected);
+
+        let _ = fs::remove_dir_all(root);
+    }
+}
@@ -0,0 +1,205 @@
+use crate::error::AppError;
+use crate::market::recorder::RecordedTradeCursor;
+use crate::market::types::{
+    MarketReplayConfig, MarketReplayControlArgs, UiReplayStatus, DEFAULT_REPLAY_SPEED,
+};
+use parking_lot::Mutex;
+use std::sync::Arc;
+use tokio::sync::Notify;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReplayPlayback {
+    pub paused: bool,
+    pub speed: f64,
+}
+
+#[derive(Debug)]
+struct ReplayControlState {
+    active: bool,
+    paused: bool,
+    speed: f64,
+    pending_steps: u32,
+    seek_to: Option<i64>,
+    cursor_t: Option<i64>,
+    finished: bool,
+}
+
+impl Default for ReplayControlState {
+    fn default() -> Self {
+        Self {
+            active: false,
+            paused: false,
+            speed: DEFAULT_REPLAY_SPEED,
+            pending_steps: 0,
+            seek_to: None,
+            cursor_t: None,
+            finished: false,
+        }
+    }
+}
+
+/// Playback controls shared between the replay commands and the running replay producer.
+/// Commands only record intent; the producer picks it up on its next iteration.
+#[derive(Debug, Default)]
+pub struct ReplayControl {
+    state: Mutex<ReplayControlState>,
+    changed: Notify,
+}
+
+impl ReplayControl {
+    pub fn begin(&self, config: &MarketReplayConfig) {
+        *self.state.lock() = ReplayControlState {
+            active: true,
+            paused: config.start_paused,
+            speed: config.speed,
+            cursor_t: Some(config.from_t),
+            ..ReplayControlState::default()
+        };
+        self.changed.notify_one();
+    }
+
+    pub fn end(&self) {
+        self.state.lock().active = false;
+    }
+
+    pub fn apply(&self, args: MarketReplayControlArgs) -> Result<UiReplayStatus, AppError> {
+        {
+            let mut writable = self.state.lock();
+            if !writable.active {
+                return Err(AppError::Conflict(
+                    "no replay stream is running".to_string(),
+                ));
+            }
+
+            match args {
+                MarketReplayControlArgs::Pause => writable.paused = true,
+                MarketReplayControlArgs::Resume => writable.paused = false,
+                MarketReplayControlArgs::Step { count } => {
+                    writable.paused = true;
+                    writable.pending_steps =
+                        writable.pending_steps.saturating_add(count.unwrap_or(1));
+                }
+                MarketReplayControlArgs::Seek { t } => {
+                    writable.seek_to = Some(t);
+                    writable.pending_steps = 0;
+                }
+                MarketReplayControlArgs::Speed { speed } => writable.speed = speed,
+            }
+        }
+        self.changed.notify_one();
+        Ok(self.status())
+    }
+
+    pub fn status(&self) -> UiReplayStatus {
+        let readable = self.state.lock();
+        UiReplayStatus {
+            active: readable.active,
+            paused: readable.paused,
+            speed: readable.speed,
+            cursor_t: readable.cursor_t,
+            finished: readable.finished,
+        }
+    }
+
+    pub fn playback(&self) -> ReplayPlayback {
+        let readable = self.state.lock();
+        ReplayPlayback {
+            paused: readable.paused,
+            speed: readable.speed,
+        }
+    }
+
+    pub fn take_seek(&self) -> Option<i64> {
+        let mut writable = self.state.lock();
+        let seek_to = writable.seek_to.take()?;
+        writable.cursor_t = Some(seek_to);
+        writable.finished = false;
+        Some(seek_to)
+    }
+
+    pub fn take_step(&self) -> bool {
+        let mut writable = self.state.lock();
+        if writable.pending_steps == 0 {
+            return false;
+        }
+        writable.pending_steps -= 1;
+        true
+    }
+
+    pub fn set_cursor(&self, trade_time: i64) {
+        self.state.lock().cursor_t = Some(trade_time);
+    }
+
+    pub fn finish(&self) {
+        let mut writable = self.state.lock();
+        writable.finished = true;
+        writable.pending_steps = 0;
+    }
+
+    /// Resolves after the next control change, or immediately if one arrived since the last
+    /// wait.
+    pub async fn changed(&self) {
+        self.changed.notified().await;
+    }
+}
+
+/// Recorded trades and the controls a replay stream plays them with.
+pub struct ReplaySource {
+    pub cursor: RecordedTradeCursor,
+    pub control: Arc<ReplayControl>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replay_config(start_paused: bool) -> MarketReplayConfig {
+        MarketReplayConfig {
+            from_t: 5_000,
+            to_t: None,
+            speed: 4.0,
+            start_paused,
+        }
+    }
+
+    #[test]
+    fn rejects_controls_without_active_replay() {
+        let control = ReplayControl::default();
+        assert!(control.apply(MarketReplayControlArgs::Pause).is_err());
+
+        control.begin(&replay_config(false));
+        control.end();
+        assert!(control.apply(MarketReplayControlArgs::Resume).is_err());
+    }
+
+    #[test]
+    fn steps_pause_playback_and_seek_clears_steps() {
+        let control = ReplayControl::default();
+        control.begin(&replay_config(false));
+        assert_eq!(
+            control.playback(),
+            ReplayPlayback {
+                paused: false,
+                speed: 4.0
+            }
+        );
+
+        let status = control
+            .apply(MarketReplayControlArgs::Step { count: Some(2) })
+            .expect("step applies");
+        assert!(status.paused);
+        assert!(control.take_step());
+
+        control.finish();
+        control
+            .apply(MarketReplayControlArgs::Seek { t: 9_000 })
+            .expect("seek applies");
+        assert_eq!(control.take_seek(), Some(9_000));
+        assert_eq!(control.take_seek(), None);
+        assert!(!control.take_step());
+
+        let status = control.status();
+        assert_eq!(status.cursor_t, Some(9_000));
+        assert!(!status.finished);
+    }
+}
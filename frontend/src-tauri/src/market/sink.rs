@@ -0,0 +1,441 @@
+use crate::error::AppError;
+use crate::market::pipeline::now_unix_ms;
+use crate::market::types::{
+    MarketEventSeq, MarketPerfSnapshot, MarketStreamStatusSnapshot, UiCandle, UiCandlesBootstrap,
+    UiCandlesRepair, UiDeltaCandle, UiDeltaCandlesBootstrap, UiExportProgress,
+    UiHistoryLoadProgress, UiMarketFrameUpdate, UiReplayStatus, UiTick, UiTradeTapeAppend,
+};
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, WebviewWindow};
+
+use super::{
+    CANDLES_BOOTSTRAP_EVENT, CANDLES_REPAIR_EVENT, CANDLE_UPDATE_EVENT,
+    DELTA_CANDLES_BOOTSTRAP_EVENT, DELTA_CANDLE_UPDATE_EVENT, EXPORT_PROGRESS_EVENT,
+    HISTORY_LOAD_PROGRESS_EVENT, MARKET_FRAME_UPDATE_EVENT, MARKET_PERF_EVENT, MARKET_STATUS_EVENT,
+    PRICE_UPDATE_EVENT, REPLAY_STATUS_EVENT, TRADE_TAPE_EVENT,
+};
+
+/// One event published by the market pipeline. Serialises as the bare payload; sinks receive
+/// it as a [`SequencedEvent`] under [`MarketEvent::name`].
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(untagged)]
+pub enum MarketEvent<'a> {
+    Frame(&'a UiMarketFrameUpdate),
+    PriceUpdate(&'a UiTick),
+    CandleUpdate(&'a UiCandle),
+    DeltaCandleUpdate(&'a UiDeltaCandle),
+    TradeTape(&'a UiTradeTapeAppend),
+    Status(&'a MarketStreamStatusSnapshot),
+    Perf(&'a MarketPerfSnapshot),
+    CandlesBootstrap(&'a UiCandlesBootstrap),
+    DeltaCandlesBootstrap(&'a UiDeltaCandlesBootstrap),
+    CandlesRepair(&'a UiCandlesRepair),
+    HistoryLoadProgress(&'a UiHistoryLoadProgress),
+    ReplayStatus(&'a UiReplayStatus),
+    ExportProgress(&'a UiExportProgress),
+}
+
+impl MarketEvent<'_> {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Frame(_) => MARKET_FRAME_UPDATE_EVENT,
+            Self::PriceUpdate(_) => PRICE_UPDATE_EVENT,
+            Self::CandleUpdate(_) => CANDLE_UPDATE_EVENT,
+            Self::DeltaCandleUpdate(_) => DELTA_CANDLE_UPDATE_EVENT,
+            Self::TradeTape(_) => TRADE_TAPE_EVENT,
+            Self::Status(_) => MARKET_STATUS_EVENT,
+            Self::Perf(_) => MARKET_PERF_EVENT,
+            Self::CandlesBootstrap(_) => CANDLES_BOOTSTRAP_EVENT,
+            Self::DeltaCandlesBootstrap(_) => DELTA_CANDLES_BOOTSTRAP_EVENT,
+            Self::CandlesRepair(_) => CANDLES_REPAIR_EVENT,
+            Self::HistoryLoadProgress(_) => HISTORY_LOAD_PROGRESS_EVENT,
+            Self::ReplayStatus(_) => REPLAY_STATUS_EVENT,
+            Self::ExportProgress(_) => EXPORT_PROGRESS_EVENT,
+        }
+    }
+}
+
+/// An event together with its position in the stream that published it. Serialises as the
+/// payload with `generation` and `seq` added; events published outside a stream carry neither.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct SequencedEvent<'a> {
+    #[serde(flatten)]
+    pub event: MarketEvent<'a>,
+    #[serde(flatten)]
+    pub seq: Option<MarketEventSeq>,
+}
+
+impl SequencedEvent<'_> {
+    pub fn name(&self) -> &'static str {
+        self.event.name()
+    }
+}
+
+/// Destination for everything the market pipeline publishes, so the pipeline does not need a
+/// Tauri runtime to run.
+pub trait MarketEventSink: Send + Sync {
+    fn publish(&self, event: SequencedEvent<'_>) -> Result<(), AppError>;
+
+    fn emit(&self, event: MarketEvent<'_>) -> Result<(), AppError> {
+        self.publish(SequencedEvent { event, seq: None })
+    }
+
+    /// Whether a window that acknowledges `market_frame_update` currently receives this
+    /// sink's events.
+    fn reaches_windows(&self) -> bool {
+        false
+    }
+}
+
+impl<T: MarketEventSink + ?Sized> MarketEventSink for Arc<T> {
+    fn publish(&self, event: SequencedEvent<'_>) -> Result<(), AppError> {
+        (**self).publish(event)
+    }
+
+    fn reaches_windows(&self) -> bool {
+        (**self).reaches_windows()
+    }
+}
+
+/// Emits to a single webview window.
+impl MarketEventSink for WebviewWindow {
+    fn publish(&self, event: SequencedEvent<'_>) -> Result<(), AppError> {
+        Emitter::emit_to(self, self.label(), event.name(), event)?;
+        Ok(())
+    }
+
+    fn reaches_windows(&self) -> bool {
+        true
+    }
+}
+
+/// Broadcasts to every window of the app.
+impl MarketEventSink for AppHandle {
+    fn publish(&self, event: SequencedEvent<'_>) -> Result<(), AppError> {
+        Emitter::emit(self, event.name(), event)?;
+        Ok(())
+    }
+
+    fn reaches_windows(&self) -> bool {
+        true
+    }
+}
+
+/// A generation id newer than every one handed out before, in this process or an earlier one.
+fn next_generation() -> u64 {
+    static LAST_GENERATION: AtomicU64 = AtomicU64::new(0);
+    let now = now_unix_ms().max(0) as u64;
+    let previous = LAST_GENERATION
+        .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |last| {
+            Some(last.saturating_add(1).max(now))
+        })
+        .unwrap_or_default();
+    previous.saturating_add(1).max(now)
+}
+
+/// Where a stream is in its event sequence. Kept per stream id so `market_stream_snapshot`
+/// can report it while the stream runs and after it stops.
+#[derive(Debug, Default)]
+pub struct EventSequence {
+    last: Mutex<Option<MarketEventSeq>>,
+}
+
+impl EventSequence {
+    /// Starts a new generation; its first event gets `seq` 1.
+    pub fn restart(&self) {
+        *self.last.lock() = Some(MarketEventSeq {
+            generation: next_generation(),
+            seq: 0,
+        });
+    }
+
+    /// The most recently stamped event, or `seq` 0 when the generation has not published yet.
+    pub fn last(&self) -> Option<MarketEventSeq> {
+        *self.last.lock()
+    }
+}
+
+/// Stamps every event with the next [`MarketEventSeq`] of its stream before forwarding it.
+/// Stamping and forwarding happen under one lock, so sinks see `seq` strictly in order.
+pub struct SequencedEventSink {
+    inner: Arc<dyn MarketEventSink>,
+    sequence: Arc<EventSequence>,
+}
+
+impl SequencedEventSink {
+    pub fn new(inner: Arc<dyn MarketEventSink>, sequence: Arc<EventSequence>) -> Self {
+        Self { inner, sequence }
+    }
+
+    /// Publishes `event` and returns the position it was stamped with.
+    pub fn emit_sequenced(&self, event: MarketEvent<'_>) -> Result<MarketEventSeq, AppError> {
+        let mut last = self.sequence.last.lock();
+        let seq = match *last {
+            Some(previous) => MarketEventSeq {
+                seq: previous.seq + 1,
+                ..previous
+            },
+            None => MarketEventSeq {
+                generation: next_generation(),
+                seq: 1,
+            },
+        };
+        *last = Some(seq);
+        self.inner.publish(SequencedEvent {
+            event,
+            seq: Some(seq),
+        })?;
+        Ok(seq)
+    }
+}
+
+impl MarketEventSink for SequencedEventSink {
+    fn publish(&self, event: SequencedEvent<'_>) -> Result<(), AppError> {
+        self.emit_sequenced(event.event).map(|_| ())
+    }
+
+    fn reaches_windows(&self) -> bool {
+        self.inner.reaches_windows()
+    }
+}
+
+/// Emits every event to each sink in turn. All sinks are tried; the first failure is
+/// returned.
+pub struct FanoutEventSink {
+    sinks: Vec<Arc<dyn MarketEventSink>>,
+}
+
+impl FanoutEventSink {
+    pub fn new(sinks: Vec<Arc<dyn MarketEventSink>>) -> Self {
+        Self { sinks }
+    }
+}
+
+impl MarketEventSink for FanoutEventSink {
+    fn publish(&self, event: SequencedEvent<'_>) -> Result<(), AppError> {
+        let mut outcome = Ok(());
+        for sink in &self.sinks {
+            if let Err(error) = sink.publish(event) {
+                if outcome.is_ok() {
+                    outcome = Err(error);
+                }
+            }
+        }
+        outcome
+    }
+
+    fn reaches_windows(&self) -> bool {
+        self.sinks.iter().any(|sink| sink.reaches_windows())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RecordedMarketEvent {
+    pub name: &'static str,
+    pub payload: simd_json::OwnedValue,
+}
+
+/// Keeps every event in memory, for tests and embedders that inspect the stream afterwards.
+#[derive(Debug, Default)]
+pub struct MemoryEventSink {
+    events: Mutex<Vec<RecordedMarketEvent>>,
+}
+
+impl MemoryEventSink {
+    pub fn events(&self) -> Vec<RecordedMarketEvent> {
+        self.events.lock().clone()
+    }
+
+    /// Payloads of every event emitted under `name`, oldest first.
+    pub fn payloads(&self, name: &str) -> Vec<simd_json::OwnedValue> {
+        self.events
+            .lock()
+            .iter()
+            .filter(|event| event.name == name)
+            .map(|event| event.payload.clone())
+            .collect()
+    }
+
+    pub fn clear(&self) {
+        self.events.lock().clear();
+    }
+}
+
+impl MarketEventSink for MemoryEventSink {
+    fn publish(&self, event: SequencedEvent<'_>) -> Result<(), AppError> {
+        let payload = simd_json::serde::to_owned_value(event)?;
+        self.events.lock().push(RecordedMarketEvent {
+            name: event.name(),
+            payload,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct EventLogLine<'a> {
+    event: &'static str,
+    written_at_ms: i64,
+    payload: SequencedEvent<'a>,
+}
+
+/// Appends events as JSON lines (`{"event", "writtenAtMs", "payload"}`). Output is buffered;
+/// status events flush so a tailing reader sees the stream state promptly.
+pub struct FileEventSink {
+    writer: Mutex<BufWriter<File>>,
+}
+
+impl FileEventSink {
+    pub fn create(path: &Path) -> Result<Self, AppError> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = File::options().create(true).append(true).open(path)?;
+        Ok(Self {
+            writer: Mutex::new(BufWriter::new(file)),
+        })
+    }
+
+    pub fn flush(&self) -> Result<(), AppError> {
+        self.writer.lock().flush()?;
+        Ok(())
+    }
+}
+
+impl MarketEventSink for FileEventSink {
+    fn publish(&self, event: SequencedEvent<'_>) -> Result<(), AppError> {
+        let mut line = simd_json::serde::to_vec(&EventLogLine {
+            event: event.name(),
+            written_at_ms: now_unix_ms(),
+            payload: event,
+        })?;
+        line.push(b'\n');
+
+        let mut writer = self.writer.lock();
+        writer.write_all(&line)?;
+        if matches!(event.event, MarketEvent::Status(_)) {
+            writer.flush()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use simd_json::prelude::*;
+
+    fn tick() -> UiTick {
+        UiTick {
+            t: 1_000,
+            p: 100.5,
+            v: 2.0,
+            d: 1,
+        }
+    }
+
+    #[test]
+    fn memory_sink_records_payloads_by_event_name() {
+        let sink = MemoryEventSink::default();
+        let tick = tick();
+        sink.emit(MarketEvent::PriceUpdate(&tick)).expect("emit");
+
+        let events = sink.events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].name, PRICE_UPDATE_EVENT);
+        let payloads = sink.payloads(PRICE_UPDATE_EVENT);
+        assert_eq!(
+            payloads[0].get("p").and_then(|value| value.as_f64()),
+            Some(100.5)
+        );
+        assert!(sink.payloads(MARKET_STATUS_EVENT).is_empty());
+    }
+
+    #[test]
+    fn sequenced_sink_stamps_events_in_order_per_generation() {
+        let memory = Arc::new(MemoryEventSink::default());
+        let sequence = Arc::new(EventSequence::default());
+        sequence.restart();
+        let first_generation = sequence.last().expect("restarted").generation;
+        let sink = SequencedEventSink::new(memory.clone(), Arc::clone(&sequence));
+
+        let tick = tick();
+        sink.emit(MarketEvent::PriceUpdate(&tick)).expect("emit");
+        sink.emit(MarketEvent::PriceUpdate(&tick)).expect("emit");
+        let payloads = memory.payloads(PRICE_UPDATE_EVENT);
+        let seqs: Vec<Option<u64>> = payloads
+            .iter()
+            .map(|payload| payload.get("seq").and_then(|value| value.as_u64()))
+            .collect();
+        assert_eq!(seqs, vec![Some(1), Some(2)]);
+        assert_eq!(
+            payloads[1]
+                .get("generation")
+                .and_then(|value| value.as_u64()),
+            Some(first_generation)
+        );
+        assert_eq!(
+            payloads[1].get("p").and_then(|value| value.as_f64()),
+            Some(100.5)
+        );
+
+        sequence.restart();
+        let restarted = sequence.last().expect("restarted");
+        assert!(restarted.generation > first_generation);
+        assert_eq!(restarted.seq, 0);
+        sink.emit(MarketEvent::PriceUpdate(&tick)).expect("emit");
+        assert_eq!(
+            sequence.last(),
+            Some(MarketEventSeq {
+                generation: restarted.generation,
+                seq: 1,
+            })
+        );
+
+        memory.emit(MarketEvent::PriceUpdate(&tick)).expect("emit");
+        let unsequenced = memory.payloads(PRICE_UPDATE_EVENT).pop().expect("payload");
+        assert!(unsequenced.get("seq").is_none());
+    }
+
+    #[test]
+    fn file_sink_writes_json_lines() {
+        let dir = std::env::temp_dir().join(format!(
+            "market-event-sink-{}-{}",
+            std::process::id(),
+            now_unix_ms()
+        ));
+        let path = dir.join("events.jsonl");
+        let tick = tick();
+        {
+            let sink = FileEventSink::create(&path).expect("create");
+            sink.emit(MarketEvent::PriceUpdate(&tick)).expect("emit");
+            sink.emit(MarketEvent::PriceUpdate(&tick)).expect("emit");
+            sink.flush().expect("flush");
+        }
+
+        let contents = std::fs::read_to_string(&path).expect("read log");
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 2);
+        let mut first = lines[0].as_bytes().to_vec();
+        let value = simd_json::to_owned_value(&mut first).expect("json line");
+        assert_eq!(
+            value.get("event").and_then(|v| v.as_str()),
+            Some(PRICE_UPDATE_EVENT)
+        );
+        assert_eq!(
+            value
+                .get("payload")
+                .and_then(|payload| payload.get("t"))
+                .and_then(|v| v.as_i64()),
+            Some(1_000)
+        );
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
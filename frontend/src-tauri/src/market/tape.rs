@@ -0,0 +1,193 @@
+use crate::market::types::{
+    AggTradeEvent, MarketKind, MarketTradeSide, TradesRecentQuery, UiTapeTrade, UiTradeTapeAppend,
+    UiTradesSnapshot, DEFAULT_SYMBOL, TRADE_TAPE_CAPACITY,
+};
+use std::collections::VecDeque;
+
+/// Bounded time & sales buffer for the active stream. Every applied aggTrade is kept in
+/// `recent` for snapshot queries and in `pending` until the consumer drains it into the next
+/// tape event, so the UI sees every print even though candles are conflated.
+#[derive(Debug)]
+pub struct TradeTape {
+    market_kind: MarketKind,
+    symbol: String,
+    capacity: usize,
+    recent: VecDeque<UiTapeTrade>,
+    pending: VecDeque<UiTapeTrade>,
+    pending_dropped: u64,
+}
+
+impl Default for TradeTape {
+    fn default() -> Self {
+        Self::with_capacity(TRADE_TAPE_CAPACITY)
+    }
+}
+
+impl TradeTape {
+    pub fn with_capacity(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            market_kind: MarketKind::default(),
+            symbol: DEFAULT_SYMBOL.to_string(),
+            capacity,
+            recent: VecDeque::with_capacity(capacity),
+            pending: VecDeque::new(),
+            pending_dropped: 0,
+        }
+    }
+
+    pub fn reset(&mut self, market_kind: MarketKind, symbol: &str) {
+        self.market_kind = market_kind;
+        self.symbol = symbol.to_string();
+        self.recent.clear();
+        self.pending.clear();
+        self.pending_dropped = 0;
+    }
+
+    pub fn push(&mut self, event: &AggTradeEvent) {
+        let trade = UiTapeTrade {
+            id: event.aggregate_trade_id,
+            t: event.trade_time,
+            p: event.price,
+            q: event.quantity,
+            side: MarketTradeSide::from_is_buyer_maker(event.is_buyer_maker),
+        };
+
+        if self.recent.len() == self.capacity {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(trade.clone());
+
+        if self.pending.len() == self.capacity {
+            self.pending.pop_front();
+            self.pending_dropped = self.pending_dropped.saturating_add(1);
+        }
+        self.pending.push_back(trade);
+    }
+
+    pub fn drain_append(&mut self) -> Option<UiTradeTapeAppend> {
+        if self.pending.is_empty() {
+            return None;
+        }
+
+        let dropped = std::mem::take(&mut self.pending_dropped);
+        Some(UiTradeTapeAppend {
+            market_kind: self.market_kind,
+            symbol: self.symbol.clone(),
+            trades: self.pending.drain(..).collect(),
+            dropped,
+        })
+    }
+
+    /// Most recent trades matching the query, oldest first.
+    pub fn recent(&self, query: &TradesRecentQuery) -> UiTradesSnapshot {
+        let mut trades: Vec<UiTapeTrade> = self
+            .recent
+            .iter()
+            .rev()
+            .filter(|trade| query.side.is_none_or(|side| trade.side == side))
+            .filter(|trade| trade.notional() >= query.min_notional)
+            .take(query.limit)
+            .cloned()
+            .collect();
+        trades.reverse();
+
+        UiTradesSnapshot {
+            market_kind: self.market_kind,
+            symbol: self.symbol.clone(),
+            trades,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::market::types::MarketTradesRecentArgs;
+
+    fn trade(id: u64, price: f64, quantity: f64, is_buyer_maker: bool) -> AggTradeEvent {
+        AggTradeEvent {
+            event_time: id as i64,
+            aggregate_trade_id: id,
+            price,
+            quantity,
+            trade_time: id as i64,
+            is_buyer_maker,
+        }
+    }
+
+    #[test]
+    fn keeps_bounded_history_and_filters_queries() {
+        let mut tape = TradeTape::with_capacity(3);
+        tape.reset(MarketKind::FuturesUsdm, "ETHUSDT");
+        tape.push(&trade(1, 100.0, 1.0, false));
+        tape.push(&trade(2, 100.0, 5.0, true));
+        tape.push(&trade(3, 100.0, 0.5, false));
+        tape.push(&trade(4, 100.0, 3.0, false));
+
+        let all = tape.recent(
+            &MarketTradesRecentArgs::default()
+                .normalize()
+                .expect("defaults should normalize"),
+        );
+        assert_eq!(all.symbol, "ETHUSDT");
+        assert_eq!(
+            all.trades.iter().map(|trade| trade.id).collect::<Vec<_>>(),
+            vec![2, 3, 4]
+        );
+
+        let large_buys = tape.recent(
+            &MarketTradesRecentArgs {
+                stream_id: None,
+                limit: Some(10),
+                min_notional: Some(100.0),
+                side: Some(MarketTradeSide::Buy),
+            }
+            .normalize()
+            .expect("query should normalize"),
+        );
+        assert_eq!(
+            large_buys
+                .trades
+                .iter()
+                .map(|trade| trade.id)
+                .collect::<Vec<_>>(),
+            vec![4]
+        );
+    }
+
+    #[test]
+    fn drains_every_print_between_frames() {
+        let mut tape = TradeTape::with_capacity(2);
+        tape.push(&trade(1, 10.0, 1.0, false));
+        tape.push(&trade(2, 10.0, 1.0, true));
+
+        let append = tape.drain_append().expect("pending trades should drain");
+        assert_eq!(append.trades.len(), 2);
+        assert_eq!(append.trades[1].side, MarketTradeSide::Sell);
+        assert_eq!(append.dropped, 0);
+        assert!(tape.drain_append().is_none());
+
+        tape.push(&trade(3, 10.0, 1.0, false));
+        tape.push(&trade(4, 10.0, 1.0, false));
+        tape.push(&trade(5, 10.0, 1.0, false));
+        let overflowed = tape.drain_append().expect("pending trades should drain");
+        assert_eq!(overflowed.trades[0].id, 4);
+        assert_eq!(overflowed.dropped, 1);
+    }
+
+    #[test]
+    fn rejects_invalid_trade_queries() {
+        let zero_limit = MarketTradesRecentArgs {
+            limit: Some(0),
+            ..MarketTradesRecentArgs::default()
+        };
+        assert!(zero_limit.normalize().is_err());
+
+        let negative_notional = MarketTradesRecentArgs {
+            min_notional: Some(-1.0),
+            ..MarketTradesRecentArgs::default()
+        };
+        assert!(negative_notional.normalize().is_err());
+    }
+}
@@ -1,7 +1,14 @@
 use crate::error::AppError;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 
 pub const DEFAULT_SYMBOL: &str = "BTCUSDT";
+pub const DEFAULT_MARKET_STREAM_ID: &str = "main";
+pub const MAX_MARKET_STREAM_ID_LEN: usize = 64;
+pub const CHART_WINDOW_LABEL_PREFIX: &str = "chart-";
+pub const MAX_WINDOW_SUBSCRIPTIONS: usize = 16;
+pub const MAX_WINDOW_TITLE_LEN: usize = 120;
 pub const DEFAULT_MIN_NOTIONAL_USDT: f64 = 100.0;
 pub const DEFAULT_EMIT_INTERVAL_MS: u64 = 16;
 pub const DEFAULT_MOCK_MODE: bool = false;
@@ -20,6 +27,41 @@ pub const MAX_CLOCK_SYNC_INTERVAL_MS: u64 = 300_000;
 pub const MIN_HISTORY_LIMIT: u32 = 1;
 pub const MAX_HISTORY_LIMIT: u32 = 2_000_000;
 pub const MAX_DRAWING_LABEL_LEN: usize = 120;
+pub const DEFAULT_ALERT_COOLDOWN_MS: i64 = 60_000;
+pub const MAX_ALERT_COOLDOWN_MS: i64 = 86_400_000;
+pub const MIN_ALERT_WINDOW_MS: i64 = 1_000;
+pub const MAX_ALERT_WINDOW_MS: i64 = 86_400_000;
+pub const DEFAULT_ALERT_TRIGGERS_LIMIT: u32 = 100;
+pub const MAX_ALERT_TRIGGERS_LIMIT: u32 = 5_000;
+pub const MAX_WEBHOOK_HEADERS: usize = 32;
+pub const MAX_WEBHOOK_TEMPLATE_LEN: usize = 16_384;
+pub const TRADE_TAPE_CAPACITY: usize = 5_000;
+pub const DEFAULT_TRADES_RECENT_LIMIT: u32 = 200;
+pub const DEFAULT_HISTORY_PAGE_LIMIT: u16 = 500;
+pub const MAX_HISTORY_PAGE_LIMIT: u16 = 1_000;
+pub const DEFAULT_REPLAY_SPEED: f64 = 1.0;
+pub const MAX_REPLAY_SPEED: f64 = 1_000.0;
+pub const MAX_IMPORT_ARCHIVES: usize = 1_000;
+pub const DEFAULT_MOCK_SEED: u64 = 1;
+pub const DEFAULT_MOCK_START_PRICE: f64 = 100_000.0;
+pub const DEFAULT_MOCK_TRADES_PER_SECOND: f64 = 250.0;
+pub const MAX_MOCK_TRADES_PER_SECOND: f64 = 20_000.0;
+pub const DEFAULT_MOCK_RANDOM_WALK_VOLATILITY: f64 = 10.0;
+pub const DEFAULT_MOCK_GBM_VOLATILITY: f64 = 0.000_1;
+pub const DEFAULT_MOCK_TRADE_SIZE_MEAN: f64 = 0.14;
+pub const MAX_MOCK_EPISODES_PER_MINUTE: f64 = 600.0;
+pub const MAX_MOCK_EPISODE_DURATION_MS: u64 = 3_600_000;
+pub const MAX_MOCK_EPISODE_MULTIPLIER: f64 = 1_000.0;
+pub const MAX_MOCK_FAULT_LATENCY_MS: u64 = 60_000;
+pub const MAX_MOCK_CLOCK_SKEW_MS: i64 = 3_600_000;
+pub const DEFAULT_MARKET_API_PORT: u16 = 17_321;
+pub const MIN_MARKET_API_PORT: u16 = 1_024;
+pub const DEFAULT_MARKET_API_HISTORY_LIMIT: u32 = 1_000;
+pub const MAX_MARKET_API_HISTORY_LIMIT: u32 = 10_000;
+pub const MAX_SNAPSHOT_HISTORY_LIMIT: u32 = 50_000;
+// What `normalize_stream_id` and `normalize_color` accept, for the generated TS validation.
+const STREAM_ID_PATTERN: &str = "^[A-Za-z0-9_-]{1,64}$";
+const DRAWING_COLOR_PATTERN: &str = "^#[0-9A-Fa-f]{6}$";
 
 const SUPPORTED_DRAWING_TYPES: [&str; 5] = [
     "trendLine",
@@ -28,8 +70,9 @@ const SUPPORTED_DRAWING_TYPES: [&str; 5] = [
     "fibRetracement",
     "fibExtension",
 ];
+pub const ALERTABLE_DRAWING_TYPES: [&str; 2] = ["horizontalLine", "trendLine"];
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Default)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq, Hash, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum MarketKind {
     #[default]
@@ -56,7 +99,7 @@ impl MarketKind {
     }
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum MarketConnectionState {
     Connecting,
@@ -67,7 +110,7 @@ pub enum MarketConnectionState {
     Error,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq, Hash)]
 pub enum MarketTimeframe {
     #[serde(rename = "1m")]
     M1,
@@ -126,18 +169,19 @@ impl MarketTimeframe {
     }
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum MarketStartupMode {
     LiveFirst,
     HistoryFirst,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct MarketStreamStatusSnapshot {
     pub state: MarketConnectionState,
     pub market_kind: MarketKind,
+    #[schemars(length(min = 1))]
     pub symbol: String,
     pub timeframe: MarketTimeframe,
     pub last_agg_id: Option<u64>,
@@ -167,31 +211,401 @@ impl MarketStreamStatusSnapshot {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct StartMarketStreamArgs {
+    #[schemars(regex(pattern = STREAM_ID_PATTERN))]
+    pub stream_id: Option<String>,
     pub market_kind: Option<MarketKind>,
+    #[schemars(length(min = 1))]
     pub symbol: Option<String>,
+    #[schemars(range(min = 0.0))]
     pub min_notional_usdt: Option<f64>,
+    #[schemars(range(min = MIN_EMIT_INTERVAL_MS, max = MAX_EMIT_INTERVAL_MS))]
     pub emit_interval_ms: Option<u64>,
     pub mock_mode: Option<bool>,
+    pub mock_scenario: Option<MockScenarioArgs>,
+    pub mock_faults: Option<MockFaultArgs>,
     pub emit_legacy_price_event: Option<bool>,
     pub emit_legacy_frame_events: Option<bool>,
     pub perf_telemetry: Option<bool>,
+    #[schemars(range(min = MIN_CLOCK_SYNC_INTERVAL_MS, max = MAX_CLOCK_SYNC_INTERVAL_MS))]
     pub clock_sync_interval_ms: Option<u64>,
     pub timeframe: Option<MarketTimeframe>,
     pub startup_mode: Option<MarketStartupMode>,
+    #[schemars(range(min = MIN_HISTORY_LIMIT, max = MAX_HISTORY_LIMIT))]
     pub history_limit: Option<u32>,
     pub history_all: Option<bool>,
+    pub record_trades: Option<bool>,
+    pub replay: Option<MarketReplayArgs>,
+}
+
+/// Replays recorded trades instead of connecting to the exchange. `speed` is a multiple of
+/// real time; 0 replays as fast as the pipeline can apply trades.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketReplayArgs {
+    #[schemars(range(min = 0))]
+    pub from_t: Option<i64>,
+    #[schemars(range(min = 0))]
+    pub to_t: Option<i64>,
+    #[schemars(range(min = 0.0, max = MAX_REPLAY_SPEED))]
+    pub speed: Option<f64>,
+    pub start_paused: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketReplayConfig {
+    #[schemars(range(min = 0))]
+    pub from_t: i64,
+    #[schemars(range(min = 0))]
+    pub to_t: Option<i64>,
+    #[schemars(range(min = 0.0, max = MAX_REPLAY_SPEED))]
+    pub speed: f64,
+    pub start_paused: bool,
+}
+
+impl MarketReplayArgs {
+    pub fn normalize(self) -> Result<MarketReplayConfig, AppError> {
+        let from_t = self.from_t.unwrap_or(0);
+        if from_t < 0 {
+            return Err(AppError::InvalidArgument(
+                "replay.fromT must be non-negative".to_string(),
+            ));
+        }
+        if let Some(to_t) = self.to_t {
+            if to_t < from_t {
+                return Err(AppError::InvalidArgument(
+                    "replay.toT must not be before replay.fromT".to_string(),
+                ));
+            }
+        }
+
+        Ok(MarketReplayConfig {
+            from_t,
+            to_t: self.to_t,
+            speed: normalize_replay_speed(self.speed.unwrap_or(DEFAULT_REPLAY_SPEED))?,
+            start_paused: self.start_paused.unwrap_or(false),
+        })
+    }
+}
+
+pub fn normalize_replay_speed(speed: f64) -> Result<f64, AppError> {
+    if !speed.is_finite() || !(0.0..=MAX_REPLAY_SPEED).contains(&speed) {
+        return Err(AppError::InvalidArgument(format!(
+            "replay speed must be between 0 and {MAX_REPLAY_SPEED}"
+        )));
+    }
+    Ok(speed)
+}
+
+/// Seeded synthetic market for mock mode. Omitted fields fall back to a calm random walk
+/// around 100k at roughly one trade every 4ms, so the same seed always yields the same tape.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MockScenarioArgs {
+    pub seed: Option<u64>,
+    pub price_model: Option<MockPriceModel>,
+    #[schemars(extend("exclusiveMinimum" = 0))]
+    pub start_price: Option<f64>,
+    /// Absolute price units per sqrt(second) for a random walk, a fraction per sqrt(second)
+    /// for GBM.
+    #[schemars(range(min = 0.0))]
+    pub volatility: Option<f64>,
+    /// Drift per second, in the same units as `volatility`.
+    pub drift: Option<f64>,
+    #[schemars(range(max = MAX_MOCK_TRADES_PER_SECOND), extend("exclusiveMinimum" = 0))]
+    pub trades_per_second: Option<f64>,
+    pub trade_size: Option<MockTradeSize>,
+    pub volatility_spikes: Option<MockEpisode>,
+    pub bursts: Option<MockEpisode>,
+    pub quiet_periods: Option<MockEpisode>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum MockPriceModel {
+    RandomWalk,
+    Gbm,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(
+    tag = "distribution",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum MockTradeSize {
+    Fixed {
+        #[schemars(extend("exclusiveMinimum" = 0))]
+        quantity: f64,
+    },
+    Exponential {
+        #[schemars(extend("exclusiveMinimum" = 0))]
+        mean: f64,
+    },
+    LogNormal {
+        #[schemars(extend("exclusiveMinimum" = 0))]
+        median: f64,
+        #[schemars(range(min = 0.0, max = 5.0))]
+        sigma: f64,
+    },
+}
+
+/// Episodes start at random, `per_minute` times per simulated minute on average, and scale
+/// volatility (spikes) or the trade rate (bursts, quiet periods) by `multiplier` for
+/// `duration_ms`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MockEpisode {
+    #[schemars(range(min = 0.0, max = MAX_MOCK_EPISODES_PER_MINUTE))]
+    pub per_minute: f64,
+    #[schemars(range(min = 1, max = MAX_MOCK_EPISODE_DURATION_MS))]
+    pub duration_ms: u64,
+    #[schemars(range(max = MAX_MOCK_EPISODE_MULTIPLIER), extend("exclusiveMinimum" = 0))]
+    pub multiplier: f64,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MockScenarioConfig {
+    pub seed: u64,
+    pub price_model: MockPriceModel,
+    #[schemars(extend("exclusiveMinimum" = 0))]
+    pub start_price: f64,
+    #[schemars(range(min = 0.0))]
+    pub volatility: f64,
+    pub drift: f64,
+    #[schemars(extend("exclusiveMinimum" = 0))]
+    pub trades_per_second: f64,
+    pub trade_size: MockTradeSize,
+    pub volatility_spikes: Option<MockEpisode>,
+    pub bursts: Option<MockEpisode>,
+    pub quiet_periods: Option<MockEpisode>,
+}
+
+impl Default for MockScenarioConfig {
+    fn default() -> Self {
+        Self {
+            seed: DEFAULT_MOCK_SEED,
+            price_model: MockPriceModel::RandomWalk,
+            start_price: DEFAULT_MOCK_START_PRICE,
+            volatility: DEFAULT_MOCK_RANDOM_WALK_VOLATILITY,
+            drift: 0.0,
+            trades_per_second: DEFAULT_MOCK_TRADES_PER_SECOND,
+            trade_size: MockTradeSize::Exponential {
+                mean: DEFAULT_MOCK_TRADE_SIZE_MEAN,
+            },
+            volatility_spikes: None,
+            bursts: None,
+            quiet_periods: None,
+        }
+    }
+}
+
+impl MockScenarioArgs {
+    pub fn normalize(self) -> Result<MockScenarioConfig, AppError> {
+        let price_model = self.price_model.unwrap_or(MockPriceModel::RandomWalk);
+        let start_price = self.start_price.unwrap_or(DEFAULT_MOCK_START_PRICE);
+        if !start_price.is_finite() || start_price <= 0.0 {
+            return Err(AppError::InvalidArgument(
+                "mockScenario.startPrice must be a finite positive number".to_string(),
+            ));
+        }
+
+        let volatility = self.volatility.unwrap_or(match price_model {
+            MockPriceModel::RandomWalk => DEFAULT_MOCK_RANDOM_WALK_VOLATILITY,
+            MockPriceModel::Gbm => DEFAULT_MOCK_GBM_VOLATILITY,
+        });
+        let max_volatility = match price_model {
+            MockPriceModel::RandomWalk => start_price,
+            MockPriceModel::Gbm => 1.0,
+        };
+        if !volatility.is_finite() || !(0.0..=max_volatility).contains(&volatility) {
+            return Err(AppError::InvalidArgument(format!(
+                "mockScenario.volatility must be between 0 and {max_volatility}"
+            )));
+        }
+
+        let drift = self.drift.unwrap_or(0.0);
+        if !drift.is_finite() {
+            return Err(AppError::InvalidArgument(
+                "mockScenario.drift must be finite".to_string(),
+            ));
+        }
+
+        let trades_per_second = self
+            .trades_per_second
+            .unwrap_or(DEFAULT_MOCK_TRADES_PER_SECOND);
+        if !trades_per_second.is_finite()
+            || trades_per_second <= 0.0
+            || trades_per_second > MAX_MOCK_TRADES_PER_SECOND
+        {
+            return Err(AppError::InvalidArgument(format!(
+                "mockScenario.tradesPerSecond must be in (0, {MAX_MOCK_TRADES_PER_SECOND}]"
+            )));
+        }
+
+        let trade_size = self.trade_size.unwrap_or(MockTradeSize::Exponential {
+            mean: DEFAULT_MOCK_TRADE_SIZE_MEAN,
+        });
+        let trade_size_valid = match trade_size {
+            MockTradeSize::Fixed { quantity } => quantity.is_finite() && quantity > 0.0,
+            MockTradeSize::Exponential { mean } => mean.is_finite() && mean > 0.0,
+            MockTradeSize::LogNormal { median, sigma } => {
+                median.is_finite() && median > 0.0 && (0.0..=5.0).contains(&sigma)
+            }
+        };
+        if !trade_size_valid {
+            return Err(AppError::InvalidArgument(
+                "mockScenario.tradeSize needs positive sizes and a sigma between 0 and 5"
+                    .to_string(),
+            ));
+        }
+
+        Ok(MockScenarioConfig {
+            seed: self.seed.unwrap_or(DEFAULT_MOCK_SEED),
+            price_model,
+            start_price,
+            volatility,
+            drift,
+            trades_per_second,
+            trade_size,
+            volatility_spikes: normalize_mock_episode("volatilitySpikes", self.volatility_spikes)?,
+            bursts: normalize_mock_episode("bursts", self.bursts)?,
+            quiet_periods: normalize_mock_episode("quietPeriods", self.quiet_periods)?,
+        })
+    }
+}
+
+fn normalize_mock_episode(
+    name: &str,
+    episode: Option<MockEpisode>,
+) -> Result<Option<MockEpisode>, AppError> {
+    let Some(episode) = episode else {
+        return Ok(None);
+    };
+    if !episode.per_minute.is_finite()
+        || !(0.0..=MAX_MOCK_EPISODES_PER_MINUTE).contains(&episode.per_minute)
+    {
+        return Err(AppError::InvalidArgument(format!(
+            "mockScenario.{name}.perMinute must be between 0 and {MAX_MOCK_EPISODES_PER_MINUTE}"
+        )));
+    }
+    if !(1..=MAX_MOCK_EPISODE_DURATION_MS).contains(&episode.duration_ms) {
+        return Err(AppError::InvalidArgument(format!(
+            "mockScenario.{name}.durationMs must be between 1 and {MAX_MOCK_EPISODE_DURATION_MS}"
+        )));
+    }
+    if !episode.multiplier.is_finite()
+        || episode.multiplier <= 0.0
+        || episode.multiplier > MAX_MOCK_EPISODE_MULTIPLIER
+    {
+        return Err(AppError::InvalidArgument(format!(
+            "mockScenario.{name}.multiplier must be in (0, {MAX_MOCK_EPISODE_MULTIPLIER}]"
+        )));
+    }
+    Ok(Some(episode))
+}
+
+/// Faults injected into the mock tape. Probabilities apply per generated trade. A faulted mock
+/// stream is fed through the same decode, gap detection and snapshot resync path as the live
+/// exchange stream.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MockFaultArgs {
+    #[schemars(range(min = 0.0, max = 1.0))]
+    pub gap_probability: Option<f64>,
+    #[schemars(range(min = 0.0, max = 1.0))]
+    pub duplicate_probability: Option<f64>,
+    #[schemars(range(min = 0.0, max = 1.0))]
+    pub out_of_order_probability: Option<f64>,
+    #[schemars(range(min = 0.0, max = 1.0))]
+    pub corrupt_probability: Option<f64>,
+    #[schemars(range(min = 0.0, max = 1.0))]
+    pub disconnect_probability: Option<f64>,
+    #[schemars(range(max = MAX_MOCK_FAULT_LATENCY_MS))]
+    pub latency_ms: Option<u64>,
+    #[schemars(range(max = MAX_MOCK_FAULT_LATENCY_MS))]
+    pub latency_jitter_ms: Option<u64>,
+    #[schemars(range(min = -MAX_MOCK_CLOCK_SKEW_MS, max = MAX_MOCK_CLOCK_SKEW_MS))]
+    pub clock_skew_ms: Option<i64>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, JsonSchema, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MockFaultConfig {
+    #[schemars(range(min = 0.0, max = 1.0))]
+    pub gap_probability: f64,
+    #[schemars(range(min = 0.0, max = 1.0))]
+    pub duplicate_probability: f64,
+    #[schemars(range(min = 0.0, max = 1.0))]
+    pub out_of_order_probability: f64,
+    #[schemars(range(min = 0.0, max = 1.0))]
+    pub corrupt_probability: f64,
+    #[schemars(range(min = 0.0, max = 1.0))]
+    pub disconnect_probability: f64,
+    pub latency_ms: u64,
+    pub latency_jitter_ms: u64,
+    pub clock_skew_ms: i64,
+}
+
+impl MockFaultArgs {
+    pub fn normalize(self) -> Result<MockFaultConfig, AppError> {
+        let probability = |name: &str, value: Option<f64>| {
+            let value = value.unwrap_or(0.0);
+            if !value.is_finite() || !(0.0..=1.0).contains(&value) {
+                return Err(AppError::InvalidArgument(format!(
+                    "mockFaults.{name} must be between 0 and 1"
+                )));
+            }
+            Ok(value)
+        };
+
+        let latency_ms = self.latency_ms.unwrap_or(0);
+        let latency_jitter_ms = self.latency_jitter_ms.unwrap_or(0);
+        if latency_ms.saturating_add(latency_jitter_ms) > MAX_MOCK_FAULT_LATENCY_MS {
+            return Err(AppError::InvalidArgument(format!(
+                "mockFaults.latencyMs plus latencyJitterMs must not exceed {MAX_MOCK_FAULT_LATENCY_MS}"
+            )));
+        }
+        let clock_skew_ms = self.clock_skew_ms.unwrap_or(0);
+        if clock_skew_ms.abs() > MAX_MOCK_CLOCK_SKEW_MS {
+            return Err(AppError::InvalidArgument(format!(
+                "mockFaults.clockSkewMs must be within +/-{MAX_MOCK_CLOCK_SKEW_MS}"
+            )));
+        }
+
+        Ok(MockFaultConfig {
+            gap_probability: probability("gapProbability", self.gap_probability)?,
+            duplicate_probability: probability("duplicateProbability", self.duplicate_probability)?,
+            out_of_order_probability: probability(
+                "outOfOrderProbability",
+                self.out_of_order_probability,
+            )?,
+            corrupt_probability: probability("corruptProbability", self.corrupt_probability)?,
+            disconnect_probability: probability(
+                "disconnectProbability",
+                self.disconnect_probability,
+            )?,
+            latency_ms,
+            latency_jitter_ms,
+            clock_skew_ms,
+        })
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct MarketStreamConfig {
+    pub stream_id: String,
     pub market_kind: MarketKind,
     pub symbol: String,
     pub min_notional_usdt: f64,
     pub emit_interval_ms: u64,
     pub mock_mode: bool,
+    pub mock_scenario: Option<MockScenarioConfig>,
+    pub mock_faults: Option<MockFaultConfig>,
     pub emit_legacy_price_event: bool,
     pub emit_legacy_frame_events: bool,
     pub perf_telemetry: bool,
@@ -200,6 +614,8 @@ pub struct MarketStreamConfig {
     pub startup_mode: MarketStartupMode,
     pub history_limit: u32,
     pub history_all: bool,
+    pub record_trades: bool,
+    pub replay: Option<MarketReplayConfig>,
 }
 
 fn normalize_symbol(symbol: String) -> Result<String, AppError> {
@@ -212,6 +628,26 @@ fn normalize_symbol(symbol: String) -> Result<String, AppError> {
     Ok(normalized)
 }
 
+/// Stream ids are chosen by the UI and end up in window URLs, so they are kept to a safe
+/// alphabet. A missing id means the main window's stream.
+pub fn normalize_stream_id(stream_id: Option<String>) -> Result<String, AppError> {
+    let Some(stream_id) = stream_id else {
+        return Ok(DEFAULT_MARKET_STREAM_ID.to_string());
+    };
+    let normalized = stream_id.trim();
+    if normalized.is_empty()
+        || normalized.len() > MAX_MARKET_STREAM_ID_LEN
+        || !normalized
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_')
+    {
+        return Err(AppError::InvalidArgument(format!(
+            "streamId must be 1-{MAX_MARKET_STREAM_ID_LEN} characters of [A-Za-z0-9_-]"
+        )));
+    }
+    Ok(normalized.to_string())
+}
+
 fn normalize_color(color: String) -> Result<String, AppError> {
     let normalized = color.trim().to_ascii_uppercase();
     if normalized.len() != 7 || !normalized.starts_with('#') {
@@ -261,6 +697,7 @@ fn validate_drawing_type(value: &str) -> Result<(), AppError> {
 
 impl StartMarketStreamArgs {
     pub fn normalize(self) -> Result<MarketStreamConfig, AppError> {
+        let stream_id = normalize_stream_id(self.stream_id)?;
         let market_kind = self.market_kind.unwrap_or(DEFAULT_MARKET_KIND);
         let symbol = normalize_symbol(self.symbol.unwrap_or_else(|| DEFAULT_SYMBOL.to_string()))?;
 
@@ -279,6 +716,21 @@ impl StartMarketStreamArgs {
         }
 
         let mock_mode = self.mock_mode.unwrap_or(DEFAULT_MOCK_MODE);
+        let mock_scenario = match (mock_mode, self.mock_scenario) {
+            (true, scenario) => Some(scenario.unwrap_or_default().normalize()?),
+            (false, None) => None,
+            (false, Some(_)) => {
+                return Err(AppError::InvalidArgument(
+                    "mockScenario requires mockMode".to_string(),
+                ))
+            }
+        };
+        if self.mock_faults.is_some() && !mock_mode {
+            return Err(AppError::InvalidArgument(
+                "mockFaults requires mockMode".to_string(),
+            ));
+        }
+        let mock_faults = self.mock_faults.map(MockFaultArgs::normalize).transpose()?;
         let emit_legacy_price_event = self
             .emit_legacy_price_event
             .unwrap_or(DEFAULT_EMIT_LEGACY_PRICE_EVENT);
@@ -310,13 +762,22 @@ impl StartMarketStreamArgs {
             }
             requested
         };
+        let replay = self.replay.map(MarketReplayArgs::normalize).transpose()?;
+        if replay.is_some() && mock_mode {
+            return Err(AppError::InvalidArgument(
+                "mockMode and replay cannot be combined".to_string(),
+            ));
+        }
 
         Ok(MarketStreamConfig {
+            stream_id,
             market_kind,
             symbol,
             min_notional_usdt,
             emit_interval_ms,
             mock_mode,
+            mock_scenario,
+            mock_faults,
             emit_legacy_price_event,
             emit_legacy_frame_events,
             perf_telemetry,
@@ -325,38 +786,54 @@ impl StartMarketStreamArgs {
             startup_mode,
             history_limit,
             history_all,
+            record_trades: self.record_trades.unwrap_or(false),
+            replay,
         })
     }
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct MarketStreamSession {
+    #[schemars(regex(pattern = STREAM_ID_PATTERN))]
+    pub stream_id: String,
     pub running: bool,
     pub market_kind: MarketKind,
+    #[schemars(length(min = 1))]
     pub symbol: String,
+    #[schemars(range(min = 0.0))]
     pub min_notional_usdt: f64,
+    #[schemars(range(min = MIN_EMIT_INTERVAL_MS, max = MAX_EMIT_INTERVAL_MS))]
     pub emit_interval_ms: u64,
     pub mock_mode: bool,
+    pub mock_scenario: Option<MockScenarioConfig>,
+    pub mock_faults: Option<MockFaultConfig>,
     pub emit_legacy_price_event: bool,
     pub emit_legacy_frame_events: bool,
     pub perf_telemetry: bool,
+    #[schemars(range(min = MIN_CLOCK_SYNC_INTERVAL_MS, max = MAX_CLOCK_SYNC_INTERVAL_MS))]
     pub clock_sync_interval_ms: u64,
     pub timeframe: MarketTimeframe,
     pub startup_mode: MarketStartupMode,
+    #[schemars(range(min = MIN_HISTORY_LIMIT, max = MAX_HISTORY_LIMIT))]
     pub history_limit: u32,
     pub history_all: bool,
+    pub record_trades: bool,
+    pub replay: Option<MarketReplayConfig>,
 }
 
 impl MarketStreamSession {
     pub fn from_config(config: &MarketStreamConfig) -> Self {
         Self {
+            stream_id: config.stream_id.clone(),
             running: true,
             market_kind: config.market_kind,
             symbol: config.symbol.clone(),
             min_notional_usdt: config.min_notional_usdt,
             emit_interval_ms: config.emit_interval_ms,
             mock_mode: config.mock_mode,
+            mock_scenario: config.mock_scenario.clone(),
+            mock_faults: config.mock_faults,
             emit_legacy_price_event: config.emit_legacy_price_event,
             emit_legacy_frame_events: config.emit_legacy_frame_events,
             perf_telemetry: config.perf_telemetry,
@@ -365,175 +842,1202 @@ impl MarketStreamSession {
             startup_mode: config.startup_mode,
             history_limit: config.history_limit,
             history_all: config.history_all,
+            record_trades: config.record_trades,
+            replay: config.replay.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketHistoryAckArgs {
+    #[schemars(regex(pattern = STREAM_ID_PATTERN))]
+    pub stream_id: Option<String>,
+    pub bootstrap_id: u64,
+    pub seq: u32,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketHistoryAckResult {
+    pub accepted: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketImportArchivesArgs {
+    pub market_kind: MarketKind,
+    #[schemars(length(min = 1, max = MAX_IMPORT_ARCHIVES), inner(length(min = 1)))]
+    pub paths: Vec<String>,
+}
+
+impl MarketImportArchivesArgs {
+    pub fn normalize(self) -> Result<Self, AppError> {
+        if self.paths.is_empty() || self.paths.len() > MAX_IMPORT_ARCHIVES {
+            return Err(AppError::InvalidArgument(format!(
+                "paths must contain between 1 and {MAX_IMPORT_ARCHIVES} archives"
+            )));
+        }
+
+        let paths = self
+            .paths
+            .into_iter()
+            .map(|path| path.trim().to_string())
+            .collect::<Vec<_>>();
+        if paths
+            .iter()
+            .any(|path| !path.to_ascii_lowercase().ends_with(".zip"))
+        {
+            return Err(AppError::InvalidArgument(
+                "archive paths must point to .zip files".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            market_kind: self.market_kind,
+            paths,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UiArchiveImportResult {
+    pub archives_imported: u32,
+    pub candles_imported: u64,
+    pub trades_imported: u64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum MarketExportDataset {
+    Candles,
+    DeltaCandles,
+    Trades,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MarketExportFormat {
+    Csv,
+    Parquet,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketExportArgs {
+    pub market_kind: MarketKind,
+    #[schemars(length(min = 1))]
+    pub symbol: String,
+    pub timeframe: Option<MarketTimeframe>,
+    pub dataset: MarketExportDataset,
+    pub format: MarketExportFormat,
+    #[schemars(range(min = 0))]
+    pub from_t: i64,
+    #[schemars(range(min = 0))]
+    pub to_t: i64,
+    #[schemars(length(min = 1))]
+    pub path: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct MarketExportRequest {
+    pub market_kind: MarketKind,
+    pub symbol: String,
+    pub timeframe: MarketTimeframe,
+    pub dataset: MarketExportDataset,
+    pub format: MarketExportFormat,
+    pub from_t: i64,
+    pub to_t: i64,
+    pub path: PathBuf,
+}
+
+impl MarketExportArgs {
+    pub fn normalize(self) -> Result<MarketExportRequest, AppError> {
+        if self.from_t < 0 || self.to_t < self.from_t {
+            return Err(AppError::InvalidArgument(
+                "export range must satisfy 0 <= fromT <= toT".to_string(),
+            ));
+        }
+        let path = self.path.trim();
+        if path.is_empty() {
+            return Err(AppError::InvalidArgument(
+                "export path must not be empty".to_string(),
+            ));
+        }
+
+        Ok(MarketExportRequest {
+            market_kind: self.market_kind,
+            symbol: normalize_symbol(self.symbol)?,
+            timeframe: self.timeframe.unwrap_or(DEFAULT_TIMEFRAME),
+            dataset: self.dataset,
+            format: self.format,
+            from_t: self.from_t,
+            to_t: self.to_t,
+            path: PathBuf::from(path),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UiExportProgress {
+    #[schemars(length(min = 1))]
+    pub path: String,
+    pub rows_written: u64,
+    #[schemars(range(min = 0.0, max = 100.0))]
+    pub progress_pct: Option<f64>,
+    pub done: bool,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UiExportResult {
+    #[schemars(length(min = 1))]
+    pub path: String,
+    pub rows_written: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(
+    tag = "action",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum MarketReplayControlArgs {
+    Pause,
+    Resume,
+    Step {
+        #[schemars(range(min = 1))]
+        count: Option<u32>,
+    },
+    Seek {
+        #[schemars(range(min = 0))]
+        t: i64,
+    },
+    Speed {
+        #[schemars(range(min = 0.0, max = MAX_REPLAY_SPEED))]
+        speed: f64,
+    },
+}
+
+impl MarketReplayControlArgs {
+    pub fn normalize(self) -> Result<Self, AppError> {
+        match self {
+            Self::Step { count } => {
+                let count = count.unwrap_or(1);
+                if count == 0 {
+                    return Err(AppError::InvalidArgument(
+                        "step count must be greater than zero".to_string(),
+                    ));
+                }
+                Ok(Self::Step { count: Some(count) })
+            }
+            Self::Seek { t } if t < 0 => Err(AppError::InvalidArgument(
+                "seek timestamp must be non-negative".to_string(),
+            )),
+            Self::Speed { speed } => Ok(Self::Speed {
+                speed: normalize_replay_speed(speed)?,
+            }),
+            other => Ok(other),
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UiReplayStatus {
+    pub active: bool,
+    pub paused: bool,
+    #[schemars(range(min = 0.0))]
+    pub speed: f64,
+    pub cursor_t: Option<i64>,
+    pub finished: bool,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct MarketStreamStopResult {
     pub stopped: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketSymbolsArgs {
+    pub market_kind: MarketKind,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketPreferencesSnapshot {
+    pub market_kind: MarketKind,
+    #[schemars(length(min = 1))]
+    pub symbol: String,
+    pub timeframe: MarketTimeframe,
+    pub magnet_strong: bool,
+    pub updated_at_ms: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveMarketPreferencesArgs {
+    pub market_kind: MarketKind,
+    #[schemars(length(min = 1))]
+    pub symbol: String,
+    pub timeframe: MarketTimeframe,
+    pub magnet_strong: bool,
+}
+
+impl SaveMarketPreferencesArgs {
+    pub fn normalize(self) -> Result<Self, AppError> {
+        Ok(Self {
+            market_kind: self.market_kind,
+            symbol: normalize_symbol(self.symbol)?,
+            timeframe: self.timeframe,
+            magnet_strong: self.magnet_strong,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketDrawingsScopeArgs {
+    pub market_kind: MarketKind,
+    #[schemars(length(min = 1))]
+    pub symbol: String,
+    pub timeframe: MarketTimeframe,
+}
+
+impl MarketDrawingsScopeArgs {
+    pub fn normalize(self) -> Result<Self, AppError> {
+        Ok(Self {
+            market_kind: self.market_kind,
+            symbol: normalize_symbol(self.symbol)?,
+            timeframe: self.timeframe,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketDrawingDto {
+    #[schemars(length(min = 1))]
+    pub id: String,
+    pub market_kind: MarketKind,
+    #[schemars(length(min = 1))]
+    pub symbol: String,
+    pub timeframe: MarketTimeframe,
+    #[schemars(extend("enum" = SUPPORTED_DRAWING_TYPES))]
+    pub drawing_type: String,
+    #[schemars(regex(pattern = DRAWING_COLOR_PATTERN))]
+    pub color: String,
+    #[schemars(length(max = MAX_DRAWING_LABEL_LEN))]
+    pub label: Option<String>,
+    #[schemars(length(min = 1))]
+    pub payload_json: String,
+    pub created_at_ms: i64,
+    pub updated_at_ms: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketDrawingUpsertArgs {
+    #[schemars(length(min = 1))]
+    pub id: String,
+    pub market_kind: MarketKind,
+    #[schemars(length(min = 1))]
+    pub symbol: String,
+    pub timeframe: MarketTimeframe,
+    #[schemars(extend("enum" = SUPPORTED_DRAWING_TYPES))]
+    pub drawing_type: String,
+    #[schemars(regex(pattern = DRAWING_COLOR_PATTERN))]
+    pub color: String,
+    #[schemars(length(max = MAX_DRAWING_LABEL_LEN))]
+    pub label: Option<String>,
+    #[schemars(length(min = 1))]
+    pub payload_json: String,
+    pub created_at_ms: Option<i64>,
+}
+
+impl MarketDrawingUpsertArgs {
+    pub fn normalize(self) -> Result<Self, AppError> {
+        let id = self.id.trim().to_string();
+        if id.is_empty() {
+            return Err(AppError::InvalidArgument(
+                "drawing id must be non-empty".to_string(),
+            ));
+        }
+
+        let drawing_type = self.drawing_type.trim().to_string();
+        validate_drawing_type(&drawing_type)?;
+
+        let payload_json = self.payload_json.trim().to_string();
+        if payload_json.is_empty() {
+            return Err(AppError::InvalidArgument(
+                "payloadJson must be non-empty".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            id,
+            market_kind: self.market_kind,
+            symbol: normalize_symbol(self.symbol)?,
+            timeframe: self.timeframe,
+            drawing_type,
+            color: normalize_color(self.color)?,
+            label: normalize_optional_label(self.label)?,
+            payload_json,
+            created_at_ms: self.created_at_ms,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketDrawingDeleteArgs {
+    #[schemars(length(min = 1))]
+    pub id: String,
+    pub market_kind: MarketKind,
+    #[schemars(length(min = 1))]
+    pub symbol: String,
+    pub timeframe: MarketTimeframe,
+}
+
+impl MarketDrawingDeleteArgs {
+    pub fn normalize(self) -> Result<Self, AppError> {
+        let id = self.id.trim().to_string();
+        if id.is_empty() {
+            return Err(AppError::InvalidArgument(
+                "drawing id must be non-empty".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            id,
+            market_kind: self.market_kind,
+            symbol: normalize_symbol(self.symbol)?,
+            timeframe: self.timeframe,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketDrawingDeleteResult {
+    pub deleted: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum MarketAlertCrossDirection {
+    Up,
+    Down,
+    #[default]
+    Any,
+}
+
+impl MarketAlertCrossDirection {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Up => "up",
+            Self::Down => "down",
+            Self::Any => "any",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum MarketAlertCondition {
+    PriceCross {
+        #[schemars(extend("exclusiveMinimum" = 0))]
+        price: f64,
+        #[serde(default)]
+        direction: MarketAlertCrossDirection,
+    },
+    PercentMove {
+        #[schemars(range(max = 100.0), extend("exclusiveMinimum" = 0))]
+        percent: f64,
+        #[schemars(range(min = MIN_ALERT_WINDOW_MS, max = MAX_ALERT_WINDOW_MS))]
+        window_ms: i64,
+    },
+    DeltaSpike {
+        #[schemars(extend("exclusiveMinimum" = 0))]
+        threshold: f64,
+        #[schemars(range(min = MIN_ALERT_WINDOW_MS, max = MAX_ALERT_WINDOW_MS))]
+        window_ms: i64,
+    },
+    VolumeSpike {
+        #[schemars(extend("exclusiveMinimum" = 0))]
+        threshold: f64,
+        #[schemars(range(min = MIN_ALERT_WINDOW_MS, max = MAX_ALERT_WINDOW_MS))]
+        window_ms: i64,
+    },
+    DrawingCross {
+        #[schemars(length(min = 1))]
+        drawing_id: String,
+        #[serde(default)]
+        direction: MarketAlertCrossDirection,
+    },
+}
+
+impl MarketAlertCondition {
+    pub fn type_str(&self) -> &'static str {
+        match self {
+            Self::PriceCross { .. } => "priceCross",
+            Self::PercentMove { .. } => "percentMove",
+            Self::DeltaSpike { .. } => "deltaSpike",
+            Self::VolumeSpike { .. } => "volumeSpike",
+            Self::DrawingCross { .. } => "drawingCross",
+        }
+    }
+
+    pub fn normalize(self) -> Result<Self, AppError> {
+        match self {
+            Self::PriceCross { price, direction } => {
+                if !price.is_finite() || price <= 0.0 {
+                    return Err(AppError::InvalidArgument(
+                        "priceCross price must be a finite positive number".to_string(),
+                    ));
+                }
+                Ok(Self::PriceCross { price, direction })
+            }
+            Self::PercentMove { percent, window_ms } => {
+                if !percent.is_finite() || percent <= 0.0 || percent > 100.0 {
+                    return Err(AppError::InvalidArgument(
+                        "percentMove percent must be within (0, 100]".to_string(),
+                    ));
+                }
+                Ok(Self::PercentMove {
+                    percent,
+                    window_ms: validate_alert_window_ms(window_ms)?,
+                })
+            }
+            Self::DeltaSpike {
+                threshold,
+                window_ms,
+            } => Ok(Self::DeltaSpike {
+                threshold: validate_alert_threshold(threshold)?,
+                window_ms: validate_alert_window_ms(window_ms)?,
+            }),
+            Self::VolumeSpike {
+                threshold,
+                window_ms,
+            } => Ok(Self::VolumeSpike {
+                threshold: validate_alert_threshold(threshold)?,
+                window_ms: validate_alert_window_ms(window_ms)?,
+            }),
+            Self::DrawingCross {
+                drawing_id,
+                direction,
+            } => {
+                let drawing_id = drawing_id.trim().to_string();
+                if drawing_id.is_empty() {
+                    return Err(AppError::InvalidArgument(
+                        "drawingCross drawingId must be non-empty".to_string(),
+                    ));
+                }
+                Ok(Self::DrawingCross {
+                    drawing_id,
+                    direction,
+                })
+            }
+        }
+    }
+}
+
+fn validate_alert_window_ms(window_ms: i64) -> Result<i64, AppError> {
+    if !(MIN_ALERT_WINDOW_MS..=MAX_ALERT_WINDOW_MS).contains(&window_ms) {
+        return Err(AppError::InvalidArgument(format!(
+            "alert windowMs must be between {MIN_ALERT_WINDOW_MS} and {MAX_ALERT_WINDOW_MS}"
+        )));
+    }
+    Ok(window_ms)
+}
+
+fn validate_alert_threshold(threshold: f64) -> Result<f64, AppError> {
+    if !threshold.is_finite() || threshold <= 0.0 {
+        return Err(AppError::InvalidArgument(
+            "alert threshold must be a finite positive number".to_string(),
+        ));
+    }
+    Ok(threshold)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketAlertDto {
+    pub id: String,
+    pub market_kind: MarketKind,
+    pub symbol: String,
+    pub condition: MarketAlertCondition,
+    pub label: Option<String>,
+    pub enabled: bool,
+    pub one_shot: bool,
+    pub cooldown_ms: i64,
+    pub notify_os: bool,
+    pub last_triggered_at_ms: Option<i64>,
+    pub created_at_ms: i64,
+    pub updated_at_ms: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketAlertUpsertArgs {
+    #[schemars(length(min = 1))]
+    pub id: String,
+    pub market_kind: MarketKind,
+    #[schemars(length(min = 1))]
+    pub symbol: String,
+    pub condition: MarketAlertCondition,
+    #[schemars(length(max = MAX_DRAWING_LABEL_LEN))]
+    pub label: Option<String>,
+    pub enabled: Option<bool>,
+    pub one_shot: Option<bool>,
+    #[schemars(range(min = 0, max = MAX_ALERT_COOLDOWN_MS))]
+    pub cooldown_ms: Option<i64>,
+    pub notify_os: Option<bool>,
+    pub created_at_ms: Option<i64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NormalizedMarketAlertUpsert {
+    pub id: String,
+    pub market_kind: MarketKind,
+    pub symbol: String,
+    pub condition: MarketAlertCondition,
+    pub label: Option<String>,
+    pub enabled: bool,
+    pub one_shot: bool,
+    pub cooldown_ms: i64,
+    pub notify_os: bool,
+    pub created_at_ms: Option<i64>,
+}
+
+impl MarketAlertUpsertArgs {
+    pub fn normalize(self) -> Result<NormalizedMarketAlertUpsert, AppError> {
+        let id = self.id.trim().to_string();
+        if id.is_empty() {
+            return Err(AppError::InvalidArgument(
+                "alert id must be non-empty".to_string(),
+            ));
+        }
+
+        let cooldown_ms = self.cooldown_ms.unwrap_or(DEFAULT_ALERT_COOLDOWN_MS);
+        if !(0..=MAX_ALERT_COOLDOWN_MS).contains(&cooldown_ms) {
+            return Err(AppError::InvalidArgument(format!(
+                "alert cooldownMs must be between 0 and {MAX_ALERT_COOLDOWN_MS}"
+            )));
+        }
+
+        Ok(NormalizedMarketAlertUpsert {
+            id,
+            market_kind: self.market_kind,
+            symbol: normalize_symbol(self.symbol)?,
+            condition: self.condition.normalize()?,
+            label: normalize_optional_label(self.label)?,
+            enabled: self.enabled.unwrap_or(true),
+            one_shot: self.one_shot.unwrap_or(true),
+            cooldown_ms,
+            notify_os: self.notify_os.unwrap_or(false),
+            created_at_ms: self.created_at_ms,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketAlertsListArgs {
+    pub market_kind: Option<MarketKind>,
+    pub symbol: Option<String>,
+}
+
+impl MarketAlertsListArgs {
+    pub fn normalize(self) -> Result<Self, AppError> {
+        Ok(Self {
+            market_kind: self.market_kind,
+            symbol: self.symbol.map(normalize_symbol).transpose()?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketAlertDeleteArgs {
+    #[schemars(length(min = 1))]
+    pub id: String,
+}
+
+impl MarketAlertDeleteArgs {
+    pub fn normalize(self) -> Result<Self, AppError> {
+        let id = self.id.trim().to_string();
+        if id.is_empty() {
+            return Err(AppError::InvalidArgument(
+                "alert id must be non-empty".to_string(),
+            ));
+        }
+        Ok(Self { id })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketAlertDeleteResult {
+    pub deleted: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketAlertTriggersArgs {
+    pub alert_id: Option<String>,
+    #[schemars(range(min = 1, max = MAX_ALERT_TRIGGERS_LIMIT))]
+    pub limit: Option<u32>,
+}
+
+impl MarketAlertTriggersArgs {
+    pub fn normalize(self) -> Result<(Option<String>, u32), AppError> {
+        let alert_id = self
+            .alert_id
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty());
+        let limit = self.limit.unwrap_or(DEFAULT_ALERT_TRIGGERS_LIMIT);
+        if !(1..=MAX_ALERT_TRIGGERS_LIMIT).contains(&limit) {
+            return Err(AppError::InvalidArgument(format!(
+                "alert triggers limit must be between 1 and {MAX_ALERT_TRIGGERS_LIMIT}"
+            )));
+        }
+        Ok((alert_id, limit))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketAlertTriggerDto {
+    pub id: i64,
+    pub alert_id: String,
+    pub market_kind: MarketKind,
+    pub symbol: String,
+    pub condition_type: String,
+    pub label: Option<String>,
+    pub price: f64,
+    pub message: String,
+    pub triggered_at_ms: i64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEventType {
+    AlertTriggered,
+    ConnectionState,
+}
+
+impl WebhookEventType {
+    pub const ALL: [Self; 2] = [Self::AlertTriggered, Self::ConnectionState];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::AlertTriggered => "alert_triggered",
+            Self::ConnectionState => "connection_state",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookHeader {
+    #[schemars(length(min = 1))]
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookTargetDto {
+    pub id: String,
+    pub label: Option<String>,
+    pub url: String,
+    pub headers: Vec<WebhookHeader>,
+    pub body_template: Option<String>,
+    pub event_types: Vec<WebhookEventType>,
+    pub enabled: bool,
+    pub created_at_ms: i64,
+    pub updated_at_ms: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookTargetUpsertArgs {
+    #[schemars(length(min = 1))]
+    pub id: String,
+    #[schemars(length(max = MAX_DRAWING_LABEL_LEN))]
+    pub label: Option<String>,
+    #[schemars(length(min = 1))]
+    pub url: String,
+    #[schemars(length(max = MAX_WEBHOOK_HEADERS))]
+    pub headers: Option<Vec<WebhookHeader>>,
+    #[schemars(length(max = MAX_WEBHOOK_TEMPLATE_LEN))]
+    pub body_template: Option<String>,
+    pub event_types: Option<Vec<WebhookEventType>>,
+    pub enabled: Option<bool>,
+    pub created_at_ms: Option<i64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NormalizedWebhookTargetUpsert {
+    pub id: String,
+    pub label: Option<String>,
+    pub url: String,
+    pub headers: Vec<WebhookHeader>,
+    pub body_template: Option<String>,
+    pub event_types: Vec<WebhookEventType>,
+    pub enabled: bool,
+    pub created_at_ms: Option<i64>,
+}
+
+impl WebhookTargetUpsertArgs {
+    pub fn normalize(self) -> Result<NormalizedWebhookTargetUpsert, AppError> {
+        let id = self.id.trim().to_string();
+        if id.is_empty() {
+            return Err(AppError::InvalidArgument(
+                "webhook id must be non-empty".to_string(),
+            ));
+        }
+
+        let url = self.url.trim().to_string();
+        let parsed_url = reqwest::Url::parse(&url)
+            .map_err(|error| AppError::InvalidArgument(format!("invalid webhook url: {error}")))?;
+        if !matches!(parsed_url.scheme(), "http" | "https") {
+            return Err(AppError::InvalidArgument(
+                "webhook url must use http or https".to_string(),
+            ));
+        }
+
+        let headers = self.headers.unwrap_or_default();
+        if headers.len() > MAX_WEBHOOK_HEADERS {
+            return Err(AppError::InvalidArgument(format!(
+                "webhook supports at most {MAX_WEBHOOK_HEADERS} headers"
+            )));
+        }
+        let mut normalized_headers = Vec::with_capacity(headers.len());
+        for header in headers {
+            let name = header.name.trim().to_string();
+            reqwest::header::HeaderName::from_bytes(name.as_bytes()).map_err(|_| {
+                AppError::InvalidArgument(format!("invalid webhook header name '{name}'"))
+            })?;
+            reqwest::header::HeaderValue::from_str(&header.value).map_err(|_| {
+                AppError::InvalidArgument(format!("invalid value for webhook header '{name}'"))
+            })?;
+            normalized_headers.push(WebhookHeader {
+                name,
+                value: header.value,
+            });
+        }
+
+        let body_template = self
+            .body_template
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty());
+        if body_template
+            .as_ref()
+            .is_some_and(|value| value.len() > MAX_WEBHOOK_TEMPLATE_LEN)
+        {
+            return Err(AppError::InvalidArgument(format!(
+                "webhook bodyTemplate exceeds max length ({MAX_WEBHOOK_TEMPLATE_LEN})"
+            )));
+        }
+
+        let mut event_types = self
+            .event_types
+            .unwrap_or_else(|| WebhookEventType::ALL.to_vec());
+        event_types.sort_by_key(|event_type| event_type.as_str());
+        event_types.dedup();
+        if event_types.is_empty() {
+            return Err(AppError::InvalidArgument(
+                "webhook must subscribe to at least one event type".to_string(),
+            ));
+        }
+
+        Ok(NormalizedWebhookTargetUpsert {
+            id,
+            label: normalize_optional_label(self.label)?,
+            url,
+            headers: normalized_headers,
+            body_template,
+            event_types,
+            enabled: self.enabled.unwrap_or(true),
+            created_at_ms: self.created_at_ms,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookTargetDeleteArgs {
+    #[schemars(length(min = 1))]
+    pub id: String,
+}
+
+impl WebhookTargetDeleteArgs {
+    pub fn normalize(self) -> Result<Self, AppError> {
+        let id = self.id.trim().to_string();
+        if id.is_empty() {
+            return Err(AppError::InvalidArgument(
+                "webhook id must be non-empty".to_string(),
+            ));
+        }
+        Ok(Self { id })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookTargetDeleteResult {
+    pub deleted: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookOutboxSummary {
+    pub pending: i64,
+    pub failed: i64,
+    pub oldest_pending_at_ms: Option<i64>,
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookOutboxRetryResult {
+    pub requeued: u64,
+}
+
+/// Starts the localhost market API. Port 0 lets the OS pick a free port.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketApiStartArgs {
+    pub port: Option<u16>,
+}
+
+impl MarketApiStartArgs {
+    pub fn normalize(self) -> Result<u16, AppError> {
+        let port = self.port.unwrap_or(DEFAULT_MARKET_API_PORT);
+        if port != 0 && port < MIN_MARKET_API_PORT {
+            return Err(AppError::InvalidArgument(format!(
+                "market api port must be 0 or at least {MIN_MARKET_API_PORT}"
+            )));
+        }
+        Ok(port)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UiMarketApiStatus {
+    pub running: bool,
+    pub address: Option<String>,
+    pub clients: u32,
+}
+
+/// Selects one of the running streams; defaults to the main window's stream.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketStreamSelectArgs {
+    #[schemars(regex(pattern = STREAM_ID_PATTERN))]
+    pub stream_id: Option<String>,
+}
+
+impl MarketStreamSelectArgs {
+    pub fn normalize(self) -> Result<String, AppError> {
+        normalize_stream_id(self.stream_id)
+    }
+}
+
+/// Selects a stream and how many of its most recent closed buckets to return; defaults to
+/// every bucket the pipeline keeps.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketStreamSnapshotArgs {
+    #[schemars(regex(pattern = STREAM_ID_PATTERN))]
+    pub stream_id: Option<String>,
+    #[schemars(range(min = MIN_HISTORY_LIMIT, max = MAX_SNAPSHOT_HISTORY_LIMIT))]
+    pub history_limit: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarketStreamSnapshotQuery {
+    pub stream_id: String,
+    pub history_limit: usize,
+}
+
+impl MarketStreamSnapshotArgs {
+    pub fn normalize(self) -> Result<MarketStreamSnapshotQuery, AppError> {
+        let history_limit = self.history_limit.unwrap_or(MAX_SNAPSHOT_HISTORY_LIMIT);
+        if !(MIN_HISTORY_LIMIT..=MAX_SNAPSHOT_HISTORY_LIMIT).contains(&history_limit) {
+            return Err(AppError::InvalidArgument(format!(
+                "historyLimit must be between {MIN_HISTORY_LIMIT} and {MAX_SNAPSHOT_HISTORY_LIMIT}"
+            )));
+        }
+
+        Ok(MarketStreamSnapshotQuery {
+            stream_id: normalize_stream_id(self.stream_id)?,
+            history_limit: history_limit as usize,
+        })
+    }
+}
+
+/// Position of an event in the stream that published it. `generation` changes on every
+/// stream start; `seq` counts every event of the generation, of any kind, from 1.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketEventSeq {
+    pub generation: u64,
+    pub seq: u64,
+}
+
+/// Reports that a window applied every `market_frame_update` of `generation` up to `seq`.
+/// The emitter slows down while these lag behind the frames it sends.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketFrameAckArgs {
+    #[schemars(regex(pattern = STREAM_ID_PATTERN))]
+    pub stream_id: Option<String>,
+    pub generation: u64,
+    pub seq: u64,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketFrameAckResult {
+    pub accepted: bool,
+}
+
+/// Current chart state of a stream, for re-hydrating a reloaded webview without restarting
+/// it. `candles` and `deltaCandles` hold closed buckets oldest first; the open buckets are
+/// `lastCandle` and `lastDeltaCandle`. `session` is null when the stream is not running.
+/// Events of `lastSeq.generation` with a higher `seq` may repeat state already in the
+/// snapshot, but none newer is missing from it.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UiMarketStreamSnapshot {
+    pub session: Option<MarketStreamSession>,
+    pub status: MarketStreamStatusSnapshot,
+    pub last_seq: Option<MarketEventSeq>,
+    pub last_agg_id: Option<u64>,
+    pub last_price: Option<f64>,
+    pub last_candle: Option<UiCandle>,
+    pub last_delta_candle: Option<UiDeltaCandle>,
+    pub candles: Vec<UiCandle>,
+    pub delta_candles: Vec<UiDeltaCandle>,
+}
+
+/// A window receives the events of `stream_id`, optionally only while that stream runs on
+/// `timeframe`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketWindowSubscription {
+    #[schemars(regex(pattern = STREAM_ID_PATTERN))]
+    pub stream_id: String,
+    pub timeframe: Option<MarketTimeframe>,
+}
+
+impl MarketWindowSubscription {
+    pub fn matches(&self, stream_id: &str, timeframe: MarketTimeframe) -> bool {
+        self.stream_id == stream_id && self.timeframe.is_none_or(|wanted| wanted == timeframe)
+    }
+}
+
+fn normalize_window_subscriptions(
+    subscriptions: Vec<MarketWindowSubscription>,
+) -> Result<Vec<MarketWindowSubscription>, AppError> {
+    if subscriptions.len() > MAX_WINDOW_SUBSCRIPTIONS {
+        return Err(AppError::InvalidArgument(format!(
+            "a window can subscribe to at most {MAX_WINDOW_SUBSCRIPTIONS} streams"
+        )));
+    }
+    let mut normalized: Vec<MarketWindowSubscription> = Vec::with_capacity(subscriptions.len());
+    for subscription in subscriptions {
+        let subscription = MarketWindowSubscription {
+            stream_id: normalize_stream_id(Some(subscription.stream_id))?,
+            timeframe: subscription.timeframe,
+        };
+        if !normalized.contains(&subscription) {
+            normalized.push(subscription);
+        }
+    }
+    Ok(normalized)
+}
+
+/// Opens a detached chart window. Without subscriptions the window gets a stream of its own,
+/// whose id is the window label.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 #[serde(rename_all = "camelCase")]
-pub struct MarketSymbolsArgs {
-    pub market_kind: MarketKind,
+pub struct MarketWindowOpenArgs {
+    #[schemars(length(max = MAX_WINDOW_TITLE_LEN))]
+    pub title: Option<String>,
+    #[serde(default)]
+    #[schemars(length(max = MAX_WINDOW_SUBSCRIPTIONS))]
+    pub subscriptions: Vec<MarketWindowSubscription>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct MarketPreferencesSnapshot {
-    pub market_kind: MarketKind,
-    pub symbol: String,
-    pub timeframe: MarketTimeframe,
-    pub magnet_strong: bool,
-    pub updated_at_ms: i64,
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarketWindowOpenRequest {
+    pub title: Option<String>,
+    pub subscriptions: Vec<MarketWindowSubscription>,
+}
+
+impl MarketWindowOpenArgs {
+    pub fn normalize(self) -> Result<MarketWindowOpenRequest, AppError> {
+        let title = self
+            .title
+            .map(|title| title.trim().to_string())
+            .filter(|title| !title.is_empty());
+        if title
+            .as_ref()
+            .is_some_and(|title| title.chars().count() > MAX_WINDOW_TITLE_LEN)
+        {
+            return Err(AppError::InvalidArgument(format!(
+                "window title must be at most {MAX_WINDOW_TITLE_LEN} characters"
+            )));
+        }
+        Ok(MarketWindowOpenRequest {
+            title,
+            subscriptions: normalize_window_subscriptions(self.subscriptions)?,
+        })
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Replaces the calling window's subscriptions.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 #[serde(rename_all = "camelCase")]
-pub struct SaveMarketPreferencesArgs {
-    pub market_kind: MarketKind,
-    pub symbol: String,
-    pub timeframe: MarketTimeframe,
-    pub magnet_strong: bool,
+pub struct MarketWindowSubscribeArgs {
+    #[schemars(length(max = MAX_WINDOW_SUBSCRIPTIONS))]
+    pub subscriptions: Vec<MarketWindowSubscription>,
 }
 
-impl SaveMarketPreferencesArgs {
-    pub fn normalize(self) -> Result<Self, AppError> {
-        Ok(Self {
-            market_kind: self.market_kind,
-            symbol: normalize_symbol(self.symbol)?,
-            timeframe: self.timeframe,
-            magnet_strong: self.magnet_strong,
-        })
+impl MarketWindowSubscribeArgs {
+    pub fn normalize(self) -> Result<Vec<MarketWindowSubscription>, AppError> {
+        normalize_window_subscriptions(self.subscriptions)
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
 #[serde(rename_all = "camelCase")]
-pub struct MarketDrawingsScopeArgs {
-    pub market_kind: MarketKind,
-    pub symbol: String,
-    pub timeframe: MarketTimeframe,
+pub struct UiMarketWindow {
+    pub label: String,
+    pub subscriptions: Vec<MarketWindowSubscription>,
 }
 
-impl MarketDrawingsScopeArgs {
-    pub fn normalize(self) -> Result<Self, AppError> {
-        Ok(Self {
-            market_kind: self.market_kind,
-            symbol: normalize_symbol(self.symbol)?,
-            timeframe: self.timeframe,
-        })
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MarketTradeSide {
+    Buy,
+    Sell,
+}
+
+impl MarketTradeSide {
+    pub fn from_is_buyer_maker(is_buyer_maker: bool) -> Self {
+        if is_buyer_maker {
+            Self::Sell
+        } else {
+            Self::Buy
+        }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 #[serde(rename_all = "camelCase")]
-pub struct MarketDrawingDto {
-    pub id: String,
-    pub market_kind: MarketKind,
-    pub symbol: String,
-    pub timeframe: MarketTimeframe,
-    pub drawing_type: String,
-    pub color: String,
-    pub label: Option<String>,
-    pub payload_json: String,
-    pub created_at_ms: i64,
-    pub updated_at_ms: i64,
+pub struct MarketTradesRecentArgs {
+    #[schemars(regex(pattern = STREAM_ID_PATTERN))]
+    pub stream_id: Option<String>,
+    #[schemars(range(min = 1, max = TRADE_TAPE_CAPACITY))]
+    pub limit: Option<u32>,
+    #[schemars(range(min = 0.0))]
+    pub min_notional: Option<f64>,
+    pub side: Option<MarketTradeSide>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct MarketDrawingUpsertArgs {
-    pub id: String,
-    pub market_kind: MarketKind,
-    pub symbol: String,
-    pub timeframe: MarketTimeframe,
-    pub drawing_type: String,
-    pub color: String,
-    pub label: Option<String>,
-    pub payload_json: String,
-    pub created_at_ms: Option<i64>,
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TradesRecentQuery {
+    pub limit: usize,
+    pub min_notional: f64,
+    pub side: Option<MarketTradeSide>,
 }
 
-impl MarketDrawingUpsertArgs {
-    pub fn normalize(self) -> Result<Self, AppError> {
-        let id = self.id.trim().to_string();
-        if id.is_empty() {
-            return Err(AppError::InvalidArgument(
-                "drawing id must be non-empty".to_string(),
-            ));
+impl MarketTradesRecentArgs {
+    pub fn normalize(self) -> Result<TradesRecentQuery, AppError> {
+        let limit = self.limit.unwrap_or(DEFAULT_TRADES_RECENT_LIMIT);
+        if limit == 0 || limit as usize > TRADE_TAPE_CAPACITY {
+            return Err(AppError::InvalidArgument(format!(
+                "trades limit must be between 1 and {TRADE_TAPE_CAPACITY}"
+            )));
         }
 
-        let drawing_type = self.drawing_type.trim().to_string();
-        validate_drawing_type(&drawing_type)?;
-
-        let payload_json = self.payload_json.trim().to_string();
-        if payload_json.is_empty() {
+        let min_notional = self.min_notional.unwrap_or(0.0);
+        if !min_notional.is_finite() || min_notional < 0.0 {
             return Err(AppError::InvalidArgument(
-                "payloadJson must be non-empty".to_string(),
+                "minNotional must be a finite value >= 0".to_string(),
             ));
         }
 
-        Ok(Self {
-            id,
-            market_kind: self.market_kind,
-            symbol: normalize_symbol(self.symbol)?,
-            timeframe: self.timeframe,
-            drawing_type,
-            color: normalize_color(self.color)?,
-            label: normalize_optional_label(self.label)?,
-            payload_json,
-            created_at_ms: self.created_at_ms,
+        Ok(TradesRecentQuery {
+            limit: limit as usize,
+            min_notional,
+            side: self.side,
         })
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// One raw aggTrade print for the time & sales tape. `t` is the exchange trade time in ms.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
 #[serde(rename_all = "camelCase")]
-pub struct MarketDrawingDeleteArgs {
-    pub id: String,
-    pub market_kind: MarketKind,
-    pub symbol: String,
-    pub timeframe: MarketTimeframe,
+pub struct UiTapeTrade {
+    pub id: u64,
+    pub t: i64,
+    pub p: f64,
+    pub q: f64,
+    pub side: MarketTradeSide,
 }
 
-impl MarketDrawingDeleteArgs {
-    pub fn normalize(self) -> Result<Self, AppError> {
-        let id = self.id.trim().to_string();
-        if id.is_empty() {
-            return Err(AppError::InvalidArgument(
-                "drawing id must be non-empty".to_string(),
-            ));
-        }
-
-        Ok(Self {
-            id,
-            market_kind: self.market_kind,
-            symbol: normalize_symbol(self.symbol)?,
-            timeframe: self.timeframe,
-        })
+impl UiTapeTrade {
+    pub fn notional(&self) -> f64 {
+        self.p * self.q
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
 #[serde(rename_all = "camelCase")]
-pub struct MarketDrawingDeleteResult {
-    pub deleted: bool,
+pub struct UiTradesSnapshot {
+    pub market_kind: MarketKind,
+    pub symbol: String,
+    pub trades: Vec<UiTapeTrade>,
+}
+
+/// Trades printed since the previous tape event, oldest first. `dropped` counts prints that
+/// overflowed the tape buffer before the UI could be sent them.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UiTradeTapeAppend {
+    pub market_kind: MarketKind,
+    pub symbol: String,
+    pub trades: Vec<UiTapeTrade>,
+    pub dropped: u64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct UiTick {
     pub t: i64,
     pub p: f64,
+    #[schemars(range(min = 0.0))]
     pub v: f64,
+    #[schemars(extend("enum" = [-1, 0, 1]))]
     pub d: i8,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct UiMarketFrameUpdate {
     pub tick: Option<UiTick>,
@@ -542,7 +2046,7 @@ pub struct UiMarketFrameUpdate {
     pub local_pipeline_latency_ms: Option<i64>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct UiCandle {
     pub t: i64,
@@ -550,6 +2054,7 @@ pub struct UiCandle {
     pub h: f64,
     pub l: f64,
     pub c: f64,
+    #[schemars(range(min = 0.0))]
     pub v: f64,
 }
 
@@ -573,7 +2078,7 @@ impl UiCandle {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct UiDeltaCandle {
     pub t: i64,
@@ -581,6 +2086,7 @@ pub struct UiDeltaCandle {
     pub h: f64,
     pub l: f64,
     pub c: f64,
+    #[schemars(range(min = 0.0))]
     pub v: f64,
 }
 
@@ -610,7 +2116,7 @@ impl UiDeltaCandle {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UiCandlesBootstrap {
     pub symbol: String,
@@ -618,7 +2124,7 @@ pub struct UiCandlesBootstrap {
     pub candles: Vec<UiCandle>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UiDeltaCandlesBootstrap {
     pub symbol: String,
@@ -626,7 +2132,78 @@ pub struct UiDeltaCandlesBootstrap {
     pub candles: Vec<UiDeltaCandle>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Exchange-authoritative candles replacing buckets the live stream built incorrectly.
+/// The UI upserts them by `t`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UiCandlesRepair {
+    pub symbol: String,
+    pub timeframe: MarketTimeframe,
+    pub candles: Vec<UiCandle>,
+    pub delta_candles: Vec<UiDeltaCandle>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketHistoryPageArgs {
+    pub market_kind: MarketKind,
+    #[schemars(length(min = 1))]
+    pub symbol: String,
+    pub timeframe: MarketTimeframe,
+    #[schemars(range(min = 1))]
+    pub before_t: i64,
+    #[schemars(range(min = 1, max = MAX_HISTORY_PAGE_LIMIT))]
+    pub limit: Option<u16>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryPageQuery {
+    pub market_kind: MarketKind,
+    pub symbol: String,
+    pub timeframe: MarketTimeframe,
+    pub before_t: i64,
+    pub limit: u16,
+}
+
+impl MarketHistoryPageArgs {
+    pub fn normalize(self) -> Result<HistoryPageQuery, AppError> {
+        let limit = self.limit.unwrap_or(DEFAULT_HISTORY_PAGE_LIMIT);
+        if limit == 0 || limit > MAX_HISTORY_PAGE_LIMIT {
+            return Err(AppError::InvalidArgument(format!(
+                "history page limit must be between 1 and {MAX_HISTORY_PAGE_LIMIT}"
+            )));
+        }
+        if self.before_t <= 0 {
+            return Err(AppError::InvalidArgument(
+                "beforeT must be a positive unix ms timestamp".to_string(),
+            ));
+        }
+
+        Ok(HistoryPageQuery {
+            market_kind: self.market_kind,
+            symbol: normalize_symbol(self.symbol)?,
+            timeframe: self.timeframe,
+            before_t: self.before_t,
+            limit,
+        })
+    }
+}
+
+/// Candles opening strictly before the requested `beforeT`, oldest first. `hasMore` is false
+/// once the page reaches the first kline the exchange has for the symbol.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UiHistoryPage {
+    pub market_kind: MarketKind,
+    pub symbol: String,
+    pub timeframe: MarketTimeframe,
+    pub candles: Vec<UiCandle>,
+    pub delta_candles: Vec<UiDeltaCandle>,
+    pub has_more: bool,
+    pub from_cache: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UiHistoryLoadProgress {
     pub market_kind: MarketKind,
@@ -639,7 +2216,7 @@ pub struct UiHistoryLoadProgress {
     pub done: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct MarketPerfSnapshot {
     pub t: i64,
@@ -654,6 +2231,31 @@ pub struct MarketPerfSnapshot {
     pub local_pipeline_p99_ms: Option<u32>,
     pub ingest_count: u64,
     pub emit_count: u64,
+    pub reconcile_checks: u64,
+    pub reconcile_divergences: u64,
+    pub last_candle_divergence: Option<MarketCandleDivergence>,
+    /// Current interval between frames; above the configured one while the webview lags
+    /// behind on frame acks.
+    pub emit_interval_ms: u64,
+    pub backpressure_active: bool,
+    pub frames_in_flight: u32,
+    /// Ticks whose pending update was held back and went out merged into a later frame.
+    pub frames_merged: u64,
+    /// Frames that failed to emit.
+    pub frames_dropped: u64,
+}
+
+/// Live-built candle minus the exchange kline for a closed bucket.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketCandleDivergence {
+    pub t: i64,
+    pub open_diff: f64,
+    pub high_diff: f64,
+    pub low_diff: f64,
+    pub close_diff: f64,
+    pub volume_diff: f64,
+    pub delta_diff: f64,
 }
 
 #[derive(Debug, Deserialize)]
@@ -867,6 +2469,7 @@ mod tests {
             .normalize()
             .expect("defaults should be valid");
 
+        assert_eq!(config.stream_id, DEFAULT_MARKET_STREAM_ID);
         assert_eq!(config.market_kind, DEFAULT_MARKET_KIND);
         assert_eq!(config.symbol, DEFAULT_SYMBOL);
         assert_eq!(config.min_notional_usdt, DEFAULT_MIN_NOTIONAL_USDT);
@@ -891,14 +2494,65 @@ mod tests {
         assert!(!config.history_all);
     }
 
+    #[test]
+    fn normalizes_stream_ids_and_window_subscriptions() {
+        assert_eq!(
+            normalize_stream_id(Some(" chart-2 ".to_string())).expect("valid id"),
+            "chart-2"
+        );
+        assert!(normalize_stream_id(Some("a/b".to_string())).is_err());
+        assert!(normalize_stream_id(Some(String::new())).is_err());
+        assert!(normalize_stream_id(Some("x".repeat(MAX_MARKET_STREAM_ID_LEN + 1))).is_err());
+
+        let subscription = MarketWindowSubscription {
+            stream_id: "eth".to_string(),
+            timeframe: Some(MarketTimeframe::H1),
+        };
+        let request = MarketWindowOpenArgs {
+            title: Some("  ".to_string()),
+            subscriptions: vec![subscription.clone(), subscription.clone()],
+        }
+        .normalize()
+        .expect("open args should normalize");
+        assert_eq!(request.title, None);
+        assert_eq!(request.subscriptions, vec![subscription.clone()]);
+        assert!(subscription.matches("eth", MarketTimeframe::H1));
+        assert!(!subscription.matches("eth", MarketTimeframe::M1));
+
+        let too_many = MarketWindowSubscribeArgs {
+            subscriptions: vec![subscription; MAX_WINDOW_SUBSCRIPTIONS + 1],
+        };
+        assert!(too_many.normalize().is_err());
+    }
+
+    #[test]
+    fn normalizes_stream_snapshot_args() {
+        let query = MarketStreamSnapshotArgs::default()
+            .normalize()
+            .expect("defaults should normalize");
+        assert_eq!(query.stream_id, DEFAULT_MARKET_STREAM_ID);
+        assert_eq!(query.history_limit, MAX_SNAPSHOT_HISTORY_LIMIT as usize);
+
+        for history_limit in [0, MAX_SNAPSHOT_HISTORY_LIMIT + 1] {
+            let args = MarketStreamSnapshotArgs {
+                stream_id: None,
+                history_limit: Some(history_limit),
+            };
+            assert!(args.normalize().is_err());
+        }
+    }
+
     #[test]
     fn validates_emit_interval_range() {
         let result = StartMarketStreamArgs {
+            stream_id: None,
             market_kind: Some(MarketKind::Spot),
             symbol: Some("BTCUSDT".to_string()),
             min_notional_usdt: Some(50.0),
             emit_interval_ms: Some(1),
             mock_mode: None,
+            mock_scenario: None,
+            mock_faults: None,
             emit_legacy_price_event: None,
             emit_legacy_frame_events: None,
             perf_telemetry: None,
@@ -907,6 +2561,8 @@ mod tests {
             startup_mode: None,
             history_limit: None,
             history_all: None,
+            record_trades: None,
+            replay: None,
         }
         .normalize();
 
@@ -916,11 +2572,14 @@ mod tests {
     #[test]
     fn keeps_explicit_history_limit_when_not_all() {
         let config = StartMarketStreamArgs {
+            stream_id: None,
             market_kind: Some(MarketKind::Spot),
             symbol: Some("BTCUSDT".to_string()),
             min_notional_usdt: Some(50.0),
             emit_interval_ms: Some(16),
             mock_mode: None,
+            mock_scenario: None,
+            mock_faults: None,
             emit_legacy_price_event: None,
             emit_legacy_frame_events: None,
             perf_telemetry: None,
@@ -929,6 +2588,8 @@ mod tests {
             startup_mode: None,
             history_limit: Some(25_000),
             history_all: Some(false),
+            record_trades: None,
+            replay: None,
         }
         .normalize()
         .expect("history limit should be preserved");
@@ -940,11 +2601,14 @@ mod tests {
     #[test]
     fn history_all_uses_default_chunk_limit() {
         let config = StartMarketStreamArgs {
+            stream_id: None,
             market_kind: Some(MarketKind::Spot),
             symbol: Some("BTCUSDT".to_string()),
             min_notional_usdt: Some(50.0),
             emit_interval_ms: Some(16),
             mock_mode: None,
+            mock_scenario: None,
+            mock_faults: None,
             emit_legacy_price_event: None,
             emit_legacy_frame_events: None,
             perf_telemetry: None,
@@ -953,6 +2617,8 @@ mod tests {
             startup_mode: None,
             history_limit: Some(10),
             history_all: Some(true),
+            record_trades: None,
+            replay: None,
         }
         .normalize()
         .expect("history all should normalize");
@@ -964,11 +2630,14 @@ mod tests {
     #[test]
     fn validates_clock_sync_interval_range() {
         let result = StartMarketStreamArgs {
+            stream_id: None,
             market_kind: Some(MarketKind::Spot),
             symbol: Some("BTCUSDT".to_string()),
             min_notional_usdt: Some(50.0),
             emit_interval_ms: Some(16),
             mock_mode: None,
+            mock_scenario: None,
+            mock_faults: None,
             emit_legacy_price_event: None,
             emit_legacy_frame_events: None,
             perf_telemetry: None,
@@ -977,12 +2646,233 @@ mod tests {
             startup_mode: None,
             history_limit: Some(500),
             history_all: None,
+            record_trades: None,
+            replay: None,
         }
         .normalize();
 
         assert!(result.is_err());
     }
 
+    #[test]
+    fn normalizes_replay_args() {
+        let config = StartMarketStreamArgs {
+            replay: Some(MarketReplayArgs {
+                from_t: Some(1_000),
+                to_t: None,
+                speed: None,
+                start_paused: Some(true),
+            }),
+            ..Default::default()
+        }
+        .normalize()
+        .expect("replay should normalize");
+        let replay = config.replay.expect("replay config");
+        assert_eq!(replay.from_t, 1_000);
+        assert_eq!(replay.speed, DEFAULT_REPLAY_SPEED);
+        assert!(replay.start_paused);
+
+        let combined = StartMarketStreamArgs {
+            mock_mode: Some(true),
+            replay: Some(MarketReplayArgs::default()),
+            ..Default::default()
+        }
+        .normalize();
+        assert!(combined.is_err());
+
+        let backwards = MarketReplayArgs {
+            from_t: Some(2_000),
+            to_t: Some(1_000),
+            ..Default::default()
+        }
+        .normalize();
+        assert!(backwards.is_err());
+
+        let mut payload = br#"{"action":"step"}"#.to_vec();
+        let control: MarketReplayControlArgs =
+            simd_json::serde::from_slice(&mut payload).expect("control should parse");
+        assert_eq!(
+            control.normalize().expect("step should normalize"),
+            MarketReplayControlArgs::Step { count: Some(1) }
+        );
+        assert!(MarketReplayControlArgs::Speed { speed: -1.0 }
+            .normalize()
+            .is_err());
+    }
+
+    #[test]
+    fn normalizes_mock_scenario() {
+        let config = StartMarketStreamArgs {
+            mock_mode: Some(true),
+            ..Default::default()
+        }
+        .normalize()
+        .expect("mock mode should normalize");
+        assert_eq!(config.mock_scenario, Some(MockScenarioConfig::default()));
+
+        let mut payload = br#"{"seed":9,"priceModel":"gbm","tradeSize":{"distribution":"logNormal","median":0.05,"sigma":1.2},"bursts":{"perMinute":2,"durationMs":3000,"multiplier":8}}"#.to_vec();
+        let scenario: MockScenarioArgs =
+            simd_json::serde::from_slice(&mut payload).expect("scenario should parse");
+        let scenario = scenario.normalize().expect("scenario should normalize");
+        assert_eq!(scenario.seed, 9);
+        assert_eq!(scenario.volatility, DEFAULT_MOCK_GBM_VOLATILITY);
+        assert_eq!(
+            scenario.trade_size,
+            MockTradeSize::LogNormal {
+                median: 0.05,
+                sigma: 1.2
+            }
+        );
+
+        let without_mock_mode = StartMarketStreamArgs {
+            mock_scenario: Some(MockScenarioArgs::default()),
+            ..Default::default()
+        }
+        .normalize();
+        assert!(without_mock_mode.is_err());
+
+        let invalid_rate = MockScenarioArgs {
+            trades_per_second: Some(0.0),
+            ..Default::default()
+        }
+        .normalize();
+        assert!(invalid_rate.is_err());
+    }
+
+    #[test]
+    fn normalizes_mock_faults() {
+        let config = StartMarketStreamArgs {
+            mock_mode: Some(true),
+            mock_faults: Some(MockFaultArgs {
+                gap_probability: Some(0.01),
+                latency_ms: Some(40),
+                clock_skew_ms: Some(-250),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+        .normalize()
+        .expect("mock faults should normalize");
+        let faults = config.mock_faults.expect("fault config");
+        assert_eq!(faults.gap_probability, 0.01);
+        assert_eq!(faults.duplicate_probability, 0.0);
+        assert_eq!(faults.clock_skew_ms, -250);
+
+        let without_mock_mode = StartMarketStreamArgs {
+            mock_faults: Some(MockFaultArgs::default()),
+            ..Default::default()
+        }
+        .normalize();
+        assert!(without_mock_mode.is_err());
+
+        let invalid_probability = MockFaultArgs {
+            corrupt_probability: Some(1.5),
+            ..Default::default()
+        }
+        .normalize();
+        assert!(invalid_probability.is_err());
+    }
+
+    #[test]
+    fn parses_and_normalizes_alert_conditions() {
+        let mut payload =
+            br#"{"type":"drawingCross","drawingId":"  line-1 ","direction":"up"}"#.to_vec();
+        let condition: MarketAlertCondition =
+            simd_json::serde::from_slice(&mut payload).expect("condition should parse");
+        assert_eq!(
+            condition.normalize().expect("condition should normalize"),
+            MarketAlertCondition::DrawingCross {
+                drawing_id: "line-1".to_string(),
+                direction: MarketAlertCrossDirection::Up,
+            }
+        );
+
+        let mut payload = br#"{"type":"priceCross","price":65000.5}"#.to_vec();
+        let condition: MarketAlertCondition =
+            simd_json::serde::from_slice(&mut payload).expect("condition should parse");
+        assert_eq!(
+            condition,
+            MarketAlertCondition::PriceCross {
+                price: 65_000.5,
+                direction: MarketAlertCrossDirection::Any,
+            }
+        );
+
+        let invalid_window = MarketAlertCondition::VolumeSpike {
+            threshold: 10.0,
+            window_ms: 10,
+        }
+        .normalize();
+        assert!(invalid_window.is_err());
+    }
+
+    #[test]
+    fn normalizes_alert_upsert_defaults() {
+        let alert = MarketAlertUpsertArgs {
+            id: " alert-1 ".to_string(),
+            market_kind: MarketKind::Spot,
+            symbol: "btcusdt".to_string(),
+            condition: MarketAlertCondition::PercentMove {
+                percent: 1.5,
+                window_ms: 60_000,
+            },
+            label: None,
+            enabled: None,
+            one_shot: None,
+            cooldown_ms: None,
+            notify_os: None,
+            created_at_ms: None,
+        }
+        .normalize()
+        .expect("alert should normalize");
+
+        assert_eq!(alert.id, "alert-1");
+        assert_eq!(alert.symbol, "BTCUSDT");
+        assert!(alert.enabled);
+        assert!(alert.one_shot);
+        assert_eq!(alert.cooldown_ms, DEFAULT_ALERT_COOLDOWN_MS);
+        assert!(!alert.notify_os);
+    }
+
+    #[test]
+    fn normalizes_webhook_target_args() {
+        let target = WebhookTargetUpsertArgs {
+            id: " hook-1 ".to_string(),
+            label: Some(" Team chat ".to_string()),
+            url: " http://127.0.0.1:9000/hook ".to_string(),
+            headers: Some(vec![WebhookHeader {
+                name: " Authorization ".to_string(),
+                value: "Bearer token".to_string(),
+            }]),
+            body_template: Some("   ".to_string()),
+            event_types: None,
+            enabled: None,
+            created_at_ms: None,
+        }
+        .normalize()
+        .expect("webhook target should normalize");
+
+        assert_eq!(target.id, "hook-1");
+        assert_eq!(target.url, "http://127.0.0.1:9000/hook");
+        assert_eq!(target.headers[0].name, "Authorization");
+        assert!(target.body_template.is_none());
+        assert_eq!(target.event_types.len(), WebhookEventType::ALL.len());
+        assert!(target.enabled);
+
+        let invalid_scheme = WebhookTargetUpsertArgs {
+            id: "hook-2".to_string(),
+            label: None,
+            url: "ftp://example.com".to_string(),
+            headers: None,
+            body_template: None,
+            event_types: None,
+            enabled: None,
+            created_at_ms: None,
+        }
+        .normalize();
+        assert!(invalid_scheme.is_err());
+    }
+
     #[test]
     fn normalizes_market_preferences_and_drawings_args() {
         let preferences = SaveMarketPreferencesArgs {
@@ -0,0 +1,801 @@
+use crate::error::AppError;
+use crate::market::pipeline::now_unix_ms;
+use crate::market::types::{
+    MarketAlertTriggerDto, MarketConnectionState, MarketKind, MarketStreamStatusSnapshot,
+    MarketTimeframe, WebhookEventType, WebhookHeader, WebhookOutboxSummary,
+};
+use reqwest::Client;
+use serde::Serialize;
+use sqlx::{Row, SqlitePool};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, Notify};
+
+const WEBHOOK_REQUEST_TIMEOUT_MS: u64 = 10_000;
+const WEBHOOK_DELIVERY_BATCH: i64 = 32;
+const WEBHOOK_IDLE_POLL_MS: i64 = 5_000;
+const WEBHOOK_RETRY_BASE_MS: i64 = 1_000;
+const WEBHOOK_RETRY_MAX_MS: i64 = 600_000;
+const WEBHOOK_MAX_ATTEMPTS: i64 = 12;
+const WEBHOOK_ERROR_MAX_LEN: usize = 500;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookConnectionStatePayload {
+    pub previous_state: MarketConnectionState,
+    pub state: MarketConnectionState,
+    pub market_kind: MarketKind,
+    pub symbol: String,
+    pub timeframe: MarketTimeframe,
+    pub reason: Option<String>,
+    pub changed_at_ms: i64,
+}
+
+#[derive(Debug, Clone)]
+pub enum WebhookEvent {
+    AlertTriggered(MarketAlertTriggerDto),
+    ConnectionState(WebhookConnectionStatePayload),
+}
+
+impl WebhookEvent {
+    pub fn connection_state(
+        previous_state: MarketConnectionState,
+        snapshot: &MarketStreamStatusSnapshot,
+    ) -> Self {
+        Self::ConnectionState(WebhookConnectionStatePayload {
+            previous_state,
+            state: snapshot.state,
+            market_kind: snapshot.market_kind,
+            symbol: snapshot.symbol.clone(),
+            timeframe: snapshot.timeframe,
+            reason: snapshot.reason.clone(),
+            changed_at_ms: now_unix_ms(),
+        })
+    }
+
+    pub fn event_type(&self) -> WebhookEventType {
+        match self {
+            Self::AlertTriggered(_) => WebhookEventType::AlertTriggered,
+            Self::ConnectionState(_) => WebhookEventType::ConnectionState,
+        }
+    }
+
+    fn payload_json(&self) -> Result<String, AppError> {
+        let json = match self {
+            Self::AlertTriggered(payload) => simd_json::serde::to_string(payload)?,
+            Self::ConnectionState(payload) => simd_json::serde::to_string(payload)?,
+        };
+        Ok(json)
+    }
+
+    /// Placeholder values for body templates. String values are JSON-escaped so they can be
+    /// embedded inside quoted template strings; `payload` is the raw JSON object.
+    fn template_vars(&self) -> Result<Vec<(&'static str, String)>, AppError> {
+        let mut vars = vec![
+            ("event", self.event_type().as_str().to_string()),
+            ("payload", self.payload_json()?),
+        ];
+        match self {
+            Self::AlertTriggered(trigger) => {
+                let label = trigger.label.clone().unwrap_or_default();
+                vars.extend([
+                    ("alertId", json_escape(&trigger.alert_id)?),
+                    ("marketKind", trigger.market_kind.as_str().to_string()),
+                    ("symbol", json_escape(&trigger.symbol)?),
+                    ("timeframe", String::new()),
+                    ("conditionType", json_escape(&trigger.condition_type)?),
+                    ("label", json_escape(&label)?),
+                    ("message", json_escape(&trigger.message)?),
+                    ("price", trigger.price.to_string()),
+                    ("state", String::new()),
+                    ("previousState", String::new()),
+                    ("reason", String::new()),
+                    ("timestampMs", trigger.triggered_at_ms.to_string()),
+                ]);
+            }
+            Self::ConnectionState(change) => {
+                let reason = change.reason.clone().unwrap_or_default();
+                let message = match change.reason.as_deref() {
+                    Some(reason) => format!(
+                        "{} {} is {}: {reason}",
+                        change.market_kind.as_str(),
+                        change.symbol,
+                        connection_state_str(change.state)
+                    ),
+                    None => format!(
+                        "{} {} is {}",
+                        change.market_kind.as_str(),
+                        change.symbol,
+                        connection_state_str(change.state)
+                    ),
+                };
+                vars.extend([
+                    ("alertId", String::new()),
+                    ("marketKind", change.market_kind.as_str().to_string()),
+                    ("symbol", json_escape(&change.symbol)?),
+                    ("timeframe", change.timeframe.as_str().to_string()),
+                    ("conditionType", String::new()),
+                    ("label", String::new()),
+                    ("message", json_escape(&message)?),
+                    ("price", "null".to_string()),
+                    ("state", connection_state_str(change.state).to_string()),
+                    (
+                        "previousState",
+                        connection_state_str(change.previous_state).to_string(),
+                    ),
+                    ("reason", json_escape(&reason)?),
+                    ("timestampMs", change.changed_at_ms.to_string()),
+                ]);
+            }
+        }
+        Ok(vars)
+    }
+
+    pub fn render_body(&self, template: Option<&str>) -> Result<String, AppError> {
+        match template {
+            Some(template) => Ok(render_template(template, &self.template_vars()?)),
+            None => Ok(format!(
+                r#"{{"event":"{}","payload":{}}}"#,
+                self.event_type().as_str(),
+                self.payload_json()?
+            )),
+        }
+    }
+}
+
+fn connection_state_str(state: MarketConnectionState) -> &'static str {
+    match state {
+        MarketConnectionState::Connecting => "connecting",
+        MarketConnectionState::Live => "live",
+        MarketConnectionState::Desynced => "desynced",
+        MarketConnectionState::Reconnecting => "reconnecting",
+        MarketConnectionState::Stopped => "stopped",
+        MarketConnectionState::Error => "error",
+    }
+}
+
+/// Connection changes worth leaving the app for: entering a degraded state, and recovering
+/// from one. Routine connecting/reconnecting churn stays local.
+pub fn is_webhook_connection_transition(
+    previous: MarketConnectionState,
+    next: MarketConnectionState,
+) -> bool {
+    if previous == next {
+        return false;
+    }
+    let degraded = |state| {
+        matches!(
+            state,
+            MarketConnectionState::Desynced | MarketConnectionState::Error
+        )
+    };
+    degraded(next) || (degraded(previous) && next == MarketConnectionState::Live)
+}
+
+fn json_escape(value: &str) -> Result<String, AppError> {
+    let quoted = simd_json::serde::to_string(value)?;
+    Ok(quoted[1..quoted.len() - 1].to_string())
+}
+
+fn render_template(template: &str, vars: &[(&str, String)]) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        let after_open = &rest[start + 2..];
+        let Some(end) = after_open.find("}}") else {
+            rendered.push_str(&rest[start..]);
+            return rendered;
+        };
+        let key = after_open[..end].trim();
+        match vars.iter().find(|(name, _)| *name == key) {
+            Some((_, value)) => rendered.push_str(value),
+            None => rendered.push_str(&rest[start..start + end + 4]),
+        }
+        rest = &after_open[end + 2..];
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+fn sample_event(event_type: WebhookEventType) -> WebhookEvent {
+    match event_type {
+        WebhookEventType::AlertTriggered => WebhookEvent::AlertTriggered(MarketAlertTriggerDto {
+            id: 0,
+            alert_id: "sample".to_string(),
+            market_kind: MarketKind::Spot,
+            symbol: "BTCUSDT".to_string(),
+            condition_type: "priceCross".to_string(),
+            label: Some("sample \"label\"".to_string()),
+            price: 1.0,
+            message: "sample message".to_string(),
+            triggered_at_ms: 0,
+        }),
+        WebhookEventType::ConnectionState => {
+            WebhookEvent::ConnectionState(WebhookConnectionStatePayload {
+                previous_state: MarketConnectionState::Live,
+                state: MarketConnectionState::Desynced,
+                market_kind: MarketKind::Spot,
+                symbol: "BTCUSDT".to_string(),
+                timeframe: MarketTimeframe::M1,
+                reason: Some("sample reason".to_string()),
+                changed_at_ms: 0,
+            })
+        }
+    }
+}
+
+/// Rejects templates that reference unknown placeholders or do not render to valid JSON for
+/// every subscribed event type.
+pub fn validate_body_template(
+    template: &str,
+    event_types: &[WebhookEventType],
+) -> Result<(), AppError> {
+    for event_type in event_types {
+        let vars = sample_event(*event_type).template_vars()?;
+        let rendered = render_template(template, &vars);
+        if let Some(start) = rendered.find("{{") {
+            let placeholder: String = rendered[start..].chars().take(40).collect();
+            return Err(AppError::InvalidArgument(format!(
+                "webhook bodyTemplate has an unknown placeholder near '{placeholder}'"
+            )));
+        }
+        let mut bytes = rendered.into_bytes();
+        simd_json::to_owned_value(&mut bytes).map_err(|error| {
+            AppError::InvalidArgument(format!(
+                "webhook bodyTemplate does not render valid JSON for {}: {error}",
+                event_type.as_str()
+            ))
+        })?;
+    }
+    Ok(())
+}
+
+pub fn webhook_retry_delay_ms(attempts: i64) -> i64 {
+    let exponent = attempts.clamp(0, 20) as u32;
+    WEBHOOK_RETRY_BASE_MS
+        .saturating_mul(1_i64 << exponent)
+        .min(WEBHOOK_RETRY_MAX_MS)
+}
+
+/// Renders the event for every enabled target subscribed to it and stores the bodies in the
+/// outbox, so a restart never drops an undelivered event.
+pub async fn enqueue_webhook_event(
+    pool: &SqlitePool,
+    event: &WebhookEvent,
+    now_ms: i64,
+) -> Result<u64, AppError> {
+    let event_type = event.event_type();
+    let rows = sqlx::query(
+        "SELECT id, body_template, event_types_json FROM webhook_targets WHERE enabled = 1",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut enqueued = 0_u64;
+    for row in rows {
+        let event_types_json: String = row.try_get("event_types_json")?;
+        let mut event_types_bytes = event_types_json.into_bytes();
+        let event_types: Vec<WebhookEventType> =
+            simd_json::serde::from_slice(&mut event_types_bytes)?;
+        if !event_types.contains(&event_type) {
+            continue;
+        }
+
+        let target_id: String = row.try_get("id")?;
+        let body_template: Option<String> = row.try_get("body_template")?;
+        let body = event.render_body(body_template.as_deref())?;
+        sqlx::query(
+            "INSERT INTO webhook_outbox (target_id, event_type, body, status, attempts, next_attempt_at_ms, created_at_ms) \
+             VALUES (?, ?, ?, 'pending', 0, ?, ?)",
+        )
+        .bind(target_id)
+        .bind(event_type.as_str())
+        .bind(body)
+        .bind(now_ms)
+        .bind(now_ms)
+        .execute(pool)
+        .await?;
+        enqueued = enqueued.saturating_add(1);
+    }
+
+    Ok(enqueued)
+}
+
+struct OutboxEntry {
+    id: i64,
+    body: String,
+    attempts: i64,
+}
+
+struct TargetDeliveries {
+    target_id: String,
+    url: String,
+    headers: Vec<WebhookHeader>,
+    entries: Vec<OutboxEntry>,
+}
+
+/// Attempts every due outbox entry once. Targets are delivered concurrently so a slow or dead
+/// target does not hold up the others; each target gets its entries in outbox order. Returns
+/// the number of attempted deliveries.
+pub async fn deliver_due_webhooks(
+    pool: &SqlitePool,
+    client: &Client,
+    now_ms: i64,
+) -> Result<usize, AppError> {
+    let rows = sqlx::query(
+        "SELECT o.id, o.target_id, o.body, o.attempts, t.url, t.headers_json \
+         FROM webhook_outbox o JOIN webhook_targets t ON t.id = o.target_id \
+         WHERE o.status = 'pending' AND o.next_attempt_at_ms <= ? AND t.enabled = 1 \
+         ORDER BY o.id ASC \
+         LIMIT ?",
+    )
+    .bind(now_ms)
+    .bind(WEBHOOK_DELIVERY_BATCH)
+    .fetch_all(pool)
+    .await?;
+
+    let mut targets: Vec<TargetDeliveries> = Vec::new();
+    for row in &rows {
+        let target_id: String = row.try_get("target_id")?;
+        let entry = OutboxEntry {
+            id: row.try_get("id")?,
+            body: row.try_get("body")?,
+            attempts: row.try_get("attempts")?,
+        };
+        match targets
+            .iter_mut()
+            .find(|target| target.target_id == target_id)
+        {
+            Some(target) => target.entries.push(entry),
+            None => {
+                let headers_json: String = row.try_get("headers_json")?;
+                let mut headers_bytes = headers_json.into_bytes();
+                targets.push(TargetDeliveries {
+                    target_id,
+                    url: row.try_get("url")?,
+                    headers: simd_json::serde::from_slice(&mut headers_bytes)?,
+                    entries: vec![entry],
+                });
+            }
+        }
+    }
+
+    let outcomes = futures_util::future::join_all(
+        targets
+            .into_iter()
+            .map(|target| deliver_target(pool, client, target, now_ms)),
+    )
+    .await;
+    let mut attempted = 0;
+    for outcome in outcomes {
+        attempted += outcome?;
+    }
+    Ok(attempted)
+}
+
+/// Delivers a target's entries in order. The first failure reschedules it and defers the
+/// entries queued behind it, so they are neither sent out of order nor sent into a target
+/// that just failed.
+async fn deliver_target(
+    pool: &SqlitePool,
+    client: &Client,
+    target: TargetDeliveries,
+    now_ms: i64,
+) -> Result<usize, AppError> {
+    let mut attempted = 0;
+    for entry in target.entries {
+        attempted += 1;
+        let error = match post_webhook(client, &target.url, &target.headers, entry.body).await {
+            Ok(()) => {
+                sqlx::query("DELETE FROM webhook_outbox WHERE id = ?")
+                    .bind(entry.id)
+                    .execute(pool)
+                    .await?;
+                continue;
+            }
+            Err(error) => error,
+        };
+
+        let attempts = entry.attempts.saturating_add(1);
+        let status = if attempts >= WEBHOOK_MAX_ATTEMPTS {
+            "failed"
+        } else {
+            "pending"
+        };
+        let next_attempt_at_ms = now_ms.saturating_add(webhook_retry_delay_ms(attempts));
+        let mut message = error.to_string();
+        message.truncate(WEBHOOK_ERROR_MAX_LEN);
+        sqlx::query(
+            "UPDATE webhook_outbox SET status = ?, attempts = ?, next_attempt_at_ms = ?, last_error = ? WHERE id = ?",
+        )
+        .bind(status)
+        .bind(attempts)
+        .bind(next_attempt_at_ms)
+        .bind(message)
+        .bind(entry.id)
+        .execute(pool)
+        .await?;
+        sqlx::query(
+            "UPDATE webhook_outbox SET next_attempt_at_ms = MAX(next_attempt_at_ms, ?) \
+             WHERE target_id = ? AND status = 'pending' AND id > ?",
+        )
+        .bind(next_attempt_at_ms)
+        .bind(&target.target_id)
+        .bind(entry.id)
+        .execute(pool)
+        .await?;
+        break;
+    }
+    Ok(attempted)
+}
+
+async fn post_webhook(
+    client: &Client,
+    url: &str,
+    headers: &[WebhookHeader],
+    body: String,
+) -> Result<(), AppError> {
+    let mut request = client
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(body);
+    for header in headers {
+        request = request.header(header.name.as_str(), header.value.as_str());
+    }
+    request.send().await?.error_for_status()?;
+    Ok(())
+}
+
+async fn next_webhook_due_at(pool: &SqlitePool) -> Result<Option<i64>, AppError> {
+    let next_due = sqlx::query_scalar::<_, Option<i64>>(
+        "SELECT MIN(o.next_attempt_at_ms) FROM webhook_outbox o \
+         JOIN webhook_targets t ON t.id = o.target_id \
+         WHERE o.status = 'pending' AND t.enabled = 1",
+    )
+    .fetch_one(pool)
+    .await?;
+    Ok(next_due)
+}
+
+pub async fn webhook_outbox_summary(pool: &SqlitePool) -> Result<WebhookOutboxSummary, AppError> {
+    let row = sqlx::query(
+        "SELECT \
+           COALESCE(SUM(CASE WHEN status = 'pending' THEN 1 ELSE 0 END), 0) AS pending, \
+           COALESCE(SUM(CASE WHEN status = 'failed' THEN 1 ELSE 0 END), 0) AS failed, \
+           MIN(CASE WHEN status = 'pending' THEN created_at_ms END) AS oldest_pending_at_ms \
+         FROM webhook_outbox",
+    )
+    .fetch_one(pool)
+    .await?;
+    let last_error = sqlx::query_scalar::<_, Option<String>>(
+        "SELECT last_error FROM webhook_outbox WHERE last_error IS NOT NULL ORDER BY id DESC LIMIT 1",
+    )
+    .fetch_optional(pool)
+    .await?
+    .flatten();
+
+    Ok(WebhookOutboxSummary {
+        pending: row.try_get("pending")?,
+        failed: row.try_get("failed")?,
+        oldest_pending_at_ms: row.try_get("oldest_pending_at_ms")?,
+        last_error,
+    })
+}
+
+pub async fn requeue_failed_webhooks(pool: &SqlitePool, now_ms: i64) -> Result<u64, AppError> {
+    let result = sqlx::query(
+        "UPDATE webhook_outbox SET status = 'pending', attempts = 0, next_attempt_at_ms = ? WHERE status = 'failed'",
+    )
+    .bind(now_ms)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+/// Owns the outbox worker. Events are persisted before delivery is attempted; the worker
+/// retries with exponential backoff and resumes pending entries after a restart.
+pub struct WebhookDispatcher {
+    db_pool: SqlitePool,
+    wake: Notify,
+    detached: mpsc::UnboundedSender<WebhookEvent>,
+}
+
+impl WebhookDispatcher {
+    pub fn start(db_pool: SqlitePool) -> Result<Arc<Self>, AppError> {
+        let http_client = Client::builder()
+            .timeout(Duration::from_millis(WEBHOOK_REQUEST_TIMEOUT_MS))
+            .build()?;
+        let (detached, mut detached_events) = mpsc::unbounded_channel::<WebhookEvent>();
+        let dispatcher = Arc::new(Self {
+            db_pool,
+            wake: Notify::new(),
+            detached,
+        });
+
+        let worker = Arc::clone(&dispatcher);
+        tauri::async_runtime::spawn(async move {
+            worker.run_delivery_loop(http_client).await;
+        });
+
+        // One task persists detached events, so they reach the outbox in the order they
+        // happened.
+        let enqueuer = Arc::clone(&dispatcher);
+        tauri::async_runtime::spawn(async move {
+            while let Some(event) = detached_events.recv().await {
+                if let Err(error) = enqueuer.enqueue(&event).await {
+                    eprintln!(
+                        "failed to enqueue {} webhook: {error}",
+                        event.event_type().as_str()
+                    );
+                }
+            }
+        });
+
+        Ok(dispatcher)
+    }
+
+    pub async fn enqueue(&self, event: &WebhookEvent) -> Result<(), AppError> {
+        let enqueued = enqueue_webhook_event(&self.db_pool, event, now_unix_ms()).await?;
+        if enqueued > 0 {
+            self.wake.notify_one();
+        }
+        Ok(())
+    }
+
+    /// Fire-and-forget variant for hot paths that must not wait on SQLite. Events keep their
+    /// order.
+    pub fn enqueue_detached(&self, event: WebhookEvent) {
+        if let Err(error) = self.detached.send(event) {
+            eprintln!(
+                "failed to enqueue {} webhook: dispatcher stopped",
+                error.0.event_type().as_str()
+            );
+        }
+    }
+
+    pub fn wake(&self) {
+        self.wake.notify_one();
+    }
+
+    async fn run_delivery_loop(&self, http_client: Client) {
+        loop {
+            if let Err(error) =
+                deliver_due_webhooks(&self.db_pool, &http_client, now_unix_ms()).await
+            {
+                eprintln!("webhook delivery failed: {error}");
+            }
+
+            let sleep_ms = match next_webhook_due_at(&self.db_pool).await {
+                Ok(Some(due_at_ms)) => due_at_ms
+                    .saturating_sub(now_unix_ms())
+                    .clamp(0, WEBHOOK_IDLE_POLL_MS),
+                Ok(None) => WEBHOOK_IDLE_POLL_MS,
+                Err(error) => {
+                    eprintln!("webhook outbox query failed: {error}");
+                    WEBHOOK_IDLE_POLL_MS
+                }
+            };
+
+            tokio::select! {
+                _ = self.wake.notified() => {}
+                _ = tokio::time::sleep(Duration::from_millis(sleep_ms as u64)) => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::run_migrations;
+    use sqlx::sqlite::SqlitePoolOptions;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    async fn test_pool() -> SqlitePool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("in-memory sqlite should initialize");
+        run_migrations(&pool)
+            .await
+            .expect("migrations should apply");
+        pool
+    }
+
+    /// Minimal HTTP stub: answers `responses.len()` requests in order and returns their bodies.
+    fn spawn_stub_server(
+        responses: Vec<&'static str>,
+    ) -> (String, std::thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("stub server should bind");
+        let url = format!("http://{}/hook", listener.local_addr().expect("addr"));
+        let handle = std::thread::spawn(move || {
+            let mut bodies = Vec::new();
+            for status_line in responses {
+                let (mut stream, _) = listener.accept().expect("stub server should accept");
+                let mut buffer = Vec::new();
+                let mut chunk = [0_u8; 1024];
+                let body = loop {
+                    let read = stream.read(&mut chunk).expect("stub server should read");
+                    buffer.extend_from_slice(&chunk[..read]);
+                    let text = String::from_utf8_lossy(&buffer).to_string();
+                    if let Some(header_end) = text.find("\r\n\r\n") {
+                        let content_length = text[..header_end]
+                            .lines()
+                            .find_map(|line| {
+                                let (name, value) = line.split_once(':')?;
+                                name.eq_ignore_ascii_case("content-length")
+                                    .then(|| value.trim().parse::<usize>().ok())
+                                    .flatten()
+                            })
+                            .unwrap_or(0);
+                        if buffer.len() >= header_end + 4 + content_length {
+                            break text[header_end + 4..].to_string();
+                        }
+                    }
+                };
+                bodies.push(body);
+                let response = format!(
+                    "HTTP/1.1 {status_line}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                );
+                stream
+                    .write_all(response.as_bytes())
+                    .expect("stub server should respond");
+            }
+            bodies
+        });
+        (url, handle)
+    }
+
+    async fn insert_target(pool: &SqlitePool, id: &str, url: &str, template: Option<&str>) {
+        sqlx::query(
+            "INSERT INTO webhook_targets (id, label, url, headers_json, body_template, event_types_json, enabled, created_at_ms, updated_at_ms) \
+             VALUES (?, NULL, ?, '[{\"name\":\"X-Test\",\"value\":\"1\"}]', ?, '[\"alert_triggered\"]', 1, 0, 0)",
+        )
+        .bind(id)
+        .bind(url)
+        .bind(template)
+        .execute(pool)
+        .await
+        .expect("target should insert");
+    }
+
+    fn sample_alert_event() -> WebhookEvent {
+        sample_event(WebhookEventType::AlertTriggered)
+    }
+
+    #[test]
+    fn renders_templates_with_escaped_values() {
+        let body = sample_alert_event()
+            .render_body(Some(
+                r#"{"text":"{{symbol}}: {{label}}","price":{{price}},"raw":{{payload}}}"#,
+            ))
+            .expect("template should render");
+        assert!(body.starts_with(r#"{"text":"BTCUSDT: sample \"label\"","price":1,"raw":{"#));
+
+        let mut bytes = body.into_bytes();
+        assert!(simd_json::to_owned_value(&mut bytes).is_ok());
+    }
+
+    #[test]
+    fn validates_templates_against_subscribed_events() {
+        assert!(
+            validate_body_template(r#"{"text":"{{message}}"}"#, &WebhookEventType::ALL).is_ok()
+        );
+        assert!(
+            validate_body_template(r#"{"text":"{{unknown}}"}"#, &WebhookEventType::ALL).is_err()
+        );
+        assert!(validate_body_template(r#"{"text":{{message}}}"#, &WebhookEventType::ALL).is_err());
+    }
+
+    #[test]
+    fn detects_notable_connection_transitions() {
+        use MarketConnectionState::*;
+        assert!(is_webhook_connection_transition(Live, Desynced));
+        assert!(is_webhook_connection_transition(Reconnecting, Error));
+        assert!(is_webhook_connection_transition(Desynced, Live));
+        assert!(!is_webhook_connection_transition(Connecting, Live));
+        assert!(!is_webhook_connection_transition(Error, Error));
+    }
+
+    #[test]
+    fn retry_delay_backs_off_and_caps() {
+        assert_eq!(webhook_retry_delay_ms(0), 1_000);
+        assert_eq!(webhook_retry_delay_ms(3), 8_000);
+        assert_eq!(webhook_retry_delay_ms(40), WEBHOOK_RETRY_MAX_MS);
+    }
+
+    #[tokio::test]
+    async fn delivers_outbox_entries_to_target() {
+        let pool = test_pool().await;
+        let (url, server) = spawn_stub_server(vec!["200 OK"]);
+        insert_target(&pool, "hook-1", &url, Some(r#"{"text":"{{message}}"}"#)).await;
+
+        let enqueued = enqueue_webhook_event(&pool, &sample_alert_event(), 1_000)
+            .await
+            .expect("event should enqueue");
+        assert_eq!(enqueued, 1);
+
+        let ignored = enqueue_webhook_event(
+            &pool,
+            &sample_event(WebhookEventType::ConnectionState),
+            1_000,
+        )
+        .await
+        .expect("unsubscribed event should be skipped");
+        assert_eq!(ignored, 0);
+
+        let attempted = deliver_due_webhooks(&pool, &Client::new(), 1_000)
+            .await
+            .expect("delivery should run");
+        assert_eq!(attempted, 1);
+
+        let bodies = server.join().expect("stub server should finish");
+        assert_eq!(bodies, vec![r#"{"text":"sample message"}"#.to_string()]);
+
+        let summary = webhook_outbox_summary(&pool).await.expect("summary");
+        assert_eq!(summary.pending, 0);
+        assert_eq!(summary.failed, 0);
+    }
+
+    #[tokio::test]
+    async fn keeps_failed_deliveries_pending_with_backoff() {
+        let pool = test_pool().await;
+        let (url, server) = spawn_stub_server(vec!["500 Internal Server Error"]);
+        insert_target(&pool, "hook-1", &url, None).await;
+
+        enqueue_webhook_event(&pool, &sample_alert_event(), 1_000)
+            .await
+            .expect("event should enqueue");
+        deliver_due_webhooks(&pool, &Client::new(), 1_000)
+            .await
+            .expect("delivery should run");
+        let bodies = server.join().expect("stub server should finish");
+        assert!(bodies[0].starts_with(r#"{"event":"alert_triggered","payload":{"#));
+
+        let summary = webhook_outbox_summary(&pool).await.expect("summary");
+        assert_eq!(summary.pending, 1);
+        assert!(summary.last_error.is_some());
+
+        let attempted_early = deliver_due_webhooks(&pool, &Client::new(), 1_500)
+            .await
+            .expect("delivery should run");
+        assert_eq!(attempted_early, 0);
+        assert_eq!(
+            next_webhook_due_at(&pool).await.expect("due"),
+            Some(1_000 + webhook_retry_delay_ms(1))
+        );
+    }
+
+    #[tokio::test]
+    async fn failing_target_defers_its_queue_without_blocking_others() {
+        let pool = test_pool().await;
+        let (dead_url, dead_server) = spawn_stub_server(vec!["503 Service Unavailable"]);
+        let (live_url, live_server) = spawn_stub_server(vec!["200 OK", "200 OK"]);
+        insert_target(&pool, "hook-dead", &dead_url, None).await;
+        insert_target(&pool, "hook-live", &live_url, None).await;
+
+        for _ in 0..2 {
+            enqueue_webhook_event(&pool, &sample_alert_event(), 1_000)
+                .await
+                .expect("event should enqueue");
+        }
+        let attempted = deliver_due_webhooks(&pool, &Client::new(), 1_000)
+            .await
+            .expect("delivery should run");
+        assert_eq!(attempted, 3);
+        assert_eq!(dead_server.join().expect("dead server").len(), 1);
+        assert_eq!(live_server.join().expect("live server").len(), 2);
+
+        let deferred = sqlx::query_scalar::<_, i64>(
+            "SELECT next_attempt_at_ms FROM webhook_outbox WHERE target_id = 'hook-dead' ORDER BY id",
+        )
+        .fetch_all(&pool)
+        .await
+        .expect("outbox rows");
+        let retry_at = 1_000 + webhook_retry_delay_ms(1);
+        assert_eq!(deferred, vec![retry_at, retry_at]);
+    }
+}
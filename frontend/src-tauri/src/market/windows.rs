@@ -0,0 +1,222 @@
+use crate::error::AppError;
+use crate::market::sink::{MarketEventSink, SequencedEvent};
+use crate::market::types::{
+    MarketTimeframe, MarketWindowSubscription, UiMarketWindow, CHART_WINDOW_LABEL_PREFIX,
+    DEFAULT_MARKET_STREAM_ID,
+};
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter};
+
+pub const MAIN_WINDOW_LABEL: &str = "main";
+
+/// Which windows want which streams. The main window starts subscribed to the main stream on
+/// every timeframe, so a single-window app behaves as before.
+pub struct WindowSubscriptions {
+    windows: RwLock<HashMap<String, Vec<MarketWindowSubscription>>>,
+    next_window_id: AtomicU32,
+}
+
+impl Default for WindowSubscriptions {
+    fn default() -> Self {
+        let mut windows = HashMap::new();
+        windows.insert(
+            MAIN_WINDOW_LABEL.to_string(),
+            vec![MarketWindowSubscription {
+                stream_id: DEFAULT_MARKET_STREAM_ID.to_string(),
+                timeframe: None,
+            }],
+        );
+        Self {
+            windows: RwLock::new(windows),
+            next_window_id: AtomicU32::new(1),
+        }
+    }
+}
+
+impl WindowSubscriptions {
+    /// A fresh `chart-N` label. Detached windows only get labels from here, which is what the
+    /// `chart-*` capability grants IPC access to.
+    pub fn next_label(&self) -> String {
+        loop {
+            let id = self.next_window_id.fetch_add(1, Ordering::Relaxed);
+            let label = format!("{CHART_WINDOW_LABEL_PREFIX}{id}");
+            if !self.windows.read().contains_key(&label) {
+                return label;
+            }
+        }
+    }
+
+    pub fn set(&self, label: &str, subscriptions: Vec<MarketWindowSubscription>) -> UiMarketWindow {
+        self.windows
+            .write()
+            .insert(label.to_string(), subscriptions.clone());
+        UiMarketWindow {
+            label: label.to_string(),
+            subscriptions,
+        }
+    }
+
+    pub fn remove(&self, label: &str) -> bool {
+        self.windows.write().remove(label).is_some()
+    }
+
+    pub fn list(&self) -> Vec<UiMarketWindow> {
+        let mut windows: Vec<UiMarketWindow> = self
+            .windows
+            .read()
+            .iter()
+            .map(|(label, subscriptions)| UiMarketWindow {
+                label: label.clone(),
+                subscriptions: subscriptions.clone(),
+            })
+            .collect();
+        windows.sort_by(|left, right| left.label.cmp(&right.label));
+        windows
+    }
+
+    /// Whether any window should receive events from `stream_id` on `timeframe`.
+    pub fn has_subscribers(&self, stream_id: &str, timeframe: MarketTimeframe) -> bool {
+        self.windows.read().values().any(|subscriptions| {
+            subscriptions
+                .iter()
+                .any(|subscription| subscription.matches(stream_id, timeframe))
+        })
+    }
+
+    /// Labels of the windows that should receive events from `stream_id` on `timeframe`.
+    pub fn subscribers(&self, stream_id: &str, timeframe: MarketTimeframe) -> Vec<String> {
+        self.windows
+            .read()
+            .iter()
+            .filter(|(_, subscriptions)| {
+                subscriptions
+                    .iter()
+                    .any(|subscription| subscription.matches(stream_id, timeframe))
+            })
+            .map(|(label, _)| label.clone())
+            .collect()
+    }
+
+    /// Whether any window still subscribes to `stream_id`, on any timeframe.
+    pub fn is_watched(&self, stream_id: &str) -> bool {
+        self.windows.read().values().any(|subscriptions| {
+            subscriptions
+                .iter()
+                .any(|subscription| subscription.stream_id == stream_id)
+        })
+    }
+}
+
+/// Emits one stream's events only to the windows subscribed to it. Subscriptions are looked
+/// up per event, so windows that subscribe or close mid-stream take effect immediately.
+pub struct WindowRouterSink {
+    app: AppHandle,
+    subscriptions: Arc<WindowSubscriptions>,
+    stream_id: String,
+    timeframe: MarketTimeframe,
+}
+
+impl WindowRouterSink {
+    pub fn new(
+        app: AppHandle,
+        subscriptions: Arc<WindowSubscriptions>,
+        stream_id: &str,
+        timeframe: MarketTimeframe,
+    ) -> Self {
+        Self {
+            app,
+            subscriptions,
+            stream_id: stream_id.to_string(),
+            timeframe,
+        }
+    }
+}
+
+impl MarketEventSink for WindowRouterSink {
+    fn publish(&self, event: SequencedEvent<'_>) -> Result<(), AppError> {
+        let mut outcome = Ok(());
+        for label in self
+            .subscriptions
+            .subscribers(&self.stream_id, self.timeframe)
+        {
+            if let Err(error) = self.app.emit_to(label.as_str(), event.name(), event) {
+                if outcome.is_ok() {
+                    outcome = Err(error.into());
+                }
+            }
+        }
+        outcome
+    }
+
+    fn reaches_windows(&self) -> bool {
+        self.subscriptions
+            .has_subscribers(&self.stream_id, self.timeframe)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subscription(
+        stream_id: &str,
+        timeframe: Option<MarketTimeframe>,
+    ) -> MarketWindowSubscription {
+        MarketWindowSubscription {
+            stream_id: stream_id.to_string(),
+            timeframe,
+        }
+    }
+
+    #[test]
+    fn routes_streams_to_subscribed_windows_only() {
+        let subscriptions = WindowSubscriptions::default();
+        let detached = subscriptions.next_label();
+        assert_eq!(detached, "chart-1");
+        subscriptions.set(
+            &detached,
+            vec![
+                subscription("eth", Some(MarketTimeframe::H1)),
+                subscription(DEFAULT_MARKET_STREAM_ID, None),
+            ],
+        );
+
+        let mut main_subscribers =
+            subscriptions.subscribers(DEFAULT_MARKET_STREAM_ID, MarketTimeframe::M1);
+        main_subscribers.sort();
+        assert_eq!(
+            main_subscribers,
+            vec!["chart-1".to_string(), "main".to_string()]
+        );
+        assert_eq!(
+            subscriptions.subscribers("eth", MarketTimeframe::H1),
+            vec!["chart-1".to_string()]
+        );
+        assert!(subscriptions
+            .subscribers("eth", MarketTimeframe::M5)
+            .is_empty());
+        assert!(subscriptions.has_subscribers("eth", MarketTimeframe::H1));
+        assert!(!subscriptions.has_subscribers("eth", MarketTimeframe::M5));
+        assert!(subscriptions.is_watched("eth"));
+    }
+
+    #[test]
+    fn closing_a_window_drops_its_subscriptions() {
+        let subscriptions = WindowSubscriptions::default();
+        let detached = subscriptions.next_label();
+        subscriptions.set(&detached, vec![subscription(&detached, None)]);
+        assert!(subscriptions.is_watched(&detached));
+
+        assert!(subscriptions.remove(&detached));
+        assert!(!subscriptions.is_watched(&detached));
+        assert!(subscriptions
+            .subscribers(&detached, MarketTimeframe::M1)
+            .is_empty());
+        assert!(!subscriptions.remove(&detached));
+        assert_eq!(subscriptions.list().len(), 1);
+        assert_ne!(subscriptions.next_label(), detached);
+    }
+}
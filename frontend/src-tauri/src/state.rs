@@ -1,5 +1,20 @@
-use crate::market::types::{MarketStreamStatusSnapshot, DEFAULT_SYMBOL};
+use crate::market::alerts::AlertEngine;
+use crate::market::backpressure::FrameAcks;
+use crate::market::binance::BinanceClient;
+use crate::market::history_stream::HistoryChunkAcks;
+use crate::market::local_api::MarketApiServer;
+use crate::market::pipeline::ConflatedMarketState;
+use crate::market::replay::ReplayControl;
+use crate::market::sink::EventSequence;
+use crate::market::tape::TradeTape;
+use crate::market::types::{
+    MarketKind, MarketStreamSession, MarketStreamStatusSnapshot, DEFAULT_MARKET_STREAM_ID,
+    DEFAULT_SYMBOL,
+};
+use crate::market::webhooks::WebhookDispatcher;
+use crate::market::windows::WindowSubscriptions;
 use sqlx::SqlitePool;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time::Instant;
 use tokio::sync::{Mutex, RwLock};
@@ -8,27 +23,96 @@ use tokio_util::sync::CancellationToken;
 pub struct MarketStreamHandle {
     pub cancellation_token: CancellationToken,
     pub join_handle: tauri::async_runtime::JoinHandle<()>,
+    pub session: MarketStreamSession,
+}
+
+/// Everything kept per stream id. Status, chart state, event sequence, acks and tape outlive
+/// the running task so a stopped stream still answers `market_stream_status` and
+/// `market_stream_snapshot`.
+pub struct MarketStreamEntry {
+    pub status: Arc<RwLock<MarketStreamStatusSnapshot>>,
+    pub chart_state: Arc<parking_lot::Mutex<ConflatedMarketState>>,
+    pub event_sequence: Arc<EventSequence>,
+    pub frame_acks: Arc<FrameAcks>,
+    pub history_acks: Arc<HistoryChunkAcks>,
+    pub trade_tape: Arc<parking_lot::Mutex<TradeTape>>,
+    pub handle: Option<MarketStreamHandle>,
+    /// Session of a start that passed the conflict checks and has not installed its handle.
+    pub starting: Option<MarketStreamSession>,
+}
+
+impl MarketStreamEntry {
+    pub fn idle() -> Self {
+        Self {
+            status: Arc::new(RwLock::new(idle_status())),
+            chart_state: Arc::default(),
+            event_sequence: Arc::default(),
+            frame_acks: Arc::default(),
+            history_acks: Arc::default(),
+            trade_tape: Arc::new(parking_lot::Mutex::new(TradeTape::default())),
+            handle: None,
+            starting: None,
+        }
+    }
+
+    /// Session of the running or starting stream.
+    pub fn session(&self) -> Option<&MarketStreamSession> {
+        self.handle
+            .as_ref()
+            .map(|handle| &handle.session)
+            .or(self.starting.as_ref())
+    }
+}
+
+fn idle_status() -> MarketStreamStatusSnapshot {
+    MarketStreamStatusSnapshot::stopped(DEFAULT_SYMBOL.to_string(), Some("stream idle".to_string()))
 }
 
 pub struct AppState {
     pub started_at: Instant,
     pub db_pool: SqlitePool,
-    pub market_stream: Mutex<Option<MarketStreamHandle>>,
+    pub binance: BinanceClient,
+    pub market_streams: Mutex<HashMap<String, MarketStreamEntry>>,
+    /// Status of the main stream, which the local market API serves.
     pub market_status: Arc<RwLock<MarketStreamStatusSnapshot>>,
+    pub alert_engine: Arc<AlertEngine>,
+    pub webhooks: Arc<WebhookDispatcher>,
+    pub trade_tape: Arc<parking_lot::Mutex<TradeTape>>,
+    pub replay_control: Arc<ReplayControl>,
+    pub market_api: Arc<MarketApiServer>,
+    pub window_subscriptions: Arc<WindowSubscriptions>,
+    /// Series whose trade recordings an archive import is writing. Taken under
+    /// `market_streams`, where starts reserve their session before opening a recorder, so
+    /// imports and recorders exclude each other.
+    pub trade_imports: parking_lot::Mutex<HashSet<(MarketKind, String)>>,
 }
 
 impl AppState {
-    pub fn new(db_pool: SqlitePool) -> Self {
-        let market_status = MarketStreamStatusSnapshot::stopped(
-            DEFAULT_SYMBOL.to_string(),
-            Some("stream idle".to_string()),
-        );
+    pub fn new(
+        db_pool: SqlitePool,
+        binance: BinanceClient,
+        alert_engine: Arc<AlertEngine>,
+        webhooks: Arc<WebhookDispatcher>,
+    ) -> Self {
+        let main_stream = MarketStreamEntry::idle();
+        let market_status = Arc::clone(&main_stream.status);
+        let trade_tape = Arc::clone(&main_stream.trade_tape);
+        let mut market_streams = HashMap::new();
+        market_streams.insert(DEFAULT_MARKET_STREAM_ID.to_string(), main_stream);
 
         Self {
             started_at: Instant::now(),
             db_pool,
-            market_stream: Mutex::new(None),
-            market_status: Arc::new(RwLock::new(market_status)),
+            binance,
+            market_streams: Mutex::new(market_streams),
+            market_status,
+            alert_engine,
+            webhooks,
+            trade_tape,
+            replay_control: Arc::new(ReplayControl::default()),
+            market_api: Arc::new(MarketApiServer::default()),
+            window_subscriptions: Arc::new(WindowSubscriptions::default()),
+            trade_imports: parking_lot::Mutex::new(HashSet::new()),
         }
     }
 }
@@ -49,7 +49,15 @@ import {
   invokeStartMarketStream,
   invokeStopMarketStream,
 } from "@lib/ipc/invoke";
-import { listenMarketEvents } from "@lib/ipc/market-events";
+import {
+  currentWindowStreamId,
+  listenMarketEvents,
+  upsertCandlesByTime,
+} from "@lib/ipc/market-events";
+import {
+  createHistoryChunkAssembler,
+  invokeStartMarketStreamWithHistoryChunks,
+} from "@lib/ipc/history-chunks";
 import {
   $marketDrawings,
   $marketKind,
@@ -62,9 +70,11 @@ import {
   $marketSymbol,
   $marketTimeframe,
   applyDeltaCandlesBootstrap,
+  applyDeltaCandlesRepair,
   applyMarketPerfSnapshot,
   applyMarketStatus,
   clearMarketSharedCrosshairBySource,
+  prependDeltaCandlesHistory,
   resetMarketStatus,
   setMarketSharedCrosshair,
   setMarketFrontendRenderLatency,
@@ -349,6 +359,15 @@ const toCandleSnapshot = (candle: UiCandle): CandleSnapshot => {
   };
 };
 
+const fromCandleSnapshot = (snapshot: CandleSnapshot): UiCandle => ({
+  t: Number(snapshot.time) * 1_000,
+  o: snapshot.open,
+  h: snapshot.high,
+  l: snapshot.low,
+  c: snapshot.close,
+  v: snapshot.volume,
+});
+
 const findNearestTimestampIndex = (
   timestamps: ReadonlyArray<UTCTimestamp>,
   target: number,
@@ -1792,6 +1811,45 @@ export const MarketPriceChartIsland = () => {
     cancelCurrentDraft();
   };
 
+  const indexedCandles = (): UiCandle[] =>
+    candleTimestampsRef.current.flatMap((time) => {
+      const snapshot = candleSnapshotsRef.current.get(time);
+      return snapshot ? [fromCandleSnapshot(snapshot)] : [];
+    });
+
+  const applyCandlesBootstrap = (candles: ReadonlyArray<UiCandle>) => {
+    const chart = chartRef.current;
+    if (!candleSeriesRef.current) {
+      return;
+    }
+    chart?.priceScale("right").applyOptions({ autoScale: true });
+    const mergedCandles = mergeCandlesByTime(candles, pendingLiveCandlesRef.current);
+    renderCandlesOnSeries(mergedCandles);
+    chart?.timeScale().fitContent();
+    chart?.priceScale("right").applyOptions({ autoScale: false });
+    const range = chart?.timeScale().getVisibleLogicalRange() ?? null;
+    if (range && Number.isFinite(range.from) && Number.isFinite(range.to)) {
+      setMarketVisibleLogicalRange({ from: range.from, to: range.to });
+    }
+    rebuildCandleIndex(mergedCandles);
+    const lastCandle = mergedCandles[mergedCandles.length - 1];
+    if (lastCandle) {
+      updateCurrentPriceLine(lastCandle.c);
+    }
+    hasBootstrapCandlesRef.current = true;
+    pendingLiveCandlesRef.current.clear();
+    requestOverlayRedraw();
+  };
+
+  // Older history chunks land behind what the chart already shows, live candles included,
+  // without moving the viewport.
+  const prependHistoryCandles = (candles: ReadonlyArray<UiCandle>) => {
+    const mergedCandles = upsertCandlesByTime(candles, indexedCandles());
+    renderCandlesOnSeries(mergedCandles);
+    rebuildCandleIndex(mergedCandles);
+    requestOverlayRedraw();
+  };
+
   const resetSeriesForStreamRestart = () => {
     firstCandleTimeRef.current = null;
     candleSnapshotsRef.current = new Map();
@@ -1833,15 +1891,39 @@ export const MarketPriceChartIsland = () => {
       setHistoryLoadWidget(null);
     }
     resetSeriesForStreamRestart();
-    await invokeStartMarketStream({
+    const streamArgs = {
       ...DEFAULT_STREAM_ARGS,
+      streamId: currentWindowStreamId(),
       marketKind: nextMarketKind,
       symbol: nextSymbol,
       timeframe: nextTimeframe,
       historyLimit,
       historyAll,
       mockMode: shouldUseDeterministicMock(),
-    });
+    };
+    if (historyAll) {
+      // Full history arrives as binary chunks, newest first: the first one bootstraps the
+      // chart and the rest are drawn behind it once the last chunk lands.
+      const assembler = createHistoryChunkAssembler();
+      let hasRenderedNewest = false;
+      await invokeStartMarketStreamWithHistoryChunks(streamArgs, (chunk) => {
+        if (!assembler.push(chunk)) {
+          return;
+        }
+        if (!hasRenderedNewest && chunk.candles.length > 0) {
+          hasRenderedNewest = true;
+          applyCandlesBootstrap(chunk.candles);
+          applyDeltaCandlesBootstrap(chunk.deltaCandles);
+          return;
+        }
+        if (chunk.isFinal) {
+          prependHistoryCandles(assembler.candles());
+          prependDeltaCandlesHistory(assembler.deltaCandles());
+        }
+      });
+    } else {
+      await invokeStartMarketStream(streamArgs);
+    }
     hasStartedRef.current = true;
   };
 
@@ -2260,27 +2342,26 @@ export const MarketPriceChartIsland = () => {
           setMarketFrontendRenderLatency(renderLatencyMs);
         },
         onCandlesBootstrap: (payload) => {
-          const chart = chartRef.current;
-          if (!candleSeriesRef.current) {
+          applyCandlesBootstrap(payload.candles);
+        },
+        onCandlesRepair: (payload) => {
+          const isStaleRepair =
+            payload.symbol !== desiredSymbolRef.current ||
+            payload.timeframe !== desiredTimeframeRef.current;
+          if (isStaleRepair || !candleSeriesRef.current) {
             return;
           }
-          chart?.priceScale("right").applyOptions({ autoScale: true });
-          const mergedCandles = mergeCandlesByTime(payload.candles, pendingLiveCandlesRef.current);
-          renderCandlesOnSeries(mergedCandles);
-          chart?.timeScale().fitContent();
-          chart?.priceScale("right").applyOptions({ autoScale: false });
-          const range = chart?.timeScale().getVisibleLogicalRange() ?? null;
-          if (range && Number.isFinite(range.from) && Number.isFinite(range.to)) {
-            setMarketVisibleLogicalRange({ from: range.from, to: range.to });
-          }
-          rebuildCandleIndex(mergedCandles);
-          const lastCandle = mergedCandles[mergedCandles.length - 1];
-          if (lastCandle) {
-            updateCurrentPriceLine(lastCandle.c);
+          if (!hasBootstrapCandlesRef.current) {
+            for (const candle of payload.candles) {
+              pendingLiveCandlesRef.current.set(Number(toUtcTimestamp(candle.t)), candle);
+            }
+          } else if (payload.candles.length > 0) {
+            const repairedCandles = upsertCandlesByTime(indexedCandles(), payload.candles);
+            renderCandlesOnSeries(repairedCandles);
+            rebuildCandleIndex(repairedCandles);
+            requestOverlayRedraw();
           }
-          hasBootstrapCandlesRef.current = true;
-          pendingLiveCandlesRef.current.clear();
-          requestOverlayRedraw();
+          applyDeltaCandlesRepair(payload.deltaCandles);
         },
         onStatus: (status) => {
           const isStaleStatus =
@@ -2369,7 +2450,7 @@ export const MarketPriceChartIsland = () => {
       }
       unlisten?.();
       resetMarketStatus();
-      void invokeStopMarketStream().catch((error) => {
+      void invokeStopMarketStream({ streamId: currentWindowStreamId() }).catch((error) => {
         console.error("No se pudo detener market stream", error);
       });
     };
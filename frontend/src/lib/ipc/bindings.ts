@@ -0,0 +1,937 @@
+// Generated from the Rust IPC types by src-tauri/src/bindings.rs. Do not edit by hand;
+// regenerate with `UPDATE_IPC_BINDINGS=1 cargo test bindings` in src-tauri.
+import { z } from "zod";
+
+/** Variant-specific context serialized next to the error code. Absent fields are omitted. */
+export const appErrorDetailsSchema = z.object({
+  label: z.string().nullable().optional(),
+  retryAfterMs: z.number().int().nonnegative().nullable().optional(),
+  status: z.number().int().nonnegative().max(65_535).nullable().optional(),
+  symbol: z.string().nullable().optional(),
+});
+export type AppErrorDetails = z.infer<typeof appErrorDetailsSchema>;
+
+export const appErrorSchema = z.object({
+  code: z.enum(["invalid_argument", "invalid_symbol", "rate_limited", "http_status", "database", "migration", "io", "network", "websocket", "json_decode", "parse", "archive", "parquet", "window_not_found", "conflict", "unavailable", "timeout", "cancelled", "corrupt_data", "internal", "runtime"]),
+  details: appErrorDetailsSchema.nullable(),
+  message: z.string(),
+  retryable: z.boolean(),
+});
+export type AppError = z.infer<typeof appErrorSchema>;
+
+export const appInfoResponseSchema = z.object({
+  arch: z.string().min(1),
+  identifier: z.string().min(1),
+  platform: z.string().min(1),
+  productName: z.string().min(1),
+  version: z.string().min(1),
+});
+export type AppInfoResponse = z.infer<typeof appInfoResponseSchema>;
+
+export const healthResponseSchema = z.object({
+  db: z.enum(["ok", "error"]),
+  status: z.literal("ok"),
+  uptimeMs: z.number().int().nonnegative(),
+});
+export type HealthResponse = z.infer<typeof healthResponseSchema>;
+
+export const marketAlertCrossDirectionSchema = z.enum(["up", "down", "any"]);
+export type MarketAlertCrossDirection = z.infer<typeof marketAlertCrossDirectionSchema>;
+
+export const marketAlertConditionSchema = z.discriminatedUnion("type", [
+  z.object({
+    direction: marketAlertCrossDirectionSchema,
+    price: z.number().finite().positive(),
+    type: z.literal("priceCross"),
+  }),
+  z.object({
+    percent: z.number().finite().positive().max(100),
+    type: z.literal("percentMove"),
+    windowMs: z.number().int().min(1_000).max(86_400_000),
+  }),
+  z.object({
+    threshold: z.number().finite().positive(),
+    type: z.literal("deltaSpike"),
+    windowMs: z.number().int().min(1_000).max(86_400_000),
+  }),
+  z.object({
+    threshold: z.number().finite().positive(),
+    type: z.literal("volumeSpike"),
+    windowMs: z.number().int().min(1_000).max(86_400_000),
+  }),
+  z.object({
+    direction: marketAlertCrossDirectionSchema,
+    drawingId: z.string().min(1),
+    type: z.literal("drawingCross"),
+  }),
+]);
+export type MarketAlertCondition = z.infer<typeof marketAlertConditionSchema>;
+
+export const marketAlertConditionInputSchema = z.discriminatedUnion("type", [
+  z.object({
+    direction: marketAlertCrossDirectionSchema.optional(),
+    price: z.number().finite().positive(),
+    type: z.literal("priceCross"),
+  }),
+  z.object({
+    percent: z.number().finite().positive().max(100),
+    type: z.literal("percentMove"),
+    windowMs: z.number().int().min(1_000).max(86_400_000),
+  }),
+  z.object({
+    threshold: z.number().finite().positive(),
+    type: z.literal("deltaSpike"),
+    windowMs: z.number().int().min(1_000).max(86_400_000),
+  }),
+  z.object({
+    threshold: z.number().finite().positive(),
+    type: z.literal("volumeSpike"),
+    windowMs: z.number().int().min(1_000).max(86_400_000),
+  }),
+  z.object({
+    direction: marketAlertCrossDirectionSchema.optional(),
+    drawingId: z.string().min(1),
+    type: z.literal("drawingCross"),
+  }),
+]);
+export type MarketAlertConditionInput = z.infer<typeof marketAlertConditionInputSchema>;
+
+export const marketAlertDeleteArgsSchema = z.object({
+  id: z.string().min(1),
+});
+export type MarketAlertDeleteArgs = z.infer<typeof marketAlertDeleteArgsSchema>;
+
+export const marketAlertDeleteResultSchema = z.object({
+  deleted: z.boolean(),
+});
+export type MarketAlertDeleteResult = z.infer<typeof marketAlertDeleteResultSchema>;
+
+export const marketKindSchema = z.enum(["spot", "futures_usdm"]);
+export type MarketKind = z.infer<typeof marketKindSchema>;
+
+export const marketAlertDtoSchema = z.object({
+  condition: marketAlertConditionSchema,
+  cooldownMs: z.number().int(),
+  createdAtMs: z.number().int(),
+  enabled: z.boolean(),
+  id: z.string(),
+  label: z.string().nullable(),
+  lastTriggeredAtMs: z.number().int().nullable(),
+  marketKind: marketKindSchema,
+  notifyOs: z.boolean(),
+  oneShot: z.boolean(),
+  symbol: z.string(),
+  updatedAtMs: z.number().int(),
+});
+export type MarketAlertDto = z.infer<typeof marketAlertDtoSchema>;
+
+export const marketAlertTriggerDtoSchema = z.object({
+  alertId: z.string(),
+  conditionType: z.string(),
+  id: z.number().int(),
+  label: z.string().nullable(),
+  marketKind: marketKindSchema,
+  message: z.string(),
+  price: z.number().finite(),
+  symbol: z.string(),
+  triggeredAtMs: z.number().int(),
+});
+export type MarketAlertTriggerDto = z.infer<typeof marketAlertTriggerDtoSchema>;
+
+export const marketAlertTriggersArgsSchema = z.object({
+  alertId: z.string().nullable().optional(),
+  limit: z.number().int().min(1).max(5_000).nullable().optional(),
+});
+export type MarketAlertTriggersArgs = z.infer<typeof marketAlertTriggersArgsSchema>;
+
+export const marketAlertUpsertArgsSchema = z.object({
+  condition: marketAlertConditionInputSchema,
+  cooldownMs: z.number().int().nonnegative().max(86_400_000).nullable().optional(),
+  createdAtMs: z.number().int().nullable().optional(),
+  enabled: z.boolean().nullable().optional(),
+  id: z.string().min(1),
+  label: z.string().max(120).nullable().optional(),
+  marketKind: marketKindSchema,
+  notifyOs: z.boolean().nullable().optional(),
+  oneShot: z.boolean().nullable().optional(),
+  symbol: z.string().min(1),
+});
+export type MarketAlertUpsertArgs = z.infer<typeof marketAlertUpsertArgsSchema>;
+
+export const marketAlertsListArgsSchema = z.object({
+  marketKind: marketKindSchema.nullable().optional(),
+  symbol: z.string().nullable().optional(),
+});
+export type MarketAlertsListArgs = z.infer<typeof marketAlertsListArgsSchema>;
+
+/** Starts the localhost market API. Port 0 lets the OS pick a free port. */
+export const marketApiStartArgsSchema = z.object({
+  port: z.number().int().nonnegative().max(65_535).nullable().optional(),
+});
+export type MarketApiStartArgs = z.infer<typeof marketApiStartArgsSchema>;
+
+/** Live-built candle minus the exchange kline for a closed bucket. */
+export const marketCandleDivergenceSchema = z.object({
+  closeDiff: z.number().finite(),
+  deltaDiff: z.number().finite(),
+  highDiff: z.number().finite(),
+  lowDiff: z.number().finite(),
+  openDiff: z.number().finite(),
+  t: z.number().int(),
+  volumeDiff: z.number().finite(),
+});
+export type MarketCandleDivergence = z.infer<typeof marketCandleDivergenceSchema>;
+
+export const marketConnectionStateSchema = z.enum(["connecting", "live", "desynced", "reconnecting", "stopped", "error"]);
+export type MarketConnectionState = z.infer<typeof marketConnectionStateSchema>;
+
+export const marketTimeframeSchema = z.enum(["1m", "5m", "1h", "4h", "1d", "1w", "1M"]);
+export type MarketTimeframe = z.infer<typeof marketTimeframeSchema>;
+
+export const marketDrawingDeleteArgsSchema = z.object({
+  id: z.string().min(1),
+  marketKind: marketKindSchema,
+  symbol: z.string().min(1),
+  timeframe: marketTimeframeSchema,
+});
+export type MarketDrawingDeleteArgs = z.infer<typeof marketDrawingDeleteArgsSchema>;
+
+export const marketDrawingDeleteResultSchema = z.object({
+  deleted: z.boolean(),
+});
+export type MarketDrawingDeleteResult = z.infer<typeof marketDrawingDeleteResultSchema>;
+
+export const marketDrawingDtoSchema = z.object({
+  color: z.string().regex(/^#[0-9A-Fa-f]{6}$/),
+  createdAtMs: z.number().int(),
+  drawingType: z.enum(["trendLine", "horizontalLine", "ruler", "fibRetracement", "fibExtension"]),
+  id: z.string().min(1),
+  label: z.string().max(120).nullable(),
+  marketKind: marketKindSchema,
+  payloadJson: z.string().min(1),
+  symbol: z.string().min(1),
+  timeframe: marketTimeframeSchema,
+  updatedAtMs: z.number().int(),
+});
+export type MarketDrawingDto = z.infer<typeof marketDrawingDtoSchema>;
+
+export const marketDrawingUpsertArgsSchema = z.object({
+  color: z.string().regex(/^#[0-9A-Fa-f]{6}$/),
+  createdAtMs: z.number().int().nullable().optional(),
+  drawingType: z.enum(["trendLine", "horizontalLine", "ruler", "fibRetracement", "fibExtension"]),
+  id: z.string().min(1),
+  label: z.string().max(120).nullable().optional(),
+  marketKind: marketKindSchema,
+  payloadJson: z.string().min(1),
+  symbol: z.string().min(1),
+  timeframe: marketTimeframeSchema,
+});
+export type MarketDrawingUpsertArgs = z.infer<typeof marketDrawingUpsertArgsSchema>;
+
+export const marketDrawingsScopeArgsSchema = z.object({
+  marketKind: marketKindSchema,
+  symbol: z.string().min(1),
+  timeframe: marketTimeframeSchema,
+});
+export type MarketDrawingsScopeArgs = z.infer<typeof marketDrawingsScopeArgsSchema>;
+
+/**
+ * Position of an event in the stream that published it. `generation` changes on every
+ * stream start; `seq` counts every event of the generation, of any kind, from 1.
+ */
+export const marketEventSeqSchema = z.object({
+  generation: z.number().int().nonnegative(),
+  seq: z.number().int().nonnegative(),
+});
+export type MarketEventSeq = z.infer<typeof marketEventSeqSchema>;
+
+export const marketExportDatasetSchema = z.enum(["candles", "deltaCandles", "trades"]);
+export type MarketExportDataset = z.infer<typeof marketExportDatasetSchema>;
+
+export const marketExportFormatSchema = z.enum(["csv", "parquet"]);
+export type MarketExportFormat = z.infer<typeof marketExportFormatSchema>;
+
+export const marketExportArgsSchema = z.object({
+  dataset: marketExportDatasetSchema,
+  format: marketExportFormatSchema,
+  fromT: z.number().int().nonnegative(),
+  marketKind: marketKindSchema,
+  path: z.string().min(1),
+  symbol: z.string().min(1),
+  timeframe: marketTimeframeSchema.nullable().optional(),
+  toT: z.number().int().nonnegative(),
+});
+export type MarketExportArgs = z.infer<typeof marketExportArgsSchema>;
+
+/**
+ * Reports that a window applied every `market_frame_update` of `generation` up to `seq`.
+ * The emitter slows down while these lag behind the frames it sends.
+ */
+export const marketFrameAckArgsSchema = z.object({
+  generation: z.number().int().nonnegative(),
+  seq: z.number().int().nonnegative(),
+  streamId: z.string().regex(/^[A-Za-z0-9_-]{1,64}$/).nullable().optional(),
+});
+export type MarketFrameAckArgs = z.infer<typeof marketFrameAckArgsSchema>;
+
+export const marketFrameAckResultSchema = z.object({
+  accepted: z.boolean(),
+});
+export type MarketFrameAckResult = z.infer<typeof marketFrameAckResultSchema>;
+
+export const marketHistoryAckArgsSchema = z.object({
+  bootstrapId: z.number().int().nonnegative(),
+  seq: z.number().int().nonnegative(),
+  streamId: z.string().regex(/^[A-Za-z0-9_-]{1,64}$/).nullable().optional(),
+});
+export type MarketHistoryAckArgs = z.infer<typeof marketHistoryAckArgsSchema>;
+
+export const marketHistoryAckResultSchema = z.object({
+  accepted: z.boolean(),
+});
+export type MarketHistoryAckResult = z.infer<typeof marketHistoryAckResultSchema>;
+
+export const marketHistoryPageArgsSchema = z.object({
+  beforeT: z.number().int().min(1),
+  limit: z.number().int().min(1).max(1_000).nullable().optional(),
+  marketKind: marketKindSchema,
+  symbol: z.string().min(1),
+  timeframe: marketTimeframeSchema,
+});
+export type MarketHistoryPageArgs = z.infer<typeof marketHistoryPageArgsSchema>;
+
+export const marketImportArchivesArgsSchema = z.object({
+  marketKind: marketKindSchema,
+  paths: z.array(z.string().min(1)).min(1).max(1_000),
+});
+export type MarketImportArchivesArgs = z.infer<typeof marketImportArchivesArgsSchema>;
+
+export const marketPerfSnapshotSchema = z.object({
+  applyP50Us: z.number().int().nonnegative().nullable(),
+  applyP95Us: z.number().int().nonnegative().nullable(),
+  applyP99Us: z.number().int().nonnegative().nullable(),
+  backpressureActive: z.boolean(),
+  emitCount: z.number().int().nonnegative(),
+  /**
+   * Current interval between frames; above the configured one while the webview lags
+   * behind on frame acks.
+   */
+  emitIntervalMs: z.number().int().nonnegative(),
+  /** Frames that failed to emit. */
+  framesDropped: z.number().int().nonnegative(),
+  framesInFlight: z.number().int().nonnegative(),
+  /** Ticks whose pending update was held back and went out merged into a later frame. */
+  framesMerged: z.number().int().nonnegative(),
+  ingestCount: z.number().int().nonnegative(),
+  lastCandleDivergence: marketCandleDivergenceSchema.nullable(),
+  localPipelineP50Ms: z.number().int().nonnegative().nullable(),
+  localPipelineP95Ms: z.number().int().nonnegative().nullable(),
+  localPipelineP99Ms: z.number().int().nonnegative().nullable(),
+  parseP50Us: z.number().int().nonnegative().nullable(),
+  parseP95Us: z.number().int().nonnegative().nullable(),
+  parseP99Us: z.number().int().nonnegative().nullable(),
+  reconcileChecks: z.number().int().nonnegative(),
+  reconcileDivergences: z.number().int().nonnegative(),
+  t: z.number().int(),
+});
+export type MarketPerfSnapshot = z.infer<typeof marketPerfSnapshotSchema>;
+
+export const marketPreferencesSnapshotSchema = z.object({
+  magnetStrong: z.boolean(),
+  marketKind: marketKindSchema,
+  symbol: z.string().min(1),
+  timeframe: marketTimeframeSchema,
+  updatedAtMs: z.number().int(),
+});
+export type MarketPreferencesSnapshot = z.infer<typeof marketPreferencesSnapshotSchema>;
+
+/**
+ * Replays recorded trades instead of connecting to the exchange. `speed` is a multiple of
+ * real time; 0 replays as fast as the pipeline can apply trades.
+ */
+export const marketReplayArgsSchema = z.object({
+  fromT: z.number().int().nonnegative().nullable().optional(),
+  speed: z.number().finite().nonnegative().max(1_000).nullable().optional(),
+  startPaused: z.boolean().nullable().optional(),
+  toT: z.number().int().nonnegative().nullable().optional(),
+});
+export type MarketReplayArgs = z.infer<typeof marketReplayArgsSchema>;
+
+export const marketReplayConfigSchema = z.object({
+  fromT: z.number().int().nonnegative(),
+  speed: z.number().finite().nonnegative().max(1_000),
+  startPaused: z.boolean(),
+  toT: z.number().int().nonnegative().nullable(),
+});
+export type MarketReplayConfig = z.infer<typeof marketReplayConfigSchema>;
+
+export const marketReplayControlArgsSchema = z.discriminatedUnion("action", [
+  z.object({
+    action: z.literal("pause"),
+  }),
+  z.object({
+    action: z.literal("resume"),
+  }),
+  z.object({
+    action: z.literal("step"),
+    count: z.number().int().min(1).nullable().optional(),
+  }),
+  z.object({
+    action: z.literal("seek"),
+    t: z.number().int().nonnegative(),
+  }),
+  z.object({
+    action: z.literal("speed"),
+    speed: z.number().finite().nonnegative().max(1_000),
+  }),
+]);
+export type MarketReplayControlArgs = z.infer<typeof marketReplayControlArgsSchema>;
+
+export const marketStartupModeSchema = z.enum(["live_first", "history_first"]);
+export type MarketStartupMode = z.infer<typeof marketStartupModeSchema>;
+
+/** Selects one of the running streams; defaults to the main window's stream. */
+export const marketStreamSelectArgsSchema = z.object({
+  streamId: z.string().regex(/^[A-Za-z0-9_-]{1,64}$/).nullable().optional(),
+});
+export type MarketStreamSelectArgs = z.infer<typeof marketStreamSelectArgsSchema>;
+
+export const mockFaultConfigSchema = z.object({
+  clockSkewMs: z.number().int(),
+  corruptProbability: z.number().finite().nonnegative().max(1),
+  disconnectProbability: z.number().finite().nonnegative().max(1),
+  duplicateProbability: z.number().finite().nonnegative().max(1),
+  gapProbability: z.number().finite().nonnegative().max(1),
+  latencyJitterMs: z.number().int().nonnegative(),
+  latencyMs: z.number().int().nonnegative(),
+  outOfOrderProbability: z.number().finite().nonnegative().max(1),
+});
+export type MockFaultConfig = z.infer<typeof mockFaultConfigSchema>;
+
+/**
+ * Episodes start at random, `per_minute` times per simulated minute on average, and scale
+ * volatility (spikes) or the trade rate (bursts, quiet periods) by `multiplier` for
+ * `duration_ms`.
+ */
+export const mockEpisodeSchema = z.object({
+  durationMs: z.number().int().min(1).max(3_600_000),
+  multiplier: z.number().finite().positive().max(1_000),
+  perMinute: z.number().finite().nonnegative().max(600),
+});
+export type MockEpisode = z.infer<typeof mockEpisodeSchema>;
+
+export const mockPriceModelSchema = z.enum(["randomWalk", "gbm"]);
+export type MockPriceModel = z.infer<typeof mockPriceModelSchema>;
+
+export const mockTradeSizeSchema = z.discriminatedUnion("distribution", [
+  z.object({
+    distribution: z.literal("fixed"),
+    quantity: z.number().finite().positive(),
+  }),
+  z.object({
+    distribution: z.literal("exponential"),
+    mean: z.number().finite().positive(),
+  }),
+  z.object({
+    distribution: z.literal("logNormal"),
+    median: z.number().finite().positive(),
+    sigma: z.number().finite().nonnegative().max(5),
+  }),
+]);
+export type MockTradeSize = z.infer<typeof mockTradeSizeSchema>;
+
+export const mockScenarioConfigSchema = z.object({
+  bursts: mockEpisodeSchema.nullable(),
+  drift: z.number().finite(),
+  priceModel: mockPriceModelSchema,
+  quietPeriods: mockEpisodeSchema.nullable(),
+  seed: z.number().int().nonnegative(),
+  startPrice: z.number().finite().positive(),
+  tradeSize: mockTradeSizeSchema,
+  tradesPerSecond: z.number().finite().positive(),
+  volatility: z.number().finite().nonnegative(),
+  volatilitySpikes: mockEpisodeSchema.nullable(),
+});
+export type MockScenarioConfig = z.infer<typeof mockScenarioConfigSchema>;
+
+export const marketStreamSessionSchema = z.object({
+  clockSyncIntervalMs: z.number().int().min(5_000).max(300_000),
+  emitIntervalMs: z.number().int().min(8).max(1_000),
+  emitLegacyFrameEvents: z.boolean(),
+  emitLegacyPriceEvent: z.boolean(),
+  historyAll: z.boolean(),
+  historyLimit: z.number().int().min(1).max(2_000_000),
+  marketKind: marketKindSchema,
+  minNotionalUsdt: z.number().finite().nonnegative(),
+  mockFaults: mockFaultConfigSchema.nullable(),
+  mockMode: z.boolean(),
+  mockScenario: mockScenarioConfigSchema.nullable(),
+  perfTelemetry: z.boolean(),
+  recordTrades: z.boolean(),
+  replay: marketReplayConfigSchema.nullable(),
+  running: z.boolean(),
+  startupMode: marketStartupModeSchema,
+  streamId: z.string().regex(/^[A-Za-z0-9_-]{1,64}$/),
+  symbol: z.string().min(1),
+  timeframe: marketTimeframeSchema,
+});
+export type MarketStreamSession = z.infer<typeof marketStreamSessionSchema>;
+
+/**
+ * Selects a stream and how many of its most recent closed buckets to return; defaults to
+ * every bucket the pipeline keeps.
+ */
+export const marketStreamSnapshotArgsSchema = z.object({
+  historyLimit: z.number().int().min(1).max(50_000).nullable().optional(),
+  streamId: z.string().regex(/^[A-Za-z0-9_-]{1,64}$/).nullable().optional(),
+});
+export type MarketStreamSnapshotArgs = z.infer<typeof marketStreamSnapshotArgsSchema>;
+
+export const marketStreamStatusSnapshotSchema = z.object({
+  adjustedNetworkLatencyMs: z.number().int().nullable(),
+  clockOffsetMs: z.number().int().nullable(),
+  lastAggId: z.number().int().nonnegative().nullable(),
+  latencyMs: z.number().int().nullable(),
+  localPipelineLatencyMs: z.number().int().nullable(),
+  marketKind: marketKindSchema,
+  rawExchangeLatencyMs: z.number().int().nullable(),
+  reason: z.string().nullable(),
+  state: marketConnectionStateSchema,
+  symbol: z.string().min(1),
+  timeframe: marketTimeframeSchema,
+});
+export type MarketStreamStatusSnapshot = z.infer<typeof marketStreamStatusSnapshotSchema>;
+
+export const marketStreamStopResultSchema = z.object({
+  stopped: z.boolean(),
+});
+export type MarketStreamStopResult = z.infer<typeof marketStreamStopResultSchema>;
+
+export const marketSymbolsArgsSchema = z.object({
+  marketKind: marketKindSchema,
+});
+export type MarketSymbolsArgs = z.infer<typeof marketSymbolsArgsSchema>;
+
+export const marketTradeSideSchema = z.enum(["buy", "sell"]);
+export type MarketTradeSide = z.infer<typeof marketTradeSideSchema>;
+
+export const marketTradesRecentArgsSchema = z.object({
+  limit: z.number().int().min(1).max(5_000).nullable().optional(),
+  minNotional: z.number().finite().nonnegative().nullable().optional(),
+  side: marketTradeSideSchema.nullable().optional(),
+  streamId: z.string().regex(/^[A-Za-z0-9_-]{1,64}$/).nullable().optional(),
+});
+export type MarketTradesRecentArgs = z.infer<typeof marketTradesRecentArgsSchema>;
+
+/**
+ * A window receives the events of `stream_id`, optionally only while that stream runs on
+ * `timeframe`.
+ */
+export const marketWindowSubscriptionInputSchema = z.object({
+  streamId: z.string().regex(/^[A-Za-z0-9_-]{1,64}$/),
+  timeframe: marketTimeframeSchema.nullable().optional(),
+});
+export type MarketWindowSubscriptionInput = z.infer<typeof marketWindowSubscriptionInputSchema>;
+
+/**
+ * Opens a detached chart window. Without subscriptions the window gets a stream of its own,
+ * whose id is the window label.
+ */
+export const marketWindowOpenArgsSchema = z.object({
+  subscriptions: z.array(marketWindowSubscriptionInputSchema).max(16).optional(),
+  title: z.string().max(120).nullable().optional(),
+});
+export type MarketWindowOpenArgs = z.infer<typeof marketWindowOpenArgsSchema>;
+
+/** Replaces the calling window's subscriptions. */
+export const marketWindowSubscribeArgsSchema = z.object({
+  subscriptions: z.array(marketWindowSubscriptionInputSchema).max(16),
+});
+export type MarketWindowSubscribeArgs = z.infer<typeof marketWindowSubscribeArgsSchema>;
+
+/**
+ * A window receives the events of `stream_id`, optionally only while that stream runs on
+ * `timeframe`.
+ */
+export const marketWindowSubscriptionSchema = z.object({
+  streamId: z.string().regex(/^[A-Za-z0-9_-]{1,64}$/),
+  timeframe: marketTimeframeSchema.nullable(),
+});
+export type MarketWindowSubscription = z.infer<typeof marketWindowSubscriptionSchema>;
+
+/**
+ * Faults injected into the mock tape. Probabilities apply per generated trade. A faulted mock
+ * stream is fed through the same decode, gap detection and snapshot resync path as the live
+ * exchange stream.
+ */
+export const mockFaultArgsSchema = z.object({
+  clockSkewMs: z.number().int().min(-3_600_000).max(3_600_000).nullable().optional(),
+  corruptProbability: z.number().finite().nonnegative().max(1).nullable().optional(),
+  disconnectProbability: z.number().finite().nonnegative().max(1).nullable().optional(),
+  duplicateProbability: z.number().finite().nonnegative().max(1).nullable().optional(),
+  gapProbability: z.number().finite().nonnegative().max(1).nullable().optional(),
+  latencyJitterMs: z.number().int().nonnegative().max(60_000).nullable().optional(),
+  latencyMs: z.number().int().nonnegative().max(60_000).nullable().optional(),
+  outOfOrderProbability: z.number().finite().nonnegative().max(1).nullable().optional(),
+});
+export type MockFaultArgs = z.infer<typeof mockFaultArgsSchema>;
+
+/**
+ * Seeded synthetic market for mock mode. Omitted fields fall back to a calm random walk
+ * around 100k at roughly one trade every 4ms, so the same seed always yields the same tape.
+ */
+export const mockScenarioArgsSchema = z.object({
+  bursts: mockEpisodeSchema.nullable().optional(),
+  /** Drift per second, in the same units as `volatility`. */
+  drift: z.number().finite().nullable().optional(),
+  priceModel: mockPriceModelSchema.nullable().optional(),
+  quietPeriods: mockEpisodeSchema.nullable().optional(),
+  seed: z.number().int().nonnegative().nullable().optional(),
+  startPrice: z.number().finite().positive().nullable().optional(),
+  tradeSize: mockTradeSizeSchema.nullable().optional(),
+  tradesPerSecond: z.number().finite().positive().max(20_000).nullable().optional(),
+  /**
+   * Absolute price units per sqrt(second) for a random walk, a fraction per sqrt(second)
+   * for GBM.
+   */
+  volatility: z.number().finite().nonnegative().nullable().optional(),
+  volatilitySpikes: mockEpisodeSchema.nullable().optional(),
+});
+export type MockScenarioArgs = z.infer<typeof mockScenarioArgsSchema>;
+
+export const saveMarketPreferencesArgsSchema = z.object({
+  magnetStrong: z.boolean(),
+  marketKind: marketKindSchema,
+  symbol: z.string().min(1),
+  timeframe: marketTimeframeSchema,
+});
+export type SaveMarketPreferencesArgs = z.infer<typeof saveMarketPreferencesArgsSchema>;
+
+export const startMarketStreamArgsSchema = z.object({
+  clockSyncIntervalMs: z.number().int().min(5_000).max(300_000).nullable().optional(),
+  emitIntervalMs: z.number().int().min(8).max(1_000).nullable().optional(),
+  emitLegacyFrameEvents: z.boolean().nullable().optional(),
+  emitLegacyPriceEvent: z.boolean().nullable().optional(),
+  historyAll: z.boolean().nullable().optional(),
+  historyLimit: z.number().int().min(1).max(2_000_000).nullable().optional(),
+  marketKind: marketKindSchema.nullable().optional(),
+  minNotionalUsdt: z.number().finite().nonnegative().nullable().optional(),
+  mockFaults: mockFaultArgsSchema.nullable().optional(),
+  mockMode: z.boolean().nullable().optional(),
+  mockScenario: mockScenarioArgsSchema.nullable().optional(),
+  perfTelemetry: z.boolean().nullable().optional(),
+  recordTrades: z.boolean().nullable().optional(),
+  replay: marketReplayArgsSchema.nullable().optional(),
+  startupMode: marketStartupModeSchema.nullable().optional(),
+  streamId: z.string().regex(/^[A-Za-z0-9_-]{1,64}$/).nullable().optional(),
+  symbol: z.string().min(1).nullable().optional(),
+  timeframe: marketTimeframeSchema.nullable().optional(),
+});
+export type StartMarketStreamArgs = z.infer<typeof startMarketStreamArgsSchema>;
+
+export const uiArchiveImportResultSchema = z.object({
+  archivesImported: z.number().int().nonnegative(),
+  candlesImported: z.number().int().nonnegative(),
+  tradesImported: z.number().int().nonnegative(),
+});
+export type UiArchiveImportResult = z.infer<typeof uiArchiveImportResultSchema>;
+
+export const uiCandleSchema = z.object({
+  c: z.number().finite(),
+  h: z.number().finite(),
+  l: z.number().finite(),
+  o: z.number().finite(),
+  t: z.number().int(),
+  v: z.number().finite().nonnegative(),
+});
+export type UiCandle = z.infer<typeof uiCandleSchema>;
+
+export const uiCandlesBootstrapSchema = z.object({
+  candles: z.array(uiCandleSchema),
+  symbol: z.string(),
+  timeframe: marketTimeframeSchema,
+});
+export type UiCandlesBootstrap = z.infer<typeof uiCandlesBootstrapSchema>;
+
+export const uiDeltaCandleSchema = z.object({
+  c: z.number().finite(),
+  h: z.number().finite(),
+  l: z.number().finite(),
+  o: z.number().finite(),
+  t: z.number().int(),
+  v: z.number().finite().nonnegative(),
+});
+export type UiDeltaCandle = z.infer<typeof uiDeltaCandleSchema>;
+
+/**
+ * Exchange-authoritative candles replacing buckets the live stream built incorrectly.
+ * The UI upserts them by `t`.
+ */
+export const uiCandlesRepairSchema = z.object({
+  candles: z.array(uiCandleSchema),
+  deltaCandles: z.array(uiDeltaCandleSchema),
+  symbol: z.string(),
+  timeframe: marketTimeframeSchema,
+});
+export type UiCandlesRepair = z.infer<typeof uiCandlesRepairSchema>;
+
+export const uiDeltaCandlesBootstrapSchema = z.object({
+  candles: z.array(uiDeltaCandleSchema),
+  symbol: z.string(),
+  timeframe: marketTimeframeSchema,
+});
+export type UiDeltaCandlesBootstrap = z.infer<typeof uiDeltaCandlesBootstrapSchema>;
+
+export const uiExportProgressSchema = z.object({
+  done: z.boolean(),
+  path: z.string().min(1),
+  progressPct: z.number().finite().nonnegative().max(100).nullable(),
+  rowsWritten: z.number().int().nonnegative(),
+});
+export type UiExportProgress = z.infer<typeof uiExportProgressSchema>;
+
+export const uiExportResultSchema = z.object({
+  path: z.string().min(1),
+  rowsWritten: z.number().int().nonnegative(),
+});
+export type UiExportResult = z.infer<typeof uiExportResultSchema>;
+
+export const uiHistoryLoadProgressSchema = z.object({
+  candlesFetched: z.number().int().nonnegative(),
+  done: z.boolean(),
+  estimatedTotalCandles: z.number().int().nonnegative().nullable(),
+  marketKind: marketKindSchema,
+  pagesFetched: z.number().int().nonnegative(),
+  progressPct: z.number().finite().nullable(),
+  symbol: z.string(),
+  timeframe: marketTimeframeSchema,
+});
+export type UiHistoryLoadProgress = z.infer<typeof uiHistoryLoadProgressSchema>;
+
+/**
+ * Candles opening strictly before the requested `beforeT`, oldest first. `hasMore` is false
+ * once the page reaches the first kline the exchange has for the symbol.
+ */
+export const uiHistoryPageSchema = z.object({
+  candles: z.array(uiCandleSchema),
+  deltaCandles: z.array(uiDeltaCandleSchema),
+  fromCache: z.boolean(),
+  hasMore: z.boolean(),
+  marketKind: marketKindSchema,
+  symbol: z.string(),
+  timeframe: marketTimeframeSchema,
+});
+export type UiHistoryPage = z.infer<typeof uiHistoryPageSchema>;
+
+export const uiMarketApiStatusSchema = z.object({
+  address: z.string().nullable(),
+  clients: z.number().int().nonnegative(),
+  running: z.boolean(),
+});
+export type UiMarketApiStatus = z.infer<typeof uiMarketApiStatusSchema>;
+
+export const uiTickSchema = z.object({
+  d: z.union([z.literal(-1), z.literal(0), z.literal(1)]),
+  p: z.number().finite(),
+  t: z.number().int(),
+  v: z.number().finite().nonnegative(),
+});
+export type UiTick = z.infer<typeof uiTickSchema>;
+
+export const uiMarketFrameUpdateSchema = z.object({
+  candle: uiCandleSchema.nullable(),
+  deltaCandle: uiDeltaCandleSchema.nullable(),
+  localPipelineLatencyMs: z.number().int().nullable(),
+  tick: uiTickSchema.nullable(),
+});
+export type UiMarketFrameUpdate = z.infer<typeof uiMarketFrameUpdateSchema>;
+
+/**
+ * Current chart state of a stream, for re-hydrating a reloaded webview without restarting
+ * it. `candles` and `deltaCandles` hold closed buckets oldest first; the open buckets are
+ * `lastCandle` and `lastDeltaCandle`. `session` is null when the stream is not running.
+ * Events of `lastSeq.generation` with a higher `seq` may repeat state already in the
+ * snapshot, but none newer is missing from it.
+ */
+export const uiMarketStreamSnapshotSchema = z.object({
+  candles: z.array(uiCandleSchema),
+  deltaCandles: z.array(uiDeltaCandleSchema),
+  lastAggId: z.number().int().nonnegative().nullable(),
+  lastCandle: uiCandleSchema.nullable(),
+  lastDeltaCandle: uiDeltaCandleSchema.nullable(),
+  lastPrice: z.number().finite().nullable(),
+  lastSeq: marketEventSeqSchema.nullable(),
+  session: marketStreamSessionSchema.nullable(),
+  status: marketStreamStatusSnapshotSchema,
+});
+export type UiMarketStreamSnapshot = z.infer<typeof uiMarketStreamSnapshotSchema>;
+
+export const uiMarketWindowSchema = z.object({
+  label: z.string(),
+  subscriptions: z.array(marketWindowSubscriptionSchema),
+});
+export type UiMarketWindow = z.infer<typeof uiMarketWindowSchema>;
+
+export const uiReplayStatusSchema = z.object({
+  active: z.boolean(),
+  cursorT: z.number().int().nullable(),
+  finished: z.boolean(),
+  paused: z.boolean(),
+  speed: z.number().finite().nonnegative(),
+});
+export type UiReplayStatus = z.infer<typeof uiReplayStatusSchema>;
+
+/** One raw aggTrade print for the time & sales tape. `t` is the exchange trade time in ms. */
+export const uiTapeTradeSchema = z.object({
+  id: z.number().int().nonnegative(),
+  p: z.number().finite(),
+  q: z.number().finite(),
+  side: marketTradeSideSchema,
+  t: z.number().int(),
+});
+export type UiTapeTrade = z.infer<typeof uiTapeTradeSchema>;
+
+/**
+ * Trades printed since the previous tape event, oldest first. `dropped` counts prints that
+ * overflowed the tape buffer before the UI could be sent them.
+ */
+export const uiTradeTapeAppendSchema = z.object({
+  dropped: z.number().int().nonnegative(),
+  marketKind: marketKindSchema,
+  symbol: z.string(),
+  trades: z.array(uiTapeTradeSchema),
+});
+export type UiTradeTapeAppend = z.infer<typeof uiTradeTapeAppendSchema>;
+
+export const uiTradesSnapshotSchema = z.object({
+  marketKind: marketKindSchema,
+  symbol: z.string(),
+  trades: z.array(uiTapeTradeSchema),
+});
+export type UiTradesSnapshot = z.infer<typeof uiTradesSnapshotSchema>;
+
+export const webhookEventTypeSchema = z.enum(["alert_triggered", "connection_state"]);
+export type WebhookEventType = z.infer<typeof webhookEventTypeSchema>;
+
+export const webhookHeaderSchema = z.object({
+  name: z.string().min(1),
+  value: z.string(),
+});
+export type WebhookHeader = z.infer<typeof webhookHeaderSchema>;
+
+export const webhookOutboxRetryResultSchema = z.object({
+  requeued: z.number().int().nonnegative(),
+});
+export type WebhookOutboxRetryResult = z.infer<typeof webhookOutboxRetryResultSchema>;
+
+export const webhookOutboxSummarySchema = z.object({
+  failed: z.number().int(),
+  lastError: z.string().nullable(),
+  oldestPendingAtMs: z.number().int().nullable(),
+  pending: z.number().int(),
+});
+export type WebhookOutboxSummary = z.infer<typeof webhookOutboxSummarySchema>;
+
+export const webhookTargetDeleteArgsSchema = z.object({
+  id: z.string().min(1),
+});
+export type WebhookTargetDeleteArgs = z.infer<typeof webhookTargetDeleteArgsSchema>;
+
+export const webhookTargetDeleteResultSchema = z.object({
+  deleted: z.boolean(),
+});
+export type WebhookTargetDeleteResult = z.infer<typeof webhookTargetDeleteResultSchema>;
+
+export const webhookTargetDtoSchema = z.object({
+  bodyTemplate: z.string().nullable(),
+  createdAtMs: z.number().int(),
+  enabled: z.boolean(),
+  eventTypes: z.array(webhookEventTypeSchema),
+  headers: z.array(webhookHeaderSchema),
+  id: z.string(),
+  label: z.string().nullable(),
+  updatedAtMs: z.number().int(),
+  url: z.string(),
+});
+export type WebhookTargetDto = z.infer<typeof webhookTargetDtoSchema>;
+
+export const webhookTargetUpsertArgsSchema = z.object({
+  bodyTemplate: z.string().max(16_384).nullable().optional(),
+  createdAtMs: z.number().int().nullable().optional(),
+  enabled: z.boolean().nullable().optional(),
+  eventTypes: z.array(webhookEventTypeSchema).nullable().optional(),
+  headers: z.array(webhookHeaderSchema).max(32).nullable().optional(),
+  id: z.string().min(1),
+  label: z.string().max(120).nullable().optional(),
+  url: z.string().min(1),
+});
+export type WebhookTargetUpsertArgs = z.infer<typeof webhookTargetUpsertArgsSchema>;
+
+export const ipcCommands = {
+  health: { args: z.undefined(), result: healthResponseSchema },
+  app_info: { args: z.undefined(), result: appInfoResponseSchema },
+  start_market_stream: { args: startMarketStreamArgsSchema.nullable().optional(), result: marketStreamSessionSchema },
+  stop_market_stream: { args: marketStreamSelectArgsSchema.nullable().optional(), result: marketStreamStopResultSchema },
+  market_stream_status: { args: marketStreamSelectArgsSchema.nullable().optional(), result: marketStreamStatusSnapshotSchema },
+  market_stream_snapshot: { args: marketStreamSnapshotArgsSchema.nullable().optional(), result: uiMarketStreamSnapshotSchema },
+  market_streams_list: { args: z.undefined(), result: z.array(marketStreamSessionSchema) },
+  market_symbols: { args: marketSymbolsArgsSchema, result: z.array(z.string()) },
+  market_spot_symbols: { args: z.undefined(), result: z.array(z.string()) },
+  market_trades_recent: { args: marketTradesRecentArgsSchema.nullable().optional(), result: uiTradesSnapshotSchema },
+  market_history_ack: { args: marketHistoryAckArgsSchema, result: marketHistoryAckResultSchema },
+  market_frame_ack: { args: marketFrameAckArgsSchema, result: marketFrameAckResultSchema },
+  market_history_page: { args: marketHistoryPageArgsSchema, result: uiHistoryPageSchema },
+  market_replay_control: { args: marketReplayControlArgsSchema, result: uiReplayStatusSchema },
+  market_import_archives: { args: marketImportArchivesArgsSchema, result: uiArchiveImportResultSchema },
+  market_export: { args: marketExportArgsSchema, result: uiExportResultSchema },
+  market_window_open: { args: marketWindowOpenArgsSchema.nullable().optional(), result: uiMarketWindowSchema },
+  market_window_subscribe: { args: marketWindowSubscribeArgsSchema, result: uiMarketWindowSchema },
+  market_windows_list: { args: z.undefined(), result: z.array(uiMarketWindowSchema) },
+  market_api_start: { args: marketApiStartArgsSchema.nullable().optional(), result: uiMarketApiStatusSchema },
+  market_api_stop: { args: z.undefined(), result: uiMarketApiStatusSchema },
+  market_api_status: { args: z.undefined(), result: uiMarketApiStatusSchema },
+  market_preferences_get: { args: z.undefined(), result: marketPreferencesSnapshotSchema },
+  market_preferences_save: { args: saveMarketPreferencesArgsSchema, result: marketPreferencesSnapshotSchema },
+  market_drawings_list: { args: marketDrawingsScopeArgsSchema, result: z.array(marketDrawingDtoSchema) },
+  market_drawing_upsert: { args: marketDrawingUpsertArgsSchema, result: marketDrawingDtoSchema },
+  market_drawing_delete: { args: marketDrawingDeleteArgsSchema, result: marketDrawingDeleteResultSchema },
+  market_alerts_list: { args: marketAlertsListArgsSchema.nullable().optional(), result: z.array(marketAlertDtoSchema) },
+  market_alert_upsert: { args: marketAlertUpsertArgsSchema, result: marketAlertDtoSchema },
+  market_alert_delete: { args: marketAlertDeleteArgsSchema, result: marketAlertDeleteResultSchema },
+  market_alert_triggers_list: { args: marketAlertTriggersArgsSchema.nullable().optional(), result: z.array(marketAlertTriggerDtoSchema) },
+  webhook_targets_list: { args: z.undefined(), result: z.array(webhookTargetDtoSchema) },
+  webhook_target_upsert: { args: webhookTargetUpsertArgsSchema, result: webhookTargetDtoSchema },
+  webhook_target_delete: { args: webhookTargetDeleteArgsSchema, result: webhookTargetDeleteResultSchema },
+  webhook_outbox_summary: { args: z.undefined(), result: webhookOutboxSummarySchema },
+  webhook_outbox_retry_failed: { args: z.undefined(), result: webhookOutboxRetryResultSchema },
+} as const;
+
+export const ipcEvents = {
+  price_update: uiTickSchema,
+  market_status: marketStreamStatusSnapshotSchema,
+  market_frame_update: uiMarketFrameUpdateSchema,
+  market_perf: marketPerfSnapshotSchema,
+  candle_update: uiCandleSchema,
+  candles_bootstrap: uiCandlesBootstrapSchema,
+  candles_repair: uiCandlesRepairSchema,
+  delta_candle_update: uiDeltaCandleSchema,
+  delta_candles_bootstrap: uiDeltaCandlesBootstrapSchema,
+  history_load_progress: uiHistoryLoadProgressSchema,
+  archive_import_progress: uiHistoryLoadProgressSchema,
+  export_progress: uiExportProgressSchema,
+  alert_triggered: marketAlertTriggerDtoSchema,
+  trade_tape: uiTradeTapeAppendSchema,
+  replay_status: uiReplayStatusSchema,
+} as const;
+
+export type IpcCommandName = keyof typeof ipcCommands;
+export type IpcArgsMap = {
+  [K in IpcCommandName]: z.infer<(typeof ipcCommands)[K]["args"]>;
+};
+export type IpcResponseMap = {
+  [K in IpcCommandName]: z.infer<(typeof ipcCommands)[K]["result"]>;
+};
+export type IpcEventName = keyof typeof ipcEvents;
+export type IpcEventPayloadMap = {
+  [K in IpcEventName]: z.infer<(typeof ipcEvents)[K]>;
+};
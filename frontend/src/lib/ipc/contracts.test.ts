@@ -1,7 +1,9 @@
 import { describe, expect, it } from "vitest";
 import {
+  appErrorSchema,
   appInfoResponseSchema,
   healthResponseSchema,
+  ipcCommands,
   marketFrameUpdateSchema,
   marketPerfSnapshotSchema,
   marketStatusSchema,
@@ -10,6 +12,18 @@ import {
 } from "./contracts";
 
 describe("ipc contracts", () => {
+  it("accepts a typed app error payload", () => {
+    const parsed = appErrorSchema.parse({
+      code: "rate_limited",
+      message: "rate limited (HTTP 429)",
+      retryable: true,
+      details: { status: 429, retryAfterMs: 12_000 },
+    });
+
+    expect(parsed.details?.retryAfterMs).toBe(12_000);
+    expect(() => appErrorSchema.parse("request error: timeout")).toThrow();
+  });
+
   it("accepts a valid health payload", () => {
     const parsed = healthResponseSchema.parse({
       status: "ok",
@@ -99,7 +113,32 @@ describe("ipc contracts", () => {
       localPipelineP99Ms: 5,
       ingestCount: 1_000,
       emitCount: 200,
+      reconcileChecks: 4,
+      reconcileDivergences: 1,
+      lastCandleDivergence: null,
+      emitIntervalMs: 32,
+      backpressureActive: true,
+      framesInFlight: 3,
+      framesMerged: 12,
+      framesDropped: 0,
     });
     expect(parsed.emitCount).toBe(200);
   });
+
+  it("validates command args with the generated schemas", () => {
+    expect(ipcCommands.health.args.safeParse(undefined).success).toBe(true);
+    expect(ipcCommands.start_market_stream.args.safeParse(undefined).success).toBe(true);
+    expect(ipcCommands.market_symbols.args.safeParse({ marketKind: "spot" }).success).toBe(true);
+    expect(
+      ipcCommands.stop_market_stream.args.safeParse({ streamId: "bad id!" }).success,
+    ).toBe(false);
+    expect(
+      ipcCommands.market_history_page.args.safeParse({
+        marketKind: "spot",
+        symbol: "BTCUSDT",
+        timeframe: "1m",
+        beforeT: 0,
+      }).success,
+    ).toBe(false);
+  });
 });
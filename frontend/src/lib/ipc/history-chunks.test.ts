@@ -0,0 +1,28 @@
+import { describe, expect, it } from "vitest";
+import { createHistoryChunkAssembler, type HistoryChunk } from "./history-chunks";
+
+const chunk = (bootstrapId: number, seq: number, times: number[], isFinal = false) =>
+  ({
+    bootstrapId,
+    seq,
+    isFinal,
+    candles: times.map((t) => ({ t, o: 1, h: 2, l: 0.5, c: 1.5, v: 3 })),
+    deltaCandles: times.map((t) => ({ t, o: 0, h: 1, l: -1, c: 0.5, v: 3 })),
+  }) satisfies HistoryChunk;
+
+describe("history chunks", () => {
+  it("assembles newest-first chunks into oldest-first candles", () => {
+    const assembler = createHistoryChunkAssembler();
+
+    expect(assembler.push(chunk(2, 1, [300, 400]))).toBe(true);
+    expect(assembler.push(chunk(2, 2, [100, 200]))).toBe(true);
+    expect(assembler.push(chunk(1, 3, [0]))).toBe(false);
+    expect(assembler.push(chunk(2, 3, [], true))).toBe(true);
+
+    expect(assembler.candles().map((candle) => candle.t)).toEqual([100, 200, 300, 400]);
+    expect(assembler.deltaCandles().map((candle) => candle.t)).toEqual([100, 200, 300, 400]);
+
+    expect(assembler.push(chunk(3, 1, [500]))).toBe(true);
+    expect(assembler.candles().map((candle) => candle.t)).toEqual([500]);
+  });
+});
@@ -0,0 +1,114 @@
+import { Channel, invoke as tauriInvoke } from "@tauri-apps/api/core";
+import {
+  marketStreamSessionSchema,
+  startMarketStreamArgsSchema,
+  type MarketStreamSession,
+  type StartMarketStreamArgs,
+  type UiCandle,
+  type UiDeltaCandle,
+} from "./contracts";
+
+const HISTORY_CHUNK_MAGIC = "HCK1";
+const HISTORY_CHUNK_HEADER_LEN = 24;
+const HISTORY_CHUNK_FLAG_FINAL = 1;
+
+export type HistoryChunk = {
+  bootstrapId: number;
+  seq: number;
+  isFinal: boolean;
+  candles: UiCandle[];
+  deltaCandles: UiDeltaCandle[];
+};
+
+// Mirrors `encode_history_chunk` in src-tauri/src/market/history_stream.rs.
+export const decodeHistoryChunk = (buffer: ArrayBuffer): HistoryChunk => {
+  const view = new DataView(buffer);
+  const magic = String.fromCharCode(
+    view.getUint8(0),
+    view.getUint8(1),
+    view.getUint8(2),
+    view.getUint8(3),
+  );
+  if (magic !== HISTORY_CHUNK_MAGIC) {
+    throw new Error(`Chunk de historial invalido: ${magic}`);
+  }
+
+  const flags = view.getUint32(4, true);
+  const bootstrapId = Number(view.getBigUint64(8, true));
+  const seq = view.getUint32(16, true);
+  const count = view.getUint32(20, true);
+
+  const times = new BigInt64Array(buffer, HISTORY_CHUNK_HEADER_LEN, count);
+  const column = (index: number): Float64Array =>
+    new Float64Array(buffer, HISTORY_CHUNK_HEADER_LEN + (index + 1) * count * 8, count);
+  const [o, h, l, c, v, deltaH, deltaL, deltaC] = [0, 1, 2, 3, 4, 5, 6, 7].map(column);
+
+  const candles: UiCandle[] = new Array(count);
+  const deltaCandles: UiDeltaCandle[] = new Array(count);
+  for (let row = 0; row < count; row += 1) {
+    const t = Number(times[row]);
+    candles[row] = { t, o: o[row], h: h[row], l: l[row], c: c[row], v: v[row] };
+    deltaCandles[row] = { t, o: 0, h: deltaH[row], l: deltaL[row], c: deltaC[row], v: v[row] };
+  }
+
+  return {
+    bootstrapId,
+    seq,
+    isFinal: (flags & HISTORY_CHUNK_FLAG_FINAL) !== 0,
+    candles,
+    deltaCandles,
+  };
+};
+
+// Starts the stream with history delivered as binary chunks, newest first. Each chunk is
+// acknowledged after `onChunk` returns so the core never runs ahead of the chart.
+export const invokeStartMarketStreamWithHistoryChunks = async (
+  args: StartMarketStreamArgs | undefined,
+  onChunk: (chunk: HistoryChunk) => void,
+): Promise<MarketStreamSession> => {
+  const parsedArgs = args === undefined ? undefined : startMarketStreamArgsSchema.parse(args);
+  const streamId = parsedArgs?.streamId ?? undefined;
+  const historyChannel = new Channel<ArrayBuffer>();
+  historyChannel.onmessage = (message) => {
+    const chunk = decodeHistoryChunk(message);
+    onChunk(chunk);
+    void tauriInvoke("market_history_ack", {
+      args: { streamId, bootstrapId: chunk.bootstrapId, seq: chunk.seq },
+    });
+  };
+
+  const rawResponse = await tauriInvoke<unknown>("start_market_stream", {
+    args: parsedArgs,
+    historyChannel,
+  });
+  return marketStreamSessionSchema.parse(rawResponse);
+};
+
+/**
+ * Collects the chunks of one bootstrap, which arrive newest first, into oldest-first candle
+ * arrays. Chunks of an earlier bootstrap are rejected.
+ */
+export const createHistoryChunkAssembler = () => {
+  let bootstrapId: number | null = null;
+  let chunks: HistoryChunk[] = [];
+
+  return {
+    push(chunk: HistoryChunk): boolean {
+      if (bootstrapId !== null && chunk.bootstrapId < bootstrapId) {
+        return false;
+      }
+      if (chunk.bootstrapId !== bootstrapId) {
+        bootstrapId = chunk.bootstrapId;
+        chunks = [];
+      }
+      chunks.push(chunk);
+      return true;
+    },
+    candles(): UiCandle[] {
+      return [...chunks].reverse().flatMap((chunk) => chunk.candles);
+    },
+    deltaCandles(): UiDeltaCandle[] {
+      return [...chunks].reverse().flatMap((chunk) => chunk.deltaCandles);
+    },
+  };
+};
@@ -1,53 +1,72 @@
 import { invoke as tauriInvoke } from "@tauri-apps/api/core";
 import type { ZodType } from "zod";
 import {
-  appInfoResponseSchema,
-  healthResponseSchema,
-  marketDrawingDeleteArgsSchema,
-  marketDrawingDeleteResultSchema,
-  marketDrawingUpsertArgsSchema,
-  marketDrawingDtoSchema,
-  marketDrawingsScopeArgsSchema,
-  marketSpotSymbolsSchema,
-  marketStatusSchema,
-  marketStreamSessionSchema,
-  marketStreamStopResultSchema,
-  marketSymbolsArgsSchema,
-  marketSymbolsSchema,
-  marketPreferencesSnapshotSchema,
-  saveMarketPreferencesArgsSchema,
-  startMarketStreamArgsSchema,
+  appErrorSchema,
+  ipcCommands,
+  type AppError,
+  type AppErrorCode,
   type AppInfoResponse,
   type HealthResponse,
   type IpcArgsMap,
   type IpcCommandName,
   type IpcResponseMap,
+  type MarketApiStartArgs,
   type MarketDrawingDeleteArgs,
   type MarketDrawingDeleteResult,
   type MarketDrawingDto,
   type MarketDrawingUpsertArgs,
   type MarketDrawingsScopeArgs,
+  type MarketHistoryPageArgs,
+  type MarketExportArgs,
+  type MarketImportArchivesArgs,
   type MarketPreferencesSnapshot,
+  type MarketReplayControlArgs,
   type MarketStatus,
   type MarketSpotSymbols,
   type MarketStreamSession,
+  type MarketStreamSelectArgs,
+  type MarketStreamSnapshotArgs,
   type MarketStreamStopResult,
   type MarketSymbols,
   type MarketSymbolsArgs,
+  type MarketWindowOpenArgs,
+  type MarketWindowSubscribeArgs,
   type SaveMarketPreferencesArgs,
   type StartMarketStreamArgs,
+  type UiArchiveImportResult,
+  type UiExportResult,
+  type UiHistoryPage,
+  type UiMarketApiStatus,
+  type UiMarketStreamSnapshot,
+  type UiMarketWindow,
+  type UiReplayStatus,
 } from "./contracts";
 
 export class IpcInvokeError extends Error {
   command: string;
+  code: AppErrorCode | null;
+  retryable: boolean;
+  details: AppError["details"];
 
-  constructor(command: string, message: string) {
+  constructor(command: string, message: string, error?: AppError) {
     super(message);
     this.name = "IpcInvokeError";
     this.command = command;
+    this.code = error?.code ?? null;
+    this.retryable = error?.retryable ?? false;
+    this.details = error?.details ?? null;
   }
 }
 
+/** Wraps a rejected command in an `IpcInvokeError`, keeping the core's error code when present. */
+export const toIpcInvokeError = (command: string, error: unknown): IpcInvokeError => {
+  const parsed = appErrorSchema.safeParse(error);
+  if (parsed.success) {
+    return new IpcInvokeError(command, parsed.data.message, parsed.data);
+  }
+  return new IpcInvokeError(command, typeof error === "string" ? error : String(error));
+};
+
 const hasTauriRuntime = (): boolean => {
   if (typeof window === "undefined") {
     return false;
@@ -56,43 +75,27 @@ const hasTauriRuntime = (): boolean => {
   return "__TAURI_INTERNALS__" in window;
 };
 
-const invokeTyped = async <K extends IpcCommandName>(
+/** Validates `args` and the response against the schemas generated for `command`. */
+export const invokeIpc = async <K extends IpcCommandName>(
   command: K,
-  schema: ZodType<IpcResponseMap[K]>,
   args: IpcArgsMap[K],
 ): Promise<IpcResponseMap[K]> => {
   if (!hasTauriRuntime()) {
     throw new IpcInvokeError(command, "Tauri runtime no disponible.");
   }
 
-  const rawResponse =
-    args === undefined
-      ? await tauriInvoke<unknown>(command)
-      : await tauriInvoke<unknown>(command, { args });
-  return schema.parse(rawResponse);
-};
-
-const schemasByCommand: Record<IpcCommandName, ZodType<IpcResponseMap[IpcCommandName]>> = {
-  health: healthResponseSchema,
-  app_info: appInfoResponseSchema,
-  start_market_stream: marketStreamSessionSchema,
-  stop_market_stream: marketStreamStopResultSchema,
-  market_stream_status: marketStatusSchema,
-  market_symbols: marketSymbolsSchema,
-  market_spot_symbols: marketSpotSymbolsSchema,
-  market_preferences_get: marketPreferencesSnapshotSchema,
-  market_preferences_save: marketPreferencesSnapshotSchema,
-  market_drawings_list: marketDrawingDtoSchema.array(),
-  market_drawing_upsert: marketDrawingDtoSchema,
-  market_drawing_delete: marketDrawingDeleteResultSchema,
-};
-
-export const invokeIpc = async <K extends IpcCommandName>(
-  command: K,
-  args: IpcArgsMap[K],
-): Promise<IpcResponseMap[K]> => {
-  const schema = schemasByCommand[command] as ZodType<IpcResponseMap[K]>;
-  return invokeTyped(command, schema, args);
+  const { args: argsSchema, result: resultSchema } = ipcCommands[command];
+  const parsedArgs = (argsSchema as ZodType<IpcArgsMap[K]>).parse(args);
+  let rawResponse: unknown;
+  try {
+    rawResponse =
+      parsedArgs === undefined
+        ? await tauriInvoke<unknown>(command)
+        : await tauriInvoke<unknown>(command, { args: parsedArgs });
+  } catch (error) {
+    throw toIpcInvokeError(command, error);
+  }
+  return (resultSchema as ZodType<IpcResponseMap[K]>).parse(rawResponse);
 };
 
 export const invokeHealth = async (): Promise<HealthResponse> => invokeIpc("health", undefined);
@@ -101,21 +104,25 @@ export const invokeAppInfo = async (): Promise<AppInfoResponse> => invokeIpc("ap
 
 export const invokeStartMarketStream = async (
   args?: StartMarketStreamArgs,
-): Promise<MarketStreamSession> => {
-  const parsedArgs = args === undefined ? undefined : startMarketStreamArgsSchema.parse(args);
-  return invokeIpc("start_market_stream", parsedArgs);
-};
+): Promise<MarketStreamSession> => invokeIpc("start_market_stream", args);
 
-export const invokeStopMarketStream = async (): Promise<MarketStreamStopResult> =>
-  invokeIpc("stop_market_stream", undefined);
+export const invokeStopMarketStream = async (
+  args?: MarketStreamSelectArgs,
+): Promise<MarketStreamStopResult> => invokeIpc("stop_market_stream", args);
 
-export const invokeMarketStreamStatus = async (): Promise<MarketStatus> =>
-  invokeIpc("market_stream_status", undefined);
+export const invokeMarketStreamStatus = async (
+  args?: MarketStreamSelectArgs,
+): Promise<MarketStatus> => invokeIpc("market_stream_status", args);
 
-export const invokeMarketSymbols = async (args: MarketSymbolsArgs): Promise<MarketSymbols> => {
-  const parsedArgs = marketSymbolsArgsSchema.parse(args);
-  return invokeIpc("market_symbols", parsedArgs);
-};
+export const invokeMarketStreamsList = async (): Promise<MarketStreamSession[]> =>
+  invokeIpc("market_streams_list", undefined);
+
+export const invokeMarketStreamSnapshot = async (
+  args?: MarketStreamSnapshotArgs,
+): Promise<UiMarketStreamSnapshot> => invokeIpc("market_stream_snapshot", args);
+
+export const invokeMarketSymbols = async (args: MarketSymbolsArgs): Promise<MarketSymbols> =>
+  invokeIpc("market_symbols", args);
 
 export const invokeMarketSpotSymbols = async (): Promise<MarketSpotSymbols> =>
   invokeIpc("market_spot_symbols", undefined);
@@ -125,28 +132,51 @@ export const invokeMarketPreferencesGet = async (): Promise<MarketPreferencesSna
 
 export const invokeMarketPreferencesSave = async (
   args: SaveMarketPreferencesArgs,
-): Promise<MarketPreferencesSnapshot> => {
-  const parsedArgs = saveMarketPreferencesArgsSchema.parse(args);
-  return invokeIpc("market_preferences_save", parsedArgs);
-};
+): Promise<MarketPreferencesSnapshot> => invokeIpc("market_preferences_save", args);
 
 export const invokeMarketDrawingsList = async (
   args: MarketDrawingsScopeArgs,
-): Promise<MarketDrawingDto[]> => {
-  const parsedArgs = marketDrawingsScopeArgsSchema.parse(args);
-  return invokeIpc("market_drawings_list", parsedArgs);
-};
+): Promise<MarketDrawingDto[]> => invokeIpc("market_drawings_list", args);
 
 export const invokeMarketDrawingUpsert = async (
   args: MarketDrawingUpsertArgs,
-): Promise<MarketDrawingDto> => {
-  const parsedArgs = marketDrawingUpsertArgsSchema.parse(args);
-  return invokeIpc("market_drawing_upsert", parsedArgs);
-};
+): Promise<MarketDrawingDto> => invokeIpc("market_drawing_upsert", args);
 
 export const invokeMarketDrawingDelete = async (
   args: MarketDrawingDeleteArgs,
-): Promise<MarketDrawingDeleteResult> => {
-  const parsedArgs = marketDrawingDeleteArgsSchema.parse(args);
-  return invokeIpc("market_drawing_delete", parsedArgs);
-};
+): Promise<MarketDrawingDeleteResult> => invokeIpc("market_drawing_delete", args);
+
+export const invokeMarketHistoryPage = async (
+  args: MarketHistoryPageArgs,
+): Promise<UiHistoryPage> => invokeIpc("market_history_page", args);
+
+export const invokeMarketReplayControl = async (
+  args: MarketReplayControlArgs,
+): Promise<UiReplayStatus> => invokeIpc("market_replay_control", args);
+
+export const invokeMarketImportArchives = async (
+  args: MarketImportArchivesArgs,
+): Promise<UiArchiveImportResult> => invokeIpc("market_import_archives", args);
+
+export const invokeMarketExport = async (args: MarketExportArgs): Promise<UiExportResult> =>
+  invokeIpc("market_export", args);
+
+export const invokeMarketApiStart = async (args?: MarketApiStartArgs): Promise<UiMarketApiStatus> =>
+  invokeIpc("market_api_start", args);
+
+export const invokeMarketApiStop = async (): Promise<UiMarketApiStatus> =>
+  invokeIpc("market_api_stop", undefined);
+
+export const invokeMarketApiStatus = async (): Promise<UiMarketApiStatus> =>
+  invokeIpc("market_api_status", undefined);
+
+export const invokeMarketWindowOpen = async (
+  args?: MarketWindowOpenArgs,
+): Promise<UiMarketWindow> => invokeIpc("market_window_open", args);
+
+export const invokeMarketWindowSubscribe = async (
+  args: MarketWindowSubscribeArgs,
+): Promise<UiMarketWindow> => invokeIpc("market_window_subscribe", args);
+
+export const invokeMarketWindowsList = async (): Promise<UiMarketWindow[]> =>
+  invokeIpc("market_windows_list", undefined);
@@ -1,5 +1,7 @@
 import { afterEach, beforeEach, describe, expect, it, vi } from "vitest";
 import {
+  createFrameAcker,
+  createMarketEventSeqTracker,
   listenMarketEvents,
   parseMarketFramePayload,
   parseMarketPerfPayload,
@@ -9,6 +11,7 @@ import {
   parseUiDeltaCandlesBootstrapPayload,
   parseUiDeltaCandlePayload,
   parseUiTickPayload,
+  upsertCandlesByTime,
 } from "./market-events";
 
 const { listenMock } = vi.hoisted(() => {
@@ -23,6 +26,12 @@ vi.mock("@tauri-apps/api/event", () => {
   };
 });
 
+vi.mock("@tauri-apps/api/webviewWindow", () => {
+  return {
+    getCurrentWebviewWindow: () => ({ label: "chart-1" }),
+  };
+});
+
 describe("market events", () => {
   beforeEach(() => {
     (window as Window & { __TAURI_INTERNALS__?: object }).__TAURI_INTERNALS__ = {};
@@ -33,6 +42,39 @@ describe("market events", () => {
     delete (window as Window & { __TAURI_INTERNALS__?: object }).__TAURI_INTERNALS__;
   });
 
+  it("tracks event sequence gaps and stream restarts", () => {
+    const tracker = createMarketEventSeqTracker({ generation: 10, seq: 4 });
+
+    expect(tracker.observe({ t: 1, generation: 10, seq: 4 })).toBe("stale");
+    expect(tracker.observe({ t: 1, generation: 10, seq: 5 })).toBe("next");
+    expect(tracker.observe({ t: 1, generation: 10, seq: 8 })).toBe("gap");
+    expect(tracker.observe({ t: 1, generation: 9, seq: 20 })).toBe("stale");
+    expect(tracker.observe({ t: 1, generation: 11, seq: 1 })).toBe("restarted");
+    expect(tracker.observe({ t: 1 })).toBe("unsequenced");
+    expect(tracker.last()).toEqual({ generation: 11, seq: 1 });
+  });
+
+  it("acknowledges frames one call at a time, coalescing to the latest", async () => {
+    const sent: Array<{ generation: number; seq: number }> = [];
+    let release: (() => void) | undefined;
+    const acker = createFrameAcker("chart-1", (args) => {
+      sent.push({ generation: args.generation, seq: args.seq });
+      return new Promise<void>((resolve) => {
+        release = resolve;
+      });
+    });
+
+    acker.ack({ tick: null, generation: 3, seq: 10 });
+    acker.ack({ tick: null, generation: 3, seq: 12 });
+    acker.ack({ tick: null, generation: 3, seq: 15 });
+    acker.ack({ tick: null });
+    expect(sent).toEqual([{ generation: 3, seq: 10 }]);
+
+    release?.();
+    await vi.waitFor(() => expect(sent).toHaveLength(2));
+    expect(sent[1]).toEqual({ generation: 3, seq: 15 });
+  });
+
   it("parses valid ui tick payload", () => {
     const parsed = parseUiTickPayload({
       t: 1_700_000_000_000,
@@ -170,6 +212,14 @@ describe("market events", () => {
       localPipelineP99Ms: 4,
       ingestCount: 100,
       emitCount: 20,
+      reconcileChecks: 0,
+      reconcileDivergences: 0,
+      lastCandleDivergence: null,
+      emitIntervalMs: 16,
+      backpressureActive: false,
+      framesInFlight: 0,
+      framesMerged: 0,
+      framesDropped: 0,
     });
 
     expect(parsed.ingestCount).toBe(100);
@@ -333,6 +383,50 @@ describe("market events", () => {
     expect(unlistenDeltaBootstrap).toHaveBeenCalledTimes(1);
   });
 
+  it("upserts repaired candles by open time", () => {
+    const candle = (t: number, c: number) => ({ t, o: 100, h: 110, l: 90, c, v: 1 });
+    const current = [candle(60_000, 100), candle(120_000, 101), candle(240_000, 103)];
+
+    expect(upsertCandlesByTime(current, [candle(180_000, 102), candle(120_000, 105)])).toEqual([
+      candle(60_000, 100),
+      candle(120_000, 105),
+      candle(180_000, 102),
+      candle(240_000, 103),
+    ]);
+    expect(upsertCandlesByTime(current, [])).toEqual(current);
+  });
+
+  it("forwards candles_repair payloads", async () => {
+    let repairHandler: ((event: { payload: unknown }) => void) | undefined;
+    const unlistenRepair = vi.fn();
+    listenMock.mockImplementation(
+      (eventName: string, handler: (event: { payload: unknown }) => void) => {
+        if (eventName === "candles_repair") {
+          repairHandler = handler;
+          return Promise.resolve(unlistenRepair);
+        }
+        throw new Error(`Unexpected event: ${eventName}`);
+      },
+    );
+
+    const onCandlesRepair = vi.fn();
+    const unlistenAll = await listenMarketEvents({ onCandlesRepair });
+    repairHandler?.({ payload: { symbol: "BTCUSDT", timeframe: "1m", candles: [] } });
+    repairHandler?.({
+      payload: {
+        symbol: "BTCUSDT",
+        timeframe: "1m",
+        candles: [{ t: 60_000, o: 100, h: 104, l: 99, c: 103, v: 2.5 }],
+        deltaCandles: [{ t: 60_000, o: 0, h: 1.5, l: -0.5, c: 1.2, v: 2.5 }],
+      },
+    });
+
+    expect(onCandlesRepair).toHaveBeenCalledTimes(1);
+    expect(onCandlesRepair.mock.calls[0]?.[0].candles[0].c).toBe(103);
+    unlistenAll();
+    expect(unlistenRepair).toHaveBeenCalledTimes(1);
+  });
+
   it("registers only event listeners with provided handlers", async () => {
     const unlistenStatus = vi.fn();
     listenMock.mockImplementation((eventName: string) => {
@@ -347,7 +441,9 @@ describe("market events", () => {
     });
 
     expect(listenMock).toHaveBeenCalledTimes(1);
-    expect(listenMock).toHaveBeenCalledWith("market_status", expect.any(Function));
+    expect(listenMock).toHaveBeenCalledWith("market_status", expect.any(Function), {
+      target: { kind: "WebviewWindow", label: "chart-1" },
+    });
 
     unlistenAll();
     expect(unlistenStatus).toHaveBeenCalledTimes(1);
@@ -403,6 +499,14 @@ describe("market events", () => {
         localPipelineP99Ms: 4,
         ingestCount: 10,
         emitCount: 5,
+        reconcileChecks: 0,
+        reconcileDivergences: 0,
+        lastCandleDivergence: null,
+        emitIntervalMs: 16,
+        backpressureActive: false,
+        framesInFlight: 0,
+        framesMerged: 0,
+        framesDropped: 0,
       },
     });
 
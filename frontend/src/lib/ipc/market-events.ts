@@ -1,24 +1,39 @@
-import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import {
+  listen as listenAnyTarget,
+  type EventCallback,
+  type UnlistenFn,
+} from "@tauri-apps/api/event";
+import { getCurrentWebviewWindow } from "@tauri-apps/api/webviewWindow";
 import {
   historyLoadProgressSchema,
+  marketEventSeqSchema,
   marketFrameUpdateSchema,
   marketPerfSnapshotSchema,
   marketStatusSchema,
   uiCandlesBootstrapSchema,
+  uiCandlesRepairSchema,
   uiCandleSchema,
   uiDeltaCandlesBootstrapSchema,
   uiDeltaCandleSchema,
+  uiExportProgressSchema,
+  uiReplayStatusSchema,
   uiTickSchema,
+  type MarketEventSeq,
+  type MarketFrameAckArgs,
   type MarketFrameUpdate,
   type HistoryLoadProgress,
   type MarketPerfSnapshot,
   type MarketStatus,
   type UiCandlesBootstrap,
+  type UiCandlesRepair,
   type UiCandle,
   type UiDeltaCandlesBootstrap,
   type UiDeltaCandle,
+  type UiExportProgress,
+  type UiReplayStatus,
   type UiTick,
 } from "./contracts";
+import { invokeIpc } from "./invoke";
 
 type MarketEventHandlers = {
   onTick?: (tick: UiTick) => void;
@@ -26,10 +41,14 @@ type MarketEventHandlers = {
   onPerf?: (perf: MarketPerfSnapshot) => void;
   onCandle?: (candle: UiCandle) => void;
   onCandlesBootstrap?: (payload: UiCandlesBootstrap) => void;
+  onCandlesRepair?: (payload: UiCandlesRepair) => void;
   onDeltaCandle?: (candle: UiDeltaCandle) => void;
   onDeltaCandlesBootstrap?: (payload: UiDeltaCandlesBootstrap) => void;
   onStatus?: (status: MarketStatus) => void;
   onHistoryLoadProgress?: (progress: HistoryLoadProgress) => void;
+  onReplayStatus?: (status: UiReplayStatus) => void;
+  onArchiveImportProgress?: (progress: HistoryLoadProgress) => void;
+  onExportProgress?: (progress: UiExportProgress) => void;
 };
 
 const hasTauriRuntime = (): boolean => {
@@ -42,6 +61,21 @@ const hasTauriRuntime = (): boolean => {
 
 const isDevRuntime = (): boolean => import.meta.env.DEV;
 
+// The core routes stream events only to the windows subscribed to that stream. A listener
+// without a target would also receive events addressed to other windows.
+const listen = <T>(event: string, handler: EventCallback<T>): Promise<UnlistenFn> =>
+  listenAnyTarget<T>(event, handler, {
+    target: { kind: "WebviewWindow", label: getCurrentWebviewWindow().label },
+  });
+
+/** Stream shown by this window: detached chart windows get it through `?streamId=`. */
+export const currentWindowStreamId = (): string => {
+  if (typeof window === "undefined") {
+    return "main";
+  }
+  return new URLSearchParams(window.location.search).get("streamId") ?? "main";
+};
+
 export const parseUiTickPayload = (payload: unknown): UiTick => uiTickSchema.parse(payload);
 export const parseMarketFramePayload = (payload: unknown): MarketFrameUpdate =>
   marketFrameUpdateSchema.parse(payload);
@@ -61,9 +95,114 @@ export const parseUiDeltaCandlePayload = (payload: unknown): UiDeltaCandle =>
 export const parseUiDeltaCandlesBootstrapPayload = (payload: unknown): UiDeltaCandlesBootstrap =>
   uiDeltaCandlesBootstrapSchema.parse(payload);
 
+export const parseUiCandlesRepairPayload = (payload: unknown): UiCandlesRepair =>
+  uiCandlesRepairSchema.parse(payload);
+
+/**
+ * Replaces the candles of `current` that share an open time with `repaired` and inserts the
+ * ones it lacks, keeping the result sorted by `t`.
+ */
+export const upsertCandlesByTime = <T extends { t: number }>(
+  current: ReadonlyArray<T>,
+  repaired: ReadonlyArray<T>,
+): T[] => {
+  if (repaired.length === 0) {
+    return [...current];
+  }
+  const byTime = new Map<number, T>();
+  for (const candle of current) {
+    byTime.set(candle.t, candle);
+  }
+  for (const candle of repaired) {
+    byTime.set(candle.t, candle);
+  }
+  return [...byTime.values()].sort((left, right) => left.t - right.t);
+};
+
 export const parseMarketStatusPayload = (payload: unknown): MarketStatus =>
   marketStatusSchema.parse(payload);
 
+/**
+ * `next`: the event follows the last one. `gap`: events were lost and the window should
+ * resync through `market_stream_snapshot`. `stale`: an older generation or an event already
+ * applied, to be ignored. `restarted`: first event of a new stream generation.
+ */
+export type MarketEventSeqCheck = "next" | "gap" | "stale" | "restarted" | "unsequenced";
+
+/** Follows the `generation`/`seq` stamps of one stream, starting from a snapshot's `lastSeq`. */
+export const createMarketEventSeqTracker = (initial: MarketEventSeq | null = null) => {
+  let last = initial;
+
+  return {
+    observe(payload: unknown): MarketEventSeqCheck {
+      const parsed = marketEventSeqSchema.safeParse(payload);
+      if (!parsed.success) {
+        return "unsequenced";
+      }
+      const current = parsed.data;
+      if (last !== null && current.generation < last.generation) {
+        return "stale";
+      }
+      if (last === null || current.generation > last.generation) {
+        last = current;
+        return current.seq === 1 ? "restarted" : "gap";
+      }
+      if (current.seq <= last.seq) {
+        return "stale";
+      }
+      const check = current.seq === last.seq + 1 ? "next" : "gap";
+      last = current;
+      return check;
+    },
+    reset(next: MarketEventSeq | null): void {
+      last = next;
+    },
+    last(): MarketEventSeq | null {
+      return last;
+    },
+  };
+};
+
+/**
+ * Reports applied `market_frame_update` events so the core can slow its emitter while this
+ * window falls behind. One ack is in flight at a time; frames applied meanwhile are covered
+ * by the next one.
+ */
+export const createFrameAcker = (
+  streamId: string,
+  send: (args: MarketFrameAckArgs) => Promise<unknown> = (args) =>
+    invokeIpc("market_frame_ack", args),
+) => {
+  let pending: MarketEventSeq | null = null;
+  let inFlight = false;
+
+  const flush = (): void => {
+    if (inFlight || pending === null) {
+      return;
+    }
+    const { generation, seq } = pending;
+    pending = null;
+    inFlight = true;
+    void send({ streamId, generation, seq })
+      .catch(() => undefined)
+      .finally(() => {
+        inFlight = false;
+        flush();
+      });
+  };
+
+  return {
+    ack(payload: unknown): void {
+      const parsed = marketEventSeqSchema.safeParse(payload);
+      if (!parsed.success) {
+        return;
+      }
+      pending = parsed.data;
+      flush();
+    },
+  };
+};
+
 export const listenMarketEvents = async (handlers: MarketEventHandlers): Promise<UnlistenFn> => {
   if (!hasTauriRuntime()) {
     return () => undefined;
@@ -86,14 +225,17 @@ export const listenMarketEvents = async (handlers: MarketEventHandlers): Promise
   }
 
   if (handlers.onMarketFrameUpdate) {
+    const frameAcker = createFrameAcker(currentWindowStreamId());
     const unlistenFrame = await listen<unknown>("market_frame_update", (event) => {
       if (!isDevRuntime()) {
         handlers.onMarketFrameUpdate?.(event.payload as MarketFrameUpdate);
+        frameAcker.ack(event.payload);
         return;
       }
       const parsed = marketFrameUpdateSchema.safeParse(event.payload);
       if (parsed.success) {
         handlers.onMarketFrameUpdate?.(parsed.data);
+        frameAcker.ack(event.payload);
       }
     });
     unlistenFns.push(unlistenFrame);
@@ -143,6 +285,16 @@ export const listenMarketEvents = async (handlers: MarketEventHandlers): Promise
     unlistenFns.push(unlistenBootstrap);
   }
 
+  if (handlers.onCandlesRepair) {
+    const unlistenRepair = await listen<unknown>("candles_repair", (event) => {
+      const parsed = uiCandlesRepairSchema.safeParse(event.payload);
+      if (parsed.success) {
+        handlers.onCandlesRepair?.(parsed.data);
+      }
+    });
+    unlistenFns.push(unlistenRepair);
+  }
+
   if (handlers.onDeltaCandle) {
     const unlistenDeltaCandle = await listen<unknown>("delta_candle_update", (event) => {
       if (!isDevRuntime()) {
@@ -177,6 +329,36 @@ export const listenMarketEvents = async (handlers: MarketEventHandlers): Promise
     unlistenFns.push(unlistenHistoryProgress);
   }
 
+  if (handlers.onArchiveImportProgress) {
+    const unlistenImportProgress = await listen<unknown>("archive_import_progress", (event) => {
+      const parsed = historyLoadProgressSchema.safeParse(event.payload);
+      if (parsed.success) {
+        handlers.onArchiveImportProgress?.(parsed.data);
+      }
+    });
+    unlistenFns.push(unlistenImportProgress);
+  }
+
+  if (handlers.onReplayStatus) {
+    const unlistenReplayStatus = await listen<unknown>("replay_status", (event) => {
+      const parsed = uiReplayStatusSchema.safeParse(event.payload);
+      if (parsed.success) {
+        handlers.onReplayStatus?.(parsed.data);
+      }
+    });
+    unlistenFns.push(unlistenReplayStatus);
+  }
+
+  if (handlers.onExportProgress) {
+    const unlistenExportProgress = await listen<unknown>("export_progress", (event) => {
+      const parsed = uiExportProgressSchema.safeParse(event.payload);
+      if (parsed.success) {
+        handlers.onExportProgress?.(parsed.data);
+      }
+    });
+    unlistenFns.push(unlistenExportProgress);
+  }
+
   return () => {
     for (const unlisten of unlistenFns) {
       unlisten();
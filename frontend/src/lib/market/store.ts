@@ -7,6 +7,7 @@ import type {
   MarketTimeframe,
   UiDeltaCandle,
 } from "@lib/ipc/contracts";
+import { upsertCandlesByTime } from "@lib/ipc/market-events";
 import type { PersistedDrawing } from "@lib/market/drawings";
 
 export type SharedCrosshairState = {
@@ -102,6 +103,23 @@ export const upsertDeltaCandle = (candle: UiDeltaCandle): void => {
   });
 };
 
+/** Folds exchange-repaired buckets into the delta history, keeping the live candle. */
+export const applyDeltaCandlesRepair = (candles: ReadonlyArray<UiDeltaCandle>): void => {
+  if (candles.length === 0) {
+    return;
+  }
+  const live = $marketDeltaLiveUpdate.get()?.candle;
+  const current = upsertCandlesByTime($marketDeltaCandles.get(), live ? [live] : []);
+  $marketDeltaCandles.set(upsertCandlesByTime(current, candles));
+};
+
+/** Puts older history behind the delta candles already shown, which win on overlap. */
+export const prependDeltaCandlesHistory = (candles: ReadonlyArray<UiDeltaCandle>): void => {
+  const live = $marketDeltaLiveUpdate.get()?.candle;
+  const current = upsertCandlesByTime($marketDeltaCandles.get(), live ? [live] : []);
+  $marketDeltaCandles.set(upsertCandlesByTime(candles, current));
+};
+
 export const setMarketVisibleLogicalRange = (range: { from: number; to: number } | null): void => {
   $marketVisibleLogicalRange.set(range);
 };